@@ -0,0 +1,4 @@
+ALTER TABLE bria_batch_wallet_summaries
+ADD COLUMN replaced_batch_created_ledger_tx_id UUID DEFAULT NULL,
+ADD COLUMN replaced_batch_broadcast_ledger_tx_id UUID DEFAULT NULL,
+ADD COLUMN batch_dropped_ledger_tx_id UUID DEFAULT NULL;
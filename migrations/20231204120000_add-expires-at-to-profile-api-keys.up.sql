@@ -0,0 +1 @@
+ALTER TABLE bria_profile_api_keys ADD COLUMN expires_at TIMESTAMPTZ DEFAULT NULL;
@@ -0,0 +1 @@
+ALTER TABLE bria_profile_api_keys DROP COLUMN scopes;
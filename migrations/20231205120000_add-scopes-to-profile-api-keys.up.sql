@@ -0,0 +1,2 @@
+ALTER TABLE bria_profile_api_keys
+ADD COLUMN scopes VARCHAR[] NOT NULL DEFAULT ARRAY['read', 'addresses:write', 'payouts:write', 'wallets:admin', 'signers:admin', 'profiles:admin'];
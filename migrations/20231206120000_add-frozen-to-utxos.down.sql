@@ -0,0 +1,4 @@
+ALTER TABLE bria_utxos
+DROP COLUMN frozen_at,
+DROP COLUMN frozen_reason,
+DROP COLUMN frozen_by_profile_id;
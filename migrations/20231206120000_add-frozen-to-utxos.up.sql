@@ -0,0 +1,4 @@
+ALTER TABLE bria_utxos
+ADD COLUMN frozen_at TIMESTAMPTZ DEFAULT NULL,
+ADD COLUMN frozen_reason VARCHAR DEFAULT NULL,
+ADD COLUMN frozen_by_profile_id UUID REFERENCES bria_profiles(id) DEFAULT NULL;
@@ -0,0 +1 @@
+ALTER TABLE bria_utxos DROP COLUMN quarantine_sources;
@@ -0,0 +1,2 @@
+ALTER TABLE bria_utxos
+ADD COLUMN quarantine_sources VARCHAR[] DEFAULT NULL;
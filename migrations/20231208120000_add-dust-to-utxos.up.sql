@@ -0,0 +1,2 @@
+ALTER TABLE bria_utxos
+ADD COLUMN dust BOOLEAN NOT NULL DEFAULT false;
@@ -0,0 +1,5 @@
+UPDATE bria_profile_api_keys
+SET scopes = array_remove(scopes, 'payouts:approve');
+
+ALTER TABLE bria_profile_api_keys
+ALTER COLUMN scopes SET DEFAULT ARRAY['read', 'addresses:write', 'payouts:write', 'wallets:admin', 'signers:admin', 'profiles:admin'];
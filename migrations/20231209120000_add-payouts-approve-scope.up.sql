@@ -0,0 +1,6 @@
+ALTER TABLE bria_profile_api_keys
+ALTER COLUMN scopes SET DEFAULT ARRAY['read', 'addresses:write', 'payouts:write', 'payouts:approve', 'wallets:admin', 'signers:admin', 'profiles:admin'];
+
+UPDATE bria_profile_api_keys
+SET scopes = array_append(scopes, 'payouts:approve')
+WHERE scopes @> ARRAY['read', 'addresses:write', 'payouts:write', 'wallets:admin', 'signers:admin', 'profiles:admin']::VARCHAR[];
@@ -94,6 +94,10 @@ impl NewAddress {
         builder
     }
 
+    pub fn address(&self) -> &bitcoin::Address {
+        &self.address
+    }
+
     pub fn initial_events(self) -> EntityEvents<AddressEvent> {
         let mut events = EntityEvents::init([
             AddressEvent::Initialized {
@@ -20,6 +20,16 @@ impl Addresses {
 
     pub async fn persist_new_address(&self, address: NewAddress) -> Result<(), AddressError> {
         let mut tx = self.pool.begin().await?;
+        self.persist_new_address_in_tx(&mut tx, address).await?;
+        tx.commit().await?;
+        Ok(())
+    }
+
+    pub async fn persist_new_address_in_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        address: NewAddress,
+    ) -> Result<(), AddressError> {
         sqlx::query!(
             r#"INSERT INTO bria_addresses
                (id, account_id, wallet_id, keychain_id, profile_id, address, kind, external_id)
@@ -33,12 +43,10 @@ impl Addresses {
             pg::PgKeychainKind::from(address.kind) as pg::PgKeychainKind,
             address.external_id,
         )
-        .execute(&mut *tx)
+        .execute(&mut **tx)
         .await?;
 
-        Self::persist_events(&mut tx, address).await?;
-        tx.commit().await?;
-        Ok(())
+        Self::persist_events(tx, address).await
     }
 
     pub async fn persist_if_not_present(
@@ -51,7 +51,7 @@ impl AdminApp {
         let profile = self.profiles.create_in_tx(&mut tx, new_profile).await?;
         let profile_key = self
             .profiles
-            .create_key_for_profile_in_tx(&mut tx, profile, true)
+            .create_key_for_profile_in_tx(&mut tx, profile, true, ApiKeyScope::all())
             .await?;
         tx.commit().await?;
         Ok((admin_key, profile_key))
@@ -89,7 +89,7 @@ impl AdminApp {
         let profile = self.profiles.create_in_tx(&mut tx, new_profile).await?;
         let key = self
             .profiles
-            .create_key_for_profile_in_tx(&mut tx, profile, false)
+            .create_key_for_profile_in_tx(&mut tx, profile, false, ApiKeyScope::all())
             .await?;
         tx.commit().await?;
         Ok(key)
@@ -32,6 +32,7 @@ pub async fn run_dev(
                 dev_constants::DEV_WALLET_NAME.to_string(),
                 xpub,
                 derivation_path,
+                None,
             )
             .await?;
         app.set_signer_config(
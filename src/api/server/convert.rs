@@ -25,6 +25,24 @@ impl From<Profile> for proto::Profile {
             id: p.id.to_string(),
             name: p.name,
             spending_policy: p.spending_policy.map(proto::SpendingPolicy::from),
+            disabled: p.disabled,
+        }
+    }
+}
+
+impl From<ProfileApiKeySummary> for proto::ProfileApiKey {
+    fn from(key: ProfileApiKeySummary) -> Self {
+        Self {
+            id: key.id.to_string(),
+            profile_id: key.profile_id.to_string(),
+            active: key.active,
+            scopes: key
+                .scopes
+                .into_iter()
+                .map(|scope| scope.to_string())
+                .collect(),
+            created_at: key.created_at.timestamp() as u32,
+            expires_at: key.expires_at.map(|time| time.timestamp() as u32),
         }
     }
 }
@@ -37,6 +55,20 @@ impl From<SpendingPolicy> for proto::SpendingPolicy {
                 .into_iter()
                 .map(|addr| addr.to_string())
                 .collect(),
+            approval: sp.approval.map(|approval| proto::ApprovalPolicy {
+                required_approvals: approval.required_approvals,
+                threshold_sats: u64::from(approval.threshold_sats),
+            }),
+            limits: sp
+                .limits
+                .into_iter()
+                .map(|limit| proto::SpendingLimit {
+                    wallet_name: limit.wallet_name,
+                    max_payout_sats: limit.max_payout_sats.map(u64::from),
+                    max_sats_per_hour: limit.max_sats_per_hour.map(u64::from),
+                    max_sats_per_day: limit.max_sats_per_day.map(u64::from),
+                })
+                .collect(),
         }
     }
 }
@@ -54,6 +86,20 @@ impl TryFrom<proto::SpendingPolicy> for SpendingPolicy {
         }
         Ok(Self {
             allowed_payout_addresses,
+            approval: sp.approval.map(|approval| ApprovalPolicy {
+                required_approvals: approval.required_approvals,
+                threshold_sats: Satoshis::from(approval.threshold_sats),
+            }),
+            limits: sp
+                .limits
+                .into_iter()
+                .map(|limit| SpendingLimit {
+                    wallet_name: limit.wallet_name,
+                    max_payout_sats: limit.max_payout_sats.map(Satoshis::from),
+                    max_sats_per_hour: limit.max_sats_per_hour.map(Satoshis::from),
+                    max_sats_per_day: limit.max_sats_per_day.map(Satoshis::from),
+                })
+                .collect(),
         })
     }
 }
@@ -147,6 +193,14 @@ impl From<WalletUtxo> for proto::Utxo {
             address: utxo.address.map(|a| a.to_string()),
             change_output: utxo.kind == KeychainKind::Internal,
             block_height: utxo.block_height,
+            frozen: utxo.frozen.is_some(),
+            frozen_reason: utxo.frozen.as_ref().map(|f| f.reason.clone()),
+            frozen_by_profile_id: utxo
+                .frozen
+                .as_ref()
+                .and_then(|f| f.profile_id.map(|id| id.to_string())),
+            frozen_at: utxo.frozen.map(|f| f.frozen_at.timestamp() as u32),
+            dust: utxo.dust,
         }
     }
 }
@@ -163,6 +217,7 @@ impl From<KeychainUtxos> for proto::KeychainUtxos {
 impl From<Payout> for proto::Payout {
     fn from(payout: Payout) -> Self {
         let cancelled = payout.is_cancelled();
+        let rejected = payout.is_rejected();
         let destination = match payout.destination {
             PayoutDestination::OnchainAddress { value } => {
                 proto::payout::Destination::OnchainAddress(value.to_string())
@@ -187,6 +242,13 @@ impl From<Payout> for proto::Payout {
             metadata: payout.metadata.map(|json| {
                 serde_json::from_value(json).expect("Could not transfer json -> struct")
             }),
+            fee_from_amount: payout.fee_from_amount,
+            sweep: payout.sweep,
+            not_before: payout.not_before.map(|time| time.timestamp() as u32),
+            not_before_block_height: payout.not_before_block_height,
+            required_approvals: payout.required_approvals,
+            approved_by_profile_ids: payout.approved_by.iter().map(|id| id.to_string()).collect(),
+            rejected,
         }
     }
 }
@@ -209,6 +271,18 @@ impl From<WalletConfig> for proto::WalletConfig {
         Self {
             settle_income_after_n_confs: config.settle_income_after_n_confs,
             settle_change_after_n_confs: config.settle_change_after_n_confs,
+            gap_limit: config.gap_limit,
+            lookahead: config.lookahead,
+            consolidation: config
+                .consolidation
+                .map(|policy| proto::WalletConsolidationPolicy {
+                    payout_queue_id: policy.payout_queue_id.to_string(),
+                    target_utxo_count: policy.target_utxo_count,
+                    max_fee_rate_sat_per_vb: policy.max_fee_rate_sat_per_vb,
+                    max_inputs_per_tx: policy.max_inputs_per_tx,
+                }),
+            dust_threshold_sats: config.dust_threshold_sats,
+            dust_sweep_max_fee_rate_sat_per_vb: config.dust_sweep_max_fee_rate_sat_per_vb,
         }
     }
 }
@@ -218,19 +292,48 @@ impl From<PayoutQueue> for proto::PayoutQueue {
         let id = payout_queue.id.to_string();
         let description = payout_queue.description();
         let name = payout_queue.name;
-        let trigger = match payout_queue.config.trigger {
+        let trigger = match payout_queue.config.trigger.clone() {
             PayoutQueueTrigger::Interval { seconds } => {
                 proto::payout_queue_config::Trigger::IntervalSecs(seconds.as_secs() as u32)
             }
+            PayoutQueueTrigger::Threshold {
+                min_payouts,
+                min_total_sats,
+                seconds,
+            } => {
+                proto::payout_queue_config::Trigger::Threshold(proto::PayoutQueueThresholdTrigger {
+                    min_payouts,
+                    min_total_sats: min_total_sats.map(u64::from),
+                    interval_secs: seconds.as_secs() as u32,
+                })
+            }
+            PayoutQueueTrigger::Schedule { cron, timezone } => {
+                proto::payout_queue_config::Trigger::Schedule(proto::PayoutQueueScheduleTrigger {
+                    cron,
+                    timezone: Some(timezone),
+                })
+            }
             PayoutQueueTrigger::Manual => proto::payout_queue_config::Trigger::Manual(true),
         };
         let tx_priority: proto::TxPriority = payout_queue.config.tx_priority.into();
+        let custom_fee_rate_sat_per_vb = match payout_queue.config.tx_priority {
+            TxPriority::CustomSatPerVb(fee_rate) => Some(fee_rate.as_sat_per_vb()),
+            _ => None,
+        };
         let config = Some(proto::PayoutQueueConfig {
             trigger: Some(trigger),
             tx_priority: tx_priority as i32,
             consolidate_deprecated_keychains: payout_queue.config.consolidate_deprecated_keychains,
             cpfp_payouts_after_mins: payout_queue.config.cpfp_payouts_after_mins,
             cpfp_payouts_after_blocks: payout_queue.config.cpfp_payouts_after_blocks,
+            bump_fee_after_mins: payout_queue.config.bump_fee_after_mins,
+            max_fee_rate_sat_per_vb: payout_queue.config.max_fee_rate_sat_per_vb,
+            max_total_fee_sats: payout_queue.config.max_total_fee_sats.map(u64::from),
+            custom_fee_rate_sat_per_vb,
+            spending_path: proto::SpendingPath::from(payout_queue.config.spending_path) as i32,
+            coin_selection: Some(proto::CoinSelectionStrategy::from(
+                payout_queue.config.coin_selection,
+            ) as i32),
         });
         proto::PayoutQueue {
             id,
@@ -247,6 +350,9 @@ impl From<TxPriority> for proto::TxPriority {
             TxPriority::NextBlock => proto::TxPriority::NextBlock,
             TxPriority::HalfHour => proto::TxPriority::HalfHour,
             TxPriority::OneHour => proto::TxPriority::OneHour,
+            TxPriority::Economy => proto::TxPriority::Economy,
+            TxPriority::Minimum => proto::TxPriority::Minimum,
+            TxPriority::CustomSatPerVb(_) => proto::TxPriority::Custom,
         }
     }
 }
@@ -259,14 +365,75 @@ impl From<SigningSession> for proto::SigningSession {
             xpub_id: session.xpub_id.to_string(),
             failure_reason: session.failure_reason().map(|r| r.to_string()),
             state: format!("{:?}", session.state()),
+            spending_path: session.spending_path.map(|p| p.to_string()),
+        }
+    }
+}
+
+impl From<SpendingPath> for proto::SpendingPath {
+    fn from(path: SpendingPath) -> Self {
+        match path {
+            SpendingPath::Primary => proto::SpendingPath::Primary,
+            SpendingPath::Recovery => proto::SpendingPath::Recovery,
+        }
+    }
+}
+
+impl From<proto::SpendingPath> for SpendingPath {
+    fn from(path: proto::SpendingPath) -> Self {
+        match path {
+            proto::SpendingPath::Primary => SpendingPath::Primary,
+            proto::SpendingPath::Recovery => SpendingPath::Recovery,
+        }
+    }
+}
+
+impl From<CoinSelectionStrategy> for proto::CoinSelectionStrategy {
+    fn from(strategy: CoinSelectionStrategy) -> Self {
+        match strategy {
+            CoinSelectionStrategy::BranchAndBound => proto::CoinSelectionStrategy::BranchAndBound,
+            CoinSelectionStrategy::LargestFirst => proto::CoinSelectionStrategy::LargestFirst,
+            CoinSelectionStrategy::OldestFirst => proto::CoinSelectionStrategy::OldestFirst,
+            CoinSelectionStrategy::Privacy => proto::CoinSelectionStrategy::Privacy,
+        }
+    }
+}
+
+pub fn coin_selection_from_proto(strategy: Option<i32>) -> Option<CoinSelectionStrategy> {
+    strategy
+        .and_then(|strategy| proto::CoinSelectionStrategy::try_from(strategy).ok())
+        .map(CoinSelectionStrategy::from)
+}
+
+impl From<proto::CoinSelectionStrategy> for CoinSelectionStrategy {
+    fn from(strategy: proto::CoinSelectionStrategy) -> Self {
+        match strategy {
+            proto::CoinSelectionStrategy::BranchAndBound => CoinSelectionStrategy::BranchAndBound,
+            proto::CoinSelectionStrategy::LargestFirst => CoinSelectionStrategy::LargestFirst,
+            proto::CoinSelectionStrategy::OldestFirst => CoinSelectionStrategy::OldestFirst,
+            proto::CoinSelectionStrategy::Privacy => CoinSelectionStrategy::Privacy,
+        }
+    }
+}
+
+impl From<proto::PolicyScriptType> for PolicyScriptType {
+    fn from(script_type: proto::PolicyScriptType) -> Self {
+        match script_type {
+            proto::PolicyScriptType::Wsh => PolicyScriptType::Wsh,
+            proto::PolicyScriptType::Tr => PolicyScriptType::Tr,
         }
     }
 }
 
-impl From<proto::PayoutQueueConfig> for PayoutQueueConfig {
-    fn from(proto_config: proto::PayoutQueueConfig) -> Self {
-        let tx_priority =
-            proto::TxPriority::try_from(proto_config.tx_priority).map(TxPriority::from);
+impl TryFrom<proto::PayoutQueueConfig> for PayoutQueueConfig {
+    type Error = tonic::Status;
+
+    fn try_from(proto_config: proto::PayoutQueueConfig) -> Result<Self, Self::Error> {
+        let tx_priority = tx_priority_from_proto(
+            proto_config.tx_priority,
+            proto_config.custom_fee_rate_sat_per_vb,
+        )
+        .map_err(tonic::Status::invalid_argument)?;
 
         let trigger = match proto_config.trigger {
             Some(proto::payout_queue_config::Trigger::IntervalSecs(interval)) => {
@@ -274,6 +441,19 @@ impl From<proto::PayoutQueueConfig> for PayoutQueueConfig {
                     seconds: Duration::from_secs(interval as u64),
                 })
             }
+            Some(proto::payout_queue_config::Trigger::Threshold(threshold)) => {
+                Some(PayoutQueueTrigger::Threshold {
+                    min_payouts: threshold.min_payouts,
+                    min_total_sats: threshold.min_total_sats.map(Satoshis::from),
+                    seconds: Duration::from_secs(threshold.interval_secs as u64),
+                })
+            }
+            Some(proto::payout_queue_config::Trigger::Schedule(schedule)) => {
+                Some(PayoutQueueTrigger::Schedule {
+                    cron: schedule.cron,
+                    timezone: schedule.timezone.unwrap_or_else(|| "UTC".to_string()),
+                })
+            }
             Some(proto::payout_queue_config::Trigger::Manual(true)) => {
                 Some(PayoutQueueTrigger::Manual)
             }
@@ -284,29 +464,66 @@ impl From<proto::PayoutQueueConfig> for PayoutQueueConfig {
             consolidate_deprecated_keychains: proto_config.consolidate_deprecated_keychains,
             cpfp_payouts_after_mins: proto_config.cpfp_payouts_after_mins,
             cpfp_payouts_after_blocks: proto_config.cpfp_payouts_after_blocks,
+            bump_fee_after_mins: proto_config.bump_fee_after_mins,
+            max_fee_rate_sat_per_vb: proto_config.max_fee_rate_sat_per_vb,
+            max_total_fee_sats: proto_config.max_total_fee_sats.map(Satoshis::from),
+            spending_path: proto::SpendingPath::try_from(proto_config.spending_path)
+                .map(SpendingPath::from)
+                .unwrap_or_default(),
+            coin_selection: coin_selection_from_proto(proto_config.coin_selection)
+                .unwrap_or_default(),
             ..Self::default()
         };
 
         if let Some(trigger) = trigger {
             ret.trigger = trigger;
         }
-        if let Ok(tx_priority) = tx_priority {
+        if let Some(tx_priority) = tx_priority {
             ret.tx_priority = tx_priority;
         }
-        ret
+        Ok(ret)
     }
 }
 
-impl From<proto::TxPriority> for TxPriority {
-    fn from(proto_tx_priority: proto::TxPriority) -> Self {
-        match proto_tx_priority {
-            proto::TxPriority::NextBlock => TxPriority::NextBlock,
-            proto::TxPriority::HalfHour => TxPriority::HalfHour,
-            proto::TxPriority::OneHour => TxPriority::OneHour,
-        }
+pub(super) fn payout_schedule_from_proto(
+    not_before: Option<u32>,
+    not_before_block_height: Option<u32>,
+) -> PayoutSchedule {
+    PayoutSchedule {
+        not_before: timestamp_from_proto(not_before),
+        not_before_block_height,
     }
 }
 
+pub(super) fn timestamp_from_proto(secs: Option<u32>) -> Option<chrono::DateTime<chrono::Utc>> {
+    use chrono::TimeZone;
+    secs.and_then(|secs| chrono::Utc.timestamp_opt(secs as i64, 0).single())
+}
+
+/// Returns `None` for an unknown priority. Errors are messages meant for `invalid_argument`.
+pub(super) fn tx_priority_from_proto(
+    tx_priority: i32,
+    custom_fee_rate_sat_per_vb: Option<f32>,
+) -> Result<Option<TxPriority>, &'static str> {
+    if let Some(sat_per_vb) = custom_fee_rate_sat_per_vb {
+        return CustomFeeRate::from_sat_per_vb(sat_per_vb)
+            .map(|fee_rate| Some(TxPriority::CustomSatPerVb(fee_rate)))
+            .ok_or("custom_fee_rate_sat_per_vb must be a positive number");
+    }
+    let tx_priority = match proto::TxPriority::try_from(tx_priority) {
+        Ok(proto::TxPriority::NextBlock) => Some(TxPriority::NextBlock),
+        Ok(proto::TxPriority::HalfHour) => Some(TxPriority::HalfHour),
+        Ok(proto::TxPriority::OneHour) => Some(TxPriority::OneHour),
+        Ok(proto::TxPriority::Economy) => Some(TxPriority::Economy),
+        Ok(proto::TxPriority::Minimum) => Some(TxPriority::Minimum),
+        Ok(proto::TxPriority::Custom) => {
+            return Err("custom tx_priority requires custom_fee_rate_sat_per_vb")
+        }
+        Err(_) => None,
+    };
+    Ok(tx_priority)
+}
+
 impl From<(WalletSummary, Vec<Payout>)> for proto::BatchWalletSummary {
     fn from((summary, payouts): (WalletSummary, Vec<Payout>)) -> Self {
         Self {
@@ -341,6 +558,28 @@ impl From<(WalletSummary, Vec<Payout>)> for proto::BatchWalletSummary {
     }
 }
 
+impl From<WalletExport> for proto::ExportWalletResponse {
+    fn from(export: WalletExport) -> Self {
+        Self {
+            wallet_id: export.wallet_id.to_string(),
+            wallet_name: export.wallet_name,
+            network: export.network.to_string(),
+            gap_limit: export.gap_limit,
+            descriptors: export
+                .descriptors
+                .into_iter()
+                .map(|descriptor| proto::ExportedDescriptor {
+                    keychain_id: descriptor.keychain_id.to_string(),
+                    descriptor: descriptor.descriptor.to_string(),
+                    internal: descriptor.kind == KeychainKind::Internal,
+                    active: descriptor.active,
+                    last_derived_index: descriptor.last_derived_index,
+                })
+                .collect(),
+        }
+    }
+}
+
 impl From<WalletBalanceSummary> for proto::GetWalletBalanceSummaryResponse {
     fn from(balance: WalletBalanceSummary) -> Self {
         Self {
@@ -362,6 +601,8 @@ impl From<WalletBalanceSummary> for proto::GetWalletBalanceSummaryResponse {
                 .expect("Satoshis -> u64 failed"),
             effective_encumbered_outgoing: u64::try_from(balance.effective_encumbered_outgoing)
                 .expect("Satoshis -> u64 failed"),
+            effective_quarantined: u64::from(balance.effective_quarantined),
+            effective_dust: u64::from(balance.effective_dust),
         }
     }
 }
@@ -425,6 +666,45 @@ impl From<OutboxEvent<Augmentation>> for proto::BriaEvent {
                 block_height: confirmation_time.height,
                 block_time: confirmation_time.timestamp,
             }),
+            OutboxEventPayload::UtxoQuarantined {
+                tx_id,
+                vout,
+                satoshis,
+                address,
+                wallet_id,
+                confirmation_time,
+                blocked_sources,
+                ..
+            } => proto::bria_event::Payload::UtxoQuarantined(proto::UtxoQuarantined {
+                wallet_id: wallet_id.to_string(),
+                tx_id: tx_id.to_string(),
+                vout,
+                satoshis: u64::from(satoshis),
+                address: address.to_string(),
+                block_height: confirmation_time.height,
+                block_time: confirmation_time.timestamp,
+                blocked_sources: blocked_sources
+                    .into_iter()
+                    .map(|addr| addr.to_string())
+                    .collect(),
+            }),
+            OutboxEventPayload::UtxoDustSettled {
+                tx_id,
+                vout,
+                satoshis,
+                address,
+                wallet_id,
+                confirmation_time,
+                ..
+            } => proto::bria_event::Payload::UtxoDustSettled(proto::UtxoDustSettled {
+                wallet_id: wallet_id.to_string(),
+                tx_id: tx_id.to_string(),
+                vout,
+                satoshis: u64::from(satoshis),
+                address: address.to_string(),
+                block_height: confirmation_time.height,
+                block_time: confirmation_time.timestamp,
+            }),
             OutboxEventPayload::UtxoDropped {
                 tx_id,
                 vout,
@@ -439,6 +719,55 @@ impl From<OutboxEvent<Augmentation>> for proto::BriaEvent {
                 satoshis: u64::from(satoshis),
                 address: address.to_string(),
             }),
+            OutboxEventPayload::UtxoTimelockExpiring {
+                tx_id,
+                vout,
+                satoshis,
+                wallet_id,
+                keychain_id,
+                spendable_at_height,
+            } => proto::bria_event::Payload::UtxoTimelockExpiring(proto::UtxoTimelockExpiring {
+                wallet_id: wallet_id.to_string(),
+                keychain_id: keychain_id.to_string(),
+                tx_id: tx_id.to_string(),
+                vout,
+                satoshis: u64::from(satoshis),
+                spendable_at_height,
+            }),
+            OutboxEventPayload::UtxoFrozen {
+                tx_id,
+                vout,
+                satoshis,
+                wallet_id,
+                keychain_id,
+                profile_id,
+                reason,
+                ..
+            } => proto::bria_event::Payload::UtxoFrozen(proto::UtxoFrozen {
+                wallet_id: wallet_id.to_string(),
+                keychain_id: keychain_id.to_string(),
+                tx_id: tx_id.to_string(),
+                vout,
+                satoshis: u64::from(satoshis),
+                profile_id: profile_id.to_string(),
+                reason,
+            }),
+            OutboxEventPayload::UtxoUnfrozen {
+                tx_id,
+                vout,
+                satoshis,
+                wallet_id,
+                keychain_id,
+                profile_id,
+                ..
+            } => proto::bria_event::Payload::UtxoUnfrozen(proto::UtxoUnfrozen {
+                wallet_id: wallet_id.to_string(),
+                keychain_id: keychain_id.to_string(),
+                tx_id: tx_id.to_string(),
+                vout,
+                satoshis: u64::from(satoshis),
+                profile_id: profile_id.to_string(),
+            }),
             OutboxEventPayload::PayoutSubmitted {
                 id,
                 wallet_id,
@@ -500,6 +829,8 @@ impl From<OutboxEvent<Augmentation>> for proto::BriaEvent {
                 satoshis,
                 destination,
                 proportional_fee,
+                net_satoshis,
+                deducted_fee_sats,
                 ..
             } => proto::bria_event::Payload::PayoutCommitted(proto::PayoutCommitted {
                 id: id.to_string(),
@@ -522,6 +853,8 @@ impl From<OutboxEvent<Augmentation>> for proto::BriaEvent {
                     }
                 }),
                 proportional_fee_sats: u64::from(proportional_fee),
+                net_satoshis: u64::from(net_satoshis.unwrap_or(satoshis)),
+                deducted_fee_sats: u64::from(deducted_fee_sats),
             }),
             OutboxEventPayload::PayoutBroadcast {
                 id,
@@ -615,13 +948,31 @@ impl From<AddressAugmentation> for proto::WalletAddress {
 impl From<ApplicationError> for tonic::Status {
     fn from(err: ApplicationError) -> Self {
         use crate::{
-            address::error::*, payout::error::*, payout_queue::error::*, profile::error::*,
-            wallet::error::*,
+            address::error::*, batch::error::*, payout::error::*, payout_queue::error::*,
+            profile::error::*, utxo::error::*, wallet::error::*,
         };
 
         match err {
-            ApplicationError::ProfileError(ProfileError::ProfileKeyNotFound) => {
-                tonic::Status::unauthenticated(err.to_string())
+            ApplicationError::ProfileError(
+                ProfileError::ProfileKeyNotFound | ProfileError::ProfileDisabled,
+            ) => tonic::Status::unauthenticated(err.to_string()),
+            ApplicationError::ProfileError(ProfileError::ApiKeyNotFound(_)) => {
+                tonic::Status::not_found(err.to_string())
+            }
+            ApplicationError::ProfileError(ProfileError::ApiKeyMissingScope(_)) => {
+                tonic::Status::permission_denied(err.to_string())
+            }
+            ApplicationError::ProfileError(ProfileError::UnknownApiKeyScope(_)) => {
+                tonic::Status::invalid_argument(err.to_string())
+            }
+            ApplicationError::ProfileError(ProfileError::ApiKeyAlreadyRevoked(_)) => {
+                tonic::Status::failed_precondition(err.to_string())
+            }
+            ApplicationError::ProfileError(ProfileError::ProfileNameAlreadyExists(_)) => {
+                tonic::Status::already_exists(err.to_string())
+            }
+            ApplicationError::ProfileError(ProfileError::CannotDisableSelf) => {
+                tonic::Status::failed_precondition(err.to_string())
             }
             ApplicationError::WalletError(WalletError::WalletNameNotFound(_)) => {
                 tonic::Status::not_found(err.to_string())
@@ -644,9 +995,15 @@ impl From<ApplicationError> for tonic::Status {
             ApplicationError::PayoutQueueError(PayoutQueueError::PayoutQueueIdNotFound(_)) => {
                 tonic::Status::not_found(err.to_string())
             }
-            ApplicationError::ProfileError(ProfileError::ProfileNameNotFound(_)) => {
-                tonic::Status::not_found(err.to_string())
+            ApplicationError::PayoutQueueError(PayoutQueueError::InvalidScheduleCron(_)) => {
+                tonic::Status::invalid_argument(err.to_string())
+            }
+            ApplicationError::PayoutQueueError(PayoutQueueError::InvalidScheduleTimezone(_)) => {
+                tonic::Status::invalid_argument(err.to_string())
             }
+            ApplicationError::ProfileError(
+                ProfileError::ProfileNameNotFound(_) | ProfileError::ProfileIdNotFound(_),
+            ) => tonic::Status::not_found(err.to_string()),
             ApplicationError::PayoutError(PayoutError::PayoutIdNotFound(_)) => {
                 tonic::Status::not_found(err.to_string())
             }
@@ -665,6 +1022,9 @@ impl From<ApplicationError> for tonic::Status {
             ApplicationError::DestinationNotAllowed(_) => {
                 tonic::Status::permission_denied(err.to_string())
             }
+            ApplicationError::SpendingLimitExceeded(..) => {
+                tonic::Status::resource_exhausted(err.to_string())
+            }
             ApplicationError::SigningSessionNotFoundForBatchId(_) => {
                 tonic::Status::not_found(err.to_string())
             }
@@ -677,15 +1037,61 @@ impl From<ApplicationError> for tonic::Status {
             ApplicationError::WalletError(WalletError::UnsignedTxnMismatch) => {
                 tonic::Status::invalid_argument(err.to_string())
             }
+            ApplicationError::WalletError(WalletError::KeychainAlreadyInUse) => {
+                tonic::Status::already_exists(err.to_string())
+            }
+            ApplicationError::WalletError(WalletError::InvalidPolicy(_)) => {
+                tonic::Status::invalid_argument(err.to_string())
+            }
+            ApplicationError::WalletError(WalletError::InvalidConfig(_)) => {
+                tonic::Status::invalid_argument(err.to_string())
+            }
             ApplicationError::CouldNotParseIncomingPsbt(_) => {
                 tonic::Status::invalid_argument(err.to_string())
             }
+            ApplicationError::CouldNotParseIncomingOutPoint(_) => {
+                tonic::Status::invalid_argument(err.to_string())
+            }
+            ApplicationError::UtxoError(UtxoError::UtxoDoesNotExistError) => {
+                tonic::Status::not_found(err.to_string())
+            }
+            ApplicationError::UtxoError(
+                UtxoError::UtxoAlreadyFrozen(_)
+                | UtxoError::UtxoNotFrozen(_)
+                | UtxoError::UtxoQuarantined(_)
+                | UtxoError::UtxoNotQuarantined(_)
+                | UtxoError::UtxoAlreadySpent(_)
+                | UtxoError::UtxoReservedInBatch(..),
+            ) => tonic::Status::failed_precondition(err.to_string()),
             ApplicationError::PayoutError(PayoutError::PayoutAlreadyCommitted) => {
                 tonic::Status::failed_precondition(err.to_string())
             }
             ApplicationError::PayoutError(PayoutError::PayoutAlreadyCancelled) => {
                 tonic::Status::failed_precondition(err.to_string())
             }
+            ApplicationError::PayoutError(
+                PayoutError::PayoutAlreadyRejected
+                | PayoutError::ApprovalNotRequired
+                | PayoutError::SubmitterCannotApprove
+                | PayoutError::SubmitterCannotReject
+                | PayoutError::AlreadyApprovedBy(_),
+            ) => tonic::Status::failed_precondition(err.to_string()),
+            ApplicationError::PayoutError(
+                PayoutError::SweepPayoutQueued(_)
+                | PayoutError::WalletHasQueuedPayouts(_)
+                | PayoutError::NothingToSweep,
+            ) => tonic::Status::failed_precondition(err.to_string()),
+            ApplicationError::BatchError(BatchError::BatchIdNotFound(_)) => {
+                tonic::Status::not_found(err.to_string())
+            }
+            ApplicationError::BatchError(
+                BatchError::BatchNotBroadcast(_)
+                | BatchError::BatchAlreadyConfirmed(_)
+                | BatchError::BatchHasUnsettledInputs(_)
+                | BatchError::BatchChangeAlreadySpent(_)
+                | BatchError::FeeRateNotIncreased(_)
+                | BatchError::InsufficientChangeForFeeBump(_),
+            ) => tonic::Status::failed_precondition(err.to_string()),
             _ => tonic::Status::internal(err.to_string()),
         }
     }
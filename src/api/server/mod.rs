@@ -28,6 +28,21 @@ pub struct Bria {
     app: App,
 }
 
+impl Bria {
+    async fn authenticate<T>(
+        &self,
+        request: &Request<T>,
+        scope: profile::ApiKeyScope,
+    ) -> Result<profile::AuthenticatedProfile, Status> {
+        let key = extract_api_token(request)?;
+        let profile = self.app.authenticate(key).await?;
+        profile
+            .ensure_scope(scope)
+            .map_err(ApplicationError::from)?;
+        Ok(profile)
+    }
+}
+
 #[tonic::async_trait]
 impl BriaService for Bria {
     #[instrument(name = "bria.create_profile", skip_all, fields(error, error.level, error.message), err)]
@@ -38,8 +53,9 @@ impl BriaService for Bria {
         crate::tracing::record_error(|| async move {
             extract_tracing(&request);
 
-            let key = extract_api_token(&request)?;
-            let profile = self.app.authenticate(key).await?;
+            let profile = self
+                .authenticate(&request, profile::ApiKeyScope::ProfilesAdmin)
+                .await?;
             let request = request.into_inner();
             let spending_policy = request
                 .spending_policy
@@ -64,8 +80,9 @@ impl BriaService for Bria {
         crate::tracing::record_error(|| async move {
             extract_tracing(&request);
 
-            let key = extract_api_token(&request)?;
-            let profile = self.app.authenticate(key).await?;
+            let profile = self
+                .authenticate(&request, profile::ApiKeyScope::Read)
+                .await?;
             let profiles = self.app.list_profiles(&profile).await?;
             let profile_messages: Vec<proto::Profile> =
                 profiles.into_iter().map(proto::Profile::from).collect();
@@ -77,6 +94,42 @@ impl BriaService for Bria {
         .await
     }
 
+    #[instrument(name = "bria.update_profile", skip_all, fields(error, error.level, error.message), err)]
+    async fn update_profile(
+        &self,
+        request: Request<UpdateProfileRequest>,
+    ) -> Result<Response<UpdateProfileResponse>, Status> {
+        crate::tracing::record_error(|| async move {
+            extract_tracing(&request);
+
+            let profile = self
+                .authenticate(&request, profile::ApiKeyScope::ProfilesAdmin)
+                .await?;
+            let request = request.into_inner();
+            let UpdateProfileRequest {
+                id,
+                spending_policy,
+                name,
+                disabled,
+            } = request;
+            let spending_policy = spending_policy
+                .map(profile::SpendingPolicy::try_from)
+                .transpose()?;
+            self.app
+                .update_profile(
+                    &profile,
+                    id.parse()
+                        .map_err(ApplicationError::CouldNotParseIncomingUuid)?,
+                    name,
+                    spending_policy,
+                    disabled,
+                )
+                .await?;
+            Ok(Response::new(UpdateProfileResponse {}))
+        })
+        .await
+    }
+
     #[instrument(name = "bria.create_profile_api_key", skip_all, fields(error, error.level, error.message), err)]
     async fn create_profile_api_key(
         &self,
@@ -85,12 +138,19 @@ impl BriaService for Bria {
         crate::tracing::record_error(|| async move {
             extract_tracing(&request);
 
-            let key = extract_api_token(&request)?;
-            let profile = self.app.authenticate(key).await?;
+            let profile = self
+                .authenticate(&request, profile::ApiKeyScope::ProfilesAdmin)
+                .await?;
             let request = request.into_inner();
+            let scopes = request
+                .scopes
+                .iter()
+                .map(|scope| scope.parse::<profile::ApiKeyScope>())
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(ApplicationError::from)?;
             let key = self
                 .app
-                .create_profile_api_key(&profile, request.profile_name)
+                .create_profile_api_key(&profile, request.profile_name, scopes)
                 .await?;
             Ok(Response::new(CreateProfileApiKeyResponse {
                 id: key.id.to_string(),
@@ -100,6 +160,90 @@ impl BriaService for Bria {
         .await
     }
 
+    #[instrument(name = "bria.list_profile_api_keys", skip_all, fields(error, error.level, error.message), err)]
+    async fn list_profile_api_keys(
+        &self,
+        request: Request<ListProfileApiKeysRequest>,
+    ) -> Result<Response<ListProfileApiKeysResponse>, Status> {
+        crate::tracing::record_error(|| async move {
+            extract_tracing(&request);
+
+            let profile = self
+                .authenticate(&request, profile::ApiKeyScope::Read)
+                .await?;
+            let request = request.into_inner();
+            let api_keys = self
+                .app
+                .list_profile_api_keys(
+                    &profile,
+                    request
+                        .profile_id
+                        .parse()
+                        .map_err(ApplicationError::CouldNotParseIncomingUuid)?,
+                )
+                .await?;
+            Ok(Response::new(ListProfileApiKeysResponse {
+                api_keys: api_keys
+                    .into_iter()
+                    .map(proto::ProfileApiKey::from)
+                    .collect(),
+            }))
+        })
+        .await
+    }
+
+    #[instrument(name = "bria.revoke_profile_api_key", skip_all, fields(error, error.level, error.message), err)]
+    async fn revoke_profile_api_key(
+        &self,
+        request: Request<RevokeProfileApiKeyRequest>,
+    ) -> Result<Response<RevokeProfileApiKeyResponse>, Status> {
+        crate::tracing::record_error(|| async move {
+            extract_tracing(&request);
+
+            let profile = self
+                .authenticate(&request, profile::ApiKeyScope::ProfilesAdmin)
+                .await?;
+            let request = request.into_inner();
+            self.app
+                .revoke_profile_api_key(
+                    &profile,
+                    request
+                        .id
+                        .parse()
+                        .map_err(ApplicationError::CouldNotParseIncomingUuid)?,
+                )
+                .await?;
+            Ok(Response::new(RevokeProfileApiKeyResponse {}))
+        })
+        .await
+    }
+
+    #[instrument(name = "bria.set_profile_api_key_expiry", skip_all, fields(error, error.level, error.message), err)]
+    async fn set_profile_api_key_expiry(
+        &self,
+        request: Request<SetProfileApiKeyExpiryRequest>,
+    ) -> Result<Response<SetProfileApiKeyExpiryResponse>, Status> {
+        crate::tracing::record_error(|| async move {
+            extract_tracing(&request);
+
+            let profile = self
+                .authenticate(&request, profile::ApiKeyScope::ProfilesAdmin)
+                .await?;
+            let request = request.into_inner();
+            let SetProfileApiKeyExpiryRequest { id, expires_at } = request;
+            self.app
+                .set_profile_api_key_expiry(
+                    &profile,
+                    id.parse()
+                        .map_err(ApplicationError::CouldNotParseIncomingUuid)?,
+                    convert::timestamp_from_proto(expires_at),
+                )
+                .await?;
+            Ok(Response::new(SetProfileApiKeyExpiryResponse {}))
+        })
+        .await
+    }
+
     #[instrument(name = "bria.import_xpub", skip_all, fields(error, error.level, error.message), err)]
     async fn import_xpub(
         &self,
@@ -108,8 +252,9 @@ impl BriaService for Bria {
         crate::tracing::record_error(|| async move {
             extract_tracing(&request);
 
-            let key = extract_api_token(&request)?;
-            let profile = self.app.authenticate(key).await?;
+            let profile = self
+                .authenticate(&request, profile::ApiKeyScope::SignersAdmin)
+                .await?;
             let ImportXpubRequest {
                 name,
                 xpub,
@@ -137,8 +282,9 @@ impl BriaService for Bria {
         crate::tracing::record_error(|| async move {
             extract_tracing(&request);
 
-            let key = extract_api_token(&request)?;
-            let profile = self.app.authenticate(key).await?;
+            let profile = self
+                .authenticate(&request, profile::ApiKeyScope::Read)
+                .await?;
             let xpubs = self.app.list_xpubs(&profile).await?;
             let xpub_messages: Vec<proto::Xpub> =
                 xpubs.into_iter().map(proto::Xpub::from).collect();
@@ -158,8 +304,9 @@ impl BriaService for Bria {
         crate::tracing::record_error(|| async move {
             extract_tracing(&request);
 
-            let key = extract_api_token(&request)?;
-            let profile = self.app.authenticate(key).await?;
+            let profile = self
+                .authenticate(&request, profile::ApiKeyScope::SignersAdmin)
+                .await?;
             let SetSignerConfigRequest { xpub_ref, config } = request.into_inner();
             self.app
                 .set_signer_config(&profile, xpub_ref, config.try_into()?)
@@ -176,8 +323,9 @@ impl BriaService for Bria {
     ) -> Result<Response<SubmitSignedPsbtResponse>, Status> {
         crate::tracing::record_error(|| async move {
             extract_tracing(&request);
-            let key = extract_api_token(&request)?;
-            let profile = self.app.authenticate(key).await?;
+            let profile = self
+                .authenticate(&request, profile::ApiKeyScope::SignersAdmin)
+                .await?;
             let request = request.into_inner();
             let SubmitSignedPsbtRequest {
                 batch_id,
@@ -209,12 +357,23 @@ impl BriaService for Bria {
         crate::tracing::record_error(|| async move {
             extract_tracing(&request);
 
-            let key = extract_api_token(&request)?;
-            let profile = self.app.authenticate(key).await?;
+            let profile = self.authenticate(&request, profile::ApiKeyScope::WalletsAdmin).await?;
             let CreateWalletRequest {
                 name,
                 keychain_config,
+                gap_limit,
+                lookahead,
             } = request.into_inner();
+            let wallet_config = if gap_limit.is_some() || lookahead.is_some() {
+                let default = crate::wallet::WalletConfig::default();
+                Some(crate::wallet::WalletConfig {
+                    gap_limit: gap_limit.unwrap_or(default.gap_limit),
+                    lookahead: lookahead.unwrap_or(default.lookahead),
+                    ..default
+                })
+            } else {
+                None
+            };
             let (id, xpub_ids) = match keychain_config {
                 Some(KeychainConfig {
                     config:
@@ -224,7 +383,7 @@ impl BriaService for Bria {
                         })),
                 }) => {
                     self.app
-                        .create_wpkh_wallet(&profile, name, xpub, derivation_path)
+                        .create_wpkh_wallet(&profile, name, xpub, derivation_path, wallet_config)
                         .await?
                 }
                 Some(KeychainConfig {
@@ -235,7 +394,7 @@ impl BriaService for Bria {
                         })),
                 }) => {
                     self.app
-                        .create_descriptors_wallet(&profile, name, external, internal)
+                        .create_descriptors_wallet(&profile, name, external, internal, wallet_config)
                         .await?
                 }
                 Some(KeychainConfig {
@@ -246,7 +405,31 @@ impl BriaService for Bria {
                                 threshold,
                             })),
                 }) => {
-                    self.app.create_sorted_multisig_wallet(&profile, name, xpubs, threshold).await?
+                    self.app.create_sorted_multisig_wallet(&profile, name, xpubs, threshold, wallet_config).await?
+                }
+                Some(KeychainConfig {
+                    config:
+                        Some(keychain_config::Config::Tr(keychain_config::Tr {
+                            xpub,
+                            derivation_path,
+                        })),
+                }) => {
+                    self.app
+                        .create_tr_wallet(&profile, name, xpub, derivation_path, wallet_config)
+                        .await?
+                }
+                Some(KeychainConfig {
+                    config:
+                        Some(keychain_config::Config::Policy(keychain_config::Policy {
+                            policy,
+                            script_type,
+                        })),
+                }) => {
+                    let script_type = proto::PolicyScriptType::try_from(script_type)
+                        .map_err(|_| Status::invalid_argument("invalid script type"))?;
+                    self.app
+                        .create_policy_wallet(&profile, name, policy, script_type.into(), wallet_config)
+                        .await?
                 }
                 _ => {
                     return Err(Status::invalid_argument("invalid keychain config"));
@@ -260,6 +443,95 @@ impl BriaService for Bria {
         .await
     }
 
+    #[instrument(name = "bria.rotate_wallet_keychain", skip_all, fields(error, error.level, error.message), err)]
+    async fn rotate_wallet_keychain(
+        &self,
+        request: Request<RotateWalletKeychainRequest>,
+    ) -> Result<Response<RotateWalletKeychainResponse>, Status> {
+        crate::tracing::record_error(|| async move {
+            extract_tracing(&request);
+
+            let profile = self
+                .authenticate(&request, profile::ApiKeyScope::WalletsAdmin)
+                .await?;
+            let RotateWalletKeychainRequest {
+                wallet_name,
+                keychain_config,
+            } = request.into_inner();
+            let (keychain_id, xpub_ids) = match keychain_config {
+                Some(KeychainConfig {
+                    config:
+                        Some(keychain_config::Config::Wpkh(keychain_config::Wpkh {
+                            xpub,
+                            derivation_path,
+                        })),
+                }) => {
+                    self.app
+                        .rotate_wpkh_wallet_keychain(&profile, wallet_name, xpub, derivation_path)
+                        .await?
+                }
+                Some(KeychainConfig {
+                    config:
+                        Some(keychain_config::Config::Descriptors(keychain_config::Descriptors {
+                            external,
+                            internal,
+                        })),
+                }) => {
+                    self.app
+                        .rotate_descriptors_wallet_keychain(&profile, wallet_name, external, internal)
+                        .await?
+                }
+                Some(KeychainConfig {
+                    config:
+                        Some(keychain_config::Config::SortedMultisig(
+                            keychain_config::SortedMultisig { xpubs, threshold },
+                        )),
+                }) => {
+                    self.app
+                        .rotate_sorted_multisig_wallet_keychain(&profile, wallet_name, xpubs, threshold)
+                        .await?
+                }
+                Some(KeychainConfig {
+                    config:
+                        Some(keychain_config::Config::Tr(keychain_config::Tr {
+                            xpub,
+                            derivation_path,
+                        })),
+                }) => {
+                    self.app
+                        .rotate_tr_wallet_keychain(&profile, wallet_name, xpub, derivation_path)
+                        .await?
+                }
+                Some(KeychainConfig {
+                    config:
+                        Some(keychain_config::Config::Policy(keychain_config::Policy {
+                            policy,
+                            script_type,
+                        })),
+                }) => {
+                    let script_type = proto::PolicyScriptType::try_from(script_type)
+                        .map_err(|_| Status::invalid_argument("invalid script type"))?;
+                    self.app
+                        .rotate_policy_wallet_keychain(
+                            &profile,
+                            wallet_name,
+                            policy,
+                            script_type.into(),
+                        )
+                        .await?
+                }
+                _ => {
+                    return Err(Status::invalid_argument("invalid keychain config"));
+                }
+            };
+            Ok(Response::new(RotateWalletKeychainResponse {
+                keychain_id: keychain_id.to_string(),
+                xpub_ids: xpub_ids.into_iter().map(|id| id.to_string()).collect(),
+            }))
+        })
+        .await
+    }
+
     #[instrument(name = "bria.get_wallet_balance_summary", skip_all, fields(error, error.level, error.message), err)]
     async fn get_wallet_balance_summary(
         &self,
@@ -268,8 +540,9 @@ impl BriaService for Bria {
         crate::tracing::record_error(|| async move {
             extract_tracing(&request);
 
-            let key = extract_api_token(&request)?;
-            let profile = self.app.authenticate(key).await?;
+            let profile = self
+                .authenticate(&request, profile::ApiKeyScope::Read)
+                .await?;
             let request = request.into_inner();
             let balance = self
                 .app
@@ -283,6 +556,140 @@ impl BriaService for Bria {
         .await
     }
 
+    #[instrument(name = "bria.update_wallet_config", skip_all, fields(error, error.level, error.message), err)]
+    async fn update_wallet_config(
+        &self,
+        request: Request<UpdateWalletConfigRequest>,
+    ) -> Result<Response<UpdateWalletConfigResponse>, Status> {
+        crate::tracing::record_error(|| async move {
+            extract_tracing(&request);
+
+            let profile = self
+                .authenticate(&request, profile::ApiKeyScope::WalletsAdmin)
+                .await?;
+            let UpdateWalletConfigRequest {
+                wallet_name,
+                settle_income_after_n_confs,
+                settle_change_after_n_confs,
+                gap_limit,
+                lookahead,
+                dust_threshold_sats,
+                dust_sweep_max_fee_rate_sat_per_vb,
+            } = request.into_inner();
+            self.app
+                .update_wallet_config(
+                    &profile,
+                    wallet_name,
+                    settle_income_after_n_confs,
+                    settle_change_after_n_confs,
+                    gap_limit,
+                    lookahead,
+                    dust_threshold_sats,
+                    dust_sweep_max_fee_rate_sat_per_vb,
+                )
+                .await?;
+
+            Ok(Response::new(UpdateWalletConfigResponse {}))
+        })
+        .await
+    }
+
+    #[instrument(name = "bria.set_wallet_consolidation_policy", skip_all, fields(error, error.level, error.message), err)]
+    async fn set_wallet_consolidation_policy(
+        &self,
+        request: Request<SetWalletConsolidationPolicyRequest>,
+    ) -> Result<Response<SetWalletConsolidationPolicyResponse>, Status> {
+        crate::tracing::record_error(|| async move {
+            extract_tracing(&request);
+
+            let profile = self
+                .authenticate(&request, profile::ApiKeyScope::WalletsAdmin)
+                .await?;
+            let SetWalletConsolidationPolicyRequest {
+                wallet_name,
+                policy,
+            } = request.into_inner();
+            match policy {
+                Some(WalletConsolidationPolicyConfig {
+                    payout_queue_name,
+                    target_utxo_count,
+                    max_fee_rate_sat_per_vb,
+                    max_inputs_per_tx,
+                }) => {
+                    self.app
+                        .set_wallet_consolidation_policy(
+                            &profile,
+                            wallet_name,
+                            payout_queue_name,
+                            target_utxo_count,
+                            max_fee_rate_sat_per_vb,
+                            max_inputs_per_tx,
+                        )
+                        .await?
+                }
+                None => {
+                    self.app
+                        .remove_wallet_consolidation_policy(&profile, wallet_name)
+                        .await?
+                }
+            }
+
+            Ok(Response::new(SetWalletConsolidationPolicyResponse {}))
+        })
+        .await
+    }
+
+    #[instrument(name = "bria.export_wallet", skip_all, fields(error, error.level, error.message), err)]
+    async fn export_wallet(
+        &self,
+        request: Request<ExportWalletRequest>,
+    ) -> Result<Response<ExportWalletResponse>, Status> {
+        crate::tracing::record_error(|| async move {
+            extract_tracing(&request);
+
+            let profile = self
+                .authenticate(&request, profile::ApiKeyScope::WalletsAdmin)
+                .await?;
+            let request = request.into_inner();
+            let export = self
+                .app
+                .export_wallet(&profile, request.wallet_name)
+                .await?;
+
+            Ok(Response::new(ExportWalletResponse::from(export)))
+        })
+        .await
+    }
+
+    #[instrument(name = "bria.rescan_wallet", skip_all, fields(error, error.level, error.message), err)]
+    async fn rescan_wallet(
+        &self,
+        request: Request<RescanWalletRequest>,
+    ) -> Result<Response<RescanWalletResponse>, Status> {
+        crate::tracing::record_error(|| async move {
+            extract_tracing(&request);
+
+            let profile = self
+                .authenticate(&request, profile::ApiKeyScope::WalletsAdmin)
+                .await?;
+            let request = request.into_inner();
+            if request.gap_limit == Some(0) {
+                return Err(Status::invalid_argument("gap limit must be positive"));
+            }
+            self.app
+                .rescan_wallet(
+                    &profile,
+                    request.wallet_name,
+                    request.start_height,
+                    request.gap_limit,
+                )
+                .await?;
+
+            Ok(Response::new(RescanWalletResponse {}))
+        })
+        .await
+    }
+
     #[instrument(name = "bria.get_account_balance_summary", skip_all, fields(error, error.level, error.message), err)]
     async fn get_account_balance_summary(
         &self,
@@ -291,8 +698,9 @@ impl BriaService for Bria {
         crate::tracing::record_error(|| async move {
             extract_tracing(&request);
 
-            let key = extract_api_token(&request)?;
-            let profile = self.app.authenticate(key).await?;
+            let profile = self
+                .authenticate(&request, profile::ApiKeyScope::Read)
+                .await?;
             let balance = self.app.get_account_balance_summary(&profile).await?;
             Ok(Response::new(GetAccountBalanceSummaryResponse::from(
                 balance,
@@ -309,8 +717,9 @@ impl BriaService for Bria {
         crate::tracing::record_error(|| async move {
             extract_tracing(&request);
 
-            let key = extract_api_token(&request)?;
-            let profile = self.app.authenticate(key).await?;
+            let profile = self
+                .authenticate(&request, profile::ApiKeyScope::AddressesWrite)
+                .await?;
             let request = request.into_inner();
             let NewAddressRequest {
                 wallet_name,
@@ -345,8 +754,9 @@ impl BriaService for Bria {
         crate::tracing::record_error(|| async move {
             extract_tracing(&request);
 
-            let key = extract_api_token(&request)?;
-            let profile = self.app.authenticate(key).await?;
+            let profile = self
+                .authenticate(&request, profile::ApiKeyScope::AddressesWrite)
+                .await?;
             let request = request.into_inner();
             let UpdateAddressRequest {
                 address,
@@ -378,8 +788,9 @@ impl BriaService for Bria {
         crate::tracing::record_error(|| async move {
             extract_tracing(&request);
 
-            let key = extract_api_token(&request)?;
-            let profile = self.app.authenticate(key).await?;
+            let profile = self
+                .authenticate(&request, profile::ApiKeyScope::Read)
+                .await?;
             let wallet_name = request.into_inner().wallet_name;
 
             let (wallet_id, addresses) = self
@@ -405,8 +816,9 @@ impl BriaService for Bria {
     ) -> Result<Response<GetAddressResponse>, Status> {
         crate::tracing::record_error(|| async move {
             extract_tracing(&request);
-            let key = extract_api_token(&request)?;
-            let profile = self.app.authenticate(key).await?;
+            let profile = self
+                .authenticate(&request, profile::ApiKeyScope::Read)
+                .await?;
             let request = request.into_inner();
             let addr = match request.identifier {
                 Some(get_address_request::Identifier::Address(address)) => {
@@ -437,8 +849,9 @@ impl BriaService for Bria {
         crate::tracing::record_error(|| async move {
             extract_tracing(&request);
 
-            let key = extract_api_token(&request)?;
-            let profile = self.app.authenticate(key).await?;
+            let profile = self
+                .authenticate(&request, profile::ApiKeyScope::Read)
+                .await?;
             let request = request.into_inner();
             let (wallet_id, keychain_utxos) =
                 self.app.list_utxos(&profile, request.wallet_name).await?;
@@ -456,6 +869,95 @@ impl BriaService for Bria {
         .await
     }
 
+    #[instrument(name = "bria.freeze_utxo", skip_all, fields(error, error.level, error.message), err)]
+    async fn freeze_utxo(
+        &self,
+        request: Request<FreezeUtxoRequest>,
+    ) -> Result<Response<FreezeUtxoResponse>, Status> {
+        crate::tracing::record_error(|| async move {
+            extract_tracing(&request);
+
+            let profile = self
+                .authenticate(&request, profile::ApiKeyScope::WalletsAdmin)
+                .await?;
+            let FreezeUtxoRequest {
+                wallet_name,
+                outpoint,
+                reason,
+            } = request.into_inner();
+            self.app
+                .freeze_utxo(
+                    &profile,
+                    wallet_name,
+                    outpoint
+                        .parse()
+                        .map_err(ApplicationError::CouldNotParseIncomingOutPoint)?,
+                    reason,
+                )
+                .await?;
+            Ok(Response::new(FreezeUtxoResponse {}))
+        })
+        .await
+    }
+
+    #[instrument(name = "bria.unfreeze_utxo", skip_all, fields(error, error.level, error.message), err)]
+    async fn unfreeze_utxo(
+        &self,
+        request: Request<UnfreezeUtxoRequest>,
+    ) -> Result<Response<UnfreezeUtxoResponse>, Status> {
+        crate::tracing::record_error(|| async move {
+            extract_tracing(&request);
+
+            let profile = self
+                .authenticate(&request, profile::ApiKeyScope::WalletsAdmin)
+                .await?;
+            let UnfreezeUtxoRequest {
+                wallet_name,
+                outpoint,
+            } = request.into_inner();
+            self.app
+                .unfreeze_utxo(
+                    &profile,
+                    wallet_name,
+                    outpoint
+                        .parse()
+                        .map_err(ApplicationError::CouldNotParseIncomingOutPoint)?,
+                )
+                .await?;
+            Ok(Response::new(UnfreezeUtxoResponse {}))
+        })
+        .await
+    }
+
+    #[instrument(name = "bria.release_quarantined_utxo", skip_all, fields(error, error.level, error.message), err)]
+    async fn release_quarantined_utxo(
+        &self,
+        request: Request<ReleaseQuarantinedUtxoRequest>,
+    ) -> Result<Response<ReleaseQuarantinedUtxoResponse>, Status> {
+        crate::tracing::record_error(|| async move {
+            extract_tracing(&request);
+
+            let profile = self
+                .authenticate(&request, profile::ApiKeyScope::WalletsAdmin)
+                .await?;
+            let ReleaseQuarantinedUtxoRequest {
+                wallet_name,
+                outpoint,
+            } = request.into_inner();
+            self.app
+                .release_quarantined_utxo(
+                    &profile,
+                    wallet_name,
+                    outpoint
+                        .parse()
+                        .map_err(ApplicationError::CouldNotParseIncomingOutPoint)?,
+                )
+                .await?;
+            Ok(Response::new(ReleaseQuarantinedUtxoResponse {}))
+        })
+        .await
+    }
+
     #[instrument(name = "bria.create_payout_queue", skip_all, fields(error, error.level, error.message), err)]
     async fn create_payout_queue(
         &self,
@@ -464,8 +966,9 @@ impl BriaService for Bria {
         crate::tracing::record_error(|| async move {
             extract_tracing(&request);
 
-            let key = extract_api_token(&request)?;
-            let profile = self.app.authenticate(key).await?;
+            let profile = self
+                .authenticate(&request, profile::ApiKeyScope::WalletsAdmin)
+                .await?;
             let request = request.into_inner();
             let id = self
                 .app
@@ -473,7 +976,10 @@ impl BriaService for Bria {
                     &profile,
                     request.name,
                     request.description,
-                    request.config.map(payout_queue::PayoutQueueConfig::from),
+                    request
+                        .config
+                        .map(payout_queue::PayoutQueueConfig::try_from)
+                        .transpose()?,
                 )
                 .await?;
             Ok(Response::new(CreatePayoutQueueResponse {
@@ -490,11 +996,17 @@ impl BriaService for Bria {
     ) -> Result<Response<TriggerPayoutQueueResponse>, Status> {
         crate::tracing::record_error(|| async move {
             extract_tracing(&request);
-            let key = extract_api_token(&request)?;
-            let profile = self.app.authenticate(key).await?;
+            let profile = self
+                .authenticate(&request, profile::ApiKeyScope::PayoutsWrite)
+                .await?;
             let request = request.into_inner();
-            let TriggerPayoutQueueRequest { name } = request;
-            self.app.trigger_payout_queue(&profile, name).await?;
+            let TriggerPayoutQueueRequest {
+                name,
+                ignore_fee_caps,
+            } = request;
+            self.app
+                .trigger_payout_queue(&profile, name, ignore_fee_caps.unwrap_or(false))
+                .await?;
             Ok(Response::new(TriggerPayoutQueueResponse {}))
         })
         .await
@@ -508,45 +1020,95 @@ impl BriaService for Bria {
         crate::tracing::record_error(|| async move {
             extract_tracing(&request);
 
-            let key = extract_api_token(&request)?;
-            let profile = self.app.authenticate(key).await?;
+            let profile = self
+                .authenticate(&request, profile::ApiKeyScope::Read)
+                .await?;
             let request = request.into_inner();
             let EstimatePayoutFeeRequest {
                 wallet_name,
                 payout_queue_name,
                 destination,
                 satoshis,
+                tx_priority,
+                custom_fee_rate_sat_per_vb,
+                sweep,
             } = request;
+            let tx_priority = match (tx_priority, custom_fee_rate_sat_per_vb) {
+                (None, None) => None,
+                (tx_priority, custom_fee_rate_sat_per_vb) => Some(
+                    convert::tx_priority_from_proto(
+                        tx_priority.unwrap_or(proto::TxPriority::Custom as i32),
+                        custom_fee_rate_sat_per_vb,
+                    )
+                    .and_then(|tx_priority| tx_priority.ok_or("unknown tx_priority"))
+                    .map_err(tonic::Status::invalid_argument)?,
+                ),
+            };
 
-            let sats = match destination {
+            let (sats, net_sats) = match destination {
                 Some(proto::estimate_payout_fee_request::Destination::OnchainAddress(address)) => {
-                    self.app
-                        .estimate_payout_fee_to_address(
-                            &profile,
-                            wallet_name,
-                            payout_queue_name,
-                            address.parse().map_err(|_| {
-                                tonic::Status::new(
-                                    tonic::Code::InvalidArgument,
-                                    "on chain address couldn't be parsed",
-                                )
-                            })?,
-                            Satoshis::from(satoshis),
+                    let address: bitcoin::Address = address.parse().map_err(|_| {
+                        tonic::Status::new(
+                            tonic::Code::InvalidArgument,
+                            "on chain address couldn't be parsed",
                         )
-                        .await?
+                    })?;
+                    if sweep {
+                        let (fee, net) = self
+                            .app
+                            .estimate_sweep_to_address(
+                                &profile,
+                                wallet_name,
+                                payout_queue_name,
+                                address,
+                                tx_priority,
+                            )
+                            .await?;
+                        (fee, Some(net))
+                    } else {
+                        let fee = self
+                            .app
+                            .estimate_payout_fee_to_address(
+                                &profile,
+                                wallet_name,
+                                payout_queue_name,
+                                address,
+                                Satoshis::from(satoshis),
+                                tx_priority,
+                            )
+                            .await?;
+                        (fee, None)
+                    }
                 }
                 Some(proto::estimate_payout_fee_request::Destination::DestinationWalletName(
                     name,
                 )) => {
-                    self.app
-                        .estimate_payout_fee_to_wallet(
-                            &profile,
-                            wallet_name,
-                            payout_queue_name,
-                            name,
-                            Satoshis::from(satoshis),
-                        )
-                        .await?
+                    if sweep {
+                        let (fee, net) = self
+                            .app
+                            .estimate_sweep_to_wallet(
+                                &profile,
+                                wallet_name,
+                                payout_queue_name,
+                                name,
+                                tx_priority,
+                            )
+                            .await?;
+                        (fee, Some(net))
+                    } else {
+                        let fee = self
+                            .app
+                            .estimate_payout_fee_to_wallet(
+                                &profile,
+                                wallet_name,
+                                payout_queue_name,
+                                name,
+                                Satoshis::from(satoshis),
+                                tx_priority,
+                            )
+                            .await?;
+                        (fee, None)
+                    }
                 }
                 None => {
                     return Err(tonic::Status::new(
@@ -557,6 +1119,7 @@ impl BriaService for Bria {
             };
             Ok(Response::new(EstimatePayoutFeeResponse {
                 satoshis: u64::from(sats),
+                net_satoshis: net_sats.map(u64::from),
             }))
         })
         .await
@@ -570,8 +1133,9 @@ impl BriaService for Bria {
         crate::tracing::record_error(|| async move {
             extract_tracing(&request);
 
-            let key = extract_api_token(&request)?;
-            let profile = self.app.authenticate(key).await?;
+            let profile = self
+                .authenticate(&request, profile::ApiKeyScope::PayoutsWrite)
+                .await?;
             let request = request.into_inner();
             let SubmitPayoutRequest {
                 wallet_name,
@@ -580,7 +1144,12 @@ impl BriaService for Bria {
                 satoshis,
                 external_id,
                 metadata,
+                fee_from_amount,
+                sweep,
+                not_before,
+                not_before_block_height,
             } = request;
+            let schedule = convert::payout_schedule_from_proto(not_before, not_before_block_height);
 
             let (id, estimated_time) = match destination {
                 Some(proto::submit_payout_request::Destination::OnchainAddress(address)) => {
@@ -596,6 +1165,9 @@ impl BriaService for Bria {
                                 )
                             })?,
                             Satoshis::from(satoshis),
+                            fee_from_amount,
+                            sweep,
+                            schedule,
                             external_id,
                             metadata
                                 .map(serde_json::to_value)
@@ -612,6 +1184,9 @@ impl BriaService for Bria {
                             payout_queue_name,
                             name,
                             Satoshis::from(satoshis),
+                            fee_from_amount,
+                            sweep,
+                            schedule,
                             external_id,
                             metadata
                                 .map(serde_json::to_value)
@@ -636,6 +1211,126 @@ impl BriaService for Bria {
         .await
     }
 
+    #[instrument(name = "bria.submit_payouts", skip_all, fields(error, error.level, error.message), err)]
+    async fn submit_payouts(
+        &self,
+        request: Request<SubmitPayoutsRequest>,
+    ) -> Result<Response<SubmitPayoutsResponse>, Status> {
+        crate::tracing::record_error(|| async move {
+            extract_tracing(&request);
+
+            let profile = self
+                .authenticate(&request, profile::ApiKeyScope::PayoutsWrite)
+                .await?;
+            let request = request.into_inner();
+            let SubmitPayoutsRequest { payouts, atomic } = request;
+
+            // Malformed entries are reported per entry like the ones failing validation
+            let mut indices = Vec::with_capacity(payouts.len());
+            let mut submissions = Vec::with_capacity(payouts.len());
+            let mut failed = Vec::new();
+            for (idx, payout) in payouts.into_iter().enumerate() {
+                let SubmitPayoutRequest {
+                    wallet_name,
+                    payout_queue_name,
+                    destination,
+                    satoshis,
+                    external_id,
+                    metadata,
+                    fee_from_amount,
+                    sweep,
+                    not_before,
+                    not_before_block_height,
+                } = payout;
+                if sweep {
+                    failed.push((idx, "sweep payouts cannot be submitted in bulk".to_string()));
+                    continue;
+                }
+                let destination = match destination {
+                    Some(proto::submit_payout_request::Destination::OnchainAddress(address)) => {
+                        match address.parse() {
+                            Ok(address) => PayoutSubmissionDestination::OnchainAddress(address),
+                            Err(_) => {
+                                failed
+                                    .push((idx, "on chain address couldn't be parsed".to_string()));
+                                continue;
+                            }
+                        }
+                    }
+                    Some(proto::submit_payout_request::Destination::DestinationWalletName(
+                        name,
+                    )) => PayoutSubmissionDestination::WalletName(name),
+                    None => {
+                        failed.push((idx, "missing destination".to_string()));
+                        continue;
+                    }
+                };
+                let metadata = match metadata.map(serde_json::to_value).transpose() {
+                    Ok(metadata) => metadata,
+                    Err(err) => {
+                        failed.push((
+                            idx,
+                            ApplicationError::CouldNotParseIncomingMetadata(err).to_string(),
+                        ));
+                        continue;
+                    }
+                };
+                indices.push(idx);
+                submissions.push(PayoutSubmission {
+                    wallet_name,
+                    payout_queue_name,
+                    destination,
+                    satoshis: Satoshis::from(satoshis),
+                    fee_from_amount,
+                    schedule: convert::payout_schedule_from_proto(
+                        not_before,
+                        not_before_block_height,
+                    ),
+                    external_id,
+                    metadata,
+                });
+            }
+
+            let mut submitted = Vec::new();
+            if !atomic || failed.is_empty() {
+                let result = self
+                    .app
+                    .submit_payouts(&profile, submissions, atomic)
+                    .await?;
+                submitted.extend(
+                    result
+                        .submitted
+                        .into_iter()
+                        .map(|(idx, id)| (indices[idx], id)),
+                );
+                failed.extend(
+                    result
+                        .failed
+                        .into_iter()
+                        .map(|(idx, err)| (indices[idx], err.to_string())),
+                );
+                failed.sort_by_key(|(idx, _)| *idx);
+            }
+            Ok(Response::new(SubmitPayoutsResponse {
+                submitted: submitted
+                    .into_iter()
+                    .map(|(idx, id)| SubmittedPayout {
+                        index: idx as u32,
+                        id: id.to_string(),
+                    })
+                    .collect(),
+                failed: failed
+                    .into_iter()
+                    .map(|(idx, error)| FailedPayoutSubmission {
+                        index: idx as u32,
+                        error,
+                    })
+                    .collect(),
+            }))
+        })
+        .await
+    }
+
     #[instrument(name = "bria.list_payouts", skip_all, fields(error, error.level, error.message), err)]
     async fn list_payouts(
         &self,
@@ -644,8 +1339,9 @@ impl BriaService for Bria {
         crate::tracing::record_error(|| async move {
             extract_tracing(&request);
 
-            let key = extract_api_token(&request)?;
-            let profile = self.app.authenticate(key).await?;
+            let profile = self
+                .authenticate(&request, profile::ApiKeyScope::Read)
+                .await?;
             let payouts = self
                 .app
                 .list_payouts(&profile, request.into_inner().wallet_name)
@@ -669,8 +1365,9 @@ impl BriaService for Bria {
         use std::str::FromStr;
         crate::tracing::record_error(|| async move {
             extract_tracing(&request);
-            let key = extract_api_token(&request)?;
-            let profile = self.app.authenticate(key).await?;
+            let profile = self
+                .authenticate(&request, profile::ApiKeyScope::Read)
+                .await?;
             let request = request.into_inner();
             let payout = match request.identifier {
                 Some(get_payout_request::Identifier::Id(id)) => {
@@ -706,8 +1403,9 @@ impl BriaService for Bria {
     ) -> Result<Response<CancelPayoutResponse>, Status> {
         crate::tracing::record_error(|| async move {
             extract_tracing(&request);
-            let key = extract_api_token(&request)?;
-            let profile = self.app.authenticate(key).await?;
+            let profile = self
+                .authenticate(&request, profile::ApiKeyScope::PayoutsWrite)
+                .await?;
             let request = request.into_inner();
             let CancelPayoutRequest { id } = request;
             self.app
@@ -722,6 +1420,84 @@ impl BriaService for Bria {
         .await
     }
 
+    #[instrument(name = "bria.approve_payout", skip_all, fields(error, error.level, error.message), err)]
+    async fn approve_payout(
+        &self,
+        request: Request<ApprovePayoutRequest>,
+    ) -> Result<Response<ApprovePayoutResponse>, Status> {
+        crate::tracing::record_error(|| async move {
+            extract_tracing(&request);
+            let profile = self
+                .authenticate(&request, profile::ApiKeyScope::PayoutsApprove)
+                .await?;
+            let request = request.into_inner();
+            let ApprovePayoutRequest { id } = request;
+            self.app
+                .approve_payout(
+                    &profile,
+                    id.parse()
+                        .map_err(ApplicationError::CouldNotParseIncomingUuid)?,
+                )
+                .await?;
+            Ok(Response::new(ApprovePayoutResponse {}))
+        })
+        .await
+    }
+
+    #[instrument(name = "bria.reject_payout", skip_all, fields(error, error.level, error.message), err)]
+    async fn reject_payout(
+        &self,
+        request: Request<RejectPayoutRequest>,
+    ) -> Result<Response<RejectPayoutResponse>, Status> {
+        crate::tracing::record_error(|| async move {
+            extract_tracing(&request);
+            let profile = self
+                .authenticate(&request, profile::ApiKeyScope::PayoutsApprove)
+                .await?;
+            let request = request.into_inner();
+            let RejectPayoutRequest { id, reason } = request;
+            self.app
+                .reject_payout(
+                    &profile,
+                    id.parse()
+                        .map_err(ApplicationError::CouldNotParseIncomingUuid)?,
+                    reason,
+                )
+                .await?;
+            Ok(Response::new(RejectPayoutResponse {}))
+        })
+        .await
+    }
+
+    #[instrument(name = "bria.update_payout_schedule", skip_all, fields(error, error.level, error.message), err)]
+    async fn update_payout_schedule(
+        &self,
+        request: Request<UpdatePayoutScheduleRequest>,
+    ) -> Result<Response<UpdatePayoutScheduleResponse>, Status> {
+        crate::tracing::record_error(|| async move {
+            extract_tracing(&request);
+            let profile = self
+                .authenticate(&request, profile::ApiKeyScope::PayoutsWrite)
+                .await?;
+            let request = request.into_inner();
+            let UpdatePayoutScheduleRequest {
+                id,
+                not_before,
+                not_before_block_height,
+            } = request;
+            self.app
+                .update_payout_schedule(
+                    &profile,
+                    id.parse()
+                        .map_err(ApplicationError::CouldNotParseIncomingUuid)?,
+                    convert::payout_schedule_from_proto(not_before, not_before_block_height),
+                )
+                .await?;
+            Ok(Response::new(UpdatePayoutScheduleResponse {}))
+        })
+        .await
+    }
+
     #[instrument(name = "bria.list_wallets", skip_all, fields(error, error.level, error.message), err)]
     async fn list_wallets(
         &self,
@@ -730,8 +1506,9 @@ impl BriaService for Bria {
         crate::tracing::record_error(|| async move {
             extract_tracing(&request);
 
-            let key = extract_api_token(&request)?;
-            let profile = self.app.authenticate(key).await?;
+            let profile = self
+                .authenticate(&request, profile::ApiKeyScope::Read)
+                .await?;
             let wallets = self.app.list_wallets(&profile).await?;
             let wallet_messages: Vec<proto::Wallet> =
                 wallets.into_iter().map(proto::Wallet::from).collect();
@@ -751,8 +1528,9 @@ impl BriaService for Bria {
         crate::tracing::record_error(|| async move {
             extract_tracing(&request);
 
-            let key = extract_api_token(&request)?;
-            let profile = self.app.authenticate(key).await?;
+            let profile = self
+                .authenticate(&request, profile::ApiKeyScope::Read)
+                .await?;
             let payout_queues = self.app.list_payout_queues(&profile).await?;
             let payout_queue_messages: Vec<proto::PayoutQueue> = payout_queues
                 .into_iter()
@@ -774,8 +1552,9 @@ impl BriaService for Bria {
         crate::tracing::record_error(|| async move {
             extract_tracing(&request);
 
-            let key = extract_api_token(&request)?;
-            let profile = self.app.authenticate(key).await?;
+            let profile = self
+                .authenticate(&request, profile::ApiKeyScope::WalletsAdmin)
+                .await?;
             let request = request.into_inner();
             let UpdatePayoutQueueRequest {
                 id,
@@ -783,13 +1562,19 @@ impl BriaService for Bria {
                 new_config,
             } = request;
 
+            let new_coin_selection = new_config
+                .as_ref()
+                .and_then(|config| convert::coin_selection_from_proto(config.coin_selection));
             self.app
                 .update_payout_queue(
                     &profile,
                     id.parse()
                         .map_err(ApplicationError::CouldNotParseIncomingUuid)?,
                     new_description,
-                    new_config.map(payout_queue::PayoutQueueConfig::from),
+                    new_coin_selection,
+                    new_config
+                        .map(payout_queue::PayoutQueueConfig::try_from)
+                        .transpose()?,
                 )
                 .await?;
             Ok(Response::new(UpdatePayoutQueueResponse {}))
@@ -805,8 +1590,9 @@ impl BriaService for Bria {
         crate::tracing::record_error(|| async move {
             extract_tracing(&request);
 
-            let key = extract_api_token(&request)?;
-            let profile = self.app.authenticate(key).await?;
+            let profile = self
+                .authenticate(&request, profile::ApiKeyScope::Read)
+                .await?;
             let batch_id = request.into_inner().id;
 
             let (batch, mut payouts, sessions) = self
@@ -848,6 +1634,33 @@ impl BriaService for Bria {
         .await
     }
 
+    #[instrument(name = "bria.bump_batch_fee", skip_all, fields(error, error.level, error.message), err)]
+    async fn bump_batch_fee(
+        &self,
+        request: Request<BumpBatchFeeRequest>,
+    ) -> Result<Response<BumpBatchFeeResponse>, Status> {
+        crate::tracing::record_error(|| async move {
+            extract_tracing(&request);
+
+            let profile = self
+                .authenticate(&request, profile::ApiKeyScope::PayoutsWrite)
+                .await?;
+            let request = request.into_inner();
+            self.app
+                .bump_batch_fee(
+                    &profile,
+                    request
+                        .id
+                        .parse()
+                        .map_err(ApplicationError::CouldNotParseIncomingUuid)?,
+                    request.fee_rate_sat_per_vbyte,
+                )
+                .await?;
+            Ok(Response::new(BumpBatchFeeResponse {}))
+        })
+        .await
+    }
+
     type SubscribeAllStream = std::pin::Pin<
         Box<dyn futures::Stream<Item = Result<BriaEvent, Status>> + Send + Sync + 'static>,
     >;
@@ -859,8 +1672,9 @@ impl BriaService for Bria {
     ) -> Result<Response<Self::SubscribeAllStream>, Status> {
         extract_tracing(&request);
 
-        let key = extract_api_token(&request)?;
-        let profile = self.app.authenticate(key).await?;
+        let profile = self
+            .authenticate(&request, profile::ApiKeyScope::Read)
+            .await?;
         let SubscribeAllRequest {
             after_sequence,
             augment,
@@ -0,0 +1,30 @@
+use super::error::ApplicationError;
+use crate::{
+    payout::PayoutSchedule,
+    primitives::{bitcoin, PayoutId, Satoshis},
+};
+
+#[derive(Debug)]
+pub enum PayoutSubmissionDestination {
+    OnchainAddress(bitcoin::Address),
+    WalletName(String),
+}
+
+#[derive(Debug)]
+pub struct PayoutSubmission {
+    pub wallet_name: String,
+    pub payout_queue_name: String,
+    pub destination: PayoutSubmissionDestination,
+    pub satoshis: Satoshis,
+    pub fee_from_amount: bool,
+    pub schedule: PayoutSchedule,
+    pub external_id: Option<String>,
+    pub metadata: Option<serde_json::Value>,
+}
+
+/// Outcome of a bulk submission keyed by the index of each submission in the request
+#[derive(Debug, Default)]
+pub struct BulkPayoutSubmission {
+    pub submitted: Vec<(usize, PayoutId)>,
+    pub failed: Vec<(usize, ApplicationError)>,
+}
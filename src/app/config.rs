@@ -2,7 +2,7 @@ use serde::{Deserialize, Deserializer, Serialize};
 use std::collections::HashSet;
 
 use crate::{
-    fees::MempoolSpaceConfig,
+    fees::FeesConfig,
     job::JobsConfig,
     primitives::{
         bitcoin::{self, Network},
@@ -57,12 +57,6 @@ fn default_electrum_url() -> String {
     "127.0.0.1:50001".to_string()
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
-pub struct FeesConfig {
-    #[serde(default)]
-    pub mempool_space: MempoolSpaceConfig,
-}
-
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct SecurityConfig {
     blocked_addresses: HashSet<bitcoin::Address>,
@@ -73,6 +67,14 @@ impl SecurityConfig {
         self.blocked_addresses
             .contains(destination.onchain_address())
     }
+
+    pub fn has_blocked_addresses(&self) -> bool {
+        !self.blocked_addresses.is_empty()
+    }
+
+    pub fn is_blocked_source(&self, address: &bitcoin::Address) -> bool {
+        self.blocked_addresses.contains(address)
+    }
 }
 
 fn deserialize_network<'de, D>(deserializer: D) -> Result<Network, D::Error>
@@ -12,8 +12,8 @@ use crate::{
     outbox::error::OutboxError,
     payout::error::PayoutError,
     payout_queue::error::PayoutQueueError,
-    primitives::{bitcoin, PayoutDestination},
-    profile::error::ProfileError,
+    primitives::{bitcoin, PayoutDestination, Satoshis},
+    profile::{error::ProfileError, SpendingLimitWindow},
     signing_session::error::SigningSessionError,
     utxo::error::UtxoError,
     wallet::error::WalletError,
@@ -66,12 +66,18 @@ pub enum ApplicationError {
     DestinationBlocked(PayoutDestination),
     #[error("DestinationNotAllowed - profile is not allowed to send to '{0}'")]
     DestinationNotAllowed(PayoutDestination),
+    #[error(
+        "SpendingLimitExceeded - {0} spending limit exceeded, remaining allowance is {1} sats"
+    )]
+    SpendingLimitExceeded(SpendingLimitWindow, Satoshis),
     #[error("Signing Session not found for batch id: {0}")]
     SigningSessionNotFoundForBatchId(crate::primitives::BatchId),
     #[error("Signing Session not found for xpub id: {0}")]
     SigningSessionNotFoundForXPubId(crate::primitives::XPubId),
     #[error("Could not parse incoming psbt: {0}")]
     CouldNotParseIncomingPsbt(bitcoin::psbt::PsbtParseError),
+    #[error("Could not parse incoming outpoint: {0}")]
+    CouldNotParseIncomingOutPoint(bitcoin::ParseOutPointError),
     #[error("Hex decode error: {0}")]
     HexDecodeError(#[from] hex::FromHexError),
     #[error("Could not decrypt the encrypted key: {0}")]
@@ -1,11 +1,13 @@
+mod bulk_payouts;
 mod config;
 pub mod error;
 
 use sqlxmq::JobRunnerHandle;
 use tracing::instrument;
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
+pub use bulk_payouts::*;
 pub use config::*;
 use error::*;
 
@@ -18,10 +20,10 @@ use crate::{
     job,
     ledger::*,
     outbox::*,
-    payout::*,
+    payout::{error::PayoutError, *},
     payout_queue::*,
     primitives::*,
-    profile::*,
+    profile::{error::ProfileError, *},
     signing_session::*,
     utxo::*,
     wallet::{balance::*, *},
@@ -43,7 +45,7 @@ pub struct App {
     ledger: Ledger,
     utxos: Utxos,
     addresses: Addresses,
-    mempool_space_client: MempoolSpaceClient,
+    fees_client: FeesClient,
     pool: sqlx::PgPool,
     config: AppConfig,
 }
@@ -60,7 +62,7 @@ impl App {
         let signing_sessions = SigningSessions::new(&pool);
         let addresses = Addresses::new(&pool);
         let outbox = Outbox::init(&pool, Augmenter::new(&addresses, &payouts)).await?;
-        let mempool_space_client = MempoolSpaceClient::new(config.fees.mempool_space.clone());
+        let fees_client = FeesClient::new(config.fees.clone(), &config.blockchain.electrum_url)?;
         let runner = job::start_job_runner(
             &pool,
             outbox.clone(),
@@ -75,8 +77,9 @@ impl App {
             addresses.clone(),
             config.jobs.clone(),
             config.blockchain.clone(),
+            config.security.clone(),
             config.signer_encryption.clone(),
-            mempool_space_client.clone(),
+            fees_client.clone(),
         )
         .await?;
         Self::spawn_sync_all_wallets(pool.clone(), config.jobs.sync_all_wallets_delay).await?;
@@ -90,6 +93,16 @@ impl App {
             config.jobs.respawn_all_outbox_handlers_delay,
         )
         .await?;
+        Self::spawn_check_expiring_timelocks(
+            pool.clone(),
+            config.jobs.check_expiring_timelocks_delay,
+        )
+        .await?;
+        Self::spawn_consolidate_all_wallets(
+            pool.clone(),
+            config.jobs.consolidate_all_wallets_delay,
+        )
+        .await?;
         let app = Self {
             outbox,
             profiles: Profiles::new(&pool),
@@ -104,7 +117,7 @@ impl App {
             ledger,
             utxos,
             addresses,
-            mempool_space_client,
+            fees_client,
             config,
             _runner: runner,
         };
@@ -117,7 +130,7 @@ impl App {
     }
 
     #[instrument(name = "app.authenticate", skip_all, err)]
-    pub async fn authenticate(&self, key: &str) -> Result<Profile, ApplicationError> {
+    pub async fn authenticate(&self, key: &str) -> Result<AuthenticatedProfile, ApplicationError> {
         let profile = self.profiles.find_by_key(key).await?;
         Ok(profile)
     }
@@ -147,12 +160,105 @@ impl App {
         Ok(profiles)
     }
 
+    #[instrument(name = "app.update_profile", skip(self), err)]
+    pub async fn update_profile(
+        &self,
+        profile: &Profile,
+        id: ProfileId,
+        name: Option<String>,
+        spending_policy: Option<SpendingPolicy>,
+        disabled: Option<bool>,
+    ) -> Result<(), ApplicationError> {
+        if disabled == Some(true) && id == profile.id {
+            return Err(ProfileError::CannotDisableSelf.into());
+        }
+        let mut target = self.profiles.find_by_id(profile.account_id, id).await?;
+        if let Some(name) = name {
+            target.update_name(name);
+        }
+        if let Some(spending_policy) = spending_policy {
+            target.update_spending_policy(spending_policy);
+        }
+        match disabled {
+            Some(true) => target.disable(),
+            Some(false) => target.enable(),
+            None => (),
+        }
+        let mut tx = self.pool.begin().await?;
+        self.profiles.update_in_tx(&mut tx, target).await?;
+        tx.commit().await?;
+        Ok(())
+    }
+
+    #[instrument(name = "app.list_profile_api_keys", skip(self), err)]
+    pub async fn list_profile_api_keys(
+        &self,
+        profile: &Profile,
+        profile_id: ProfileId,
+    ) -> Result<Vec<ProfileApiKeySummary>, ApplicationError> {
+        let target = self
+            .profiles
+            .find_by_id(profile.account_id, profile_id)
+            .await?;
+        Ok(self.profiles.list_api_keys(target.id).await?)
+    }
+
+    #[instrument(name = "app.revoke_profile_api_key", skip(self), err)]
+    pub async fn revoke_profile_api_key(
+        &self,
+        profile: &Profile,
+        api_key_id: ProfileApiKeyId,
+    ) -> Result<(), ApplicationError> {
+        let mut target = self
+            .profiles
+            .find_by_api_key_id(profile.account_id, api_key_id)
+            .await?;
+        target.revoke_api_key(api_key_id);
+        let mut tx = self.pool.begin().await?;
+        self.profiles
+            .revoke_api_key_in_tx(&mut tx, target.id, api_key_id)
+            .await?;
+        self.profiles.update_in_tx(&mut tx, target).await?;
+        tx.commit().await?;
+        Ok(())
+    }
+
+    #[instrument(name = "app.set_profile_api_key_expiry", skip(self), err)]
+    pub async fn set_profile_api_key_expiry(
+        &self,
+        profile: &Profile,
+        api_key_id: ProfileApiKeyId,
+        expires_at: Option<chrono::DateTime<chrono::Utc>>,
+    ) -> Result<(), ApplicationError> {
+        let mut target = self
+            .profiles
+            .find_by_api_key_id(profile.account_id, api_key_id)
+            .await?;
+        target.update_api_key_expiry(api_key_id, expires_at);
+        let mut tx = self.pool.begin().await?;
+        self.profiles
+            .update_api_key_expiry_in_tx(&mut tx, target.id, api_key_id, expires_at)
+            .await?;
+        self.profiles.update_in_tx(&mut tx, target).await?;
+        tx.commit().await?;
+        Ok(())
+    }
+
     #[instrument(name = "app.create_profile_api_key", skip(self), err)]
     pub async fn create_profile_api_key(
         &self,
-        profile: &Profile,
+        profile: &AuthenticatedProfile,
         profile_name: String,
+        scopes: Vec<ApiKeyScope>,
     ) -> Result<ProfileApiKey, ApplicationError> {
+        let scopes = if scopes.is_empty() {
+            profile.scopes.clone()
+        } else {
+            for scope in scopes.iter() {
+                profile.ensure_scope(*scope)?;
+            }
+            scopes
+        };
         let found_profile = self
             .profiles
             .find_by_name(profile.account_id, profile_name)
@@ -160,7 +266,7 @@ impl App {
         let mut tx = self.pool.begin().await?;
         let key = self
             .profiles
-            .create_key_for_profile_in_tx(&mut tx, found_profile, false)
+            .create_key_for_profile_in_tx(&mut tx, found_profile, false, scopes)
             .await?;
         tx.commit().await?;
         Ok(key)
@@ -294,22 +400,25 @@ impl App {
         wallet_name: String,
         xpub: String,
         derivation: Option<String>,
+        wallet_config: Option<WalletConfig>,
     ) -> Result<(WalletId, Vec<XPubId>), ApplicationError> {
-        let keychain = if let Ok(xpub) = XPub::try_from((&xpub, derivation)) {
-            KeychainConfig::wpkh(xpub)
-        } else {
-            KeychainConfig::wpkh(
-                self.xpubs
-                    .find_from_ref(
-                        profile.account_id,
-                        xpub.parse::<XPubRef>()
-                            .expect("xpub_ref should always parse"),
-                    )
-                    .await?
-                    .value,
-            )
-        };
-        self.create_wallet(profile, wallet_name, keychain).await
+        let keychain = KeychainConfig::wpkh(self.single_xpub(profile, xpub, derivation).await?);
+        self.create_wallet(profile, wallet_name, keychain, wallet_config)
+            .await
+    }
+
+    #[instrument(name = "app.create_tr_wallet", skip(self), err)]
+    pub async fn create_tr_wallet(
+        &self,
+        profile: &Profile,
+        wallet_name: String,
+        xpub: String,
+        derivation: Option<String>,
+        wallet_config: Option<WalletConfig>,
+    ) -> Result<(WalletId, Vec<XPubId>), ApplicationError> {
+        let keychain = KeychainConfig::tr(self.single_xpub(profile, xpub, derivation).await?);
+        self.create_wallet(profile, wallet_name, keychain, wallet_config)
+            .await
     }
 
     #[instrument(name = "app.create_descriptors_wallet", skip(self), err)]
@@ -319,9 +428,11 @@ impl App {
         wallet_name: String,
         external: String,
         internal: String,
+        wallet_config: Option<WalletConfig>,
     ) -> Result<(WalletId, Vec<XPubId>), ApplicationError> {
         let keychain = KeychainConfig::try_from((external.as_ref(), internal.as_ref()))?;
-        self.create_wallet(profile, wallet_name, keychain).await
+        self.create_wallet(profile, wallet_name, keychain, wallet_config)
+            .await
     }
 
     #[instrument(name = "app.create_sorted_multisig_wallet", skip(self), err)]
@@ -331,7 +442,181 @@ impl App {
         wallet_name: String,
         xpubs: Vec<String>,
         threshold: u32,
+        wallet_config: Option<WalletConfig>,
     ) -> Result<(WalletId, Vec<XPubId>), ApplicationError> {
+        let keychain = self
+            .sorted_multisig_keychain(profile, xpubs, threshold)
+            .await?;
+        self.create_wallet(profile, wallet_name, keychain, wallet_config)
+            .await
+    }
+
+    #[instrument(name = "app.create_policy_wallet", skip(self), err)]
+    pub async fn create_policy_wallet(
+        &self,
+        profile: &Profile,
+        wallet_name: String,
+        policy: String,
+        script_type: PolicyScriptType,
+        wallet_config: Option<WalletConfig>,
+    ) -> Result<(WalletId, Vec<XPubId>), ApplicationError> {
+        let keychain = self.policy_keychain(profile, policy, script_type).await?;
+        self.create_wallet(profile, wallet_name, keychain, wallet_config)
+            .await
+    }
+
+    async fn create_wallet(
+        &self,
+        profile: &Profile,
+        wallet_name: String,
+        keychain: KeychainConfig,
+        wallet_config: Option<WalletConfig>,
+    ) -> Result<(WalletId, Vec<XPubId>), ApplicationError> {
+        let wallet_config = wallet_config.unwrap_or_default();
+        wallet_config.validate()?;
+        let mut tx = self.pool.begin().await?;
+        let xpub_ids = self
+            .import_keychain_xpubs_in_tx(&mut tx, profile, &wallet_name, &keychain)
+            .await?;
+        let wallet_id = WalletId::new();
+        let wallet_ledger_accounts = self
+            .ledger
+            .create_ledger_accounts_for_wallet(&mut tx, wallet_id)
+            .await?;
+        let new_wallet = NewWallet::builder()
+            .id(wallet_id)
+            .network(self.config.blockchain.network)
+            .account_id(profile.account_id)
+            .journal_id(profile.account_id)
+            .name(wallet_name)
+            .keychain(keychain.clone())
+            .ledger_account_ids(wallet_ledger_accounts)
+            .config(wallet_config)
+            .build()
+            .expect("Couldn't build NewWallet");
+        let wallet_id = self.wallets.create_in_tx(&mut tx, new_wallet).await?;
+        self.persist_keychain_descriptors_in_tx(&mut tx, profile, wallet_id, &keychain)
+            .await?;
+        tx.commit().await?;
+        Ok((wallet_id, xpub_ids))
+    }
+
+    #[instrument(name = "app.rotate_wpkh_wallet_keychain", skip(self), err)]
+    pub async fn rotate_wpkh_wallet_keychain(
+        &self,
+        profile: &Profile,
+        wallet_name: String,
+        xpub: String,
+        derivation: Option<String>,
+    ) -> Result<(KeychainId, Vec<XPubId>), ApplicationError> {
+        let keychain = KeychainConfig::wpkh(self.single_xpub(profile, xpub, derivation).await?);
+        self.rotate_wallet_keychain(profile, wallet_name, keychain)
+            .await
+    }
+
+    #[instrument(name = "app.rotate_tr_wallet_keychain", skip(self), err)]
+    pub async fn rotate_tr_wallet_keychain(
+        &self,
+        profile: &Profile,
+        wallet_name: String,
+        xpub: String,
+        derivation: Option<String>,
+    ) -> Result<(KeychainId, Vec<XPubId>), ApplicationError> {
+        let keychain = KeychainConfig::tr(self.single_xpub(profile, xpub, derivation).await?);
+        self.rotate_wallet_keychain(profile, wallet_name, keychain)
+            .await
+    }
+
+    #[instrument(name = "app.rotate_descriptors_wallet_keychain", skip(self), err)]
+    pub async fn rotate_descriptors_wallet_keychain(
+        &self,
+        profile: &Profile,
+        wallet_name: String,
+        external: String,
+        internal: String,
+    ) -> Result<(KeychainId, Vec<XPubId>), ApplicationError> {
+        let keychain = KeychainConfig::try_from((external.as_ref(), internal.as_ref()))?;
+        self.rotate_wallet_keychain(profile, wallet_name, keychain)
+            .await
+    }
+
+    #[instrument(name = "app.rotate_sorted_multisig_wallet_keychain", skip(self), err)]
+    pub async fn rotate_sorted_multisig_wallet_keychain(
+        &self,
+        profile: &Profile,
+        wallet_name: String,
+        xpubs: Vec<String>,
+        threshold: u32,
+    ) -> Result<(KeychainId, Vec<XPubId>), ApplicationError> {
+        let keychain = self
+            .sorted_multisig_keychain(profile, xpubs, threshold)
+            .await?;
+        self.rotate_wallet_keychain(profile, wallet_name, keychain)
+            .await
+    }
+
+    #[instrument(name = "app.rotate_policy_wallet_keychain", skip(self), err)]
+    pub async fn rotate_policy_wallet_keychain(
+        &self,
+        profile: &Profile,
+        wallet_name: String,
+        policy: String,
+        script_type: PolicyScriptType,
+    ) -> Result<(KeychainId, Vec<XPubId>), ApplicationError> {
+        let keychain = self.policy_keychain(profile, policy, script_type).await?;
+        self.rotate_wallet_keychain(profile, wallet_name, keychain)
+            .await
+    }
+
+    async fn rotate_wallet_keychain(
+        &self,
+        profile: &Profile,
+        wallet_name: String,
+        keychain: KeychainConfig,
+    ) -> Result<(KeychainId, Vec<XPubId>), ApplicationError> {
+        let mut wallet = self
+            .wallets
+            .find_by_name(profile.account_id, wallet_name.clone())
+            .await?;
+        let keychain_id = wallet.rotate_keychain(keychain.clone())?;
+        let mut tx = self.pool.begin().await?;
+        let xpub_ids = self
+            .import_keychain_xpubs_in_tx(&mut tx, profile, &wallet_name, &keychain)
+            .await?;
+        self.persist_keychain_descriptors_in_tx(&mut tx, profile, wallet.id, &keychain)
+            .await?;
+        self.wallets.update_in_tx(&mut tx, wallet).await?;
+        tx.commit().await?;
+        Ok((keychain_id, xpub_ids))
+    }
+
+    async fn single_xpub(
+        &self,
+        profile: &Profile,
+        xpub: String,
+        derivation: Option<String>,
+    ) -> Result<XPub, ApplicationError> {
+        let xpub = if let Ok(xpub) = XPub::try_from((&xpub, derivation)) {
+            xpub
+        } else {
+            self.xpubs
+                .find_from_ref(
+                    profile.account_id,
+                    xpub.parse::<XPubRef>()
+                        .expect("xpub_ref should always parse"),
+                )
+                .await?
+                .value
+        };
+        Ok(xpub)
+    }
+
+    async fn sorted_multisig_keychain(
+        &self,
+        profile: &Profile,
+        xpubs: Vec<String>,
+        threshold: u32,
+    ) -> Result<KeychainConfig, ApplicationError> {
         let xpub_values: Vec<XPub> = futures::future::try_join_all(
             xpubs
                 .iter()
@@ -346,20 +631,39 @@ impl App {
         .map(|xpub| xpub.value)
         .collect();
 
-        let keychain = KeychainConfig::sorted_multisig(xpub_values, threshold);
-        self.create_wallet(profile, wallet_name, keychain).await
+        Ok(KeychainConfig::sorted_multisig(xpub_values, threshold))
     }
 
-    async fn create_wallet(
+    async fn policy_keychain(
         &self,
         profile: &Profile,
-        wallet_name: String,
-        keychain: KeychainConfig,
-    ) -> Result<(WalletId, Vec<XPubId>), ApplicationError> {
-        let mut tx = self.pool.begin().await?;
-        let xpubs = keychain.xpubs();
+        policy: String,
+        script_type: PolicyScriptType,
+    ) -> Result<KeychainConfig, ApplicationError> {
+        let mut xpubs = HashMap::new();
+        for name in KeychainConfig::policy_key_names(&policy)? {
+            let xpub = self
+                .xpubs
+                .find_from_ref(
+                    profile.account_id,
+                    name.parse::<XPubRef>()
+                        .expect("xpub_ref should always parse"),
+                )
+                .await?;
+            xpubs.insert(name, xpub.value);
+        }
+        Ok(KeychainConfig::policy(policy, script_type, xpubs)?)
+    }
+
+    async fn import_keychain_xpubs_in_tx(
+        &self,
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        profile: &Profile,
+        wallet_name: &str,
+        keychain: &KeychainConfig,
+    ) -> Result<Vec<XPubId>, ApplicationError> {
         let mut xpub_ids = Vec::new();
-        for xpub in xpubs {
+        for xpub in keychain.xpubs() {
             match self
                 .xpubs
                 .find_from_ref(profile.account_id, xpub.id())
@@ -377,26 +681,20 @@ impl App {
                         .value(xpub)
                         .build()
                         .expect("Couldn't build xpub");
-                    xpub_ids.push(self.xpubs.persist_in_tx(&mut tx, xpub).await?);
+                    xpub_ids.push(self.xpubs.persist_in_tx(tx, xpub).await?);
                 }
             }
         }
-        let wallet_id = WalletId::new();
-        let wallet_ledger_accounts = self
-            .ledger
-            .create_ledger_accounts_for_wallet(&mut tx, wallet_id)
-            .await?;
-        let new_wallet = NewWallet::builder()
-            .id(wallet_id)
-            .network(self.config.blockchain.network)
-            .account_id(profile.account_id)
-            .journal_id(profile.account_id)
-            .name(wallet_name)
-            .keychain(keychain.clone())
-            .ledger_account_ids(wallet_ledger_accounts)
-            .build()
-            .expect("Couldn't build NewWallet");
-        let wallet_id = self.wallets.create_in_tx(&mut tx, new_wallet).await?;
+        Ok(xpub_ids)
+    }
+
+    async fn persist_keychain_descriptors_in_tx(
+        &self,
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        profile: &Profile,
+        wallet_id: WalletId,
+        keychain: &KeychainConfig,
+    ) -> Result<(), ApplicationError> {
         let descriptors = vec![
             NewDescriptor::builder()
                 .account_id(profile.account_id)
@@ -413,11 +711,8 @@ impl App {
                 .build()
                 .expect("Could not build descriptor"),
         ];
-        self.descriptors
-            .persist_all_in_tx(&mut tx, descriptors)
-            .await?;
-        tx.commit().await?;
-        Ok((wallet_id, xpub_ids))
+        self.descriptors.persist_all_in_tx(tx, descriptors).await?;
+        Ok(())
     }
 
     #[instrument(name = "app.get_wallet_balance_summary", skip(self), err)]
@@ -439,6 +734,152 @@ impl App {
         Ok(summary)
     }
 
+    #[instrument(name = "app.update_wallet_config", skip(self), err)]
+    #[allow(clippy::too_many_arguments)]
+    pub async fn update_wallet_config(
+        &self,
+        profile: &Profile,
+        wallet_name: String,
+        settle_income_after_n_confs: Option<u32>,
+        settle_change_after_n_confs: Option<u32>,
+        gap_limit: Option<u32>,
+        lookahead: Option<u32>,
+        dust_threshold_sats: Option<u64>,
+        dust_sweep_max_fee_rate_sat_per_vb: Option<u64>,
+    ) -> Result<(), ApplicationError> {
+        let mut wallet = self
+            .wallets
+            .find_by_name(profile.account_id, wallet_name)
+            .await?;
+        let current = wallet.config.clone();
+        wallet.update_config(WalletConfig {
+            settle_income_after_n_confs: settle_income_after_n_confs
+                .unwrap_or(current.settle_income_after_n_confs),
+            settle_change_after_n_confs: settle_change_after_n_confs
+                .unwrap_or(current.settle_change_after_n_confs),
+            gap_limit: gap_limit.unwrap_or(current.gap_limit),
+            lookahead: lookahead.unwrap_or(current.lookahead),
+            consolidation: current.consolidation,
+            dust_threshold_sats: dust_threshold_sats.unwrap_or(current.dust_threshold_sats),
+            dust_sweep_max_fee_rate_sat_per_vb: dust_sweep_max_fee_rate_sat_per_vb
+                .unwrap_or(current.dust_sweep_max_fee_rate_sat_per_vb),
+        })?;
+        let mut tx = self.pool.begin().await?;
+        self.wallets.update_in_tx(&mut tx, wallet).await?;
+        tx.commit().await?;
+        Ok(())
+    }
+
+    #[instrument(name = "app.set_wallet_consolidation_policy", skip(self), err)]
+    pub async fn set_wallet_consolidation_policy(
+        &self,
+        profile: &Profile,
+        wallet_name: String,
+        payout_queue_name: String,
+        target_utxo_count: u32,
+        max_fee_rate_sat_per_vb: u64,
+        max_inputs_per_tx: u32,
+    ) -> Result<(), ApplicationError> {
+        let payout_queue = self
+            .payout_queues
+            .find_by_name(profile.account_id, payout_queue_name)
+            .await?;
+        self.update_wallet_consolidation_policy(
+            profile,
+            wallet_name,
+            Some(ConsolidationPolicy {
+                payout_queue_id: payout_queue.id,
+                target_utxo_count,
+                max_fee_rate_sat_per_vb,
+                max_inputs_per_tx,
+            }),
+        )
+        .await
+    }
+
+    #[instrument(name = "app.remove_wallet_consolidation_policy", skip(self), err)]
+    pub async fn remove_wallet_consolidation_policy(
+        &self,
+        profile: &Profile,
+        wallet_name: String,
+    ) -> Result<(), ApplicationError> {
+        self.update_wallet_consolidation_policy(profile, wallet_name, None)
+            .await
+    }
+
+    async fn update_wallet_consolidation_policy(
+        &self,
+        profile: &Profile,
+        wallet_name: String,
+        consolidation: Option<ConsolidationPolicy>,
+    ) -> Result<(), ApplicationError> {
+        let mut wallet = self
+            .wallets
+            .find_by_name(profile.account_id, wallet_name)
+            .await?;
+        wallet.update_config(WalletConfig {
+            consolidation,
+            ..wallet.config.clone()
+        })?;
+        let mut tx = self.pool.begin().await?;
+        self.wallets.update_in_tx(&mut tx, wallet).await?;
+        tx.commit().await?;
+        Ok(())
+    }
+
+    #[instrument(name = "app.export_wallet", skip(self), err)]
+    pub async fn export_wallet(
+        &self,
+        profile: &Profile,
+        wallet_name: String,
+    ) -> Result<WalletExport, ApplicationError> {
+        let wallet = self
+            .wallets
+            .find_by_name(profile.account_id, wallet_name)
+            .await?;
+        let mut descriptors = Vec::new();
+        for (idx, keychain_wallet) in wallet.keychain_wallets(self.pool.clone()).enumerate() {
+            for kind in [
+                bitcoin::KeychainKind::External,
+                bitcoin::KeychainKind::Internal,
+            ] {
+                descriptors.push(ExportedDescriptor {
+                    keychain_id: keychain_wallet.keychain_id,
+                    kind,
+                    descriptor: keychain_wallet.descriptor(kind),
+                    active: idx == 0,
+                    last_derived_index: keychain_wallet.last_derived_index(kind).await?,
+                });
+            }
+        }
+        Ok(WalletExport {
+            wallet_id: wallet.id,
+            wallet_name: wallet.name,
+            network: wallet.network,
+            gap_limit: wallet.config.gap_limit,
+            descriptors,
+        })
+    }
+
+    #[instrument(name = "app.rescan_wallet", skip(self), err)]
+    pub async fn rescan_wallet(
+        &self,
+        profile: &Profile,
+        wallet_name: String,
+        start_height: Option<u32>,
+        gap_limit: Option<u32>,
+    ) -> Result<(), ApplicationError> {
+        let wallet = self
+            .wallets
+            .find_by_name(profile.account_id, wallet_name)
+            .await?;
+        for keychain_id in wallet.keychain_ids() {
+            crate::bdk::reset_sync_state(&self.pool, keychain_id, start_height).await?;
+        }
+        job::spawn_rescan_wallet(&self.pool, profile.account_id, wallet.id, gap_limit).await?;
+        Ok(())
+    }
+
     #[instrument(name = "app.get_account_balance_summary", skip(self), err)]
     pub async fn get_account_balance_summary(
         &self,
@@ -464,6 +905,22 @@ impl App {
             .wallets
             .find_by_name(profile.account_id, wallet_name)
             .await?;
+        let new_address = self
+            .derive_new_address(profile, &wallet, external_id, metadata)
+            .await?;
+        let address = new_address.address().clone();
+        self.addresses.persist_new_address(new_address).await?;
+
+        Ok((wallet.id, address))
+    }
+
+    async fn derive_new_address(
+        &self,
+        profile: &Profile,
+        wallet: &Wallet,
+        external_id: Option<String>,
+        metadata: Option<serde_json::Value>,
+    ) -> Result<NewAddress, ApplicationError> {
         let keychain_wallet = wallet.current_keychain_wallet(&self.pool);
         let addr = keychain_wallet.new_external_address().await?;
 
@@ -480,10 +937,7 @@ impl App {
         if let Some(external_id) = external_id {
             builder.external_id(external_id);
         }
-        let new_address = builder.build().expect("Couldn't build NewAddress");
-        self.addresses.persist_new_address(new_address).await?;
-
-        Ok((wallet.id, addr.address))
+        Ok(builder.build().expect("Couldn't build NewAddress"))
     }
 
     #[instrument(name = "app.update_address", skip(self), err)]
@@ -582,6 +1036,134 @@ impl App {
         Ok((wallet.id, ordered_utxos))
     }
 
+    #[instrument(name = "app.freeze_utxo", skip(self), err)]
+    pub async fn freeze_utxo(
+        &self,
+        profile: &Profile,
+        wallet_name: String,
+        outpoint: bitcoin::OutPoint,
+        reason: String,
+    ) -> Result<(), ApplicationError> {
+        let wallet = self
+            .wallets
+            .find_by_name(profile.account_id, wallet_name)
+            .await?;
+        let mut tx = self.pool.begin().await?;
+        let utxo = self
+            .utxos
+            .freeze_utxo(&mut tx, wallet.keychain_ids(), outpoint, profile.id, reason)
+            .await?;
+        self.outbox
+            .publish_payload_in_tx(
+                tx,
+                profile.account_id,
+                OutboxEventPayload::UtxoFrozen {
+                    tx_id: utxo.outpoint.txid,
+                    vout: utxo.outpoint.vout,
+                    satoshis: utxo.value,
+                    wallet_id: utxo.wallet_id,
+                    keychain_id: utxo.keychain_id,
+                    profile_id: profile.id,
+                    reason: utxo.freeze.reason,
+                    frozen_at: utxo.freeze.frozen_at,
+                },
+            )
+            .await?;
+        Ok(())
+    }
+
+    #[instrument(name = "app.unfreeze_utxo", skip(self), err)]
+    pub async fn unfreeze_utxo(
+        &self,
+        profile: &Profile,
+        wallet_name: String,
+        outpoint: bitcoin::OutPoint,
+    ) -> Result<(), ApplicationError> {
+        let wallet = self
+            .wallets
+            .find_by_name(profile.account_id, wallet_name)
+            .await?;
+        let mut tx = self.pool.begin().await?;
+        let utxo = self
+            .utxos
+            .unfreeze_utxo(&mut tx, wallet.keychain_ids(), outpoint)
+            .await?;
+        self.outbox
+            .publish_payload_in_tx(
+                tx,
+                profile.account_id,
+                OutboxEventPayload::UtxoUnfrozen {
+                    tx_id: utxo.outpoint.txid,
+                    vout: utxo.outpoint.vout,
+                    satoshis: utxo.value,
+                    wallet_id: utxo.wallet_id,
+                    keychain_id: utxo.keychain_id,
+                    profile_id: profile.id,
+                    unfrozen_at: chrono::Utc::now(),
+                },
+            )
+            .await?;
+        Ok(())
+    }
+
+    /// Releases a utxo that was quarantined because of a falsely attributed blocked source.
+    #[instrument(name = "app.release_quarantined_utxo", skip(self), err)]
+    pub async fn release_quarantined_utxo(
+        &self,
+        profile: &Profile,
+        wallet_name: String,
+        outpoint: bitcoin::OutPoint,
+    ) -> Result<(), ApplicationError> {
+        let wallet = self
+            .wallets
+            .find_by_name(profile.account_id, wallet_name)
+            .await?;
+        let mut tx = self.pool.begin().await?;
+        let utxo = self
+            .utxos
+            .release_quarantine(&mut tx, wallet.keychain_ids(), outpoint)
+            .await?;
+        // Pending utxos will simply be settled as usual,
+        // settled ones have to be moved out of the quarantined account
+        if utxo.settled {
+            let savepoint = sqlx::Acquire::begin(&mut tx).await?;
+            self.ledger
+                .utxo_quarantine_released(
+                    savepoint,
+                    LedgerTransactionId::new(),
+                    UtxoQuarantineReleasedParams {
+                        journal_id: wallet.journal_id,
+                        ledger_account_ids: wallet.ledger_account_ids,
+                        meta: UtxoQuarantineReleasedMeta {
+                            account_id: profile.account_id,
+                            wallet_id: wallet.id,
+                            keychain_id: utxo.keychain_id,
+                            outpoint,
+                            satoshis: utxo.value,
+                            released_by_profile_id: profile.id,
+                        },
+                    },
+                )
+                .await?;
+        }
+        self.outbox
+            .publish_payload_in_tx(
+                tx,
+                profile.account_id,
+                OutboxEventPayload::UtxoUnfrozen {
+                    tx_id: utxo.outpoint.txid,
+                    vout: utxo.outpoint.vout,
+                    satoshis: utxo.value,
+                    wallet_id: utxo.wallet_id,
+                    keychain_id: utxo.keychain_id,
+                    profile_id: profile.id,
+                    unfrozen_at: chrono::Utc::now(),
+                },
+            )
+            .await?;
+        Ok(())
+    }
+
     #[instrument(name = "app.create_payout_queue", skip(self), err)]
     pub async fn create_payout_queue(
         &self,
@@ -596,6 +1178,7 @@ impl App {
             .name(payout_queue_name)
             .description(description);
         if let Some(config) = config {
+            config.trigger.validate()?;
             builder.config(config);
         }
         let payout_queue = builder.build().expect("Couldn't build NewPayoutQueue");
@@ -608,25 +1191,126 @@ impl App {
         &self,
         profile: &Profile,
         name: String,
+        ignore_fee_caps: bool,
     ) -> Result<(), ApplicationError> {
         let payout_queue = self
             .payout_queues
             .find_by_name(profile.account_id, name)
             .await?;
-        job::spawn_process_payout_queue(&self.pool, (payout_queue.account_id, payout_queue.id))
-            .await?;
+        job::spawn_process_payout_queue(
+            &self.pool,
+            (payout_queue.account_id, payout_queue.id, ignore_fee_caps),
+        )
+        .await?;
         Ok(())
     }
 
-    #[instrument(name = "app.estimate_payout_fee_to_wallet", skip(self), ret, err)]
-    pub async fn estimate_payout_fee_to_wallet(
+    #[instrument(name = "app.estimate_payout_fee_to_wallet", skip(self), ret, err)]
+    pub async fn estimate_payout_fee_to_wallet(
+        &self,
+        profile: &Profile,
+        wallet_name: String,
+        queue_name: String,
+        destination_wallet_name: String,
+        sats: Satoshis,
+        tx_priority: Option<TxPriority>,
+    ) -> Result<Satoshis, ApplicationError> {
+        let destination_wallet = self
+            .wallets
+            .find_by_name(profile.account_id, destination_wallet_name)
+            .await?;
+        let destination = destination_wallet
+            .current_keychain_wallet(&self.pool)
+            .example_address()
+            .await?;
+        self.estimate_payout_fee_to_address(
+            profile,
+            wallet_name,
+            queue_name,
+            destination.address,
+            sats,
+            tx_priority,
+        )
+        .await
+    }
+
+    #[instrument(name = "app.estimate_payout_fee_to_address", skip(self), ret, err)]
+    pub async fn estimate_payout_fee_to_address(
+        &self,
+        profile: &Profile,
+        wallet_name: String,
+        queue_name: String,
+        destination: bitcoin::Address,
+        sats: Satoshis,
+        tx_priority: Option<TxPriority>,
+    ) -> Result<Satoshis, ApplicationError> {
+        let wallet = self
+            .wallets
+            .find_by_name(profile.account_id, wallet_name)
+            .await?;
+        let payout_queue = self
+            .payout_queues
+            .find_by_name(profile.account_id, queue_name)
+            .await?;
+        let mut unbatched_payouts = self
+            .payouts
+            .list_unbatched(profile.account_id, payout_queue.id)
+            .await?;
+        let payout_id = uuid::Uuid::new_v4();
+        unbatched_payouts
+            .include_simulated_payout(wallet.id, (payout_id, destination.clone(), sats));
+
+        let queue_id = payout_queue.id;
+        let tx_priority = tx_priority.unwrap_or(payout_queue.config.tx_priority);
+        let fee_rate = self.fees_client.fee_rate(tx_priority).await?;
+
+        let psbt = {
+            let mut tx = self.pool.begin().await?;
+            job::process_payout_queue::construct_psbt(
+                &self.pool,
+                &mut tx,
+                &unbatched_payouts,
+                &self.utxos,
+                &self.wallets,
+                payout_queue,
+                fee_rate,
+                true,
+            )
+            .await?
+        };
+
+        if let Some(fee) = psbt.proportional_fee(&wallet.id, sats) {
+            return Ok(fee);
+        }
+
+        // No utxos were available to simulate the batch
+        let avg_utxo_size = self.utxos.average_utxo_value(wallet.id, queue_id).await?;
+        let (n_payouts, payout_size) = self
+            .payouts
+            .average_payout_per_batch(wallet.id, queue_id)
+            .await?;
+        let keychain_wallet = wallet.current_keychain_wallet(&self.pool);
+        Ok(fees::estimate_proportional_fee(
+            avg_utxo_size,
+            keychain_wallet.max_satisfaction_weight(),
+            fee_rate,
+            n_payouts,
+            payout_size,
+            destination,
+            sats,
+            keychain_wallet.change_script_pubkey(),
+        ))
+    }
+
+    #[instrument(name = "app.estimate_sweep_to_wallet", skip(self), ret, err)]
+    pub async fn estimate_sweep_to_wallet(
         &self,
         profile: &Profile,
         wallet_name: String,
         queue_name: String,
         destination_wallet_name: String,
-        sats: Satoshis,
-    ) -> Result<Satoshis, ApplicationError> {
+        tx_priority: Option<TxPriority>,
+    ) -> Result<(Satoshis, Satoshis), ApplicationError> {
         let destination_wallet = self
             .wallets
             .find_by_name(profile.account_id, destination_wallet_name)
@@ -635,25 +1319,26 @@ impl App {
             .current_keychain_wallet(&self.pool)
             .example_address()
             .await?;
-        self.estimate_payout_fee_to_address(
+        self.estimate_sweep_to_address(
             profile,
             wallet_name,
             queue_name,
             destination.address,
-            sats,
+            tx_priority,
         )
         .await
     }
 
-    #[instrument(name = "app.estimate_payout_fee_to_address", skip(self), ret, err)]
-    pub async fn estimate_payout_fee_to_address(
+    /// Returns the fee and the net amount that sweeping the wallet would deliver
+    #[instrument(name = "app.estimate_sweep_to_address", skip(self), ret, err)]
+    pub async fn estimate_sweep_to_address(
         &self,
         profile: &Profile,
         wallet_name: String,
         queue_name: String,
         destination: bitcoin::Address,
-        sats: Satoshis,
-    ) -> Result<Satoshis, ApplicationError> {
+        tx_priority: Option<TxPriority>,
+    ) -> Result<(Satoshis, Satoshis), ApplicationError> {
         let wallet = self
             .wallets
             .find_by_name(profile.account_id, wallet_name)
@@ -666,14 +1351,12 @@ impl App {
             .payouts
             .list_unbatched(profile.account_id, payout_queue.id)
             .await?;
+        let spendable = self.utxos.spendable_value(wallet.keychain_ids()).await?;
         let payout_id = uuid::Uuid::new_v4();
-        unbatched_payouts
-            .include_simulated_payout(wallet.id, (payout_id, destination.clone(), sats));
-
-        let queue_id = payout_queue.id;
-        let tx_priority = payout_queue.config.tx_priority;
-        let fee_rate = self.mempool_space_client.fee_rate(tx_priority).await?;
+        unbatched_payouts.include_simulated_sweep(wallet.id, (payout_id, destination, spendable));
 
+        let tx_priority = tx_priority.unwrap_or(payout_queue.config.tx_priority);
+        let fee_rate = self.fees_client.fee_rate(tx_priority).await?;
         let psbt = {
             let mut tx = self.pool.begin().await?;
             job::process_payout_queue::construct_psbt(
@@ -689,27 +1372,18 @@ impl App {
             .await?
         };
 
-        if let Some(fee) = psbt.proportional_fee(&wallet.id, sats) {
-            return Ok(fee);
-        }
-
-        // No utxos were available to simulate the batch
-        let avg_utxo_size = self.utxos.average_utxo_value(wallet.id, queue_id).await?;
-        let (n_payouts, payout_size) = self
-            .payouts
-            .average_payout_per_batch(wallet.id, queue_id)
-            .await?;
-        Ok(fees::estimate_proportional_fee(
-            avg_utxo_size,
-            wallet
-                .current_keychain_wallet(&self.pool)
-                .max_satisfaction_weight(),
-            fee_rate,
-            n_payouts,
-            payout_size,
-            destination,
-            sats,
-        ))
+        let swept = psbt
+            .included_payouts
+            .get(&wallet.id)
+            .and_then(|payouts| payouts.iter().find(|((id, _, _), _)| id == &payout_id))
+            .map(|((_, _, sats), _)| *sats)
+            .ok_or(PayoutError::NothingToSweep)?;
+        let fee = psbt
+            .wallet_totals
+            .get(&wallet.id)
+            .map(|total| total.total_fee_satoshis)
+            .unwrap_or_default();
+        Ok((fee, swept))
     }
 
     #[instrument(name = "app.submit_payout_to_address", skip(self), err)]
@@ -721,6 +1395,9 @@ impl App {
         queue_name: String,
         address: bitcoin::Address,
         sats: Satoshis,
+        fee_from_amount: bool,
+        sweep: bool,
+        schedule: PayoutSchedule,
         external_id: Option<String>,
         metadata: Option<serde_json::Value>,
     ) -> Result<(PayoutId, Option<chrono::DateTime<chrono::Utc>>), ApplicationError> {
@@ -739,6 +1416,9 @@ impl App {
             PayoutId::new(),
             PayoutDestination::OnchainAddress { value: address },
             sats,
+            fee_from_amount,
+            sweep,
+            schedule,
             external_id,
             metadata,
         )
@@ -754,6 +1434,9 @@ impl App {
         queue_name: String,
         destination_wallet_name: String,
         sats: Satoshis,
+        fee_from_amount: bool,
+        sweep: bool,
+        schedule: PayoutSchedule,
         external_id: Option<String>,
         metadata: Option<serde_json::Value>,
     ) -> Result<(PayoutId, Option<chrono::DateTime<chrono::Utc>>), ApplicationError> {
@@ -784,6 +1467,9 @@ impl App {
                 address,
             },
             sats,
+            fee_from_amount,
+            sweep,
+            schedule,
             external_id,
             metadata,
         )
@@ -799,15 +1485,318 @@ impl App {
         id: PayoutId,
         destination: PayoutDestination,
         sats: Satoshis,
+        fee_from_amount: bool,
+        sweep: bool,
+        schedule: PayoutSchedule,
         external_id: Option<String>,
         metadata: Option<serde_json::Value>,
     ) -> Result<(PayoutId, Option<chrono::DateTime<chrono::Utc>>), ApplicationError> {
+        let (new_payout, params) = self
+            .prepare_payout(
+                profile,
+                &wallet,
+                &payout_queue,
+                id,
+                destination,
+                sats,
+                fee_from_amount,
+                sweep,
+                schedule,
+                external_id,
+                metadata,
+            )
+            .await?;
+        let mut tx = self.pool.begin().await?;
+        let id = self.payouts.create_in_tx(&mut tx, new_payout).await?;
+        self.enforce_spending_limits(&mut tx, profile, &wallet, params.meta.satoshis)
+            .await?;
+        self.ledger.payout_submitted(tx, id, params).await?;
+        let expected_time = if let Some(interval) = payout_queue.spawn_in() {
+            match job::next_attempt_of_queue(&self.pool, payout_queue.id).await {
+                Ok(Some(next_attempt)) => Some(next_attempt),
+                Ok(None) | Err(_) => Some(
+                    chrono::Utc::now()
+                        + chrono::Duration::from_std(interval)
+                            .expect("interval value will always be less than i64"),
+                ),
+            }
+        } else {
+            None
+        };
+        let expected_time = match (expected_time, schedule.not_before) {
+            (Some(expected), Some(not_before)) => Some(expected.max(not_before)),
+            (expected, not_before) => expected.or(not_before),
+        };
+        Ok((id, expected_time))
+    }
+
+    #[instrument(name = "app.approve_payout", skip(self), err)]
+    pub async fn approve_payout(
+        &self,
+        profile: &Profile,
+        id: PayoutId,
+    ) -> Result<(), ApplicationError> {
+        let mut tx = self.pool.begin().await?;
+        let mut payout = self
+            .payouts
+            .find_by_id_for_update(&mut tx, profile.account_id, id)
+            .await?;
+        payout.approve(profile.id)?;
+        self.payouts.update(&mut tx, payout).await?;
+        tx.commit().await?;
+        Ok(())
+    }
+
+    #[instrument(name = "app.reject_payout", skip(self), err)]
+    pub async fn reject_payout(
+        &self,
+        profile: &Profile,
+        id: PayoutId,
+        reason: Option<String>,
+    ) -> Result<(), ApplicationError> {
+        let mut tx = self.pool.begin().await?;
+        let mut payout = self
+            .payouts
+            .find_by_id_for_update(&mut tx, profile.account_id, id)
+            .await?;
+        payout.reject(profile.id, reason)?;
+        self.payouts.update(&mut tx, payout).await?;
+        self.ledger
+            .payout_cancelled(tx, LedgerTransactionId::new(), id)
+            .await?;
+        Ok(())
+    }
+
+    #[instrument(name = "app.update_payout_schedule", skip(self), err)]
+    pub async fn update_payout_schedule(
+        &self,
+        profile: &Profile,
+        id: PayoutId,
+        schedule: PayoutSchedule,
+    ) -> Result<(), ApplicationError> {
+        let mut tx = self.pool.begin().await?;
+        let mut payout = self
+            .payouts
+            .find_by_id_for_update(&mut tx, profile.account_id, id)
+            .await?;
+        payout.update_schedule(schedule.not_before, schedule.not_before_block_height)?;
+        self.payouts.update(&mut tx, payout).await?;
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Validates all submissions before persisting the valid ones in a single transaction.
+    /// When `atomic` is set nothing is persisted if any submission fails validation.
+    #[instrument(
+        name = "app.submit_payouts",
+        skip(self, submissions),
+        fields(n_submissions = submissions.len()),
+        err
+    )]
+    pub async fn submit_payouts(
+        &self,
+        profile: &Profile,
+        submissions: Vec<PayoutSubmission>,
+        atomic: bool,
+    ) -> Result<BulkPayoutSubmission, ApplicationError> {
+        let mut wallets = HashMap::new();
+        let mut payout_queues = HashMap::new();
+        let mut external_ids = HashSet::new();
+        let mut result = BulkPayoutSubmission::default();
+        let mut prepared = Vec::new();
+        for (idx, submission) in submissions.into_iter().enumerate() {
+            if let Some(external_id) = submission.external_id.as_ref() {
+                if !external_ids.insert(external_id.clone()) {
+                    result.failed.push((
+                        idx,
+                        PayoutError::DuplicateExternalId(external_id.clone()).into(),
+                    ));
+                    continue;
+                }
+            }
+            let wallet_name = submission.wallet_name.clone();
+            match self
+                .prepare_payout_submission(profile, &mut wallets, &mut payout_queues, submission)
+                .await
+            {
+                Ok((new_address, payout)) => prepared.push((idx, wallet_name, new_address, payout)),
+                Err(err) => result.failed.push((idx, err)),
+            }
+        }
+        if atomic && !result.failed.is_empty() {
+            return Ok(result);
+        }
+
+        let mut tx = self.pool.begin().await?;
+        for (idx, wallet_name, new_address, (new_payout, params)) in prepared {
+            // Each entry is persisted in a savepoint of the outer transaction so that
+            // a payout failing to persist can be rolled back on its own
+            let mut savepoint = sqlx::Acquire::begin(&mut tx).await?;
+            match self
+                .persist_payout_submission(
+                    &mut savepoint,
+                    profile,
+                    &wallets[&wallet_name],
+                    new_address,
+                    new_payout,
+                    params.meta.satoshis,
+                )
+                .await
+            {
+                Ok(id) => match self.ledger.payout_submitted(savepoint, id, params).await {
+                    Ok(()) => result.submitted.push((idx, id)),
+                    Err(err) => result.failed.push((idx, err.into())),
+                },
+                Err(err) => result.failed.push((idx, err)),
+            }
+        }
+        if atomic && !result.failed.is_empty() {
+            result.submitted.clear();
+            return Ok(result);
+        }
+        tx.commit().await?;
+        Ok(result)
+    }
+
+    async fn persist_payout_submission(
+        &self,
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        profile: &Profile,
+        wallet: &Wallet,
+        new_address: Option<NewAddress>,
+        new_payout: NewPayout,
+        satoshis: Satoshis,
+    ) -> Result<PayoutId, ApplicationError> {
+        if let Some(new_address) = new_address {
+            self.addresses
+                .persist_new_address_in_tx(tx, new_address)
+                .await?;
+        }
+        let id = self.payouts.create_in_tx(tx, new_payout).await?;
+        self.enforce_spending_limits(tx, profile, wallet, satoshis)
+            .await?;
+        Ok(id)
+    }
+
+    /// Validates a submission. Addresses of wallet destinations are derived
+    /// but only persisted together with the payout.
+    #[allow(clippy::type_complexity)]
+    async fn prepare_payout_submission(
+        &self,
+        profile: &Profile,
+        wallets: &mut HashMap<String, Wallet>,
+        payout_queues: &mut HashMap<String, PayoutQueue>,
+        submission: PayoutSubmission,
+    ) -> Result<(Option<NewAddress>, (NewPayout, PayoutSubmittedParams)), ApplicationError> {
+        let PayoutSubmission {
+            wallet_name,
+            payout_queue_name,
+            destination,
+            satoshis,
+            fee_from_amount,
+            schedule,
+            external_id,
+            metadata,
+        } = submission;
+        if !wallets.contains_key(&wallet_name) {
+            let wallet = self
+                .wallets
+                .find_by_name(profile.account_id, wallet_name.clone())
+                .await?;
+            wallets.insert(wallet_name.clone(), wallet);
+        }
+        if !payout_queues.contains_key(&payout_queue_name) {
+            let payout_queue = self
+                .payout_queues
+                .find_by_name(profile.account_id, payout_queue_name.clone())
+                .await?;
+            payout_queues.insert(payout_queue_name.clone(), payout_queue);
+        }
+        let id = PayoutId::new();
+        let (destination, new_address) = match destination {
+            PayoutSubmissionDestination::OnchainAddress(address) => {
+                (PayoutDestination::OnchainAddress { value: address }, None)
+            }
+            PayoutSubmissionDestination::WalletName(destination_wallet_name) => {
+                let destination_wallet = self
+                    .wallets
+                    .find_by_name(profile.account_id, destination_wallet_name)
+                    .await?;
+                let new_address = self
+                    .derive_new_address(
+                        profile,
+                        &destination_wallet,
+                        Some(external_id.clone().unwrap_or_else(|| id.to_string())),
+                        metadata.clone(),
+                    )
+                    .await?;
+                (
+                    PayoutDestination::Wallet {
+                        id: destination_wallet.id,
+                        address: new_address.address().clone(),
+                    },
+                    Some(new_address),
+                )
+            }
+        };
+        let payout = self
+            .prepare_payout(
+                profile,
+                &wallets[&wallet_name],
+                &payout_queues[&payout_queue_name],
+                id,
+                destination,
+                satoshis,
+                fee_from_amount,
+                false,
+                schedule,
+                external_id,
+                metadata,
+            )
+            .await?;
+        Ok((new_address, payout))
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    async fn prepare_payout(
+        &self,
+        profile: &Profile,
+        wallet: &Wallet,
+        payout_queue: &PayoutQueue,
+        id: PayoutId,
+        destination: PayoutDestination,
+        sats: Satoshis,
+        fee_from_amount: bool,
+        sweep: bool,
+        schedule: PayoutSchedule,
+        external_id: Option<String>,
+        metadata: Option<serde_json::Value>,
+    ) -> Result<(NewPayout, PayoutSubmittedParams), ApplicationError> {
         if self.config.security.is_blocked(&destination) {
             return Err(ApplicationError::DestinationBlocked(destination));
         }
         if !profile.is_destination_allowed(&destination) {
             return Err(ApplicationError::DestinationNotAllowed(destination));
         }
+        let (n_queued, sweep_queued) = self
+            .payouts
+            .unbatched_for_wallet(profile.account_id, payout_queue.id, wallet.id)
+            .await?;
+        if sweep_queued {
+            return Err(PayoutError::SweepPayoutQueued(wallet.id.to_string()).into());
+        }
+        let sats = if sweep {
+            if n_queued > 0 {
+                return Err(PayoutError::WalletHasQueuedPayouts(wallet.id.to_string()).into());
+            }
+            let spendable = self.utxos.spendable_value(wallet.keychain_ids()).await?;
+            if spendable <= Satoshis::ZERO {
+                return Err(PayoutError::NothingToSweep.into());
+            }
+            spendable
+        } else {
+            sats
+        };
 
         let mut builder = NewPayout::builder(id);
         builder
@@ -817,46 +1806,81 @@ impl App {
             .payout_queue_id(payout_queue.id)
             .destination(destination.clone())
             .satoshis(sats)
-            .metadata(metadata.clone());
+            .fee_from_amount(fee_from_amount)
+            .sweep(sweep)
+            .not_before(schedule.not_before)
+            .not_before_block_height(schedule.not_before_block_height)
+            .required_approvals(profile.required_approvals(sats))
+            .metadata(metadata);
         if let Some(external_id) = external_id.as_ref() {
             builder.external_id(external_id);
         }
         let new_payout = builder.build().expect("Couldn't build NewPayout");
-        let mut tx = self.pool.begin().await?;
-        let id = self.payouts.create_in_tx(&mut tx, new_payout).await?;
-        self.ledger
-            .payout_submitted(
-                tx,
-                id,
-                PayoutSubmittedParams {
-                    journal_id: wallet.journal_id,
-                    effective_outgoing_account_id: wallet.ledger_account_ids.effective_outgoing_id,
-                    external_id: external_id.unwrap_or_else(|| id.to_string()),
-                    meta: PayoutSubmittedMeta {
-                        account_id: profile.account_id,
-                        payout_id: id,
-                        payout_queue_id: payout_queue.id,
-                        wallet_id: wallet.id,
-                        profile_id: profile.id,
-                        satoshis: sats,
-                        destination,
-                    },
-                },
-            )
-            .await?;
-        let expected_time = if let Some(interval) = payout_queue.spawn_in() {
-            match job::next_attempt_of_queue(&self.pool, payout_queue.id).await {
-                Ok(Some(next_attempt)) => Some(next_attempt),
-                Ok(None) | Err(_) => Some(
-                    chrono::Utc::now()
-                        + chrono::Duration::from_std(interval)
-                            .expect("interval value will always be less than i64"),
-                ),
-            }
-        } else {
-            None
+        let params = PayoutSubmittedParams {
+            journal_id: wallet.journal_id,
+            effective_outgoing_account_id: wallet.ledger_account_ids.effective_outgoing_id,
+            external_id: external_id.unwrap_or_else(|| id.to_string()),
+            meta: PayoutSubmittedMeta {
+                account_id: profile.account_id,
+                payout_id: id,
+                payout_queue_id: payout_queue.id,
+                wallet_id: wallet.id,
+                profile_id: profile.id,
+                satoshis: sats,
+                destination,
+                fee_from_amount,
+            },
         };
-        Ok((id, expected_time))
+        Ok((new_payout, params))
+    }
+
+    /// Must be called after the new payout has been created in `tx` so that
+    /// it is included in the rolling window totals.
+    async fn enforce_spending_limits(
+        &self,
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        profile: &Profile,
+        wallet: &Wallet,
+        sats: Satoshis,
+    ) -> Result<(), ApplicationError> {
+        let mut locked = false;
+        let now = chrono::Utc::now();
+        for limit in profile.spending_limits(&wallet.name) {
+            // What could still be paid out in a single payout given the rolling windows
+            let mut allowance = limit.max_payout_sats;
+            for (window, duration, max) in limit.rolling_windows() {
+                if !locked {
+                    self.profiles
+                        .lock_for_spending_in_tx(tx, profile.id)
+                        .await?;
+                    locked = true;
+                }
+                let submitted = self
+                    .payouts
+                    .submitted_sats_since(
+                        tx,
+                        profile.account_id,
+                        profile.id,
+                        limit.wallet_name.as_ref().map(|_| wallet.id),
+                        now - duration,
+                    )
+                    .await?;
+                let remaining = (max - (submitted - sats)).max(Satoshis::ZERO);
+                if submitted > max {
+                    return Err(ApplicationError::SpendingLimitExceeded(window, remaining));
+                }
+                allowance = Some(allowance.map_or(remaining, |a| a.min(remaining)));
+            }
+            if let (Some(max), Some(allowance)) = (limit.max_payout_sats, allowance) {
+                if sats > max {
+                    return Err(ApplicationError::SpendingLimitExceeded(
+                        SpendingLimitWindow::Payout,
+                        allowance,
+                    ));
+                }
+            }
+        }
+        Ok(())
     }
 
     pub async fn cancel_payout(
@@ -867,7 +1891,7 @@ impl App {
         let mut tx = self.pool.begin().await?;
         let mut payout = self
             .payouts
-            .find_by_id_for_cancellation(&mut tx, profile.account_id, id)
+            .find_by_id_for_update(&mut tx, profile.account_id, id)
             .await?;
         payout.cancel_payout(profile.id)?;
         self.payouts.update(&mut tx, payout).await?;
@@ -937,6 +1961,7 @@ impl App {
         profile: &Profile,
         id: PayoutQueueId,
         new_description: Option<String>,
+        new_coin_selection: Option<CoinSelectionStrategy>,
         new_config: Option<PayoutQueueConfig>,
     ) -> Result<(), ApplicationError> {
         let mut payout_queue = self
@@ -946,7 +1971,11 @@ impl App {
         if let Some(desc) = new_description {
             payout_queue.update_description(desc)
         }
-        if let Some(config) = new_config {
+        if let Some(mut config) = new_config {
+            config.trigger.validate()?;
+            // The strategy is only replaced when it was explicitly requested
+            config.coin_selection =
+                new_coin_selection.unwrap_or(payout_queue.config.coin_selection);
             payout_queue.update_config(config)
         }
         self.payout_queues.update(payout_queue).await?;
@@ -982,6 +2011,45 @@ impl App {
         Ok((batch, payouts, signing_sessions))
     }
 
+    #[instrument(name = "app.bump_batch_fee", skip(self), err)]
+    pub async fn bump_batch_fee(
+        &self,
+        profile: &Profile,
+        batch_id: BatchId,
+        fee_rate_sat_per_vbyte: Option<f32>,
+    ) -> Result<(), ApplicationError> {
+        let batch = self
+            .batches
+            .find_by_id(profile.account_id, batch_id)
+            .await?;
+        let fee_rate = match fee_rate_sat_per_vbyte {
+            Some(rate) => bitcoin::FeeRate::from_sat_per_vb(rate),
+            None => {
+                let payout_queue = self
+                    .payout_queues
+                    .find_by_id(profile.account_id, batch.payout_queue_id)
+                    .await?;
+                self.fees_client
+                    .fee_rate(payout_queue.config.tx_priority)
+                    .await?
+            }
+        };
+        job::bump_batch_fee::prepare_replacement(
+            &self.pool,
+            &self.ledger,
+            &self.utxos,
+            &batch,
+            fee_rate,
+        )
+        .await??;
+        job::spawn_bump_batch_fee(
+            &self.pool,
+            (profile.account_id, batch_id, fee_rate_sat_per_vbyte),
+        )
+        .await?;
+        Ok(())
+    }
+
     #[instrument(name = "app.subscribe_all", skip(self), err)]
     pub async fn subscribe_all(
         &self,
@@ -1047,4 +2115,36 @@ impl App {
         });
         Ok(())
     }
+
+    #[instrument(name = "app.spawn_check_expiring_timelocks", skip_all, err)]
+    async fn spawn_check_expiring_timelocks(
+        pool: sqlx::PgPool,
+        delay: std::time::Duration,
+    ) -> Result<(), ApplicationError> {
+        tokio::spawn(async move {
+            loop {
+                let _ =
+                    job::spawn_check_expiring_timelocks(&pool, std::time::Duration::from_secs(1))
+                        .await;
+                tokio::time::sleep(delay).await;
+            }
+        });
+        Ok(())
+    }
+
+    #[instrument(name = "app.spawn_consolidate_all_wallets", skip_all, err)]
+    async fn spawn_consolidate_all_wallets(
+        pool: sqlx::PgPool,
+        delay: std::time::Duration,
+    ) -> Result<(), ApplicationError> {
+        tokio::spawn(async move {
+            loop {
+                let _ =
+                    job::spawn_consolidate_all_wallets(&pool, std::time::Duration::from_secs(1))
+                        .await;
+                tokio::time::sleep(delay).await;
+            }
+        });
+        Ok(())
+    }
 }
@@ -2,7 +2,11 @@ use derive_builder::Builder;
 
 use std::collections::HashMap;
 
-use crate::primitives::*;
+use super::error::BatchError;
+use crate::primitives::{bitcoin::FeeRate, *};
+
+/// Minimum increase of the fee rate required by BIP125 for a replacement to be relayed
+const MIN_RELAY_FEE_INCREMENT_SATS_PER_VBYTE: u64 = 1;
 
 pub struct Batch {
     pub id: BatchId,
@@ -20,6 +24,103 @@ impl Batch {
             .values()
             .all(|s| s.batch_created_ledger_tx_id.is_some())
     }
+
+    pub fn is_broadcast(&self) -> bool {
+        self.signed_tx.is_some()
+            && self
+                .wallet_summaries
+                .values()
+                .all(|s| s.batch_broadcast_ledger_tx_id.is_some())
+    }
+
+    pub fn total_fee_sats(&self) -> Satoshis {
+        self.wallet_summaries
+            .values()
+            .map(|s| s.total_fee_sats)
+            .sum()
+    }
+
+    pub fn current_fee_rate(&self) -> Option<FeeRate> {
+        self.signed_tx.as_ref().map(|tx| {
+            FeeRate::from_sat_per_vb(u64::from(self.total_fee_sats()) as f32 / tx.vsize() as f32)
+        })
+    }
+
+    /// Rebuilds the transaction paying the same payouts at (at least) the given fee rate.
+    /// The additional fee is deducted from the change outputs of the wallets
+    /// proportionally to the fees they were already paying.
+    pub fn replace_by_fee(&self, fee_rate: FeeRate) -> Result<BatchReplacement, BatchError> {
+        if !self.is_broadcast() {
+            return Err(BatchError::BatchNotBroadcast(self.id.to_string()));
+        }
+        let vsize = self.signed_tx.as_ref().expect("signed tx").vsize();
+        let current_fee = u64::from(self.total_fee_sats());
+        let cpfp_fee: u64 = self
+            .wallet_summaries
+            .values()
+            .map(|s| u64::from(s.cpfp_fee_sats))
+            .sum();
+        let new_fee = (fee_rate.fee_vb(vsize) + cpfp_fee)
+            .max(current_fee + MIN_RELAY_FEE_INCREMENT_SATS_PER_VBYTE * vsize as u64);
+        let mut additional_fee = new_fee - current_fee;
+
+        let mut unsigned_psbt = self.unsigned_psbt.clone();
+        for input in unsigned_psbt.unsigned_tx.input.iter_mut() {
+            // Inputs spent via a relative timelock already signal RBF
+            if !input.sequence.is_relative_lock_time() {
+                input.sequence = bitcoin::Sequence::ENABLE_RBF_NO_LOCKTIME;
+            }
+        }
+
+        let mut wallet_summaries = self.wallet_summaries.clone();
+        let mut summaries: Vec<_> = wallet_summaries.values_mut().collect();
+        summaries.sort_by_key(|s| s.wallet_id);
+        let n_summaries = summaries.len();
+        for (idx, summary) in summaries.into_iter().enumerate() {
+            let share = if idx == n_summaries - 1 {
+                additional_fee
+            } else {
+                ((new_fee - current_fee) as u128 * u64::from(summary.total_fee_sats) as u128
+                    / current_fee.max(1) as u128) as u64
+            };
+            additional_fee -= share;
+            let change_outpoint = summary
+                .change_outpoint
+                .ok_or_else(|| BatchError::InsufficientChangeForFeeBump(summary.wallet_id))?;
+            let output = &mut unsigned_psbt.unsigned_tx.output[change_outpoint.vout as usize];
+            if output.value < share + output.script_pubkey.dust_value().to_sat() {
+                return Err(BatchError::InsufficientChangeForFeeBump(summary.wallet_id));
+            }
+            output.value -= share;
+            summary.change_sats = Satoshis::from(output.value);
+            summary.total_fee_sats += Satoshis::from(share);
+            summary.batch_created_ledger_tx_id = None;
+            summary.batch_broadcast_ledger_tx_id = None;
+        }
+
+        let tx_id = unsigned_psbt.unsigned_tx.txid();
+        for summary in wallet_summaries.values_mut() {
+            if let Some(outpoint) = summary.change_outpoint.as_mut() {
+                outpoint.txid = tx_id;
+            }
+        }
+
+        Ok(BatchReplacement {
+            replaced_tx_id: self.bitcoin_tx_id,
+            tx_id,
+            total_fee_sats: Satoshis::from(new_fee),
+            unsigned_psbt,
+            wallet_summaries,
+        })
+    }
+}
+
+pub struct BatchReplacement {
+    pub replaced_tx_id: bitcoin::Txid,
+    pub tx_id: bitcoin::Txid,
+    pub total_fee_sats: Satoshis,
+    pub unsigned_psbt: bitcoin::psbt::PartiallySignedTransaction,
+    pub wallet_summaries: HashMap<WalletId, WalletSummary>,
 }
 
 #[derive(Builder, Clone)]
@@ -62,3 +163,113 @@ pub struct WalletSummary {
     pub batch_created_ledger_tx_id: Option<LedgerTransactionId>,
     pub batch_broadcast_ledger_tx_id: Option<LedgerTransactionId>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn broadcast_batch() -> Batch {
+        let wallet_id = WalletId::new();
+        let script: bitcoin::Script =
+            "bc1qwqdg6squsna38e46795at95yu9atm8azzmyvckulcc7kytlcckxswvvzej"
+                .parse::<bitcoin::Address>()
+                .unwrap()
+                .script_pubkey();
+        let tx = bitcoin::Transaction {
+            version: 2,
+            lock_time: bdk::bitcoin::PackedLockTime(0),
+            input: vec![bdk::bitcoin::TxIn {
+                previous_output: bitcoin::OutPoint {
+                    txid: "4010e27ff7dc6d9c66a5657e6b3d94b4c4e394d968398d16fefe4637463d194d"
+                        .parse()
+                        .unwrap(),
+                    vout: 0,
+                },
+                ..Default::default()
+            }],
+            output: vec![
+                bitcoin::TxOut {
+                    value: 50_000,
+                    script_pubkey: script.clone(),
+                },
+                bitcoin::TxOut {
+                    value: 100_000,
+                    script_pubkey: script,
+                },
+            ],
+        };
+        let bitcoin_tx_id = tx.txid();
+        let summary = WalletSummary {
+            wallet_id,
+            current_keychain_id: KeychainId::new(),
+            signing_keychains: vec![],
+            total_in_sats: Satoshis::from(151_000),
+            total_spent_sats: Satoshis::from(50_000),
+            total_fee_sats: Satoshis::from(1_000),
+            cpfp_fee_sats: Satoshis::ZERO,
+            cpfp_details: HashMap::new(),
+            change_sats: Satoshis::from(100_000),
+            change_address: None,
+            change_outpoint: Some(bitcoin::OutPoint {
+                txid: bitcoin_tx_id,
+                vout: 1,
+            }),
+            batch_created_ledger_tx_id: Some(LedgerTransactionId::new()),
+            batch_broadcast_ledger_tx_id: Some(LedgerTransactionId::new()),
+        };
+        Batch {
+            id: BatchId::new(),
+            account_id: AccountId::new(),
+            payout_queue_id: PayoutQueueId::new(),
+            bitcoin_tx_id,
+            wallet_summaries: std::iter::once((wallet_id, summary)).collect(),
+            unsigned_psbt: bitcoin::psbt::PartiallySignedTransaction::from_unsigned_tx(tx.clone())
+                .unwrap(),
+            signed_tx: Some(tx),
+        }
+    }
+
+    #[test]
+    fn replace_by_fee_deducts_fee_from_change() {
+        let batch = broadcast_batch();
+        let vsize = batch.signed_tx.as_ref().unwrap().vsize() as u64;
+        let replacement = batch
+            .replace_by_fee(FeeRate::from_sat_per_vb(20.0))
+            .unwrap();
+
+        let new_fee = 20 * vsize;
+        assert_eq!(replacement.total_fee_sats, Satoshis::from(new_fee));
+        assert_ne!(replacement.tx_id, batch.bitcoin_tx_id);
+        let outputs = &replacement.unsigned_psbt.unsigned_tx.output;
+        assert_eq!(outputs[0].value, 50_000);
+        assert_eq!(outputs[1].value, 100_000 - (new_fee - 1_000));
+        let summary = replacement.wallet_summaries.values().next().unwrap();
+        assert_eq!(summary.total_fee_sats, Satoshis::from(new_fee));
+        assert_eq!(summary.change_outpoint.unwrap().txid, replacement.tx_id);
+        assert!(summary.batch_created_ledger_tx_id.is_none());
+        assert!(replacement
+            .unsigned_psbt
+            .unsigned_tx
+            .input
+            .iter()
+            .all(|i| i.sequence.is_rbf()));
+    }
+
+    #[test]
+    fn replace_by_fee_requires_min_fee_increment() {
+        let batch = broadcast_batch();
+        let vsize = batch.signed_tx.as_ref().unwrap().vsize() as u64;
+        let replacement = batch.replace_by_fee(FeeRate::from_sat_per_vb(1.0)).unwrap();
+        assert_eq!(replacement.total_fee_sats, Satoshis::from(1_000 + vsize));
+    }
+
+    #[test]
+    fn replace_by_fee_fails_without_enough_change() {
+        let batch = broadcast_batch();
+        let result = batch.replace_by_fee(FeeRate::from_sat_per_vb(10_000.0));
+        assert!(matches!(
+            result,
+            Err(BatchError::InsufficientChangeForFeeBump(_))
+        ));
+    }
+}
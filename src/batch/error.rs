@@ -4,6 +4,18 @@ use thiserror::Error;
 pub enum BatchError {
     #[error("BatchError - Could not find batch with id: {0}")]
     BatchIdNotFound(String),
+    #[error("BatchError - Batch {0} has not been broadcast yet")]
+    BatchNotBroadcast(String),
+    #[error("BatchError - Batch {0} is already confirmed")]
+    BatchAlreadyConfirmed(String),
+    #[error("BatchError - Batch {0} has inputs that were unconfirmed when it was created")]
+    BatchHasUnsettledInputs(String),
+    #[error("BatchError - Change of batch {0} is already being spent")]
+    BatchChangeAlreadySpent(String),
+    #[error("BatchError - Fee rate of batch {0} is already at or above the requested rate")]
+    FeeRateNotIncreased(String),
+    #[error("BatchError - Wallet {0} does not have enough change to pay for the fee bump")]
+    InsufficientChangeForFeeBump(crate::primitives::WalletId),
     #[error("BatchError - Sqlx: {0}")]
     Sqlx(#[from] sqlx::Error),
     #[error("BatchError - EntityError: {0}")]
@@ -7,6 +7,12 @@ use std::{collections::HashMap, str::FromStr};
 use super::{entity::*, error::BatchError};
 use crate::primitives::{bitcoin::*, *};
 
+pub struct DroppedBatchInfo {
+    pub batch_dropped_ledger_tx_id: LedgerTxId,
+    pub replaced_batch_created_ledger_tx_id: LedgerTxId,
+    pub replaced_batch_broadcast_ledger_tx_id: LedgerTxId,
+}
+
 pub struct BatchInfo {
     pub id: BatchId,
     pub payout_queue_id: PayoutQueueId,
@@ -272,4 +278,107 @@ impl Batches {
             ledger_transaction_id,
         )))
     }
+
+    #[instrument(name = "batches.replace_in_tx", skip(self, tx, replacement))]
+    pub async fn replace_in_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        batch_id: BatchId,
+        replacement: &BatchReplacement,
+    ) -> Result<(), BatchError> {
+        sqlx::query!(
+            r#"UPDATE bria_batches
+               SET bitcoin_tx_id = $1, unsigned_psbt = $2, signed_tx = NULL, total_fee_sats = $3, modified_at = NOW()
+               WHERE id = $4"#,
+            replacement.tx_id.as_ref(),
+            bitcoin::consensus::encode::serialize(&replacement.unsigned_psbt),
+            i64::from(replacement.total_fee_sats),
+            batch_id as BatchId,
+        )
+        .execute(&mut **tx)
+        .await?;
+
+        for (wallet_id, summary) in replacement.wallet_summaries.iter() {
+            sqlx::query!(
+                r#"UPDATE bria_batch_wallet_summaries
+                   SET change_sats = $1, total_fee_sats = $2,
+                       replaced_batch_created_ledger_tx_id = batch_created_ledger_tx_id,
+                       replaced_batch_broadcast_ledger_tx_id = batch_broadcast_ledger_tx_id,
+                       batch_created_ledger_tx_id = NULL,
+                       batch_broadcast_ledger_tx_id = NULL,
+                       batch_dropped_ledger_tx_id = NULL,
+                       modified_at = NOW()
+                   WHERE batch_id = $3 AND wallet_id = $4"#,
+                i64::from(summary.change_sats),
+                i64::from(summary.total_fee_sats),
+                batch_id as BatchId,
+                wallet_id as &WalletId,
+            )
+            .execute(&mut **tx)
+            .await?;
+        }
+
+        Ok(())
+    }
+
+    #[instrument(name = "batches.set_batch_dropped_ledger_tx_id", skip(self))]
+    pub async fn set_batch_dropped_ledger_tx_id(
+        &self,
+        batch_id: BatchId,
+        wallet_id: WalletId,
+    ) -> Result<Option<(Transaction<'_, Postgres>, DroppedBatchInfo)>, BatchError> {
+        let mut tx = self.pool.begin().await?;
+        let ledger_transaction_id = LedgerTxId::new();
+        let row = sqlx::query!(
+            r#"UPDATE bria_batch_wallet_summaries
+               SET batch_dropped_ledger_tx_id = $1
+               WHERE wallet_id = $2 AND batch_id = $3
+                 AND batch_dropped_ledger_tx_id IS NULL
+                 AND replaced_batch_created_ledger_tx_id IS NOT NULL
+               RETURNING replaced_batch_created_ledger_tx_id as "created!", replaced_batch_broadcast_ledger_tx_id as "broadcast!""#,
+            ledger_transaction_id as LedgerTxId,
+            wallet_id as WalletId,
+            batch_id as BatchId,
+        )
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        Ok(row.map(|row| {
+            (
+                tx,
+                DroppedBatchInfo {
+                    batch_dropped_ledger_tx_id: ledger_transaction_id,
+                    replaced_batch_created_ledger_tx_id: LedgerTxId::from(row.created),
+                    replaced_batch_broadcast_ledger_tx_id: LedgerTxId::from(row.broadcast),
+                },
+            )
+        }))
+    }
+
+    #[instrument(name = "batches.list_unconfirmed_ids_built_before", skip(self))]
+    pub async fn list_unconfirmed_ids_built_before(
+        &self,
+        payout_queue_id: PayoutQueueId,
+        built_before: chrono::DateTime<chrono::Utc>,
+    ) -> Result<Vec<BatchId>, BatchError> {
+        let rows = sqlx::query!(
+            r#"SELECT b.id
+               FROM bria_batches b
+               WHERE b.payout_queue_id = $1 AND b.signed_tx IS NOT NULL AND b.modified_at < $2
+                 AND NOT EXISTS (
+                   SELECT 1 FROM bria_batch_wallet_summaries s
+                   WHERE s.batch_id = b.id AND s.batch_broadcast_ledger_tx_id IS NULL
+                 )
+                 AND EXISTS (
+                   SELECT 1 FROM bria_utxos u
+                   WHERE u.spending_batch_id = b.id AND u.spend_settled_ledger_tx_id IS NULL
+                 )"#,
+            payout_queue_id as PayoutQueueId,
+            built_before,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.into_iter().map(|row| BatchId::from(row.id)).collect())
+    }
 }
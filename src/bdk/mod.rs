@@ -4,3 +4,30 @@ pub(crate) mod pg;
 pub async fn last_sync_time(pool: &sqlx::PgPool) -> Result<u32, error::BdkError> {
     pg::SyncTimes::last_sync_time(pool).await
 }
+
+pub async fn is_tx_confirmed(
+    pool: &sqlx::PgPool,
+    tx_id: crate::primitives::bitcoin::Txid,
+) -> Result<bool, error::BdkError> {
+    pg::Transactions::is_confirmed(pool, tx_id).await
+}
+
+/// Forgets when the keychain was last synced and flags its transactions from `start_height`
+/// onwards (all of them if `None`) to be reconciled with bria again on the next sync.
+/// The electrum scan has no notion of a start height and always fetches the full history
+/// of every script, so `start_height` does not narrow down what is rediscovered.
+pub async fn reset_sync_state(
+    pool: &sqlx::PgPool,
+    keychain_id: crate::primitives::KeychainId,
+    start_height: Option<u32>,
+) -> Result<(), error::BdkError> {
+    let mut tx = pool.begin().await?;
+    pg::SyncTimes::new(keychain_id, pool.clone())
+        .delete(&mut tx)
+        .await?;
+    pg::Transactions::new(keychain_id, pool.clone())
+        .mark_as_unsynced_from_height(&mut tx, start_height)
+        .await?;
+    tx.commit().await?;
+    Ok(())
+}
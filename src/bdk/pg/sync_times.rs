@@ -1,5 +1,5 @@
 use bdk::database::SyncTime;
-use sqlx::PgPool;
+use sqlx::{PgPool, Postgres, Transaction};
 use uuid::Uuid;
 
 use crate::{bdk::error::BdkError, primitives::*};
@@ -45,6 +45,16 @@ impl SyncTimes {
         }))
     }
 
+    pub async fn delete(&self, tx: &mut Transaction<'_, Postgres>) -> Result<(), BdkError> {
+        sqlx::query!(
+            r#"DELETE FROM bdk_sync_times WHERE keychain_id = $1"#,
+            Uuid::from(self.keychain_id),
+        )
+        .execute(&mut **tx)
+        .await?;
+        Ok(())
+    }
+
     pub async fn last_sync_time(pool: &PgPool) -> Result<u32, BdkError> {
         let sync_time =
             sqlx::query!(r#"SELECT COALESCE(MAX(height), 0) as "height!" FROM bdk_sync_times"#,)
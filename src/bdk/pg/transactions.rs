@@ -11,6 +11,7 @@ pub struct UnsyncedTransaction {
     pub vsize: u64,
     pub total_utxo_in_sats: Satoshis,
     pub fee_sats: Satoshis,
+    pub previous_outputs: Vec<bitcoin::OutPoint>,
     pub inputs: Vec<(LocalUtxo, u32)>,
     pub outputs: Vec<(LocalUtxo, u32)>,
 }
@@ -94,6 +95,19 @@ impl Transactions {
         Ok(tx.map(|tx| serde_json::from_value(tx.details_json).unwrap()))
     }
 
+    pub async fn is_confirmed(pool: &PgPool, tx_id: bitcoin::Txid) -> Result<bool, BdkError> {
+        let row = sqlx::query!(
+            r#"SELECT EXISTS(
+                 SELECT 1 FROM bdk_transactions
+                 WHERE tx_id = $1 AND height IS NOT NULL AND deleted_at IS NULL
+               ) as "confirmed!""#,
+            tx_id.to_string(),
+        )
+        .fetch_one(pool)
+        .await?;
+        Ok(row.confirmed)
+    }
+
     #[instrument(name = "bdk_transactions.list", skip(self), fields(n_rows))]
     pub async fn list(&self) -> Result<Vec<TransactionDetails>, bdk::Error> {
         let txs = sqlx::query!(
@@ -151,6 +165,7 @@ impl Transactions {
         let mut tx_id = None;
         let mut confirmation_time = None;
         let mut vsize = 0;
+        let mut previous_outputs = Vec::new();
 
         let mut total_utxo_in_sats = Satoshis::ZERO;
         let mut fee_sats = Satoshis::ZERO;
@@ -167,7 +182,13 @@ impl Transactions {
                 let details: TransactionDetails = serde_json::from_value(row.details_json)?;
                 total_utxo_in_sats = Satoshis::from(details.sent);
                 fee_sats = Satoshis::from(details.fee.expect("Fee"));
-                vsize = details.transaction.expect("transaction").vsize() as u64;
+                let transaction = details.transaction.expect("transaction");
+                vsize = transaction.vsize() as u64;
+                previous_outputs = transaction
+                    .input
+                    .iter()
+                    .map(|input| input.previous_output)
+                    .collect();
                 confirmation_time = details.confirmation_time;
             }
         }
@@ -177,6 +198,7 @@ impl Transactions {
             fee_sats,
             confirmation_time,
             vsize,
+            previous_outputs,
             inputs,
             outputs,
         }))
@@ -264,6 +286,23 @@ impl Transactions {
         Ok(())
     }
 
+    #[instrument(name = "bdk_transactions.mark_as_unsynced_from_height", skip(self, tx))]
+    pub async fn mark_as_unsynced_from_height(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        start_height: Option<u32>,
+    ) -> Result<(), BdkError> {
+        sqlx::query!(
+            r#"UPDATE bdk_transactions SET synced_to_bria = false, modified_at = NOW()
+            WHERE keychain_id = $1 AND deleted_at IS NULL AND (height IS NULL OR height >= $2)"#,
+            self.keychain_id as KeychainId,
+            start_height.unwrap_or(0) as i32,
+        )
+        .execute(&mut **tx)
+        .await?;
+        Ok(())
+    }
+
     #[instrument(name = "bdk_transactions.mark_confirmed", skip(self))]
     pub async fn mark_confirmed(
         &self,
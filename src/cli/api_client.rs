@@ -4,6 +4,7 @@ use url::Url;
 use crate::{
     api::proto,
     primitives::{bitcoin, TxPriority},
+    wallet::CoinSelectionStrategy,
 };
 type ProtoClient = proto::bria_service_client::BriaServiceClient<tonic::transport::Channel>;
 
@@ -88,8 +89,84 @@ impl ApiClient {
         output_json(response)
     }
 
-    pub async fn create_profile_api_key(&self, profile_name: String) -> anyhow::Result<()> {
-        let request = tonic::Request::new(proto::CreateProfileApiKeyRequest { profile_name });
+    #[allow(clippy::too_many_arguments)]
+    pub async fn update_profile(
+        &self,
+        id: String,
+        name: Option<String>,
+        disabled: Option<bool>,
+        allowed_payout_addresses: Vec<String>,
+        limit_wallet: Option<String>,
+        max_payout_sats: Option<u64>,
+        max_sats_per_hour: Option<u64>,
+        max_sats_per_day: Option<u64>,
+    ) -> anyhow::Result<()> {
+        let update_limit =
+            max_payout_sats.is_some() || max_sats_per_hour.is_some() || max_sats_per_day.is_some();
+        let spending_policy = if allowed_payout_addresses.is_empty() && !update_limit {
+            None
+        } else {
+            // The policy is replaced as a whole so fields that weren't passed keep their current value
+            let request = tonic::Request::new(proto::ListProfilesRequest {});
+            let mut policy = self
+                .connect()
+                .await?
+                .list_profiles(self.inject_auth_token(request)?)
+                .await?
+                .into_inner()
+                .profiles
+                .into_iter()
+                .find(|profile| profile.id == id)
+                .context("Profile not found")?
+                .spending_policy
+                .unwrap_or_default();
+            if !allowed_payout_addresses.is_empty() {
+                policy.allowed_payout_addresses = allowed_payout_addresses;
+            }
+            if update_limit {
+                match policy
+                    .limits
+                    .iter_mut()
+                    .find(|limit| limit.wallet_name == limit_wallet)
+                {
+                    Some(limit) => {
+                        limit.max_payout_sats = max_payout_sats.or(limit.max_payout_sats);
+                        limit.max_sats_per_hour = max_sats_per_hour.or(limit.max_sats_per_hour);
+                        limit.max_sats_per_day = max_sats_per_day.or(limit.max_sats_per_day);
+                    }
+                    None => policy.limits.push(proto::SpendingLimit {
+                        wallet_name: limit_wallet,
+                        max_payout_sats,
+                        max_sats_per_hour,
+                        max_sats_per_day,
+                    }),
+                }
+            }
+            Some(policy)
+        };
+        let request = tonic::Request::new(proto::UpdateProfileRequest {
+            id,
+            spending_policy,
+            name,
+            disabled,
+        });
+        let response = self
+            .connect()
+            .await?
+            .update_profile(self.inject_auth_token(request)?)
+            .await?;
+        output_json(response)
+    }
+
+    pub async fn create_profile_api_key(
+        &self,
+        profile_name: String,
+        scopes: Vec<String>,
+    ) -> anyhow::Result<()> {
+        let request = tonic::Request::new(proto::CreateProfileApiKeyRequest {
+            profile_name,
+            scopes,
+        });
         let response = self
             .connect()
             .await?
@@ -98,6 +175,43 @@ impl ApiClient {
         output_json(response)
     }
 
+    pub async fn list_profile_api_keys(&self, profile_id: String) -> anyhow::Result<()> {
+        let request = tonic::Request::new(proto::ListProfileApiKeysRequest { profile_id });
+        let response = self
+            .connect()
+            .await?
+            .list_profile_api_keys(self.inject_auth_token(request)?)
+            .await?;
+        output_json(response)
+    }
+
+    pub async fn revoke_profile_api_key(&self, id: String) -> anyhow::Result<()> {
+        let request = tonic::Request::new(proto::RevokeProfileApiKeyRequest { id });
+        let response = self
+            .connect()
+            .await?
+            .revoke_profile_api_key(self.inject_auth_token(request)?)
+            .await?;
+        output_json(response)
+    }
+
+    pub async fn set_profile_api_key_expiry(
+        &self,
+        id: String,
+        expires_at: Option<chrono::DateTime<chrono::Utc>>,
+    ) -> anyhow::Result<()> {
+        let request = tonic::Request::new(proto::SetProfileApiKeyExpiryRequest {
+            id,
+            expires_at: expires_at.map(|time| time.timestamp() as u32),
+        });
+        let response = self
+            .connect()
+            .await?
+            .set_profile_api_key_expiry(self.inject_auth_token(request)?)
+            .await?;
+        output_json(response)
+    }
+
     pub async fn import_xpub(
         &self,
         name: String,
@@ -168,12 +282,16 @@ impl ApiClient {
         &self,
         name: String,
         config: impl Into<proto::keychain_config::Config>,
+        gap_limit: Option<u32>,
+        lookahead: Option<u32>,
     ) -> anyhow::Result<()> {
         let request = tonic::Request::new(proto::CreateWalletRequest {
             name,
             keychain_config: Some(proto::KeychainConfig {
                 config: Some(config.into()),
             }),
+            gap_limit,
+            lookahead,
         });
         let response = self
             .connect()
@@ -183,6 +301,25 @@ impl ApiClient {
         output_json(response)
     }
 
+    pub async fn rotate_wallet_keychain(
+        &self,
+        wallet_name: String,
+        config: impl Into<proto::keychain_config::Config>,
+    ) -> anyhow::Result<()> {
+        let request = tonic::Request::new(proto::RotateWalletKeychainRequest {
+            wallet_name,
+            keychain_config: Some(proto::KeychainConfig {
+                config: Some(config.into()),
+            }),
+        });
+        let response = self
+            .connect()
+            .await?
+            .rotate_wallet_keychain(self.inject_auth_token(request)?)
+            .await?;
+        output_json(response)
+    }
+
     pub async fn get_wallet_balance_summary(&self, wallet_name: String) -> anyhow::Result<()> {
         let request = tonic::Request::new(proto::GetWalletBalanceSummaryRequest { wallet_name });
         let response = self
@@ -193,6 +330,118 @@ impl ApiClient {
         output_json(response)
     }
 
+    pub async fn export_wallet(&self, wallet_name: String) -> anyhow::Result<()> {
+        let request = tonic::Request::new(proto::ExportWalletRequest { wallet_name });
+        let response = self
+            .connect()
+            .await?
+            .export_wallet(self.inject_auth_token(request)?)
+            .await?
+            .into_inner();
+        // Entries are shaped so that the descriptors array can be passed
+        // to `importdescriptors` as is. Timestamp 0 rescans from genesis and
+        // the range covers the gap limit so incoming funds beyond the last
+        // derived address are still picked up.
+        let gap_limit = response.gap_limit;
+        let descriptors: Vec<_> = response
+            .descriptors
+            .into_iter()
+            .map(|descriptor| {
+                let next_index = descriptor
+                    .last_derived_index
+                    .map(|idx| idx + 1)
+                    .unwrap_or(0);
+                serde_json::json!({
+                    "desc": descriptor.descriptor,
+                    "timestamp": 0,
+                    "active": descriptor.active,
+                    "internal": descriptor.internal,
+                    "range": [0, next_index + gap_limit],
+                    "next_index": next_index,
+                })
+            })
+            .collect();
+        output_value(&serde_json::json!({
+            "wallet_name": response.wallet_name,
+            "network": response.network,
+            "descriptors": descriptors,
+        }))
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub async fn update_wallet_config(
+        &self,
+        wallet_name: String,
+        settle_income_after_n_confs: Option<u32>,
+        settle_change_after_n_confs: Option<u32>,
+        gap_limit: Option<u32>,
+        lookahead: Option<u32>,
+        dust_threshold_sats: Option<u64>,
+        dust_sweep_max_fee_rate_sat_per_vb: Option<u64>,
+    ) -> anyhow::Result<()> {
+        let request = tonic::Request::new(proto::UpdateWalletConfigRequest {
+            wallet_name,
+            settle_income_after_n_confs,
+            settle_change_after_n_confs,
+            gap_limit,
+            lookahead,
+            dust_threshold_sats,
+            dust_sweep_max_fee_rate_sat_per_vb,
+        });
+        let response = self
+            .connect()
+            .await?
+            .update_wallet_config(self.inject_auth_token(request)?)
+            .await?;
+        output_json(response)
+    }
+
+    pub async fn set_wallet_consolidation_policy(
+        &self,
+        wallet_name: String,
+        payout_queue_name: Option<String>,
+        target_utxo_count: u32,
+        max_fee_rate_sat_per_vb: u64,
+        max_inputs_per_tx: u32,
+    ) -> anyhow::Result<()> {
+        let policy =
+            payout_queue_name.map(|payout_queue_name| proto::WalletConsolidationPolicyConfig {
+                payout_queue_name,
+                target_utxo_count,
+                max_fee_rate_sat_per_vb,
+                max_inputs_per_tx,
+            });
+        let request = tonic::Request::new(proto::SetWalletConsolidationPolicyRequest {
+            wallet_name,
+            policy,
+        });
+        let response = self
+            .connect()
+            .await?
+            .set_wallet_consolidation_policy(self.inject_auth_token(request)?)
+            .await?;
+        output_json(response)
+    }
+
+    pub async fn rescan_wallet(
+        &self,
+        wallet_name: String,
+        start_height: Option<u32>,
+        gap_limit: Option<u32>,
+    ) -> anyhow::Result<()> {
+        let request = tonic::Request::new(proto::RescanWalletRequest {
+            wallet_name,
+            start_height,
+            gap_limit,
+        });
+        let response = self
+            .connect()
+            .await?
+            .rescan_wallet(self.inject_auth_token(request)?)
+            .await?;
+        output_json(response)
+    }
+
     pub async fn get_account_balance_summary(&self) -> anyhow::Result<()> {
         let request = tonic::Request::new(proto::GetAccountBalanceSummaryRequest {});
         let response = self
@@ -295,6 +544,55 @@ impl ApiClient {
         output_json(response)
     }
 
+    pub async fn freeze_utxo(
+        &self,
+        wallet: String,
+        outpoint: String,
+        reason: String,
+    ) -> anyhow::Result<()> {
+        let request = tonic::Request::new(proto::FreezeUtxoRequest {
+            wallet_name: wallet,
+            outpoint,
+            reason,
+        });
+        let response = self
+            .connect()
+            .await?
+            .freeze_utxo(self.inject_auth_token(request)?)
+            .await?;
+        output_json(response)
+    }
+
+    pub async fn unfreeze_utxo(&self, wallet: String, outpoint: String) -> anyhow::Result<()> {
+        let request = tonic::Request::new(proto::UnfreezeUtxoRequest {
+            wallet_name: wallet,
+            outpoint,
+        });
+        let response = self
+            .connect()
+            .await?
+            .unfreeze_utxo(self.inject_auth_token(request)?)
+            .await?;
+        output_json(response)
+    }
+
+    pub async fn release_quarantined_utxo(
+        &self,
+        wallet: String,
+        outpoint: String,
+    ) -> anyhow::Result<()> {
+        let request = tonic::Request::new(proto::ReleaseQuarantinedUtxoRequest {
+            wallet_name: wallet,
+            outpoint,
+        });
+        let response = self
+            .connect()
+            .await?
+            .release_quarantined_utxo(self.inject_auth_token(request)?)
+            .await?;
+        output_json(response)
+    }
+
     #[allow(clippy::too_many_arguments)]
     pub async fn create_payout_queue(
         &self,
@@ -304,25 +602,42 @@ impl ApiClient {
         consolidate_deprecated_keychains: bool,
         interval_trigger: Option<u32>,
         manual_trigger: Option<bool>,
+        threshold_min_payouts: Option<u32>,
+        threshold_min_total_sats: Option<u64>,
+        schedule_cron: Option<String>,
+        schedule_timezone: Option<String>,
         cpfp_payouts_after_mins: Option<u32>,
         cpfp_payouts_after_blocks: Option<u32>,
+        bump_fee_after_mins: Option<u32>,
+        max_fee_rate_sat_per_vb: Option<u64>,
+        max_total_fee_sats: Option<u64>,
+        recovery: bool,
+        coin_selection: CoinSelectionStrategy,
     ) -> anyhow::Result<()> {
-        let tx_priority = match tx_priority {
-            TxPriority::NextBlock => proto::TxPriority::NextBlock as i32,
-            TxPriority::HalfHour => proto::TxPriority::HalfHour as i32,
-            TxPriority::OneHour => proto::TxPriority::OneHour as i32,
-        };
+        let (tx_priority, custom_fee_rate_sat_per_vb) = tx_priority_to_proto(tx_priority);
         let trigger = match (interval_trigger, manual_trigger) {
-            (Some(interval), None) | (Some(interval), Some(false)) => {
-                Some(proto::payout_queue_config::Trigger::IntervalSecs(interval))
+            (_, Some(true))
+                if threshold_min_payouts.is_some()
+                    || threshold_min_total_sats.is_some()
+                    || schedule_cron.is_some() =>
+            {
+                return Err(anyhow::anyhow!(
+                    "Invalid parameters: a manual_trigger cannot be combined with a threshold or schedule trigger"
+                ));
             }
-            (None, Some(true)) => Some(proto::payout_queue_config::Trigger::Manual(true)),
             (Some(_), Some(true)) => {
                 return Err(anyhow::anyhow!(
                     "Invalid parameters: you should provide either an interval_trigger or a manual_trigger"
                 ));
             }
-            _ => None,
+            (None, Some(true)) => Some(proto::payout_queue_config::Trigger::Manual(true)),
+            (interval, _) => payout_queue_trigger(
+                interval,
+                threshold_min_payouts,
+                threshold_min_total_sats,
+                schedule_cron,
+                schedule_timezone,
+            )?,
         };
 
         let config = proto::PayoutQueueConfig {
@@ -331,6 +646,12 @@ impl ApiClient {
             trigger,
             cpfp_payouts_after_mins,
             cpfp_payouts_after_blocks,
+            bump_fee_after_mins,
+            max_fee_rate_sat_per_vb,
+            max_total_fee_sats,
+            custom_fee_rate_sat_per_vb,
+            spending_path: spending_path_to_proto(recovery),
+            coin_selection: Some(proto::CoinSelectionStrategy::from(coin_selection) as i32),
         };
 
         let request = tonic::Request::new(proto::CreatePayoutQueueRequest {
@@ -348,8 +669,15 @@ impl ApiClient {
         output_json(response)
     }
 
-    pub async fn trigger_payout_queue(&self, name: String) -> anyhow::Result<()> {
-        let request = tonic::Request::new(proto::TriggerPayoutQueueRequest { name });
+    pub async fn trigger_payout_queue(
+        &self,
+        name: String,
+        ignore_fee_caps: bool,
+    ) -> anyhow::Result<()> {
+        let request = tonic::Request::new(proto::TriggerPayoutQueueRequest {
+            name,
+            ignore_fee_caps: Some(ignore_fee_caps),
+        });
         let response = self
             .connect()
             .await?
@@ -364,7 +692,16 @@ impl ApiClient {
         payout_queue_name: String,
         destination: String,
         satoshis: u64,
+        sweep: bool,
+        tx_priority: Option<TxPriority>,
     ) -> anyhow::Result<()> {
+        let (tx_priority, custom_fee_rate_sat_per_vb) = match tx_priority.map(tx_priority_to_proto)
+        {
+            Some((tx_priority, custom_fee_rate_sat_per_vb)) => {
+                (Some(tx_priority), custom_fee_rate_sat_per_vb)
+            }
+            None => (None, None),
+        };
         let destination = if let Ok(addr) = destination.parse::<bitcoin::Address>() {
             proto::estimate_payout_fee_request::Destination::OnchainAddress(addr.to_string())
         } else {
@@ -375,6 +712,9 @@ impl ApiClient {
             payout_queue_name,
             destination: Some(destination),
             satoshis,
+            tx_priority,
+            custom_fee_rate_sat_per_vb,
+            sweep,
         });
         let response = self
             .connect()
@@ -390,6 +730,10 @@ impl ApiClient {
         payout_queue_name: String,
         destination: String,
         satoshis: u64,
+        fee_from_amount: bool,
+        sweep: bool,
+        not_before: Option<chrono::DateTime<chrono::Utc>>,
+        not_before_block_height: Option<u32>,
         external_id: Option<String>,
         metadata: Option<serde_json::Value>,
     ) -> anyhow::Result<()> {
@@ -405,6 +749,10 @@ impl ApiClient {
             satoshis,
             external_id,
             metadata: metadata.map(serde_json::from_value).transpose()?,
+            fee_from_amount,
+            sweep,
+            not_before: not_before.map(|time| time.timestamp() as u32),
+            not_before_block_height,
         });
         let response = self
             .connect()
@@ -414,6 +762,44 @@ impl ApiClient {
         output_json(response)
     }
 
+    pub async fn submit_payouts(
+        &self,
+        payouts: Vec<super::payouts_file::PayoutFileEntry>,
+        atomic: bool,
+    ) -> anyhow::Result<()> {
+        let payouts = payouts
+            .into_iter()
+            .map(|payout| {
+                let destination = if let Ok(addr) = payout.destination.parse::<bitcoin::Address>() {
+                    proto::submit_payout_request::Destination::OnchainAddress(addr.to_string())
+                } else {
+                    proto::submit_payout_request::Destination::DestinationWalletName(
+                        payout.destination,
+                    )
+                };
+                Ok(proto::SubmitPayoutRequest {
+                    wallet_name: payout.wallet,
+                    payout_queue_name: payout.queue_name,
+                    destination: Some(destination),
+                    satoshis: payout.amount,
+                    external_id: payout.external_id,
+                    metadata: payout.metadata.map(serde_json::from_value).transpose()?,
+                    fee_from_amount: payout.fee_from_amount,
+                    sweep: false,
+                    not_before: None,
+                    not_before_block_height: None,
+                })
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        let request = tonic::Request::new(proto::SubmitPayoutsRequest { payouts, atomic });
+        let response = self
+            .connect()
+            .await?
+            .submit_payouts(self.inject_auth_token(request)?)
+            .await?;
+        output_json(response)
+    }
+
     pub async fn list_wallets(&self) -> anyhow::Result<()> {
         let request = tonic::Request::new(proto::ListWalletsRequest {});
         let response = self
@@ -475,6 +861,45 @@ impl ApiClient {
         output_json(response)
     }
 
+    pub async fn approve_payout(&self, id: String) -> anyhow::Result<()> {
+        let request = tonic::Request::new(proto::ApprovePayoutRequest { id });
+        let response = self
+            .connect()
+            .await?
+            .approve_payout(self.inject_auth_token(request)?)
+            .await?;
+        output_json(response)
+    }
+
+    pub async fn reject_payout(&self, id: String, reason: Option<String>) -> anyhow::Result<()> {
+        let request = tonic::Request::new(proto::RejectPayoutRequest { id, reason });
+        let response = self
+            .connect()
+            .await?
+            .reject_payout(self.inject_auth_token(request)?)
+            .await?;
+        output_json(response)
+    }
+
+    pub async fn update_payout_schedule(
+        &self,
+        id: String,
+        not_before: Option<chrono::DateTime<chrono::Utc>>,
+        not_before_block_height: Option<u32>,
+    ) -> anyhow::Result<()> {
+        let request = tonic::Request::new(proto::UpdatePayoutScheduleRequest {
+            id,
+            not_before: not_before.map(|time| time.timestamp() as u32),
+            not_before_block_height,
+        });
+        let response = self
+            .connect()
+            .await?
+            .update_payout_schedule(self.inject_auth_token(request)?)
+            .await?;
+        output_json(response)
+    }
+
     pub async fn list_payout_queues(&self) -> anyhow::Result<()> {
         let request = tonic::Request::new(proto::ListPayoutQueuesRequest {});
         let response = self
@@ -487,9 +912,13 @@ impl ApiClient {
             .payout_queues
             .into_iter()
             .map(|bg| {
-                let tx_priority = TxPriority::from(
-                    proto::TxPriority::try_from(bg.config.as_ref().unwrap().tx_priority).unwrap(),
-                );
+                let config = bg.config.as_ref().unwrap();
+                let tx_priority = match config.custom_fee_rate_sat_per_vb {
+                    Some(sat_per_vb) => format!("CustomSatPerVb({sat_per_vb})"),
+                    None => proto::TxPriority::try_from(config.tx_priority)
+                        .map(|p| p.as_str_name().to_string())
+                        .unwrap_or_default(),
+                };
                 let mut json = serde_json::to_value(bg).unwrap();
                 json.as_object_mut()
                     .unwrap()
@@ -497,7 +926,7 @@ impl ApiClient {
                     .unwrap()
                     .as_object_mut()
                     .unwrap()
-                    .insert("txPriority".to_string(), format!("{tx_priority:?}").into());
+                    .insert("txPriority".to_string(), tx_priority.into());
                 json
             })
             .collect();
@@ -519,19 +948,32 @@ impl ApiClient {
         tx_priority: Option<TxPriority>,
         consolidate_deprecated_keychains: Option<bool>,
         interval_trigger: Option<u32>,
+        threshold_min_payouts: Option<u32>,
+        threshold_min_total_sats: Option<u64>,
+        schedule_cron: Option<String>,
+        schedule_timezone: Option<String>,
         cpfp_payouts_after_mins: Option<u32>,
         cpfp_payouts_after_blocks: Option<u32>,
+        bump_fee_after_mins: Option<u32>,
+        max_fee_rate_sat_per_vb: Option<u64>,
+        max_total_fee_sats: Option<u64>,
+        recovery: bool,
+        coin_selection: Option<CoinSelectionStrategy>,
     ) -> anyhow::Result<()> {
-        let tx_priority = tx_priority.map(|priority| match priority {
-            TxPriority::NextBlock => proto::TxPriority::NextBlock as i32,
-            TxPriority::HalfHour => proto::TxPriority::HalfHour as i32,
-            TxPriority::OneHour => proto::TxPriority::OneHour as i32,
-        });
+        let tx_priority = tx_priority.map(tx_priority_to_proto);
 
-        let trigger = interval_trigger.map(proto::payout_queue_config::Trigger::IntervalSecs);
+        let trigger = payout_queue_trigger(
+            interval_trigger,
+            threshold_min_payouts,
+            threshold_min_total_sats,
+            schedule_cron,
+            schedule_timezone,
+        )?;
 
-        let config = if let (Some(tx_priority), Some(consolidate_deprecated_keychains)) =
-            (tx_priority, consolidate_deprecated_keychains)
+        let config = if let (
+            Some((tx_priority, custom_fee_rate_sat_per_vb)),
+            Some(consolidate_deprecated_keychains),
+        ) = (tx_priority, consolidate_deprecated_keychains)
         {
             Some(proto::PayoutQueueConfig {
                 tx_priority,
@@ -539,6 +981,13 @@ impl ApiClient {
                 trigger,
                 cpfp_payouts_after_mins,
                 cpfp_payouts_after_blocks,
+                bump_fee_after_mins,
+                max_fee_rate_sat_per_vb,
+                max_total_fee_sats,
+                custom_fee_rate_sat_per_vb,
+                spending_path: spending_path_to_proto(recovery),
+                coin_selection: coin_selection
+                    .map(|strategy| proto::CoinSelectionStrategy::from(strategy) as i32),
             })
         } else {
             None
@@ -566,6 +1015,19 @@ impl ApiClient {
         output_json(response)
     }
 
+    pub async fn bump_batch_fee(&self, id: String, fee_rate: Option<f32>) -> anyhow::Result<()> {
+        let request = tonic::Request::new(proto::BumpBatchFeeRequest {
+            id,
+            fee_rate_sat_per_vbyte: fee_rate,
+        });
+        let response = self
+            .connect()
+            .await?
+            .bump_batch_fee(self.inject_auth_token(request)?)
+            .await?;
+        output_json(response)
+    }
+
     pub async fn watch_events(
         &self,
         one_shot: bool,
@@ -596,6 +1058,65 @@ impl ApiClient {
 }
 
 fn output_json<T: serde::Serialize>(response: tonic::Response<T>) -> anyhow::Result<()> {
-    println!("{}", serde_json::to_string_pretty(&response.into_inner())?);
+    output_value(&response.into_inner())
+}
+
+fn output_value<T: serde::Serialize>(value: &T) -> anyhow::Result<()> {
+    println!("{}", serde_json::to_string_pretty(value)?);
     Ok(())
 }
+
+fn payout_queue_trigger(
+    interval_trigger: Option<u32>,
+    threshold_min_payouts: Option<u32>,
+    threshold_min_total_sats: Option<u64>,
+    schedule_cron: Option<String>,
+    schedule_timezone: Option<String>,
+) -> anyhow::Result<Option<proto::payout_queue_config::Trigger>> {
+    let threshold = threshold_min_payouts.is_some() || threshold_min_total_sats.is_some();
+    if let Some(cron) = schedule_cron {
+        if interval_trigger.is_some() || threshold {
+            return Err(anyhow::anyhow!(
+                "Invalid parameters: a schedule trigger cannot be combined with an interval or threshold trigger"
+            ));
+        }
+        return Ok(Some(proto::payout_queue_config::Trigger::Schedule(
+            proto::PayoutQueueScheduleTrigger {
+                cron,
+                timezone: schedule_timezone,
+            },
+        )));
+    }
+    if !threshold {
+        return Ok(interval_trigger.map(proto::payout_queue_config::Trigger::IntervalSecs));
+    }
+    Ok(Some(proto::payout_queue_config::Trigger::Threshold(
+        proto::PayoutQueueThresholdTrigger {
+            min_payouts: threshold_min_payouts,
+            min_total_sats: threshold_min_total_sats,
+            interval_secs: interval_trigger.unwrap_or(60),
+        },
+    )))
+}
+
+fn spending_path_to_proto(recovery: bool) -> i32 {
+    if recovery {
+        proto::SpendingPath::Recovery as i32
+    } else {
+        proto::SpendingPath::Primary as i32
+    }
+}
+
+fn tx_priority_to_proto(tx_priority: TxPriority) -> (i32, Option<f32>) {
+    match tx_priority {
+        TxPriority::NextBlock => (proto::TxPriority::NextBlock as i32, None),
+        TxPriority::HalfHour => (proto::TxPriority::HalfHour as i32, None),
+        TxPriority::OneHour => (proto::TxPriority::OneHour as i32, None),
+        TxPriority::Economy => (proto::TxPriority::Economy as i32, None),
+        TxPriority::Minimum => (proto::TxPriority::Minimum as i32, None),
+        TxPriority::CustomSatPerVb(fee_rate) => (
+            proto::TxPriority::Custom as i32,
+            Some(fee_rate.as_sat_per_vb()),
+        ),
+    }
+}
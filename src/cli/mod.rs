@@ -4,6 +4,7 @@ mod api_client;
 mod config;
 mod db;
 mod gen;
+mod payouts_file;
 
 use anyhow::Context;
 use clap::{Parser, Subcommand};
@@ -13,8 +14,9 @@ use url::Url;
 
 use crate::{
     dev_constants,
-    primitives::{bitcoin, TxPriority},
+    primitives::{bitcoin, CustomFeeRate, TxPriority},
     token_store,
+    wallet::CoinSelectionStrategy,
 };
 use config::*;
 
@@ -98,6 +100,41 @@ enum Command {
         #[clap(env = "BRIA_API_KEY", default_value = "")]
         api_key: String,
     },
+    /// Rename, disable or replace the spending policy of a profile
+    UpdateProfile {
+        #[clap(
+            short,
+            long,
+            value_parser,
+            default_value = "http://localhost:2742",
+            env = "BRIA_API_URL"
+        )]
+        url: Option<Url>,
+        #[clap(env = "BRIA_API_KEY", default_value = "")]
+        api_key: String,
+        #[clap(short, long)]
+        id: String,
+        #[clap(short, long)]
+        name: Option<String>,
+        /// Reject all api keys of the profile until it is enabled again
+        #[clap(long, conflicts_with = "enable")]
+        disable: bool,
+        #[clap(long)]
+        enable: bool,
+        /// Addresses the profile is allowed to send to (replaces the current allow-list)
+        #[clap(long = "allowed-payout-address")]
+        allowed_payout_addresses: Vec<String>,
+        /// Restrict the spending limits to payouts from this wallet.
+        /// Limits that aren't passed keep their current value
+        #[clap(long)]
+        limit_wallet: Option<String>,
+        #[clap(long)]
+        max_payout_sats: Option<u64>,
+        #[clap(long)]
+        max_sats_per_hour: Option<u64>,
+        #[clap(long)]
+        max_sats_per_day: Option<u64>,
+    },
     /// Generate a new Api Key for the given profile name
     GenApiKey {
         #[clap(
@@ -112,6 +149,57 @@ enum Command {
         api_key: String,
         #[clap(short, long)]
         profile: String,
+        /// Scopes granted to the key (eg. read, payouts:write) - defaults to all scopes of the calling key
+        #[clap(long = "scope")]
+        scopes: Vec<String>,
+    },
+    /// List the Api Keys of a profile
+    ListApiKeys {
+        #[clap(
+            short,
+            long,
+            value_parser,
+            default_value = "http://localhost:2742",
+            env = "BRIA_API_URL"
+        )]
+        url: Option<Url>,
+        #[clap(env = "BRIA_API_KEY", default_value = "")]
+        api_key: String,
+        #[clap(short, long)]
+        profile_id: String,
+    },
+    /// Revoke an Api Key
+    RevokeApiKey {
+        #[clap(
+            short,
+            long,
+            value_parser,
+            default_value = "http://localhost:2742",
+            env = "BRIA_API_URL"
+        )]
+        url: Option<Url>,
+        #[clap(env = "BRIA_API_KEY", default_value = "")]
+        api_key: String,
+        #[clap(short, long)]
+        id: String,
+    },
+    /// Set or clear the expiry of an Api Key
+    SetApiKeyExpiry {
+        #[clap(
+            short,
+            long,
+            value_parser,
+            default_value = "http://localhost:2742",
+            env = "BRIA_API_URL"
+        )]
+        url: Option<Url>,
+        #[clap(env = "BRIA_API_KEY", default_value = "")]
+        api_key: String,
+        #[clap(short, long)]
+        id: String,
+        /// Reject the key after this time (RFC 3339) - the key never expires if omitted
+        #[clap(long)]
+        expires_at: Option<chrono::DateTime<chrono::Utc>>,
     },
     /// Import an xpub
     ImportXpub {
@@ -197,6 +285,29 @@ enum Command {
         api_key: String,
         #[clap(short, long)]
         name: String,
+        /// Number of consecutive unused addresses after which sync stops scanning
+        #[clap(long)]
+        gap_limit: Option<u32>,
+        /// Number of addresses to derive ahead of the last one handed out
+        #[clap(long)]
+        lookahead: Option<u32>,
+        #[clap(subcommand)]
+        command: CreateWalletCommand,
+    },
+    /// Replace the keychain used for new addresses of a wallet
+    RotateWalletKeychain {
+        #[clap(
+            short,
+            long,
+            value_parser,
+            default_value = "http://localhost:2742",
+            env = "BRIA_API_URL"
+        )]
+        url: Option<Url>,
+        #[clap(env = "BRIA_API_KEY", default_value = "")]
+        api_key: String,
+        #[clap(short, long)]
+        wallet: String,
         #[clap(subcommand)]
         command: CreateWalletCommand,
     },
@@ -215,6 +326,100 @@ enum Command {
         #[clap(short, long)]
         wallet: String,
     },
+    /// Export the descriptors of a wallet in the format of Bitcoin Core's `listdescriptors`
+    ExportWallet {
+        #[clap(
+            short,
+            long,
+            value_parser,
+            default_value = "http://localhost:2742",
+            env = "BRIA_API_URL"
+        )]
+        url: Option<Url>,
+        #[clap(env = "BRIA_API_KEY", default_value = "")]
+        api_key: String,
+        #[clap(short, long)]
+        wallet: String,
+    },
+    /// Periodically consolidate the utxos of a wallet while fees are low
+    SetWalletConsolidationPolicy {
+        #[clap(
+            short,
+            long,
+            value_parser,
+            default_value = "http://localhost:2742",
+            env = "BRIA_API_URL"
+        )]
+        url: Option<Url>,
+        #[clap(env = "BRIA_API_KEY", default_value = "")]
+        api_key: String,
+        #[clap(short, long)]
+        wallet: String,
+        /// Payout queue the consolidation batches are attributed to (removes the policy if omitted)
+        #[clap(short = 'q', long = "queue-name")]
+        payout_queue_name: Option<String>,
+        /// Consolidate while the wallet holds more spendable utxos than this
+        #[clap(long, default_value = "100")]
+        target_utxo_count: u32,
+        /// Only consolidate while the fee rate estimate is at or below this value
+        #[clap(long = "max-fee-rate", default_value = "5")]
+        max_fee_rate_sat_per_vb: u64,
+        #[clap(long, default_value = "100")]
+        max_inputs_per_tx: u32,
+    },
+    /// Update the config of a wallet
+    UpdateWalletConfig {
+        #[clap(
+            short,
+            long,
+            value_parser,
+            default_value = "http://localhost:2742",
+            env = "BRIA_API_URL"
+        )]
+        url: Option<Url>,
+        #[clap(env = "BRIA_API_KEY", default_value = "")]
+        api_key: String,
+        #[clap(short, long)]
+        wallet: String,
+        #[clap(long)]
+        settle_income_after_n_confs: Option<u32>,
+        #[clap(long)]
+        settle_change_after_n_confs: Option<u32>,
+        /// Number of consecutive unused addresses after which sync stops scanning
+        #[clap(long)]
+        gap_limit: Option<u32>,
+        /// Number of addresses to derive ahead of the last one handed out
+        #[clap(long)]
+        lookahead: Option<u32>,
+        /// Income utxos below this value are kept apart as dust (0 disables)
+        #[clap(long)]
+        dust_threshold_sats: Option<u64>,
+        /// Sweep dust via the consolidation policy while fees are at or below this rate (0 disables)
+        #[clap(long)]
+        dust_sweep_max_fee_rate_sat_per_vb: Option<u64>,
+    },
+    /// Resync a wallet to rediscover utxos the regular sync missed
+    RescanWallet {
+        #[clap(
+            short,
+            long,
+            value_parser,
+            default_value = "http://localhost:2742",
+            env = "BRIA_API_URL"
+        )]
+        url: Option<Url>,
+        #[clap(env = "BRIA_API_KEY", default_value = "")]
+        api_key: String,
+        #[clap(short, long)]
+        wallet: String,
+        /// Reconcile transactions from this height onwards (defaults to all).
+        /// The electrum scan itself always covers the full address history.
+        #[clap(long)]
+        start_height: Option<u32>,
+        /// Number of consecutive unused addresses after which to stop scanning
+        #[clap(long)]
+        gap_limit: Option<u32>,
+    },
 
     AccountBalance {
         #[clap(
@@ -317,6 +522,62 @@ enum Command {
         #[clap(short, long)]
         wallet: String,
     },
+    /// Freeze a UTXO so that it is excluded from coin selection
+    FreezeUtxo {
+        #[clap(
+            short,
+            long,
+            value_parser,
+            default_value = "http://localhost:2742",
+            env = "BRIA_API_URL"
+        )]
+        url: Option<Url>,
+        #[clap(env = "BRIA_API_KEY", default_value = "")]
+        api_key: String,
+        #[clap(short, long)]
+        wallet: String,
+        /// The outpoint of the UTXO in the format <txid>:<vout>
+        #[clap(short, long)]
+        outpoint: String,
+        #[clap(short, long)]
+        reason: String,
+    },
+    /// Unfreeze a previously frozen UTXO
+    UnfreezeUtxo {
+        #[clap(
+            short,
+            long,
+            value_parser,
+            default_value = "http://localhost:2742",
+            env = "BRIA_API_URL"
+        )]
+        url: Option<Url>,
+        #[clap(env = "BRIA_API_KEY", default_value = "")]
+        api_key: String,
+        #[clap(short, long)]
+        wallet: String,
+        /// The outpoint of the UTXO in the format <txid>:<vout>
+        #[clap(short, long)]
+        outpoint: String,
+    },
+    /// Release a UTXO that was quarantined for being received from a blocked address
+    ReleaseQuarantinedUtxo {
+        #[clap(
+            short,
+            long,
+            value_parser,
+            default_value = "http://localhost:2742",
+            env = "BRIA_API_URL"
+        )]
+        url: Option<Url>,
+        #[clap(env = "BRIA_API_KEY", default_value = "")]
+        api_key: String,
+        #[clap(short, long)]
+        wallet: String,
+        /// The outpoint of the UTXO in the format <txid>:<vout>
+        #[clap(short, long)]
+        outpoint: String,
+    },
     /// Create a Payuot Queue
     CreatePayoutQueue {
         #[clap(
@@ -334,17 +595,46 @@ enum Command {
         #[clap(short, long)]
         description: Option<String>,
         #[clap(short = 'p', long, default_value = "next-block")]
-        tx_priority: TxPriority,
+        tx_priority: TxPriorityArg,
+        /// Fee rate in sat/vB when the tx priority is custom
+        #[clap(long = "custom-fee-rate", required_if_eq("tx_priority", "custom"))]
+        custom_fee_rate_sat_per_vb: Option<f32>,
         #[clap(short = 'c', long = "consolidate", default_value = "true")]
         consolidate_deprecated_keychains: bool,
         #[clap(short = 'i', long = "interval-trigger")]
         interval_trigger: Option<u32>,
         #[clap(short = 'm', long = "manual")]
         manual_trigger: Option<bool>,
+        /// Process the queue once this many payouts are pending (interval-trigger is the fallback)
+        #[clap(long = "threshold-min-payouts")]
+        threshold_min_payouts: Option<u32>,
+        /// Process the queue once this many sats are pending (interval-trigger is the fallback)
+        #[clap(long = "threshold-min-total-sats")]
+        threshold_min_total_sats: Option<u64>,
+        /// Process the queue on a cron schedule (eg. "0 9,17 * * Mon-Fri")
+        #[clap(long = "schedule-cron")]
+        schedule_cron: Option<String>,
+        /// Timezone the schedule-cron is evaluated in
+        #[clap(long = "schedule-timezone", requires = "schedule_cron")]
+        schedule_timezone: Option<String>,
         #[clap(long = "cpfp-after-mins")]
         cpfp_payouts_after_mins: Option<u32>,
         #[clap(long = "cpfp-after-blocks")]
         cpfp_payouts_after_blocks: Option<u32>,
+        #[clap(long = "bump-fee-after-mins")]
+        bump_fee_after_mins: Option<u32>,
+        /// Defer batching while the fee rate estimate is above this value
+        #[clap(long = "max-fee-rate")]
+        max_fee_rate_sat_per_vb: Option<u64>,
+        /// Defer batching while the total fee of the batch would be above this value
+        #[clap(long = "max-total-fee-sats")]
+        max_total_fee_sats: Option<u64>,
+        /// Spend from policy wallets via their timelocked recovery branches
+        #[clap(long, default_value = "false")]
+        recovery: bool,
+        /// One of branch-and-bound, largest-first, oldest-first or privacy
+        #[clap(long = "coin-selection", default_value = "branch-and-bound")]
+        coin_selection: CoinSelectionStrategy,
     },
     /// Trigger Payout Queue
     TriggerPayoutQueue {
@@ -354,6 +644,9 @@ enum Command {
         api_key: String,
         #[clap(short, long)]
         name: String,
+        /// Batch even if the fee caps configured on the queue are exceeded
+        #[clap(long)]
+        ignore_fee_caps: bool,
     },
     EstimatePayoutFee {
         #[clap(
@@ -372,8 +665,17 @@ enum Command {
         queue_name: String,
         #[clap(short, long)]
         destination: String,
-        #[clap(short, long)]
-        amount: u64,
+        #[clap(short, long, required_unless_present = "sweep")]
+        amount: Option<u64>,
+        /// Estimate sweeping the wallet's entire settled balance
+        #[clap(long, conflicts_with = "amount")]
+        sweep: bool,
+        /// Override the tx priority of the queue
+        #[clap(short = 'p', long)]
+        tx_priority: Option<TxPriorityArg>,
+        /// Fee rate in sat/vB when the tx priority is custom
+        #[clap(long = "custom-fee-rate", required_if_eq("tx_priority", "custom"))]
+        custom_fee_rate_sat_per_vb: Option<f32>,
     },
     SubmitPayout {
         #[clap(
@@ -392,13 +694,44 @@ enum Command {
         queue_name: String,
         #[clap(short, long)]
         destination: String,
-        #[clap(short, long)]
-        amount: u64,
+        #[clap(short, long, required_unless_present = "sweep")]
+        amount: Option<u64>,
+        /// Deduct the payout's share of the batch fee from the amount
+        #[clap(long)]
+        fee_from_amount: bool,
+        /// Drain the wallet's entire settled balance to the destination
+        #[clap(long, conflicts_with_all = ["amount", "fee_from_amount"])]
+        sweep: bool,
+        /// Do not batch the payout before this time (RFC 3339)
+        #[clap(long)]
+        not_before: Option<chrono::DateTime<chrono::Utc>>,
+        /// Do not batch the payout before this block height
+        #[clap(long)]
+        not_before_block_height: Option<u32>,
         #[clap(short, long)]
         external_id: Option<String>,
         #[clap(short, long, value_parser = parse_json)]
         metadata: Option<serde_json::Value>,
     },
+    /// Submit Payouts in bulk from a CSV or JSON file
+    SubmitPayouts {
+        #[clap(
+            short,
+            long,
+            value_parser,
+            default_value = "http://localhost:2742",
+            env = "BRIA_API_URL"
+        )]
+        url: Option<Url>,
+        #[clap(env = "BRIA_API_KEY", default_value = "")]
+        api_key: String,
+        /// CSV (with header row) or JSON file with wallet, queue_name, destination, amount and optional fee_from_amount, external_id and metadata
+        #[clap(short, long)]
+        file: PathBuf,
+        /// Do not submit any payout if one of them fails validation
+        #[clap(long)]
+        atomic: bool,
+    },
     /// List pending Payouts
     ListPayouts {
         #[clap(
@@ -445,6 +778,59 @@ enum Command {
         #[clap(short = 'i', long)]
         id: String,
     },
+    /// Approve a Payout that requires approval
+    ApprovePayout {
+        #[clap(
+            short,
+            long,
+            value_parser,
+            default_value = "http://localhost:2742",
+            env = "BRIA_API_URL"
+        )]
+        url: Option<Url>,
+        #[clap(env = "BRIA_API_KEY", default_value = "")]
+        api_key: String,
+        #[clap(short = 'i', long)]
+        id: String,
+    },
+    /// Reject a Payout that requires approval
+    RejectPayout {
+        #[clap(
+            short,
+            long,
+            value_parser,
+            default_value = "http://localhost:2742",
+            env = "BRIA_API_URL"
+        )]
+        url: Option<Url>,
+        #[clap(env = "BRIA_API_KEY", default_value = "")]
+        api_key: String,
+        #[clap(short = 'i', long)]
+        id: String,
+        #[clap(short, long)]
+        reason: Option<String>,
+    },
+    /// Reschedule a Payout that has not been batched yet
+    UpdatePayoutSchedule {
+        #[clap(
+            short,
+            long,
+            value_parser,
+            default_value = "http://localhost:2742",
+            env = "BRIA_API_URL"
+        )]
+        url: Option<Url>,
+        #[clap(env = "BRIA_API_KEY", default_value = "")]
+        api_key: String,
+        #[clap(short = 'i', long)]
+        id: String,
+        /// Do not batch the payout before this time (RFC 3339)
+        #[clap(long)]
+        not_before: Option<chrono::DateTime<chrono::Utc>>,
+        /// Do not batch the payout before this block height
+        #[clap(long)]
+        not_before_block_height: Option<u32>,
+    },
     /// List Wallets
     ListWallets {
         #[clap(
@@ -491,15 +877,40 @@ enum Command {
         #[clap(short, long)]
         description: Option<String>,
         #[clap(short = 'p', long, default_value = "next-block")]
-        tx_priority: Option<TxPriority>,
+        tx_priority: Option<TxPriorityArg>,
+        /// Fee rate in sat/vB when the tx priority is custom
+        #[clap(long = "custom-fee-rate", required_if_eq("tx_priority", "custom"))]
+        custom_fee_rate_sat_per_vb: Option<f32>,
         #[clap(short = 'c', long = "consolidate", default_value = "true")]
         consolidate_deprecated_keychains: Option<bool>,
         #[clap(long = "interval-trigger")]
         interval_trigger: Option<u32>,
+        #[clap(long = "threshold-min-payouts")]
+        threshold_min_payouts: Option<u32>,
+        #[clap(long = "threshold-min-total-sats")]
+        threshold_min_total_sats: Option<u64>,
+        #[clap(long = "schedule-cron")]
+        schedule_cron: Option<String>,
+        #[clap(long = "schedule-timezone", requires = "schedule_cron")]
+        schedule_timezone: Option<String>,
         #[clap(long = "cpfp-after-mins")]
         cpfp_payouts_after_mins: Option<u32>,
         #[clap(long = "cpfp-after-blocks")]
         cpfp_payouts_after_blocks: Option<u32>,
+        #[clap(long = "bump-fee-after-mins")]
+        bump_fee_after_mins: Option<u32>,
+        /// Defer batching while the fee rate estimate is above this value
+        #[clap(long = "max-fee-rate")]
+        max_fee_rate_sat_per_vb: Option<u64>,
+        /// Defer batching while the total fee of the batch would be above this value
+        #[clap(long = "max-total-fee-sats")]
+        max_total_fee_sats: Option<u64>,
+        /// Spend from policy wallets via their timelocked recovery branches
+        #[clap(long, default_value = "false")]
+        recovery: bool,
+        /// One of branch-and-bound, largest-first, oldest-first or privacy (keeps the current strategy if omitted)
+        #[clap(long = "coin-selection")]
+        coin_selection: Option<CoinSelectionStrategy>,
     },
     /// Get Batch details
     GetBatch {
@@ -516,6 +927,24 @@ enum Command {
         #[clap(short, long)]
         batch_id: String,
     },
+    /// Replace an unconfirmed batch with one paying a higher fee
+    BumpBatchFee {
+        #[clap(
+            short,
+            long,
+            value_parser,
+            default_value = "http://localhost:2742",
+            env = "BRIA_API_URL"
+        )]
+        url: Option<Url>,
+        #[clap(env = "BRIA_API_KEY", default_value = "")]
+        api_key: String,
+        #[clap(short, long)]
+        batch_id: String,
+        /// The fee rate to use (defaults to the estimate for the queue's tx priority)
+        #[clap(short, long)]
+        fee_rate: Option<f32>,
+    },
     /// Watch or fetch events
     WatchEvents {
         #[clap(
@@ -615,6 +1044,24 @@ enum CreateWalletCommand {
         #[clap(short, long)]
         threshold: u32,
     },
+    /// Initialize the wallet via a single key taproot descriptor
+    Tr {
+        /// The xpub-ref or xpub to use
+        #[clap(short, long)]
+        xpub: String,
+        /// If an xpub is being imported, the derivation path to use
+        #[clap(short, long)]
+        derivation: Option<String>,
+    },
+    /// Initialize the wallet by compiling a miniscript policy referencing xpubs by name
+    Policy {
+        /// The policy eg. 'or(pk(main),and(pk(backup),older(4320)))'
+        #[clap(short, long)]
+        policy: String,
+        /// Compile the policy to a taproot instead of a segwit v0 descriptor
+        #[clap(long, default_value = "false")]
+        taproot: bool,
+    },
 }
 
 #[derive(Subcommand)]
@@ -733,13 +1180,67 @@ pub async fn run() -> anyhow::Result<()> {
             let client = api_client(cli.bria_home, url, api_key);
             client.list_profiles().await?;
         }
+        Command::UpdateProfile {
+            url,
+            api_key,
+            id,
+            name,
+            disable,
+            enable,
+            allowed_payout_addresses,
+            limit_wallet,
+            max_payout_sats,
+            max_sats_per_hour,
+            max_sats_per_day,
+        } => {
+            let client = api_client(cli.bria_home, url, api_key);
+            let disabled = match (disable, enable) {
+                (true, _) => Some(true),
+                (_, true) => Some(false),
+                _ => None,
+            };
+            client
+                .update_profile(
+                    id,
+                    name,
+                    disabled,
+                    allowed_payout_addresses,
+                    limit_wallet,
+                    max_payout_sats,
+                    max_sats_per_hour,
+                    max_sats_per_day,
+                )
+                .await?;
+        }
         Command::GenApiKey {
             url,
             api_key,
             profile,
+            scopes,
         } => {
             let client = api_client(cli.bria_home, url, api_key);
-            client.create_profile_api_key(profile).await?;
+            client.create_profile_api_key(profile, scopes).await?;
+        }
+        Command::ListApiKeys {
+            url,
+            api_key,
+            profile_id,
+        } => {
+            let client = api_client(cli.bria_home, url, api_key);
+            client.list_profile_api_keys(profile_id).await?;
+        }
+        Command::RevokeApiKey { url, api_key, id } => {
+            let client = api_client(cli.bria_home, url, api_key);
+            client.revoke_profile_api_key(id).await?;
+        }
+        Command::SetApiKeyExpiry {
+            url,
+            api_key,
+            id,
+            expires_at,
+        } => {
+            let client = api_client(cli.bria_home, url, api_key);
+            client.set_profile_api_key_expiry(id, expires_at).await?;
         }
         Command::ImportXpub {
             url,
@@ -776,10 +1277,23 @@ pub async fn run() -> anyhow::Result<()> {
             url,
             api_key,
             name,
+            gap_limit,
+            lookahead,
             command,
         } => {
             let client = api_client(cli.bria_home, url, api_key);
-            client.create_wallet(name, command).await?;
+            client
+                .create_wallet(name, command, gap_limit, lookahead)
+                .await?;
+        }
+        Command::RotateWalletKeychain {
+            url,
+            api_key,
+            wallet,
+            command,
+        } => {
+            let client = api_client(cli.bria_home, url, api_key);
+            client.rotate_wallet_keychain(wallet, command).await?;
         }
         Command::WalletBalance {
             url,
@@ -789,6 +1303,70 @@ pub async fn run() -> anyhow::Result<()> {
             let client = api_client(cli.bria_home, url, api_key);
             client.get_wallet_balance_summary(name).await?;
         }
+        Command::ExportWallet {
+            url,
+            api_key,
+            wallet,
+        } => {
+            let client = api_client(cli.bria_home, url, api_key);
+            client.export_wallet(wallet).await?;
+        }
+        Command::SetWalletConsolidationPolicy {
+            url,
+            api_key,
+            wallet,
+            payout_queue_name,
+            target_utxo_count,
+            max_fee_rate_sat_per_vb,
+            max_inputs_per_tx,
+        } => {
+            let client = api_client(cli.bria_home, url, api_key);
+            client
+                .set_wallet_consolidation_policy(
+                    wallet,
+                    payout_queue_name,
+                    target_utxo_count,
+                    max_fee_rate_sat_per_vb,
+                    max_inputs_per_tx,
+                )
+                .await?;
+        }
+        Command::UpdateWalletConfig {
+            url,
+            api_key,
+            wallet,
+            settle_income_after_n_confs,
+            settle_change_after_n_confs,
+            gap_limit,
+            lookahead,
+            dust_threshold_sats,
+            dust_sweep_max_fee_rate_sat_per_vb,
+        } => {
+            let client = api_client(cli.bria_home, url, api_key);
+            client
+                .update_wallet_config(
+                    wallet,
+                    settle_income_after_n_confs,
+                    settle_change_after_n_confs,
+                    gap_limit,
+                    lookahead,
+                    dust_threshold_sats,
+                    dust_sweep_max_fee_rate_sat_per_vb,
+                )
+                .await?;
+        }
+        Command::RescanWallet {
+            url,
+            api_key,
+            wallet,
+            start_height,
+            gap_limit,
+        } => {
+            let client = api_client(cli.bria_home, url, api_key);
+            client
+                .rescan_wallet(wallet, start_height, gap_limit)
+                .await?;
+        }
         Command::AccountBalance { url, api_key } => {
             let client = api_client(cli.bria_home, url, api_key);
             client.get_account_balance_summary().await?;
@@ -840,35 +1418,87 @@ pub async fn run() -> anyhow::Result<()> {
             let client = api_client(cli.bria_home, url, api_key);
             client.list_utxos(wallet).await?;
         }
+        Command::FreezeUtxo {
+            url,
+            api_key,
+            wallet,
+            outpoint,
+            reason,
+        } => {
+            let client = api_client(cli.bria_home, url, api_key);
+            client.freeze_utxo(wallet, outpoint, reason).await?;
+        }
+        Command::UnfreezeUtxo {
+            url,
+            api_key,
+            wallet,
+            outpoint,
+        } => {
+            let client = api_client(cli.bria_home, url, api_key);
+            client.unfreeze_utxo(wallet, outpoint).await?;
+        }
+        Command::ReleaseQuarantinedUtxo {
+            url,
+            api_key,
+            wallet,
+            outpoint,
+        } => {
+            let client = api_client(cli.bria_home, url, api_key);
+            client.release_quarantined_utxo(wallet, outpoint).await?;
+        }
         Command::CreatePayoutQueue {
             url,
             api_key,
             name,
             description,
             tx_priority,
+            custom_fee_rate_sat_per_vb,
             consolidate_deprecated_keychains,
             interval_trigger,
             manual_trigger,
+            threshold_min_payouts,
+            threshold_min_total_sats,
+            schedule_cron,
+            schedule_timezone,
             cpfp_payouts_after_mins,
             cpfp_payouts_after_blocks,
+            bump_fee_after_mins,
+            max_fee_rate_sat_per_vb,
+            max_total_fee_sats,
+            recovery,
+            coin_selection,
         } => {
             let client = api_client(cli.bria_home, url, api_key);
             client
                 .create_payout_queue(
                     name,
                     description,
-                    tx_priority,
+                    tx_priority_from_args(tx_priority, custom_fee_rate_sat_per_vb)?,
                     consolidate_deprecated_keychains,
                     interval_trigger,
                     manual_trigger,
+                    threshold_min_payouts,
+                    threshold_min_total_sats,
+                    schedule_cron,
+                    schedule_timezone,
                     cpfp_payouts_after_mins,
                     cpfp_payouts_after_blocks,
+                    bump_fee_after_mins,
+                    max_fee_rate_sat_per_vb,
+                    max_total_fee_sats,
+                    recovery,
+                    coin_selection,
                 )
                 .await?;
         }
-        Command::TriggerPayoutQueue { url, api_key, name } => {
+        Command::TriggerPayoutQueue {
+            url,
+            api_key,
+            name,
+            ignore_fee_caps,
+        } => {
             let client = api_client(cli.bria_home, url, api_key);
-            client.trigger_payout_queue(name).await?;
+            client.trigger_payout_queue(name, ignore_fee_caps).await?;
         }
         Command::EstimatePayoutFee {
             url,
@@ -877,10 +1507,22 @@ pub async fn run() -> anyhow::Result<()> {
             queue_name: group_name,
             destination,
             amount,
+            sweep,
+            tx_priority,
+            custom_fee_rate_sat_per_vb,
         } => {
             let client = api_client(cli.bria_home, url, api_key);
             client
-                .estimate_payout_fee(wallet, group_name, destination, amount)
+                .estimate_payout_fee(
+                    wallet,
+                    group_name,
+                    destination,
+                    amount.unwrap_or_default(),
+                    sweep,
+                    tx_priority
+                        .map(|p| tx_priority_from_args(p, custom_fee_rate_sat_per_vb))
+                        .transpose()?,
+                )
                 .await?;
         }
         Command::SubmitPayout {
@@ -890,6 +1532,10 @@ pub async fn run() -> anyhow::Result<()> {
             queue_name: group_name,
             destination,
             amount,
+            fee_from_amount,
+            sweep,
+            not_before,
+            not_before_block_height,
             external_id,
             metadata,
         } => {
@@ -899,12 +1545,26 @@ pub async fn run() -> anyhow::Result<()> {
                     wallet,
                     group_name,
                     destination,
-                    amount,
+                    amount.unwrap_or_default(),
+                    fee_from_amount,
+                    sweep,
+                    not_before,
+                    not_before_block_height,
                     external_id,
                     metadata,
                 )
                 .await?;
         }
+        Command::SubmitPayouts {
+            url,
+            api_key,
+            file,
+            atomic,
+        } => {
+            let payouts = payouts_file::read_payouts_file(file)?;
+            let client = api_client(cli.bria_home, url, api_key);
+            client.submit_payouts(payouts, atomic).await?;
+        }
         Command::ListPayouts {
             url,
             api_key,
@@ -926,6 +1586,31 @@ pub async fn run() -> anyhow::Result<()> {
             let client = api_client(cli.bria_home, url, api_key);
             client.cancel_payout(id).await?;
         }
+        Command::ApprovePayout { url, api_key, id } => {
+            let client = api_client(cli.bria_home, url, api_key);
+            client.approve_payout(id).await?;
+        }
+        Command::RejectPayout {
+            url,
+            api_key,
+            id,
+            reason,
+        } => {
+            let client = api_client(cli.bria_home, url, api_key);
+            client.reject_payout(id, reason).await?;
+        }
+        Command::UpdatePayoutSchedule {
+            url,
+            api_key,
+            id,
+            not_before,
+            not_before_block_height,
+        } => {
+            let client = api_client(cli.bria_home, url, api_key);
+            client
+                .update_payout_schedule(id, not_before, not_before_block_height)
+                .await?;
+        }
         Command::ListWallets { url, api_key } => {
             let client = api_client(cli.bria_home, url, api_key);
             client.list_wallets().await?;
@@ -940,21 +1625,42 @@ pub async fn run() -> anyhow::Result<()> {
             id,
             description,
             tx_priority,
+            custom_fee_rate_sat_per_vb,
             consolidate_deprecated_keychains,
             interval_trigger,
+            threshold_min_payouts,
+            threshold_min_total_sats,
+            schedule_cron,
+            schedule_timezone,
             cpfp_payouts_after_mins,
             cpfp_payouts_after_blocks,
+            bump_fee_after_mins,
+            max_fee_rate_sat_per_vb,
+            max_total_fee_sats,
+            recovery,
+            coin_selection,
         } => {
             let client = api_client(cli.bria_home, url, api_key);
             client
                 .update_payout_queue(
                     id,
                     description,
-                    tx_priority,
+                    tx_priority
+                        .map(|p| tx_priority_from_args(p, custom_fee_rate_sat_per_vb))
+                        .transpose()?,
                     consolidate_deprecated_keychains,
                     interval_trigger,
+                    threshold_min_payouts,
+                    threshold_min_total_sats,
+                    schedule_cron,
+                    schedule_timezone,
                     cpfp_payouts_after_mins,
                     cpfp_payouts_after_blocks,
+                    bump_fee_after_mins,
+                    max_fee_rate_sat_per_vb,
+                    max_total_fee_sats,
+                    recovery,
+                    coin_selection,
                 )
                 .await?;
         }
@@ -970,6 +1676,15 @@ pub async fn run() -> anyhow::Result<()> {
             let client = api_client(cli.bria_home, url, api_key);
             client.get_batch(batch_id).await?;
         }
+        Command::BumpBatchFee {
+            url,
+            api_key,
+            batch_id,
+            fee_rate,
+        } => {
+            let client = api_client(cli.bria_home, url, api_key);
+            client.bump_batch_fee(batch_id, fee_rate).await?;
+        }
         Command::WatchEvents {
             url,
             api_key,
@@ -1048,6 +1763,38 @@ async fn run_cmd(
     reason
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+enum TxPriorityArg {
+    NextBlock,
+    HalfHour,
+    OneHour,
+    Economy,
+    Minimum,
+    Custom,
+}
+
+fn tx_priority_from_args(
+    tx_priority: TxPriorityArg,
+    custom_fee_rate_sat_per_vb: Option<f32>,
+) -> anyhow::Result<TxPriority> {
+    let tx_priority = match (tx_priority, custom_fee_rate_sat_per_vb) {
+        (TxPriorityArg::Custom, Some(sat_per_vb)) => TxPriority::CustomSatPerVb(
+            CustomFeeRate::from_sat_per_vb(sat_per_vb)
+                .context("--custom-fee-rate must be a positive number")?,
+        ),
+        (TxPriorityArg::Custom, None) => {
+            anyhow::bail!("--tx-priority custom requires --custom-fee-rate")
+        }
+        (_, Some(_)) => anyhow::bail!("--custom-fee-rate requires --tx-priority custom"),
+        (TxPriorityArg::NextBlock, None) => TxPriority::NextBlock,
+        (TxPriorityArg::HalfHour, None) => TxPriority::HalfHour,
+        (TxPriorityArg::OneHour, None) => TxPriority::OneHour,
+        (TxPriorityArg::Economy, None) => TxPriority::Economy,
+        (TxPriorityArg::Minimum, None) => TxPriority::Minimum,
+    };
+    Ok(tx_priority)
+}
+
 fn read_to_base64(path: PathBuf) -> anyhow::Result<String> {
     use std::fs::File;
     use std::io::BufReader;
@@ -1118,6 +1865,18 @@ impl From<CreateWalletCommand> for crate::api::proto::keychain_config::Config {
                     threshold,
                 })
             }
+            CreateWalletCommand::Tr { xpub, derivation } => Config::Tr(Tr {
+                xpub,
+                derivation_path: derivation,
+            }),
+            CreateWalletCommand::Policy { policy, taproot } => Config::Policy(Policy {
+                policy,
+                script_type: if taproot {
+                    crate::api::proto::PolicyScriptType::Tr as i32
+                } else {
+                    crate::api::proto::PolicyScriptType::Wsh as i32
+                },
+            }),
         }
     }
 }
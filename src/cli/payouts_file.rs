@@ -0,0 +1,106 @@
+use anyhow::Context;
+use serde::Deserialize;
+use std::path::Path;
+
+#[derive(Debug, Deserialize, PartialEq)]
+pub struct PayoutFileEntry {
+    pub wallet: String,
+    pub queue_name: String,
+    pub destination: String,
+    pub amount: u64,
+    #[serde(default)]
+    pub fee_from_amount: bool,
+    pub external_id: Option<String>,
+    pub metadata: Option<serde_json::Value>,
+}
+
+/// Reads payouts from a JSON array, or from CSV with a header row when the
+/// file does not end in `.json`. CSV files can not carry metadata.
+pub fn read_payouts_file(file_path: impl AsRef<Path>) -> anyhow::Result<Vec<PayoutFileEntry>> {
+    let path = file_path.as_ref();
+    let s = std::fs::read_to_string(path).context("Couldn't read file")?;
+    if path.extension().map(|ext| ext == "json").unwrap_or(false) {
+        Ok(serde_json::from_str(&s).context("Couldn't parse json payouts")?)
+    } else {
+        parse_csv(&s)
+    }
+}
+
+fn parse_csv(contents: &str) -> anyhow::Result<Vec<PayoutFileEntry>> {
+    let mut lines = contents
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty());
+    let header: Vec<_> = lines
+        .next()
+        .context("Missing csv header")?
+        .split(',')
+        .map(str::trim)
+        .collect();
+    let column = |name: &str| header.iter().position(|col| *col == name);
+    let wallet = column("wallet").context("Missing 'wallet' column")?;
+    let queue_name = column("queue_name").context("Missing 'queue_name' column")?;
+    let destination = column("destination").context("Missing 'destination' column")?;
+    let amount = column("amount").context("Missing 'amount' column")?;
+    let fee_from_amount = column("fee_from_amount");
+    let external_id = column("external_id");
+
+    let mut entries = Vec::new();
+    for (idx, line) in lines.enumerate() {
+        let values: Vec<_> = line.split(',').map(str::trim).collect();
+        let value = |col: Option<usize>| {
+            col.and_then(|col| values.get(col))
+                .copied()
+                .filter(|v| !v.is_empty())
+        };
+        let row = idx + 2;
+        entries.push(PayoutFileEntry {
+            wallet: value(Some(wallet))
+                .with_context(|| format!("Missing wallet on line {row}"))?
+                .to_string(),
+            queue_name: value(Some(queue_name))
+                .with_context(|| format!("Missing queue_name on line {row}"))?
+                .to_string(),
+            destination: value(Some(destination))
+                .with_context(|| format!("Missing destination on line {row}"))?
+                .to_string(),
+            amount: value(Some(amount))
+                .with_context(|| format!("Missing amount on line {row}"))?
+                .parse()
+                .with_context(|| format!("Couldn't parse amount on line {row}"))?,
+            fee_from_amount: value(fee_from_amount)
+                .map(|v| v.parse())
+                .transpose()
+                .with_context(|| format!("Couldn't parse fee_from_amount on line {row}"))?
+                .unwrap_or(false),
+            external_id: value(external_id).map(ToString::to_string),
+            metadata: None,
+        });
+    }
+    Ok(entries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_csv() {
+        let csv = "wallet,queue_name,destination,amount,external_id\n\
+                   hot,payroll,bcrt1qxyz,10000,alice\n\
+                   hot,payroll,cold,20000,\n";
+        let entries = parse_csv(csv).unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].amount, 10000);
+        assert_eq!(entries[0].external_id.as_deref(), Some("alice"));
+        assert_eq!(entries[1].destination, "cold");
+        assert!(entries[1].external_id.is_none());
+        assert!(!entries[1].fee_from_amount);
+    }
+
+    #[test]
+    fn csv_requires_amount() {
+        let csv = "wallet,queue_name,destination\nhot,payroll,cold\n";
+        assert!(parse_csv(csv).is_err());
+    }
+}
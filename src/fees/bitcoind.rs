@@ -0,0 +1,60 @@
+use async_trait::async_trait;
+use bdk::FeeRate;
+use bitcoincore_rpc::{Auth, Client, RpcApi};
+use serde::{Deserialize, Serialize};
+
+use std::sync::Arc;
+
+use super::{error::FeeEstimationError, r#trait::*};
+use crate::primitives::TxPriority;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BitcoindFeeEstimatorConfig {
+    pub endpoint: String,
+    pub rpc_user: String,
+    pub rpc_password: String,
+}
+
+pub struct BitcoindFeeEstimator {
+    client: Arc<Client>,
+}
+
+impl BitcoindFeeEstimator {
+    pub fn new(config: BitcoindFeeEstimatorConfig) -> Result<Self, FeeEstimationError> {
+        let auth = Auth::UserPass(config.rpc_user, config.rpc_password);
+        let client = Client::new(&config.endpoint, auth)
+            .map_err(|e| FeeEstimationError::InvalidConfig(format!("bitcoind: {e}")))?;
+        Ok(Self {
+            client: Arc::new(client),
+        })
+    }
+}
+
+#[async_trait]
+impl FeeEstimator for BitcoindFeeEstimator {
+    fn name(&self) -> &'static str {
+        "bitcoind"
+    }
+
+    async fn fee_rate(&self, priority: TxPriority) -> Result<FeeRate, FeeEstimationError> {
+        if let Some(fee_rate) = priority.custom_fee_rate() {
+            return Ok(fee_rate);
+        }
+        let client = Arc::clone(&self.client);
+        let conf_target = priority.n_blocks() as u16;
+        let result =
+            tokio::task::spawn_blocking(move || client.estimate_smart_fee(conf_target, None))
+                .await
+                .expect("estimatesmartfee task panicked")
+                .map_err(|e| FeeEstimationError::Bitcoind(e.to_string()))?;
+        match result.fee_rate {
+            Some(fee_rate) => Ok(FeeRate::from_btc_per_kvb(fee_rate.to_btc() as f32)),
+            None => Err(FeeEstimationError::Bitcoind(
+                result
+                    .errors
+                    .map(|errors| errors.join(", "))
+                    .unwrap_or_else(|| "no fee rate returned".to_string()),
+            )),
+        }
+    }
+}
@@ -0,0 +1,119 @@
+use bdk::FeeRate;
+use tokio::sync::RwLock;
+use tracing::instrument;
+
+use std::{collections::HashMap, sync::Arc, time::Instant};
+
+use super::{
+    bitcoind::*, config::*, electrum::*, error::FeeEstimationError, mempool_space::*, r#trait::*,
+    static_rate::*,
+};
+use crate::primitives::TxPriority;
+
+#[derive(Clone)]
+pub struct FeesClient {
+    estimators: Arc<Vec<Box<dyn FeeEstimator>>>,
+    cache: Arc<RwLock<HashMap<TxPriority, (FeeRate, Instant)>>>,
+    cache_ttl: std::time::Duration,
+    cache_max_age: std::time::Duration,
+}
+
+impl std::fmt::Debug for FeesClient {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FeesClient")
+            .field(
+                "estimators",
+                &self.estimators.iter().map(|e| e.name()).collect::<Vec<_>>(),
+            )
+            .field("cache_ttl", &self.cache_ttl)
+            .field("cache_max_age", &self.cache_max_age)
+            .finish()
+    }
+}
+
+impl FeesClient {
+    pub fn new(config: FeesConfig, electrum_url: &str) -> Result<Self, FeeEstimationError> {
+        let estimators = if config.estimators.is_empty() {
+            vec![FeeEstimatorConfig::MempoolSpace(config.mempool_space)]
+        } else {
+            config.estimators
+        };
+        let estimators = estimators
+            .into_iter()
+            .map(
+                |estimator| -> Result<Box<dyn FeeEstimator>, FeeEstimationError> {
+                    Ok(match estimator {
+                        FeeEstimatorConfig::MempoolSpace(cfg) => {
+                            Box::new(MempoolSpaceClient::new(cfg))
+                        }
+                        FeeEstimatorConfig::Bitcoind(cfg) => {
+                            Box::new(BitcoindFeeEstimator::new(cfg)?)
+                        }
+                        FeeEstimatorConfig::Electrum(cfg) => {
+                            Box::new(ElectrumFeeEstimator::new(cfg, electrum_url))
+                        }
+                        FeeEstimatorConfig::Static(cfg) => Box::new(StaticFeeEstimator::new(cfg)),
+                    })
+                },
+            )
+            .collect::<Result<_, _>>()?;
+        Ok(Self::from_estimators(
+            estimators,
+            config.cache_ttl,
+            config.cache_max_age,
+        ))
+    }
+
+    pub fn from_estimators(
+        estimators: Vec<Box<dyn FeeEstimator>>,
+        cache_ttl: std::time::Duration,
+        cache_max_age: std::time::Duration,
+    ) -> Self {
+        Self {
+            estimators: Arc::new(estimators),
+            cache: Arc::new(RwLock::new(HashMap::new())),
+            cache_ttl,
+            cache_max_age,
+        }
+    }
+
+    #[instrument(name = "fees.fee_rate", skip(self), fields(estimator, cached), err)]
+    pub async fn fee_rate(&self, priority: TxPriority) -> Result<FeeRate, FeeEstimationError> {
+        if let Some(fee_rate) = priority.custom_fee_rate() {
+            return Ok(fee_rate);
+        }
+        let span = tracing::Span::current();
+        let cached = self.cache.read().await.get(&priority).copied();
+        if let Some((fee_rate, estimated_at)) = cached {
+            if estimated_at.elapsed() <= self.cache_ttl {
+                span.record("cached", true);
+                return Ok(fee_rate);
+            }
+        }
+        let mut last_err = FeeEstimationError::NoEstimatorConfigured;
+        for estimator in self.estimators.iter() {
+            match estimator.fee_rate(priority).await {
+                Ok(fee_rate) => {
+                    span.record("estimator", estimator.name());
+                    self.cache
+                        .write()
+                        .await
+                        .insert(priority, (fee_rate, Instant::now()));
+                    return Ok(fee_rate);
+                }
+                Err(e) => {
+                    tracing::warn!(estimator = estimator.name(), error = %e, "fee estimator failed");
+                    last_err = e;
+                }
+            }
+        }
+        match cached {
+            Some((fee_rate, estimated_at)) if estimated_at.elapsed() <= self.cache_max_age => {
+                tracing::warn!("all fee estimators failed, serving stale cached estimate");
+                span.record("cached", true);
+                Ok(fee_rate)
+            }
+            _ => Err(last_err),
+        }
+    }
+}
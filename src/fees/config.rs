@@ -0,0 +1,51 @@
+use serde::{Deserialize, Serialize};
+
+use super::{bitcoind::*, electrum::*, mempool_space::*, static_rate::*};
+
+#[serde_with::serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FeesConfig {
+    #[serde(default)]
+    pub mempool_space: MempoolSpaceConfig,
+    /// Estimators are queried in order until one succeeds.
+    /// Defaults to only querying mempool_space.
+    #[serde(default)]
+    pub estimators: Vec<FeeEstimatorConfig>,
+    /// How long an estimate is served from the cache before the estimators are queried again.
+    /// Kept short so that fee spikes are picked up quickly.
+    #[serde_as(as = "serde_with::DurationSeconds<u64>")]
+    #[serde(default = "default_cache_ttl")]
+    pub cache_ttl: std::time::Duration,
+    /// How old a cached estimate may be to still be served when every estimator fails.
+    #[serde_as(as = "serde_with::DurationSeconds<u64>")]
+    #[serde(default = "default_cache_max_age")]
+    pub cache_max_age: std::time::Duration,
+}
+
+impl Default for FeesConfig {
+    fn default() -> Self {
+        Self {
+            mempool_space: MempoolSpaceConfig::default(),
+            estimators: Vec::new(),
+            cache_ttl: default_cache_ttl(),
+            cache_max_age: default_cache_max_age(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum FeeEstimatorConfig {
+    MempoolSpace(MempoolSpaceConfig),
+    Bitcoind(BitcoindFeeEstimatorConfig),
+    Electrum(ElectrumFeeEstimatorConfig),
+    Static(StaticFeeEstimatorConfig),
+}
+
+fn default_cache_ttl() -> std::time::Duration {
+    std::time::Duration::from_secs(30)
+}
+
+fn default_cache_max_age() -> std::time::Duration {
+    std::time::Duration::from_secs(600)
+}
@@ -0,0 +1,87 @@
+use async_trait::async_trait;
+use bdk::FeeRate;
+use electrum_client::{Client, ConfigBuilder, ElectrumApi};
+use serde::{Deserialize, Serialize};
+
+use std::sync::Arc;
+
+use super::{error::FeeEstimationError, r#trait::*};
+use crate::primitives::TxPriority;
+
+#[serde_with::serde_as]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ElectrumFeeEstimatorConfig {
+    /// Defaults to the electrum_url of the blockchain config
+    #[serde(default)]
+    pub url: Option<String>,
+    #[serde_as(as = "Option<serde_with::DurationSeconds<u64>>")]
+    #[serde(default)]
+    pub timeout: Option<std::time::Duration>,
+}
+
+pub struct ElectrumFeeEstimator {
+    url: String,
+    timeout: std::time::Duration,
+    client: tokio::sync::OnceCell<Arc<Client>>,
+}
+
+impl ElectrumFeeEstimator {
+    pub fn new(config: ElectrumFeeEstimatorConfig, default_url: &str) -> Self {
+        Self {
+            url: config.url.unwrap_or_else(|| default_url.to_string()),
+            timeout: config
+                .timeout
+                .unwrap_or_else(|| std::time::Duration::from_secs(10)),
+            client: tokio::sync::OnceCell::new(),
+        }
+    }
+
+    /// Connects on first use so an unreachable server doesn't prevent startup
+    async fn client(&self) -> Result<Arc<Client>, FeeEstimationError> {
+        let client = self
+            .client
+            .get_or_try_init(|| {
+                let url = self.url.clone();
+                let timeout = u8::try_from(self.timeout.as_secs()).unwrap_or(u8::MAX);
+                async move {
+                    tokio::task::spawn_blocking(move || {
+                        Client::from_config(
+                            &url,
+                            ConfigBuilder::new()
+                                .timeout(Some(timeout))
+                                .expect("couldn't set electrum timeout")
+                                .build(),
+                        )
+                        .map(Arc::new)
+                    })
+                    .await
+                    .expect("electrum connect task panicked")
+                }
+            })
+            .await?;
+        Ok(Arc::clone(client))
+    }
+}
+
+#[async_trait]
+impl FeeEstimator for ElectrumFeeEstimator {
+    fn name(&self) -> &'static str {
+        "electrum"
+    }
+
+    async fn fee_rate(&self, priority: TxPriority) -> Result<FeeRate, FeeEstimationError> {
+        if let Some(fee_rate) = priority.custom_fee_rate() {
+            return Ok(fee_rate);
+        }
+        let client = self.client().await?;
+        let n_blocks = priority.n_blocks();
+        let btc_per_kvb = tokio::task::spawn_blocking(move || client.estimate_fee(n_blocks))
+            .await
+            .expect("blockchain.estimatefee task panicked")?;
+        // The server returns -1 if it doesn't have enough information to estimate
+        if btc_per_kvb <= 0.0 {
+            return Err(FeeEstimationError::EstimateUnavailable(self.name()));
+        }
+        Ok(FeeRate::from_btc_per_kvb(btc_per_kvb as f32))
+    }
+}
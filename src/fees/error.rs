@@ -4,4 +4,14 @@ use thiserror::Error;
 pub enum FeeEstimationError {
     #[error("FeeEstimationError - FeeEstimation: {0}")]
     FeeEstimation(#[from] reqwest::Error),
+    #[error("FeeEstimationError - Bitcoind: {0}")]
+    Bitcoind(String),
+    #[error("FeeEstimationError - Electrum: {0}")]
+    Electrum(#[from] electrum_client::Error),
+    #[error("FeeEstimationError - EstimateUnavailable: {0} could not provide an estimate")]
+    EstimateUnavailable(&'static str),
+    #[error("FeeEstimationError - NoEstimatorConfigured")]
+    NoEstimatorConfigured,
+    #[error("FeeEstimationError - InvalidConfig: {0}")]
+    InvalidConfig(String),
 }
@@ -1,7 +1,8 @@
+use async_trait::async_trait;
 use bdk::FeeRate;
 use serde::{Deserialize, Serialize};
 
-use super::error::FeeEstimationError;
+use super::{error::FeeEstimationError, r#trait::*};
 use crate::primitives::TxPriority;
 
 #[derive(Debug, Clone, Deserialize)]
@@ -10,28 +11,38 @@ struct RecommendedFeesResponse {
     fastest_fee: u64,
     half_hour_fee: u64,
     hour_fee: u64,
-    // economy_fee: u64,
-    // minimum_fee: u64,
+    economy_fee: u64,
+    minimum_fee: u64,
 }
 
 #[derive(Clone, Debug)]
 pub struct MempoolSpaceClient {
     config: MempoolSpaceConfig,
+    client: reqwest::Client,
 }
 
 impl MempoolSpaceClient {
     pub fn new(config: MempoolSpaceConfig) -> Self {
-        Self { config }
-    }
-
-    pub async fn fee_rate(&self, priority: TxPriority) -> Result<FeeRate, FeeEstimationError> {
         let client = reqwest::Client::builder()
-            .timeout(self.config.timeout)
+            .timeout(config.timeout)
             .build()
             .expect("Could not build reqwest client");
+        Self { config, client }
+    }
+}
 
+#[async_trait]
+impl FeeEstimator for MempoolSpaceClient {
+    fn name(&self) -> &'static str {
+        "mempool_space"
+    }
+
+    async fn fee_rate(&self, priority: TxPriority) -> Result<FeeRate, FeeEstimationError> {
+        if let Some(fee_rate) = priority.custom_fee_rate() {
+            return Ok(fee_rate);
+        }
         let url = format!("{}{}", self.config.url, "/api/v1/fees/recommended");
-        let resp = client.get(&url).send().await?;
+        let resp = self.client.get(&url).send().await?;
         let fee_estimations: RecommendedFeesResponse = resp.json().await?;
 
         match priority {
@@ -42,6 +53,9 @@ impl MempoolSpaceClient {
             TxPriority::NextBlock => {
                 Ok(FeeRate::from_sat_per_vb(fee_estimations.fastest_fee as f32))
             }
+            TxPriority::Economy => Ok(FeeRate::from_sat_per_vb(fee_estimations.economy_fee as f32)),
+            TxPriority::Minimum => Ok(FeeRate::from_sat_per_vb(fee_estimations.minimum_fee as f32)),
+            TxPriority::CustomSatPerVb(fee_rate) => Ok(fee_rate.fee_rate()),
         }
     }
 }
@@ -1,11 +1,23 @@
+mod bitcoind;
+mod client;
+mod config;
+mod electrum;
 pub mod error;
 mod mempool_space;
+mod static_rate;
+mod r#trait;
 
 use bdk::bitcoin::{LockTime, Transaction, TxOut};
 use std::collections::HashMap;
 
 use crate::primitives::*;
+pub use bitcoind::*;
+pub use client::*;
+pub use config::*;
+pub use electrum::*;
 pub use mempool_space::*;
+pub use r#trait::*;
+pub use static_rate::*;
 
 use error::FeeEstimationError;
 
@@ -14,15 +26,16 @@ use error::FeeEstimationError;
 const TXIN_BASE_WEIGHT: usize = (32 + 4 + 4) * 4;
 
 pub async fn fees_to_encumber(
-    mempool_space: &MempoolSpaceClient,
+    fees_client: &FeesClient,
     satisfaction_weight: usize,
 ) -> Result<Satoshis, FeeEstimationError> {
-    let fee_rate = mempool_space.fee_rate(TxPriority::NextBlock).await?;
+    let fee_rate = fees_client.fee_rate(TxPriority::NextBlock).await?;
     Ok(Satoshis::from(
         fee_rate.fee_wu(TXIN_BASE_WEIGHT + satisfaction_weight),
     ))
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn estimate_proportional_fee(
     avg_utxo_size: Option<Satoshis>,
     input_satisfaction_weight: usize,
@@ -31,6 +44,7 @@ pub fn estimate_proportional_fee(
     avg_payout_value: Satoshis,
     output_destination: bitcoin::Address,
     output_value: Satoshis,
+    change_script_pubkey: bitcoin::Script,
 ) -> Satoshis {
     let mut total_out = Satoshis::ZERO;
     let mut output = Vec::new();
@@ -50,7 +64,7 @@ pub fn estimate_proportional_fee(
     if avg_n_payouts == 0 {
         output.push(TxOut {
             value: 1,
-            script_pubkey: output_destination.script_pubkey(),
+            script_pubkey: change_script_pubkey,
         });
     }
     let tx = Transaction {
@@ -131,10 +145,56 @@ mod tests {
         let total_fee = Satoshis::from(fee_rate.fee_wu(tx.weight()));
 
         let descriptor : bdk::descriptor::ExtendedDescriptor = "wpkh([6f2fa1b2/84'/0'/0']tpubDDDDGYiFda8HfJRc2AHFJDxVzzEtBPrKsbh35EaW2UGd5qfzrF2G87ewAgeeRyHEz4iB3kvhAYW1sH6dpLepTkFUzAktumBN8AXeXWE9nd1/0/*)#l6n08zmr".parse().unwrap();
-        let address = "bc1qc7yu0g5qplddngesxuarkkp3na9hkrugpydqs0"
+        let address: bitcoin::Address = "bc1qc7yu0g5qplddngesxuarkkp3na9hkrugpydqs0"
             .parse()
             .unwrap();
 
+        let estimate = estimate_proportional_fee(
+            Some(Satoshis::from(200_000_000)),
+            descriptor.max_satisfaction_weight().unwrap(),
+            fee_rate,
+            0,
+            Satoshis::ZERO,
+            address.clone(),
+            Satoshis::from(127_000_000),
+            address.script_pubkey(),
+        );
+
+        assert_eq!(estimate, total_fee);
+    }
+
+    #[test]
+    fn test_fee_calculation_taproot() {
+        use bdk::bitcoin::{OutPoint, TxIn, Witness};
+
+        let descriptor: bdk::descriptor::ExtendedDescriptor = "tr([6f2fa1b2/86'/0'/0']tpubDDDDGYiFda8HfJRc2AHFJDxVzzEtBPrKsbh35EaW2UGd5qfzrF2G87ewAgeeRyHEz4iB3kvhAYW1sH6dpLepTkFUzAktumBN8AXeXWE9nd1/1/*)".parse().unwrap();
+        let change_script_pubkey = descriptor.at_derivation_index(0).script_pubkey();
+        let address: bitcoin::Address = "bc1qc7yu0g5qplddngesxuarkkp3na9hkrugpydqs0"
+            .parse()
+            .unwrap();
+        // 1 key path input (65 byte signature incl. sighash flag), a p2wpkh payout and p2tr change
+        let tx = Transaction {
+            version: 1,
+            lock_time: LockTime::ZERO.into(),
+            input: vec![TxIn {
+                previous_output: OutPoint::default(),
+                witness: Witness::from_vec(vec![vec![0; 65]]),
+                ..Default::default()
+            }],
+            output: vec![
+                TxOut {
+                    value: 127_000_000,
+                    script_pubkey: address.script_pubkey(),
+                },
+                TxOut {
+                    value: 1,
+                    script_pubkey: change_script_pubkey.clone(),
+                },
+            ],
+        };
+        let fee_rate = bitcoin::FeeRate::from_sat_per_vb(1000.);
+        let total_fee = Satoshis::from(fee_rate.fee_wu(tx.weight()));
+
         let estimate = estimate_proportional_fee(
             Some(Satoshis::from(200_000_000)),
             descriptor.max_satisfaction_weight().unwrap(),
@@ -143,6 +203,7 @@ mod tests {
             Satoshis::ZERO,
             address,
             Satoshis::from(127_000_000),
+            change_script_pubkey,
         );
 
         assert_eq!(estimate, total_fee);
@@ -181,4 +242,182 @@ mod tests {
         assert_eq!(payout_infos.values().sum::<Satoshis>(), fees);
         assert_eq!(payout_infos[&highest_payout_id], Satoshis::from(4));
     }
+
+    struct FailingEstimator;
+
+    #[async_trait::async_trait]
+    impl FeeEstimator for FailingEstimator {
+        fn name(&self) -> &'static str {
+            "failing"
+        }
+
+        async fn fee_rate(&self, _: TxPriority) -> Result<bdk::FeeRate, FeeEstimationError> {
+            Err(FeeEstimationError::EstimateUnavailable(self.name()))
+        }
+    }
+
+    fn static_estimator(sat_per_vb: f32) -> Box<dyn FeeEstimator> {
+        Box::new(StaticFeeEstimator::new(StaticFeeEstimatorConfig {
+            next_block_sat_per_vb: sat_per_vb,
+            half_hour_sat_per_vb: None,
+            one_hour_sat_per_vb: None,
+            economy_sat_per_vb: None,
+            minimum_sat_per_vb: None,
+        }))
+    }
+
+    #[tokio::test]
+    async fn fees_client_falls_back_to_next_estimator() {
+        let client = FeesClient::from_estimators(
+            vec![Box::new(FailingEstimator), static_estimator(7.0)],
+            std::time::Duration::from_secs(60),
+            std::time::Duration::from_secs(600),
+        );
+        let fee_rate = client.fee_rate(TxPriority::HalfHour).await.unwrap();
+        assert_eq!(fee_rate.as_sat_per_vb(), 7.0);
+    }
+
+    #[tokio::test]
+    async fn fees_client_errors_when_all_estimators_fail() {
+        let client = FeesClient::from_estimators(
+            vec![Box::new(FailingEstimator)],
+            std::time::Duration::from_secs(60),
+            std::time::Duration::from_secs(600),
+        );
+        assert!(matches!(
+            client.fee_rate(TxPriority::NextBlock).await,
+            Err(FeeEstimationError::EstimateUnavailable(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn fees_client_falls_back_to_stale_cache() {
+        use std::sync::{
+            atomic::{AtomicBool, Ordering},
+            Arc,
+        };
+
+        struct FlakyEstimator(Arc<AtomicBool>);
+
+        #[async_trait::async_trait]
+        impl FeeEstimator for FlakyEstimator {
+            fn name(&self) -> &'static str {
+                "flaky"
+            }
+
+            async fn fee_rate(&self, _: TxPriority) -> Result<bdk::FeeRate, FeeEstimationError> {
+                if self.0.load(Ordering::SeqCst) {
+                    Err(FeeEstimationError::EstimateUnavailable(self.name()))
+                } else {
+                    Ok(bdk::FeeRate::from_sat_per_vb(3.0))
+                }
+            }
+        }
+
+        let failing = Arc::new(AtomicBool::new(false));
+        let client = FeesClient::from_estimators(
+            vec![Box::new(FlakyEstimator(Arc::clone(&failing)))],
+            std::time::Duration::ZERO,
+            std::time::Duration::from_secs(600),
+        );
+        client.fee_rate(TxPriority::NextBlock).await.unwrap();
+        failing.store(true, Ordering::SeqCst);
+        let fee_rate = client.fee_rate(TxPriority::NextBlock).await.unwrap();
+        assert_eq!(fee_rate.as_sat_per_vb(), 3.0);
+        assert!(client.fee_rate(TxPriority::OneHour).await.is_err());
+
+        let expired = FeesClient::from_estimators(
+            vec![Box::new(FlakyEstimator(Arc::clone(&failing)))],
+            std::time::Duration::ZERO,
+            std::time::Duration::ZERO,
+        );
+        failing.store(false, Ordering::SeqCst);
+        expired.fee_rate(TxPriority::NextBlock).await.unwrap();
+        failing.store(true, Ordering::SeqCst);
+        tokio::time::sleep(std::time::Duration::from_millis(5)).await;
+        assert!(expired.fee_rate(TxPriority::NextBlock).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn fees_client_serves_fresh_cache_without_estimating() {
+        use std::sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        };
+
+        struct CountingEstimator(Arc<AtomicUsize>);
+
+        #[async_trait::async_trait]
+        impl FeeEstimator for CountingEstimator {
+            fn name(&self) -> &'static str {
+                "counting"
+            }
+
+            async fn fee_rate(&self, _: TxPriority) -> Result<bdk::FeeRate, FeeEstimationError> {
+                self.0.fetch_add(1, Ordering::SeqCst);
+                Ok(bdk::FeeRate::from_sat_per_vb(4.0))
+            }
+        }
+
+        let calls = Arc::new(AtomicUsize::new(0));
+        let client = FeesClient::from_estimators(
+            vec![Box::new(CountingEstimator(Arc::clone(&calls)))],
+            std::time::Duration::from_secs(60),
+            std::time::Duration::from_secs(600),
+        );
+        client.fee_rate(TxPriority::NextBlock).await.unwrap();
+        client.fee_rate(TxPriority::NextBlock).await.unwrap();
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+        client.fee_rate(TxPriority::Economy).await.unwrap();
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+
+        let no_ttl = FeesClient::from_estimators(
+            vec![Box::new(CountingEstimator(Arc::clone(&calls)))],
+            std::time::Duration::ZERO,
+            std::time::Duration::from_secs(600),
+        );
+        no_ttl.fee_rate(TxPriority::NextBlock).await.unwrap();
+        no_ttl.fee_rate(TxPriority::NextBlock).await.unwrap();
+        assert_eq!(calls.load(Ordering::SeqCst), 4);
+    }
+
+    #[tokio::test]
+    async fn fees_client_returns_custom_fee_rate() {
+        let client = FeesClient::from_estimators(
+            vec![Box::new(FailingEstimator)],
+            std::time::Duration::from_secs(60),
+            std::time::Duration::from_secs(600),
+        );
+        let fee_rate = client
+            .fee_rate(TxPriority::CustomSatPerVb(
+                CustomFeeRate::from_sat_per_vb(2.5).unwrap(),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(fee_rate.as_sat_per_vb(), 2.5);
+    }
+
+    #[test]
+    fn custom_fee_rate() {
+        assert!(CustomFeeRate::from_sat_per_vb(f32::NAN).is_none());
+        assert!(CustomFeeRate::from_sat_per_vb(f32::INFINITY).is_none());
+        assert!(CustomFeeRate::from_sat_per_vb(0.0).is_none());
+        assert!(CustomFeeRate::from_sat_per_vb(-1.0).is_none());
+        assert_eq!(
+            CustomFeeRate::from_sat_per_vb(2.5),
+            CustomFeeRate::from_sat_per_vb(2.5001)
+        );
+
+        let priority = TxPriority::CustomSatPerVb(CustomFeeRate::from_sat_per_vb(2.5).unwrap());
+        let json = serde_json::to_value(priority).unwrap();
+        assert_eq!(json, serde_json::json!({ "custom_sat_per_vb": 2.5 }));
+        assert_eq!(
+            serde_json::from_value::<TxPriority>(json).unwrap(),
+            priority
+        );
+        assert!(serde_json::from_value::<TxPriority>(
+            serde_json::json!({ "custom_sat_per_vb": 0 })
+        )
+        .is_err());
+    }
 }
@@ -0,0 +1,63 @@
+use async_trait::async_trait;
+use bdk::FeeRate;
+use serde::{Deserialize, Serialize};
+
+use super::{error::FeeEstimationError, r#trait::*};
+use crate::primitives::TxPriority;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StaticFeeEstimatorConfig {
+    pub next_block_sat_per_vb: f32,
+    #[serde(default)]
+    pub half_hour_sat_per_vb: Option<f32>,
+    #[serde(default)]
+    pub one_hour_sat_per_vb: Option<f32>,
+    #[serde(default)]
+    pub economy_sat_per_vb: Option<f32>,
+    #[serde(default)]
+    pub minimum_sat_per_vb: Option<f32>,
+}
+
+pub struct StaticFeeEstimator {
+    config: StaticFeeEstimatorConfig,
+}
+
+impl StaticFeeEstimator {
+    pub fn new(config: StaticFeeEstimatorConfig) -> Self {
+        Self { config }
+    }
+}
+
+#[async_trait]
+impl FeeEstimator for StaticFeeEstimator {
+    fn name(&self) -> &'static str {
+        "static"
+    }
+
+    async fn fee_rate(&self, priority: TxPriority) -> Result<FeeRate, FeeEstimationError> {
+        let cfg = &self.config;
+        let sat_per_vb = match priority {
+            TxPriority::NextBlock => cfg.next_block_sat_per_vb,
+            TxPriority::HalfHour => cfg
+                .half_hour_sat_per_vb
+                .unwrap_or(cfg.next_block_sat_per_vb),
+            TxPriority::OneHour => cfg
+                .one_hour_sat_per_vb
+                .or(cfg.half_hour_sat_per_vb)
+                .unwrap_or(cfg.next_block_sat_per_vb),
+            TxPriority::Economy => cfg
+                .economy_sat_per_vb
+                .or(cfg.one_hour_sat_per_vb)
+                .or(cfg.half_hour_sat_per_vb)
+                .unwrap_or(cfg.next_block_sat_per_vb),
+            TxPriority::Minimum => cfg
+                .minimum_sat_per_vb
+                .or(cfg.economy_sat_per_vb)
+                .or(cfg.one_hour_sat_per_vb)
+                .or(cfg.half_hour_sat_per_vb)
+                .unwrap_or(cfg.next_block_sat_per_vb),
+            TxPriority::CustomSatPerVb(fee_rate) => fee_rate.as_sat_per_vb(),
+        };
+        Ok(FeeRate::from_sat_per_vb(sat_per_vb))
+    }
+}
@@ -0,0 +1,12 @@
+use async_trait::async_trait;
+use bdk::FeeRate;
+
+use super::error::FeeEstimationError;
+use crate::primitives::TxPriority;
+
+#[async_trait]
+pub trait FeeEstimator: Send + Sync + 'static {
+    fn name(&self) -> &'static str;
+
+    async fn fee_rate(&self, priority: TxPriority) -> Result<FeeRate, FeeEstimationError>;
+}
@@ -1,11 +1,12 @@
 use serde::{Deserialize, Serialize};
 use tracing::instrument;
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use super::error::JobError;
 use crate::{
-    app::BlockchainConfig, batch::*, primitives::*, signing_session::*, wallet::*, xpub::*,
+    app::BlockchainConfig, batch::*, payout_queue::*, primitives::*, signing_session::*, wallet::*,
+    xpub::*,
 };
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -23,6 +24,7 @@ pub struct BatchSigningData {
         wallets,
         signing_sessions,
         batches,
+        payout_queues,
         xpubs,
         signer_encryption_config
     ),
@@ -35,6 +37,7 @@ pub async fn execute(
     data: BatchSigningData,
     blockchain_cfg: BlockchainConfig,
     batches: Batches,
+    payout_queues: PayoutQueues,
     signing_sessions: SigningSessions,
     wallets: Wallets,
     xpubs: XPubs,
@@ -55,13 +58,26 @@ pub async fn execute(
         let batch = batches.find_by_id(data.account_id, data.batch_id).await?;
         span.record("txid", &tracing::field::display(batch.bitcoin_tx_id));
         let unsigned_psbt = batch.unsigned_psbt;
+        let spending_path = payout_queues
+            .find_by_id(data.account_id, batch.payout_queue_id)
+            .await?
+            .config
+            .spending_path;
         for (wallet_id, summary) in batch.wallet_summaries {
             let wallet = wallets.find_by_id(wallet_id).await?;
             if current_keychain.is_none() {
                 current_keychain = Some(wallet.current_keychain_wallet(&pool));
             }
+            let timelocked_keychains: HashSet<KeychainId> = wallet
+                .timelocked_keychains()
+                .into_iter()
+                .map(|(id, _)| id)
+                .collect();
             let keychain_xpubs = wallet.xpubs_for_keychains(&summary.signing_keychains);
-            for (_, keychain_xpubs) in keychain_xpubs.into_iter() {
+            for (keychain_id, keychain_xpubs) in keychain_xpubs.into_iter() {
+                let keychain_spending_path = timelocked_keychains
+                    .contains(&keychain_id)
+                    .then_some(spending_path);
                 for xpub in keychain_xpubs.into_iter() {
                     let account_xpub = xpubs.find_from_ref(data.account_id, xpub.id()).await?;
                     let new_session = NewSigningSession::builder()
@@ -69,6 +85,7 @@ pub async fn execute(
                         .batch_id(data.batch_id)
                         .xpub_id(xpub.id())
                         .unsigned_psbt(unsigned_psbt.clone())
+                        .spending_path(keychain_spending_path)
                         .build()
                         .expect("Could not build signing session");
                     new_sessions.insert(account_xpub.id(), new_session);
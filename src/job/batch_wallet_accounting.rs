@@ -48,11 +48,26 @@ pub async fn execute(
 
     let encumbered_fees = ledger.sum_reserved_fees_in_txs(income_ids).await?;
 
+    // Consolidation batches only pay back to the wallet so they have no payouts
     let payouts = payouts
         .list_for_batch(data.account_id, data.batch_id)
         .await?
         .remove(&data.wallet_id)
-        .expect("payouts not found");
+        .unwrap_or_default();
+    if let Some((tx, dropped)) = batches
+        .set_batch_dropped_ledger_tx_id(data.batch_id, data.wallet_id)
+        .await?
+    {
+        ledger
+            .batch_dropped(
+                tx,
+                dropped.batch_dropped_ledger_tx_id,
+                dropped.replaced_batch_created_ledger_tx_id,
+                dropped.replaced_batch_broadcast_ledger_tx_id,
+                wallet.ledger_account_ids,
+            )
+            .await?;
+    }
     if let Some((tx, tx_id)) = batches
         .set_batch_created_ledger_tx_id(data.batch_id, data.wallet_id)
         .await?
@@ -123,6 +138,8 @@ impl From<Payout> for PayoutInfo {
             satoshis: payout.satoshis,
             destination: payout.destination,
             vout_in_tx: payout.outpoint.expect("payout outpoint not found").vout,
+            net_satoshis: Some(payout.satoshis - payout.deducted_fee_sats),
+            deducted_fee_sats: payout.deducted_fee_sats,
         }
     }
 }
@@ -0,0 +1,168 @@
+use serde::{Deserialize, Serialize};
+use tracing::instrument;
+
+use std::collections::HashMap;
+
+use super::error::JobError;
+use crate::{
+    batch::{error::BatchError, *},
+    fees::FeesClient,
+    ledger::Ledger,
+    payout::*,
+    payout_queue::*,
+    primitives::*,
+    signing_session::*,
+    utxo::{error::UtxoError, Utxos},
+};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BumpBatchFeeData {
+    pub(super) account_id: AccountId,
+    pub(super) batch_id: BatchId,
+    pub(super) fee_rate_sat_per_vbyte: Option<f32>,
+    #[serde(flatten)]
+    pub(super) tracing_data: HashMap<String, String>,
+}
+
+#[instrument(
+    name = "job.bump_batch_fee",
+    skip_all,
+    fields(batch_id, replaced_tx_id, tx_id, total_fee_sats, skipped),
+    err
+)]
+#[allow(clippy::type_complexity, clippy::too_many_arguments)]
+pub(super) async fn execute<'a>(
+    pool: sqlx::PgPool,
+    data: BumpBatchFeeData,
+    batches: Batches,
+    payout_queues: PayoutQueues,
+    payouts: Payouts,
+    utxos: Utxos,
+    signing_sessions: SigningSessions,
+    ledger: Ledger,
+    fees_client: FeesClient,
+) -> Result<
+    (
+        BumpBatchFeeData,
+        Option<(sqlx::Transaction<'a, sqlx::Postgres>, Vec<WalletId>)>,
+    ),
+    JobError,
+> {
+    let span = tracing::Span::current();
+    span.record("batch_id", tracing::field::display(data.batch_id));
+    let batch = batches.find_by_id(data.account_id, data.batch_id).await?;
+    let fee_rate = match data.fee_rate_sat_per_vbyte {
+        Some(rate) => bitcoin::FeeRate::from_sat_per_vb(rate),
+        None => {
+            let payout_queue = payout_queues
+                .find_by_id(data.account_id, batch.payout_queue_id)
+                .await?;
+            fees_client
+                .fee_rate(payout_queue.config.tx_priority)
+                .await?
+        }
+    };
+
+    let replacement = match prepare_replacement(&pool, &ledger, &utxos, &batch, fee_rate).await? {
+        Ok(replacement) => replacement,
+        Err(err) => {
+            span.record("skipped", tracing::field::display(err));
+            return Ok((data, None));
+        }
+    };
+    span.record(
+        "replaced_tx_id",
+        tracing::field::display(replacement.replaced_tx_id),
+    );
+    span.record("tx_id", tracing::field::display(replacement.tx_id));
+    span.record(
+        "total_fee_sats",
+        tracing::field::display(replacement.total_fee_sats),
+    );
+
+    let mut tx = pool.begin().await?;
+    batches
+        .replace_in_tx(&mut tx, batch.id, &replacement)
+        .await?;
+    match utxos
+        .release_spent_in_replaced_batch(&mut tx, batch.id, replacement.replaced_tx_id, fee_rate)
+        .await
+    {
+        Ok(()) => (),
+        // The change got spent after the eligibility check, retrying can never succeed
+        Err(err @ UtxoError::ChangeOfReplacedBatchAlreadySpent(_)) => {
+            span.record("skipped", tracing::field::display(err));
+            return Ok((data, None));
+        }
+        Err(err) => return Err(err.into()),
+    }
+    for payout in payouts
+        .list_for_batch(data.account_id, batch.id)
+        .await?
+        .into_values()
+        .flatten()
+    {
+        let mut payout = payout;
+        payout.batch_transaction_replaced(replacement.tx_id);
+        payouts.update(&mut tx, payout).await?;
+    }
+    if let Some(BatchSigningSession { mut xpub_sessions }) = signing_sessions
+        .list_for_batch(data.account_id, batch.id)
+        .await?
+    {
+        for session in xpub_sessions.values_mut() {
+            session.replace_unsigned_psbt(replacement.unsigned_psbt.clone());
+        }
+        signing_sessions
+            .update_sessions(&mut tx, &xpub_sessions)
+            .await?;
+    }
+
+    let wallet_ids = replacement.wallet_summaries.into_keys().collect();
+    Ok((data, Some((tx, wallet_ids))))
+}
+
+/// Checks that the batch can still be replaced and builds the replacement.
+/// Returns the reason as a `BatchError` if it cannot be replaced.
+pub async fn prepare_replacement(
+    pool: &sqlx::PgPool,
+    ledger: &Ledger,
+    utxos: &Utxos,
+    batch: &Batch,
+    fee_rate: bitcoin::FeeRate,
+) -> Result<Result<BatchReplacement, BatchError>, JobError> {
+    if !batch.is_broadcast() {
+        return Ok(Err(BatchError::BatchNotBroadcast(batch.id.to_string())));
+    }
+    if crate::bdk::is_tx_confirmed(pool, batch.bitcoin_tx_id).await? {
+        return Ok(Err(BatchError::BatchAlreadyConfirmed(batch.id.to_string())));
+    }
+    // Spending the change again would double spend it
+    if utxos
+        .is_change_of_batch_spent(batch.id, batch.bitcoin_tx_id)
+        .await?
+    {
+        return Ok(Err(BatchError::BatchChangeAlreadySpent(
+            batch.id.to_string(),
+        )));
+    }
+    if let Some(current_fee_rate) = batch.current_fee_rate() {
+        if fee_rate.as_sat_per_vb() <= current_fee_rate.as_sat_per_vb() {
+            return Ok(Err(BatchError::FeeRateNotIncreased(batch.id.to_string())));
+        }
+    }
+    for summary in batch.wallet_summaries.values() {
+        let created_ledger_tx_id = summary
+            .batch_created_ledger_tx_id
+            .expect("accounting should be complete");
+        if !ledger
+            .batch_inputs_settled_when_created(created_ledger_tx_id)
+            .await?
+        {
+            return Ok(Err(BatchError::BatchHasUnsettledInputs(
+                batch.id.to_string(),
+            )));
+        }
+    }
+    Ok(batch.replace_by_fee(fee_rate))
+}
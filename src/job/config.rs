@@ -13,6 +13,14 @@ pub struct JobsConfig {
     #[serde_as(as = "serde_with::DurationSeconds<u64>")]
     #[serde(default = "default_respawn_all_outbox_handlers_delay")]
     pub respawn_all_outbox_handlers_delay: Duration,
+    #[serde_as(as = "serde_with::DurationSeconds<u64>")]
+    #[serde(default = "default_check_expiring_timelocks_delay")]
+    pub check_expiring_timelocks_delay: Duration,
+    #[serde_as(as = "serde_with::DurationSeconds<u64>")]
+    #[serde(default = "default_consolidate_all_wallets_delay")]
+    pub consolidate_all_wallets_delay: Duration,
+    #[serde(default = "default_timelock_expiry_warning_blocks")]
+    pub timelock_expiry_warning_blocks: u32,
     #[serde(default)]
     pub signing: SigningJobConfig,
 }
@@ -35,6 +43,9 @@ impl Default for JobsConfig {
             sync_all_wallets_delay: default_sync_all_wallets_delay(),
             process_all_payout_queues_delay: default_process_all_payout_queues_delay(),
             respawn_all_outbox_handlers_delay: default_respawn_all_outbox_handlers_delay(),
+            check_expiring_timelocks_delay: default_check_expiring_timelocks_delay(),
+            consolidate_all_wallets_delay: default_consolidate_all_wallets_delay(),
+            timelock_expiry_warning_blocks: default_timelock_expiry_warning_blocks(),
             signing: SigningJobConfig::default(),
         }
     }
@@ -62,6 +73,18 @@ fn default_respawn_all_outbox_handlers_delay() -> Duration {
     Duration::from_secs(5)
 }
 
+fn default_check_expiring_timelocks_delay() -> Duration {
+    Duration::from_secs(600)
+}
+
+fn default_consolidate_all_wallets_delay() -> Duration {
+    Duration::from_secs(300)
+}
+
+fn default_timelock_expiry_warning_blocks() -> u32 {
+    4320 // About 30 days
+}
+
 fn default_signing_warn_retries() -> u32 {
     9 // About 8 minutes
 }
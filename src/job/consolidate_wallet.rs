@@ -0,0 +1,302 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use tracing::instrument;
+
+use super::error::JobError;
+use crate::{
+    batch::*, fees::FeesClient, ledger::*, payout_queue::*, primitives::*, utxo::*, wallet::*,
+};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConsolidateWalletData {
+    pub(super) account_id: AccountId,
+    pub(super) wallet_id: WalletId,
+    pub(super) batch_id: BatchId,
+    #[serde(flatten)]
+    pub(super) tracing_data: HashMap<String, String>,
+}
+
+impl ConsolidateWalletData {
+    pub fn new(account_id: AccountId, wallet_id: WalletId) -> Self {
+        Self {
+            account_id,
+            wallet_id,
+            batch_id: BatchId::new(),
+            tracing_data: crate::tracing::extract_tracing_data(),
+        }
+    }
+}
+
+#[instrument(
+    name = "job.consolidate_wallet",
+    skip_all,
+    fields(
+        wallet_id,
+        n_spendable_utxos,
+        n_consolidated_utxos,
+        n_swept_dust_utxos,
+        tx_id,
+        total_fee_sats,
+        batch_id,
+        deferred
+    ),
+    err
+)]
+#[allow(clippy::type_complexity)]
+#[allow(clippy::too_many_arguments)]
+pub(super) async fn execute<'a>(
+    pool: sqlx::PgPool,
+    wallets: Wallets,
+    payout_queues: PayoutQueues,
+    batches: Batches,
+    utxos: Utxos,
+    ledger: Ledger,
+    data: ConsolidateWalletData,
+    fees_client: FeesClient,
+) -> Result<
+    (
+        ConsolidateWalletData,
+        Option<(sqlx::Transaction<'a, sqlx::Postgres>, Vec<WalletId>)>,
+    ),
+    JobError,
+> {
+    let span = tracing::Span::current();
+    span.record("wallet_id", tracing::field::display(data.wallet_id));
+    let wallet = wallets.find_by_id(data.wallet_id).await?;
+    let policy = match wallet.config.consolidation.clone() {
+        Some(policy) => policy,
+        None => return Ok((data, None)),
+    };
+    let payout_queue = payout_queues
+        .find_by_id(data.account_id, policy.payout_queue_id)
+        .await?;
+    let fee_rate = fees_client
+        .fee_rate(payout_queue.config.tx_priority)
+        .await?;
+    let consolidate = !policy.fee_rate_exceeds_max(fee_rate);
+    let sweep_dust = wallet.config.should_sweep_dust(fee_rate);
+    if !consolidate && !sweep_dust {
+        span.record("deferred", true);
+        return Ok((data, None));
+    }
+
+    // Only the current keychain is consolidated, deprecated keychains
+    // are drained by payout queues with consolidate_deprecated_keychains
+    let current_keychain_id = wallet.current_keychain_id();
+    let mut tx = pool.begin().await?;
+    let reserved_utxos = utxos
+        .outpoints_bdk_should_not_select(&mut tx, wallet.keychain_ids())
+        .await?;
+    let reserved: HashSet<bitcoin::OutPoint> = reserved_utxos.values().flatten().copied().collect();
+    let keychain_utxos: Vec<WalletUtxo> = utxos
+        .find_keychain_utxos(std::iter::once(current_keychain_id))
+        .await?
+        .into_values()
+        .flat_map(|keychain_utxos| keychain_utxos.utxos)
+        .collect();
+
+    // Dust is swept on its own so that the dusted addresses don't get linked
+    // to the rest of the wallet by sharing a transaction with other utxos
+    if sweep_dust {
+        let dust: HashMap<bitcoin::OutPoint, Satoshis> = keychain_utxos
+            .iter()
+            .filter(|utxo| {
+                utxo.dust
+                    && utxo.utxo_settled_ledger_tx_id.is_some()
+                    && utxo.spending_batch_id.is_none()
+                    && utxo.frozen.is_none()
+            })
+            .take(policy.max_inputs_per_tx as usize)
+            .map(|utxo| (utxo.outpoint, utxo.value))
+            .collect();
+        // Sweeping a single dust utxo gains nothing
+        if dust.len() >= 2 {
+            let mut dust_reserved_utxos = reserved_utxos.clone();
+            let keychain_reserved = dust_reserved_utxos.entry(current_keychain_id).or_default();
+            keychain_reserved.retain(|outpoint| !dust.contains_key(outpoint));
+            keychain_reserved.extend(
+                keychain_utxos
+                    .iter()
+                    .map(|utxo| utxo.outpoint)
+                    .filter(|outpoint| !dust.contains_key(outpoint)),
+            );
+            let build = PsbtBuilder::construct_consolidation_psbt(
+                &pool,
+                fee_rate,
+                dust_reserved_utxos,
+                payout_queue.config.spending_path,
+                &wallet,
+            )
+            .await?;
+            if build.psbt.is_some() {
+                let swept: Vec<(KeychainId, bitcoin::OutPoint)> = build
+                    .included_utxos
+                    .values()
+                    .flat_map(|keychain_map| {
+                        keychain_map.iter().flat_map(|(keychain_id, outpoints)| {
+                            outpoints
+                                .iter()
+                                .map(move |outpoint| (*keychain_id, *outpoint))
+                        })
+                    })
+                    .collect();
+                span.record("n_swept_dust_utxos", swept.len());
+                for (keychain_id, outpoint) in swept {
+                    let satoshis = match dust.get(&outpoint) {
+                        Some(satoshis) => *satoshis,
+                        None => return Ok((data, None)),
+                    };
+                    let mut savepoint = sqlx::Acquire::begin(&mut tx).await?;
+                    if !utxos
+                        .release_dust(&mut savepoint, keychain_id, outpoint)
+                        .await?
+                    {
+                        // The dust has been spent or frozen concurrently, try again next round
+                        return Ok((data, None));
+                    }
+                    ledger
+                        .dust_swept(
+                            savepoint,
+                            LedgerTransactionId::new(),
+                            DustSweptParams {
+                                journal_id: wallet.journal_id,
+                                ledger_account_ids: wallet.ledger_account_ids,
+                                meta: DustSweptMeta {
+                                    account_id: data.account_id,
+                                    wallet_id: wallet.id,
+                                    keychain_id,
+                                    outpoint,
+                                    satoshis,
+                                },
+                            },
+                        )
+                        .await?;
+                }
+                let wallet_ids = create_batch(
+                    &mut tx,
+                    &batches,
+                    &utxos,
+                    &data,
+                    &payout_queue,
+                    fee_rate,
+                    build,
+                )
+                .await?;
+                return Ok((data, wallet_ids.map(|ids| (tx, ids))));
+            }
+        }
+    }
+    if !consolidate {
+        span.record("deferred", true);
+        return Ok((data, None));
+    }
+
+    let mut spendable: Vec<WalletUtxo> = keychain_utxos
+        .into_iter()
+        .filter(|utxo| !reserved.contains(&utxo.outpoint))
+        .collect();
+    span.record("n_spendable_utxos", spendable.len());
+    let n_inputs = match policy.n_inputs_to_consolidate(spendable.len()) {
+        Some(n_inputs) if n_inputs >= 2 => n_inputs,
+        _ => return Ok((data, None)),
+    };
+    spendable.sort_by_key(|utxo| utxo.value);
+    span.record("n_consolidated_utxos", n_inputs.min(spendable.len()));
+    let mut reserved_utxos = reserved_utxos;
+    reserved_utxos
+        .entry(current_keychain_id)
+        .or_default()
+        .extend(spendable.iter().skip(n_inputs).map(|utxo| utxo.outpoint));
+
+    let build = PsbtBuilder::construct_consolidation_psbt(
+        &pool,
+        fee_rate,
+        reserved_utxos,
+        payout_queue.config.spending_path,
+        &wallet,
+    )
+    .await?;
+    let wallet_ids = create_batch(
+        &mut tx,
+        &batches,
+        &utxos,
+        &data,
+        &payout_queue,
+        fee_rate,
+        build,
+    )
+    .await?;
+    Ok((data, wallet_ids.map(|ids| (tx, ids))))
+}
+
+/// Persists the batch of a consolidation psbt and reserves its inputs.
+/// Returns None if no transaction could be built.
+async fn create_batch(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    batches: &Batches,
+    utxos: &Utxos,
+    data: &ConsolidateWalletData,
+    payout_queue: &PayoutQueue,
+    fee_rate: bitcoin::FeeRate,
+    build: FinishedPsbtBuild,
+) -> Result<Option<Vec<WalletId>>, JobError> {
+    let FinishedPsbtBuild {
+        psbt,
+        included_utxos,
+        wallet_totals,
+        tx_id,
+        fee_satoshis,
+        ..
+    } = build;
+    let (tx_id, psbt) = match (tx_id, psbt) {
+        (Some(tx_id), Some(psbt)) => (tx_id, psbt),
+        _ => return Ok(None),
+    };
+    let span = tracing::Span::current();
+    span.record("tx_id", tracing::field::display(tx_id));
+    span.record("batch_id", tracing::field::display(data.batch_id));
+    span.record("total_fee_sats", tracing::field::display(fee_satoshis));
+
+    let wallet_ids = wallet_totals.keys().copied().collect();
+    let batch = NewBatch::builder()
+        .account_id(data.account_id)
+        .id(data.batch_id)
+        .payout_queue_id(payout_queue.id)
+        .tx_id(tx_id)
+        .unsigned_psbt(psbt)
+        .total_fee_sats(fee_satoshis)
+        .wallet_summaries(
+            wallet_totals
+                .into_iter()
+                .map(|(wallet_id, total)| (wallet_id, WalletSummary::from(total)))
+                .collect(),
+        )
+        .build()
+        .expect("Couldn't build batch");
+
+    let included_utxos: Box<dyn Iterator<Item = (KeychainId, bitcoin::OutPoint)> + Send> =
+        Box::new(included_utxos.into_values().flat_map(|keychain_map| {
+            keychain_map
+                .into_iter()
+                .flat_map(|(keychain_id, outpoints)| {
+                    outpoints
+                        .into_iter()
+                        .map(move |outpoint| (keychain_id, outpoint))
+                })
+        }));
+
+    let batch_id = batch.id;
+    batches.create_in_tx(tx, batch).await?;
+    utxos
+        .reserve_utxos_in_batch(
+            tx,
+            data.account_id,
+            batch_id,
+            payout_queue.id,
+            fee_rate,
+            included_utxos,
+        )
+        .await?;
+
+    Ok(Some(wallet_ids))
+}
@@ -185,10 +185,26 @@ struct JobData<T> {
     job_meta: JobMeta,
     #[serde(flatten)]
     data: Option<T>,
-    #[serde(flatten)]
+    #[serde(flatten, deserialize_with = "deserialize_string_entries")]
     tracing_data: HashMap<String, String>,
 }
 
+// Flattened job data is not always consumed before the tracing headers are collected,
+// so non string fields of the payload (eg. flags) must be skipped rather than rejected.
+fn deserialize_string_entries<'de, D>(deserializer: D) -> Result<HashMap<String, String>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let entries = HashMap::<String, serde_json::Value>::deserialize(deserializer)?;
+    Ok(entries
+        .into_iter()
+        .filter_map(|(key, value)| match value {
+            serde_json::Value::String(value) => Some((key, value)),
+            _ => None,
+        })
+        .collect())
+}
+
 impl<'a, T: Deserialize<'a>> JobData<T> {
     pub fn from_raw_payload(payload: Option<&'a str>) -> Result<Self, serde_json::Error> {
         if let Some(payload) = payload {
@@ -263,4 +279,24 @@ mod tests {
         assert!(job_data.data.is_none());
         assert_eq!(job_data.tracing_data.get("header").unwrap(), "value");
     }
+
+    #[derive(Deserialize)]
+    struct FlattenedData {
+        flag: bool,
+        #[serde(flatten)]
+        #[allow(dead_code)]
+        tracing_data: HashMap<String, String>,
+    }
+
+    #[test]
+    fn from_raw_with_non_string_fields() {
+        let json = r#"{
+            "flag": true,
+            "header": "value"
+        }"#;
+        let job_data: JobData<FlattenedData> = JobData::from_raw_payload(Some(json)).unwrap();
+        assert!(job_data.data.unwrap().flag);
+        assert_eq!(job_data.tracing_data.get("header").unwrap(), "value");
+        assert!(!job_data.tracing_data.contains_key("flag"));
+    }
 }
@@ -0,0 +1,84 @@
+use std::collections::HashMap;
+use tracing::instrument;
+
+use super::error::JobError;
+use crate::{outbox::*, utxo::Utxos, wallet::*};
+
+/// Publishes a warning for every settled utxo held by a timelocked keychain
+/// whose recovery branch becomes spendable within `warning_blocks`
+/// so that the funds can be refreshed by spending them via the primary branch.
+#[instrument(
+    name = "job.check_expiring_timelocks",
+    skip_all,
+    fields(current_height, n_warnings),
+    err
+)]
+pub async fn execute(
+    pool: sqlx::PgPool,
+    outbox: Outbox,
+    wallets: Wallets,
+    utxos: Utxos,
+    warning_blocks: u32,
+) -> Result<(), JobError> {
+    let span = tracing::Span::current();
+    let current_height = crate::bdk::last_sync_time(&pool).await?;
+    span.record("current_height", current_height);
+    let mut n_warnings = 0;
+    let account_ids: HashMap<_, _> = wallets
+        .all_ids()
+        .await?
+        .map(|(account_id, wallet_id)| (wallet_id, account_id))
+        .collect();
+    let mut all_wallets = wallets
+        .find_by_ids(account_ids.keys().copied().collect())
+        .await?;
+    for (wallet_id, account_id) in account_ids {
+        let wallet = match all_wallets.remove(&wallet_id) {
+            Some(wallet) => wallet,
+            None => continue,
+        };
+        let timelocked_keychains = wallet.timelocked_keychains();
+        if timelocked_keychains.is_empty() {
+            continue;
+        }
+        let mut keychain_utxos = utxos
+            .find_keychain_utxos(timelocked_keychains.iter().map(|(id, _)| *id))
+            .await?;
+        for (keychain_id, timelocks) in timelocked_keychains {
+            let utxos = match keychain_utxos.remove(&keychain_id) {
+                Some(keychain_utxos) => keychain_utxos.utxos,
+                None => continue,
+            };
+            for utxo in utxos {
+                if utxo.spending_batch_id.is_some() {
+                    continue;
+                }
+                let spendable_at_height = match utxo
+                    .block_height
+                    .and_then(|height| timelocks.spendable_at_height(height))
+                {
+                    Some(height) if height <= current_height + warning_blocks => height,
+                    _ => continue,
+                };
+                let published = outbox
+                    .publish_payload(
+                        account_id,
+                        OutboxEventPayload::UtxoTimelockExpiring {
+                            tx_id: utxo.outpoint.txid,
+                            vout: utxo.outpoint.vout,
+                            satoshis: utxo.value,
+                            wallet_id,
+                            keychain_id,
+                            spendable_at_height,
+                        },
+                    )
+                    .await?;
+                if published {
+                    n_warnings += 1;
+                }
+            }
+        }
+    }
+    span.record("n_warnings", n_warnings);
+    Ok(())
+}
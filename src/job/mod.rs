@@ -2,10 +2,13 @@ mod batch_broadcasting;
 mod batch_signing;
 mod batch_wallet_accounting;
 mod config;
+mod consolidate_wallet;
 mod executor;
+mod expiring_timelocks;
 mod populate_outbox;
 mod sync_wallet;
 
+pub mod bump_batch_fee;
 pub mod error;
 pub mod process_payout_queue;
 
@@ -16,13 +19,26 @@ use tracing::instrument;
 use uuid::{uuid, Uuid};
 
 use crate::{
-    account::*, address::Addresses, app::BlockchainConfig, batch::*, fees::MempoolSpaceClient,
-    ledger::Ledger, outbox::*, payout::*, payout_queue::*, primitives::*, signing_session::*,
-    utxo::Utxos, wallet::*, xpub::*,
+    account::*,
+    address::Addresses,
+    app::{BlockchainConfig, SecurityConfig},
+    batch::*,
+    fees::FeesClient,
+    ledger::Ledger,
+    outbox::*,
+    payout::*,
+    payout_queue::*,
+    primitives::*,
+    signing_session::*,
+    utxo::Utxos,
+    wallet::*,
+    xpub::*,
 };
 use batch_broadcasting::BatchBroadcastingData;
 use batch_signing::BatchSigningData;
 use batch_wallet_accounting::BatchWalletAccountingData;
+use bump_batch_fee::BumpBatchFeeData;
+use consolidate_wallet::ConsolidateWalletData;
 use error::JobError;
 pub use executor::JobExecutionError;
 use executor::JobExecutor;
@@ -33,6 +49,8 @@ use sync_wallet::SyncWalletData;
 const SYNC_ALL_WALLETS_ID: Uuid = uuid!("00000000-0000-0000-0000-000000000001");
 const PROCESS_ALL_PAYOUT_QUEUES_ID: Uuid = uuid!("00000000-0000-0000-0000-000000000002");
 const RESPAWN_ALL_OUTBOX_ID: Uuid = uuid!("00000000-0000-0000-0000-000000000003");
+const CHECK_EXPIRING_TIMELOCKS_ID: Uuid = uuid!("00000000-0000-0000-0000-000000000004");
+const CONSOLIDATE_ALL_WALLETS_ID: Uuid = uuid!("00000000-0000-0000-0000-000000000005");
 
 #[allow(clippy::too_many_arguments)]
 pub async fn start_job_runner(
@@ -49,8 +67,9 @@ pub async fn start_job_runner(
     addresses: Addresses,
     config: JobsConfig,
     blockchain_cfg: BlockchainConfig,
+    security_cfg: SecurityConfig,
     signer_encryption_config: SignerEncryptionConfig,
-    mempool_space_client: MempoolSpaceClient,
+    fees_client: FeesClient,
 ) -> Result<JobRunnerHandle, JobError> {
     let mut registry = JobRegistry::new(&[
         sync_all_wallets,
@@ -61,11 +80,16 @@ pub async fn start_job_runner(
         batch_wallet_accounting,
         batch_signing,
         batch_broadcasting,
+        bump_batch_fee,
         respawn_all_outbox_handlers,
         populate_outbox,
+        check_expiring_timelocks,
+        consolidate_all_wallets,
+        consolidate_wallet,
     ]);
     registry.set_context(config);
     registry.set_context(blockchain_cfg);
+    registry.set_context(security_cfg);
     registry.set_context(outbox);
     registry.set_context(wallets);
     registry.set_context(xpubs);
@@ -77,7 +101,7 @@ pub async fn start_job_runner(
     registry.set_context(utxos);
     registry.set_context(addresses);
     registry.set_context(signer_encryption_config);
-    registry.set_context(mempool_space_client);
+    registry.set_context(fees_client);
 
     Ok(registry.runner(pool).set_keep_alive(false).run().await?)
 }
@@ -110,6 +134,8 @@ async fn sync_all_wallets(
 async fn process_all_payout_queues(
     mut current_job: CurrentJob,
     payout_queues: PayoutQueues,
+    payouts: Payouts,
+    batches: Batches,
     JobsConfig {
         process_all_payout_queues_delay: delay,
         ..
@@ -121,16 +147,53 @@ async fn process_all_payout_queues(
         .expect("couldn't build JobExecutor")
         .execute(|_| async move {
             for group in payout_queues.all().await? {
+                if group.config.trigger.is_threshold() {
+                    match payouts.list_unbatched(group.account_id, group.id).await {
+                        Ok(unbatched_payouts) if group.threshold_reached(&unbatched_payouts) => {
+                            let _ = spawn_threshold_process_payout_queue(
+                                &pool,
+                                (group.account_id, group.id),
+                            )
+                            .await;
+                        }
+                        Ok(_) => (),
+                        Err(err) => {
+                            tracing::error!(
+                                payout_queue_id = %group.id,
+                                error = %err,
+                                "couldn't list unbatched payouts"
+                            );
+                        }
+                    }
+                }
                 if let Some(delay) = group.spawn_in() {
                     let _ = spawn_schedule_process_payout_queue(
                         &pool,
                         (group.account_id, group.id),
-                        delay
-                            .checked_sub(std::time::Duration::from_secs(1))
-                            .unwrap_or_default(),
+                        delay,
                     )
                     .await;
                 }
+                if let Some(built_before) = group.config.bump_fee_for_batches_built_before() {
+                    let batch_ids = match batches
+                        .list_unconfirmed_ids_built_before(group.id, built_before)
+                        .await
+                    {
+                        Ok(batch_ids) => batch_ids,
+                        Err(err) => {
+                            tracing::error!(
+                                payout_queue_id = %group.id,
+                                error = %err,
+                                "couldn't list unconfirmed batches to bump"
+                            );
+                            continue;
+                        }
+                    };
+                    for batch_id in batch_ids {
+                        let _ =
+                            spawn_bump_batch_fee(&pool, (group.account_id, batch_id, None)).await;
+                    }
+                }
             }
             Ok::<(), JobError>(())
         })
@@ -182,17 +245,114 @@ async fn respawn_all_outbox_handlers(
     Ok(())
 }
 
+#[job(name = "check_expiring_timelocks")]
+async fn check_expiring_timelocks(
+    mut current_job: CurrentJob,
+    outbox: Outbox,
+    wallets: Wallets,
+    utxos: Utxos,
+    JobsConfig {
+        check_expiring_timelocks_delay: delay,
+        timelock_expiry_warning_blocks: warning_blocks,
+        ..
+    }: JobsConfig,
+) -> Result<(), JobError> {
+    let pool = current_job.pool().clone();
+    JobExecutor::builder(&mut current_job)
+        .build()
+        .expect("couldn't build JobExecutor")
+        .execute(|_| async move {
+            expiring_timelocks::execute(pool, outbox, wallets, utxos, warning_blocks).await
+        })
+        .await?;
+    spawn_check_expiring_timelocks(current_job.pool(), delay).await?;
+    Ok(())
+}
+
+#[job(name = "consolidate_all_wallets")]
+async fn consolidate_all_wallets(
+    mut current_job: CurrentJob,
+    wallets: Wallets,
+    JobsConfig {
+        consolidate_all_wallets_delay: delay,
+        ..
+    }: JobsConfig,
+) -> Result<(), JobError> {
+    let pool = current_job.pool().clone();
+    JobExecutor::builder(&mut current_job)
+        .build()
+        .expect("couldn't build JobExecutor")
+        .execute(|_| async move {
+            for (account_id, wallet_id) in wallets.all_ids_with_consolidation_policy().await? {
+                if let Err(err) = spawn_consolidate_wallet(
+                    &pool,
+                    ConsolidateWalletData::new(account_id, wallet_id),
+                )
+                .await
+                {
+                    tracing::error!(%wallet_id, error = %err, "couldn't spawn consolidate_wallet");
+                }
+            }
+            Ok::<(), JobError>(())
+        })
+        .await?;
+    spawn_consolidate_all_wallets(current_job.pool(), delay).await?;
+    Ok(())
+}
+
+#[job(name = "consolidate_wallet")]
+async fn consolidate_wallet(
+    mut current_job: CurrentJob,
+    wallets: Wallets,
+    utxos: Utxos,
+    payout_queues: PayoutQueues,
+    batches: Batches,
+    ledger: Ledger,
+    fees_client: FeesClient,
+) -> Result<(), JobError> {
+    let pool = current_job.pool().clone();
+    JobExecutor::builder(&mut current_job)
+        .initial_retry_delay(std::time::Duration::from_secs(2))
+        .build()
+        .expect("couldn't build JobExecutor")
+        .execute(|data| async move {
+            let data: ConsolidateWalletData = data.expect("no ConsolidateWalletData available");
+            let (data, res) = consolidate_wallet::execute(
+                pool,
+                wallets,
+                payout_queues,
+                batches,
+                utxos,
+                ledger,
+                data,
+                fees_client,
+            )
+            .await?;
+            if let Some((mut tx, wallet_ids)) = res {
+                for id in wallet_ids {
+                    spawn_batch_wallet_accounting(&mut tx, (&data, id)).await?;
+                }
+                spawn_batch_signing(tx, &data).await?;
+            }
+
+            Ok::<_, JobError>(data)
+        })
+        .await?;
+    Ok(())
+}
+
 #[job(name = "sync_wallet")]
 #[allow(clippy::too_many_arguments)]
 async fn sync_wallet(
     mut current_job: CurrentJob,
     wallets: Wallets,
     blockchain_cfg: BlockchainConfig,
+    security_cfg: SecurityConfig,
     addresses: Addresses,
     utxos: Utxos,
     ledger: Ledger,
     batches: Batches,
-    mempool_space_client: MempoolSpaceClient,
+    fees_client: FeesClient,
 ) -> Result<(), JobError> {
     let pool = current_job.pool().clone();
     let mut has_more = false;
@@ -207,12 +367,13 @@ async fn sync_wallet(
                 pool,
                 wallets,
                 blockchain_cfg,
+                security_cfg,
                 utxos,
                 addresses,
                 ledger,
                 batches,
                 data,
-                mempool_space_client,
+                fees_client,
             )
             .await?;
             *more_ref = more;
@@ -240,6 +401,21 @@ pub async fn spawn_process_payout_queue(
     .await
 }
 
+async fn spawn_threshold_process_payout_queue(
+    pool: &sqlx::PgPool,
+    data: impl Into<ProcessPayoutQueueData>,
+) -> Result<ProcessPayoutQueueData, JobError> {
+    let data = data.into();
+    onto_account_main_channel(
+        pool,
+        data.account_id,
+        Uuid::new_v5(&Uuid::from(data.payout_queue_id), b"threshold"),
+        "process_payout_queue",
+        data,
+    )
+    .await
+}
+
 #[job(name = "schedule_process_payout_queue")]
 async fn schedule_process_payout_queue(mut current_job: CurrentJob) -> Result<(), JobError> {
     let pool = current_job.pool().clone();
@@ -263,7 +439,7 @@ async fn process_payout_queue(
     utxos: Utxos,
     payout_queues: PayoutQueues,
     batches: Batches,
-    mempool_space_client: MempoolSpaceClient,
+    fees_client: FeesClient,
 ) -> Result<(), JobError> {
     let pool = current_job.pool().clone();
     JobExecutor::builder(&mut current_job)
@@ -280,7 +456,7 @@ async fn process_payout_queue(
                 batches,
                 utxos,
                 data,
-                mempool_space_client,
+                fees_client,
             )
             .await?;
             if let Some((mut tx, wallet_ids)) = res {
@@ -343,6 +519,7 @@ async fn batch_signing(
     blockchain_cfg: BlockchainConfig,
     signer_encryption_config: SignerEncryptionConfig,
     batches: Batches,
+    payout_queues: PayoutQueues,
     wallets: Wallets,
     xpubs: XPubs,
     signing_sessions: SigningSessions,
@@ -361,6 +538,7 @@ async fn batch_signing(
                 data,
                 blockchain_cfg,
                 batches,
+                payout_queues,
                 signing_sessions,
                 wallets,
                 xpubs,
@@ -400,6 +578,50 @@ async fn batch_broadcasting(
     Ok(())
 }
 
+#[job(name = "bump_batch_fee")]
+#[allow(clippy::too_many_arguments)]
+async fn bump_batch_fee(
+    mut current_job: CurrentJob,
+    batches: Batches,
+    payout_queues: PayoutQueues,
+    payouts: Payouts,
+    utxos: Utxos,
+    signing_sessions: SigningSessions,
+    ledger: Ledger,
+    fees_client: FeesClient,
+) -> Result<(), JobError> {
+    let pool = current_job.pool().clone();
+    JobExecutor::builder(&mut current_job)
+        .initial_retry_delay(std::time::Duration::from_secs(2))
+        .build()
+        .expect("couldn't build JobExecutor")
+        .execute(|data| async move {
+            let data: BumpBatchFeeData = data.expect("no BumpBatchFeeData available");
+            let (data, res) = bump_batch_fee::execute(
+                pool,
+                data,
+                batches,
+                payout_queues,
+                payouts,
+                utxos,
+                signing_sessions,
+                ledger,
+                fees_client,
+            )
+            .await?;
+            if let Some((mut tx, wallet_ids)) = res {
+                for id in wallet_ids {
+                    spawn_batch_wallet_accounting(&mut tx, (&data, id)).await?;
+                }
+                spawn_batch_signing(tx, &data).await?;
+            }
+
+            Ok::<_, JobError>(data)
+        })
+        .await?;
+    Ok(())
+}
+
 #[instrument(name = "job.spawn_sync_all_wallets", skip_all, fields(error, error.level, error.message), err)]
 pub async fn spawn_sync_all_wallets(
     pool: &sqlx::PgPool,
@@ -420,6 +642,80 @@ pub async fn spawn_sync_all_wallets(
     }
 }
 
+#[instrument(name = "job.spawn_check_expiring_timelocks", skip_all, fields(error, error.level, error.message), err)]
+pub async fn spawn_check_expiring_timelocks(
+    pool: &sqlx::PgPool,
+    delay: std::time::Duration,
+) -> Result<(), JobError> {
+    match JobBuilder::new_with_id(CHECK_EXPIRING_TIMELOCKS_ID, "check_expiring_timelocks")
+        .set_channel_name("check_expiring_timelocks")
+        .set_delay(delay)
+        .spawn(pool)
+        .await
+    {
+        Err(sqlx::Error::Database(err)) if err.message().contains("duplicate key") => Ok(()),
+        Err(e) => {
+            crate::tracing::insert_error_fields(tracing::Level::ERROR, &e);
+            Err(e.into())
+        }
+        Ok(_) => Ok(()),
+    }
+}
+
+#[instrument(name = "job.spawn_consolidate_all_wallets", skip_all, fields(error, error.level, error.message), err)]
+pub async fn spawn_consolidate_all_wallets(
+    pool: &sqlx::PgPool,
+    delay: std::time::Duration,
+) -> Result<(), JobError> {
+    match JobBuilder::new_with_id(CONSOLIDATE_ALL_WALLETS_ID, "consolidate_all_wallets")
+        .set_channel_name("consolidate_all_wallets")
+        .set_delay(delay)
+        .spawn(pool)
+        .await
+    {
+        Err(sqlx::Error::Database(err)) if err.message().contains("duplicate key") => Ok(()),
+        Err(e) => {
+            crate::tracing::insert_error_fields(tracing::Level::ERROR, &e);
+            Err(e.into())
+        }
+        Ok(_) => Ok(()),
+    }
+}
+
+async fn spawn_consolidate_wallet(
+    pool: &sqlx::PgPool,
+    data: ConsolidateWalletData,
+) -> Result<ConsolidateWalletData, JobError> {
+    // Shares the channel with process_payout_queue so utxo reservation never races
+    onto_account_main_channel(
+        pool,
+        data.account_id,
+        Uuid::new_v5(&Uuid::from(data.wallet_id), b"consolidate"),
+        "consolidate_wallet",
+        data,
+    )
+    .await
+}
+
+#[instrument(name = "job.spawn_rescan_wallet", skip_all, fields(error, error.level, error.message), err)]
+pub async fn spawn_rescan_wallet(
+    pool: &sqlx::PgPool,
+    account_id: AccountId,
+    wallet_id: WalletId,
+    gap_limit: Option<u32>,
+) -> Result<(), JobError> {
+    // Not deduplicated against the regular sync so that the gap limit isn't dropped
+    onto_account_main_channel(
+        pool,
+        account_id,
+        Uuid::new_v4(),
+        "sync_wallet",
+        SyncWalletData::rescan(account_id, wallet_id, gap_limit),
+    )
+    .await?;
+    Ok(())
+}
+
 #[instrument(name = "job.spawn_sync_wallet", skip_all, fields(error, error.level, error.message), err)]
 async fn spawn_sync_wallet(pool: &sqlx::PgPool, data: SyncWalletData) -> Result<(), JobError> {
     onto_account_main_channel(pool, data.account_id, data.wallet_id, "sync_wallet", data).await?;
@@ -446,6 +742,15 @@ pub async fn spawn_process_all_payout_queues(
     }
 }
 
+#[instrument(name = "job.spawn_bump_batch_fee", skip_all, fields(error, error.level, error.message), err)]
+pub async fn spawn_bump_batch_fee(
+    pool: &sqlx::PgPool,
+    data: impl Into<BumpBatchFeeData>,
+) -> Result<BumpBatchFeeData, JobError> {
+    let data = data.into();
+    onto_account_main_channel(pool, data.account_id, data.batch_id, "bump_batch_fee", data).await
+}
+
 #[instrument(name = "job.schedule_spawn_process_payout_queue", skip_all, fields(error, error.level, error.message), err)]
 async fn spawn_schedule_process_payout_queue(
     pool: &sqlx::PgPool,
@@ -672,10 +977,19 @@ fn account_main_channel_arg(account_id: AccountId) -> String {
 
 impl From<(AccountId, PayoutQueueId)> for ProcessPayoutQueueData {
     fn from((account_id, payout_queue_id): (AccountId, PayoutQueueId)) -> Self {
+        Self::from((account_id, payout_queue_id, false))
+    }
+}
+
+impl From<(AccountId, PayoutQueueId, bool)> for ProcessPayoutQueueData {
+    fn from(
+        (account_id, payout_queue_id, ignore_fee_caps): (AccountId, PayoutQueueId, bool),
+    ) -> Self {
         Self {
             payout_queue_id,
             account_id,
             batch_id: BatchId::new(),
+            ignore_fee_caps,
             tracing_data: crate::tracing::extract_tracing_data(),
         }
     }
@@ -692,6 +1006,51 @@ impl From<(&ProcessPayoutQueueData, WalletId)> for BatchWalletAccountingData {
     }
 }
 
+impl From<(&ConsolidateWalletData, WalletId)> for BatchWalletAccountingData {
+    fn from((data, wallet_id): (&ConsolidateWalletData, WalletId)) -> Self {
+        Self {
+            tracing_data: crate::tracing::extract_tracing_data(),
+            account_id: data.account_id,
+            batch_id: data.batch_id,
+            wallet_id,
+        }
+    }
+}
+
+impl From<(AccountId, BatchId, Option<f32>)> for BumpBatchFeeData {
+    fn from(
+        (account_id, batch_id, fee_rate_sat_per_vbyte): (AccountId, BatchId, Option<f32>),
+    ) -> Self {
+        Self {
+            account_id,
+            batch_id,
+            fee_rate_sat_per_vbyte,
+            tracing_data: crate::tracing::extract_tracing_data(),
+        }
+    }
+}
+
+impl From<(&BumpBatchFeeData, WalletId)> for BatchWalletAccountingData {
+    fn from((data, wallet_id): (&BumpBatchFeeData, WalletId)) -> Self {
+        Self {
+            tracing_data: crate::tracing::extract_tracing_data(),
+            account_id: data.account_id,
+            batch_id: data.batch_id,
+            wallet_id,
+        }
+    }
+}
+
+impl From<&BumpBatchFeeData> for BatchSigningData {
+    fn from(data: &BumpBatchFeeData) -> Self {
+        Self {
+            account_id: data.account_id,
+            batch_id: data.batch_id,
+            tracing_data: crate::tracing::extract_tracing_data(),
+        }
+    }
+}
+
 impl From<&ProcessPayoutQueueData> for BatchSigningData {
     fn from(data: &ProcessPayoutQueueData) -> Self {
         Self {
@@ -701,6 +1060,16 @@ impl From<&ProcessPayoutQueueData> for BatchSigningData {
         }
     }
 }
+impl From<&ConsolidateWalletData> for BatchSigningData {
+    fn from(data: &ConsolidateWalletData) -> Self {
+        Self {
+            account_id: data.account_id,
+            batch_id: data.batch_id,
+            tracing_data: crate::tracing::extract_tracing_data(),
+        }
+    }
+}
+
 impl From<(AccountId, BatchId)> for BatchSigningData {
     fn from((account_id, batch_id): (AccountId, BatchId)) -> Self {
         Self {
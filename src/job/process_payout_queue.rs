@@ -4,8 +4,7 @@ use tracing::instrument;
 
 use super::error::JobError;
 use crate::{
-    batch::*, fees::MempoolSpaceClient, payout::*, payout_queue::*, primitives::*, utxo::*,
-    wallet::*,
+    batch::*, fees::FeesClient, payout::*, payout_queue::*, primitives::*, utxo::*, wallet::*,
 };
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -13,6 +12,8 @@ pub struct ProcessPayoutQueueData {
     pub(super) payout_queue_id: PayoutQueueId,
     pub(super) account_id: AccountId,
     pub(super) batch_id: BatchId,
+    #[serde(default)]
+    pub(super) ignore_fee_caps: bool,
     #[serde(flatten)]
     pub(super) tracing_data: HashMap<String, String>,
 }
@@ -30,7 +31,8 @@ pub struct ProcessPayoutQueueData {
         cpfp_fee_sats,
         psbt,
         batch_id,
-        payout_queue_id
+        payout_queue_id,
+        deferred
     ),
     err
 )]
@@ -43,7 +45,7 @@ pub(super) async fn execute<'a>(
     batches: Batches,
     utxos: Utxos,
     data: ProcessPayoutQueueData,
-    mempool_space_client: MempoolSpaceClient,
+    fees_client: FeesClient,
 ) -> Result<
     (
         ProcessPayoutQueueData,
@@ -57,15 +59,23 @@ pub(super) async fn execute<'a>(
     let mut unbatched_payouts = payouts
         .list_unbatched(data.account_id, data.payout_queue_id)
         .await?;
-    let fee_rate = mempool_space_client
+    let fee_rate = fees_client
         .fee_rate(payout_queue.config.tx_priority)
         .await?;
+    let ignore_fee_caps = data.ignore_fee_caps;
+    if let Some(reason) = fee_rate_deferral(&payout_queue.config, fee_rate, ignore_fee_caps) {
+        tracing::Span::current().record("deferred", true);
+        batch_deferred(payout_queue.id, reason);
+        return Ok((data, None));
+    }
+    let queue_cfg = payout_queue.config.clone();
     let mut tx = pool.begin().await?;
     let FinishedPsbtBuild {
         psbt,
         included_payouts,
         included_utxos,
         wallet_totals,
+        mut deducted_payout_fees,
         tx_id,
         fee_satoshis,
         ..
@@ -82,6 +92,13 @@ pub(super) async fn execute<'a>(
     .await?;
 
     let span = tracing::Span::current();
+    if let Some(reason) =
+        tx_id.and_then(|_| total_fee_deferral(&queue_cfg, fee_satoshis, ignore_fee_caps))
+    {
+        span.record("deferred", true);
+        batch_deferred(data.payout_queue_id, reason);
+        return Ok((data, None));
+    }
     if let (Some(tx_id), Some(psbt)) = (tx_id, psbt) {
         span.record("tx_id", &tracing::field::display(tx_id));
         span.record("psbt", &tracing::field::display(&psbt));
@@ -143,7 +160,11 @@ pub(super) async fn execute<'a>(
             batch_id,
             included_payouts
                 .into_values()
-                .flat_map(|payouts| payouts.into_iter().map(|((id, _, _), vout)| (id, vout))),
+                .flatten()
+                .map(|((id, _, _), vout)| {
+                    let deducted_fee_sats = deducted_payout_fees.remove(&id).unwrap_or_default();
+                    (id, vout, deducted_fee_sats)
+                }),
         );
 
         if unbatched_payouts.n_not_batched() > 0 {
@@ -217,6 +238,8 @@ pub async fn construct_psbt(
     );
 
     let tx_payouts = unbatched_payouts.into_tx_payouts();
+    let fee_from_amount_payouts = unbatched_payouts.fee_from_amount_payout_ids();
+    let sweep_payouts = unbatched_payouts.sweep_payout_ids();
 
     Ok(PsbtBuilder::construct_psbt(
         pool,
@@ -225,6 +248,10 @@ pub async fn construct_psbt(
         reserved_utxos,
         mandatory_cpfp_utxos,
         tx_payouts,
+        fee_from_amount_payouts,
+        sweep_payouts,
+        queue_cfg.spending_path,
+        queue_cfg.coin_selection,
         wallets,
         for_estimation,
     )
@@ -241,6 +268,38 @@ fn queue_drain_error(n_not_batched: usize) {
     span.record("error.message", "Queue could not be drained");
 }
 
+fn fee_rate_deferral(
+    config: &PayoutQueueConfig,
+    fee_rate: bitcoin::FeeRate,
+    ignore_fee_caps: bool,
+) -> Option<String> {
+    (!ignore_fee_caps && config.fee_rate_exceeds_cap(fee_rate)).then(|| {
+        format!(
+            "fee rate {} sat/vb exceeds max_fee_rate_sat_per_vb",
+            fee_rate.as_sat_per_vb()
+        )
+    })
+}
+
+fn total_fee_deferral(
+    config: &PayoutQueueConfig,
+    total_fee: Satoshis,
+    ignore_fee_caps: bool,
+) -> Option<String> {
+    (!ignore_fee_caps && config.total_fee_exceeds_cap(total_fee))
+        .then(|| format!("total fee {total_fee} sats exceeds max_total_fee_sats"))
+}
+
+#[instrument(name = "job.payout_queue_batch_deferred", fields(error.level, error.message))]
+fn batch_deferred(payout_queue_id: PayoutQueueId, reason: String) {
+    let span = tracing::Span::current();
+    span.record(
+        "error.level",
+        tracing::field::display(&tracing::Level::WARN),
+    );
+    span.record("error.message", reason);
+}
+
 impl From<WalletTotals> for WalletSummary {
     fn from(wt: WalletTotals) -> Self {
         let cpfp_details = wt
@@ -281,3 +340,52 @@ impl From<WalletTotals> for WalletSummary {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn capped_config() -> PayoutQueueConfig {
+        PayoutQueueConfig {
+            max_fee_rate_sat_per_vb: Some(10),
+            max_total_fee_sats: Some(Satoshis::from(5_000)),
+            ..PayoutQueueConfig::default()
+        }
+    }
+
+    #[test]
+    fn defers_when_fee_rate_exceeds_cap() {
+        let config = capped_config();
+        assert!(
+            fee_rate_deferral(&config, bitcoin::FeeRate::from_sat_per_vb(10.0), false).is_none()
+        );
+        assert!(
+            fee_rate_deferral(&config, bitcoin::FeeRate::from_sat_per_vb(11.0), false).is_some()
+        );
+    }
+
+    #[test]
+    fn defers_when_total_fee_exceeds_cap() {
+        let config = capped_config();
+        assert!(total_fee_deferral(&config, Satoshis::from(5_000), false).is_none());
+        assert!(total_fee_deferral(&config, Satoshis::from(5_001), false).is_some());
+    }
+
+    #[test]
+    fn ignore_fee_caps_overrides_deferral() {
+        let config = capped_config();
+        assert!(
+            fee_rate_deferral(&config, bitcoin::FeeRate::from_sat_per_vb(50.0), true).is_none()
+        );
+        assert!(total_fee_deferral(&config, Satoshis::from(50_000), true).is_none());
+    }
+
+    #[test]
+    fn uncapped_queue_never_defers() {
+        let config = PayoutQueueConfig::default();
+        assert!(
+            fee_rate_deferral(&config, bitcoin::FeeRate::from_sat_per_vb(500.0), false).is_none()
+        );
+        assert!(total_fee_deferral(&config, Satoshis::from(1_000_000), false).is_none());
+    }
+}
@@ -1,27 +1,31 @@
-use bdk::blockchain::{ElectrumBlockchain, GetHeight};
-use electrum_client::{Client, ConfigBuilder};
+use bdk::blockchain::{
+    electrum::ElectrumBlockchainConfig, ConfigurableBlockchain, ElectrumBlockchain, GetHeight,
+};
+use electrum_client::ElectrumApi;
 use serde::{Deserialize, Serialize};
 use tracing::{info, instrument};
 
 use super::error::JobError;
 use crate::{
     address::*,
-    app::BlockchainConfig,
+    app::{BlockchainConfig, SecurityConfig},
     batch::*,
     bdk::error::BdkError,
     bdk::pg::{ConfirmedIncomeUtxo, ConfirmedSpendTransaction, Transactions, Utxos as BdkUtxos},
-    fees::{self, MempoolSpaceClient},
+    fees::{self, FeesClient},
     ledger::*,
     primitives::*,
-    utxo::{Utxos, WalletUtxo},
+    utxo::{error::UtxoError, SettledUtxo, Utxos, WalletUtxo},
     wallet::*,
 };
-use std::collections::HashMap;
+use std::{collections::HashMap, sync::Arc};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SyncWalletData {
     pub(super) account_id: AccountId,
     pub(super) wallet_id: WalletId,
+    #[serde(default)]
+    pub(super) gap_limit: Option<u32>,
 }
 
 impl SyncWalletData {
@@ -29,6 +33,15 @@ impl SyncWalletData {
         SyncWalletData {
             account_id,
             wallet_id,
+            gap_limit: None,
+        }
+    }
+
+    pub fn rescan(account_id: AccountId, wallet_id: WalletId, gap_limit: Option<u32>) -> Self {
+        SyncWalletData {
+            account_id,
+            wallet_id,
+            gap_limit,
         }
     }
 }
@@ -50,6 +63,7 @@ impl InstrumentationTrackers {
 
 struct Deps {
     blockchain_cfg: BlockchainConfig,
+    security_cfg: SecurityConfig,
     bria_addresses: Addresses,
     bria_utxos: Utxos,
     ledger: Ledger,
@@ -59,7 +73,15 @@ const MAX_TXS_PER_SYNC: usize = 100;
 
 #[instrument(
     name = "job.sync_wallet",
-    skip(pool, wallets, batches, bria_utxos, bria_addresses, ledger),
+    skip(
+        pool,
+        wallets,
+        security_cfg,
+        batches,
+        bria_utxos,
+        bria_addresses,
+        ledger
+    ),
     fields(
         n_pending_utxos,
         n_confirmed_utxos,
@@ -74,12 +96,13 @@ pub async fn execute(
     pool: sqlx::PgPool,
     wallets: Wallets,
     blockchain_cfg: BlockchainConfig,
+    security_cfg: SecurityConfig,
     bria_utxos: Utxos,
     bria_addresses: Addresses,
     ledger: Ledger,
     batches: Batches,
     data: SyncWalletData,
-    mempool_space_client: MempoolSpaceClient,
+    fees_client: FeesClient,
 ) -> Result<(bool, SyncWalletData), JobError> {
     info!("Starting sync_wallet job: {:?}", data);
     let span = tracing::Span::current();
@@ -87,26 +110,31 @@ pub async fn execute(
     let mut trackers = InstrumentationTrackers::new();
     let deps = Deps {
         blockchain_cfg,
+        security_cfg,
         bria_addresses,
         bria_utxos,
         ledger,
     };
     let mut utxos_to_fetch = HashMap::new();
     let mut income_bria_utxos = Vec::new();
+    let mut source_lookup_client = None;
     for keychain_wallet in wallet.keychain_wallets(pool.clone()) {
         info!("Syncing keychain '{}'", keychain_wallet.keychain_id);
-        let fees_to_encumber = fees::fees_to_encumber(
-            &mempool_space_client,
-            keychain_wallet.max_satisfaction_weight(),
-        )
-        .await?;
+        let fees_to_encumber =
+            fees::fees_to_encumber(&fees_client, keychain_wallet.max_satisfaction_weight()).await?;
         let keychain_id = keychain_wallet.keychain_id;
         utxos_to_fetch.clear();
         utxos_to_fetch.insert(keychain_id, Vec::<bitcoin::OutPoint>::new());
-        let (blockchain, current_height) = init_electrum(&deps.blockchain_cfg.electrum_url).await?;
+        let (blockchain, current_height) = init_electrum(
+            &deps.blockchain_cfg.electrum_url,
+            data.gap_limit.unwrap_or(wallet.config.gap_limit),
+        )
+        .await?;
         span.record("current_height", current_height);
         let latest_change_settle_height = wallet.config.latest_change_settle_height(current_height);
-        keychain_wallet.sync(blockchain).await?;
+        keychain_wallet
+            .sync(blockchain, wallet.config.lookahead)
+            .await?;
         let bdk_txs = Transactions::new(keychain_id, pool.clone());
         let bdk_utxos = BdkUtxos::new(keychain_id, pool.clone());
         let mut txs_to_skip = Vec::new();
@@ -139,6 +167,42 @@ pub async fn execute(
                 }
             }
             txs_to_skip.clear();
+            let blocked_sources = if deps.security_cfg.has_blocked_addresses()
+                && unsynced_tx
+                    .outputs
+                    .iter()
+                    .any(|(u, _)| u.keychain == bitcoin::KeychainKind::External)
+            {
+                let own_inputs = utxos_to_fetch.get(&keychain_id).unwrap();
+                let foreign_inputs = unsynced_tx
+                    .previous_outputs
+                    .iter()
+                    .filter(|outpoint| !own_inputs.contains(outpoint))
+                    .copied()
+                    .collect();
+                match find_blocked_sources(
+                    &deps,
+                    &mut source_lookup_client,
+                    wallet.network,
+                    foreign_inputs,
+                )
+                .await
+                {
+                    Ok(blocked_sources) => blocked_sources,
+                    Err(err) => {
+                        // Leave the tx unsynced so that the check is retried on the next sync
+                        tracing::error!(
+                            tx_id = %unsynced_tx.tx_id,
+                            error = %err,
+                            "couldn't look up the sources of tx"
+                        );
+                        txs_to_skip.push(unsynced_tx.tx_id.to_string());
+                        continue;
+                    }
+                }
+            } else {
+                Vec::new()
+            };
             for output in unsynced_tx.outputs.drain(..) {
                 if output.0.keychain == bitcoin::KeychainKind::Internal {
                     change.push(output);
@@ -174,6 +238,24 @@ pub async fn execute(
                     .await?
                 {
                     trackers.n_pending_utxos += 1;
+                    if !blocked_sources.is_empty() {
+                        deps.bria_utxos
+                            .quarantine_utxo(
+                                &mut tx,
+                                keychain_id,
+                                local_utxo.outpoint,
+                                &blocked_sources,
+                            )
+                            .await?;
+                    }
+                    if wallet
+                        .config
+                        .is_dust(Satoshis::from(local_utxo.txout.value))
+                    {
+                        deps.bria_utxos
+                            .mark_as_dust(&mut tx, keychain_id, local_utxo.outpoint)
+                            .await?;
+                    }
                     deps.bria_addresses
                         .persist_if_not_present(&mut tx, found_addr)
                         .await?;
@@ -232,27 +314,16 @@ pub async fn execute(
                             .await?;
                         trackers.n_confirmed_utxos += 1;
 
-                        deps.ledger
-                            .utxo_settled(
-                                tx,
-                                utxo.utxo_settled_ledger_tx_id,
-                                UtxoSettledParams {
-                                    journal_id: wallet.journal_id,
-                                    ledger_account_ids: wallet.ledger_account_ids,
-                                    pending_id: utxo.utxo_detected_ledger_tx_id,
-                                    meta: UtxoSettledMeta {
-                                        account_id: data.account_id,
-                                        wallet_id: data.wallet_id,
-                                        keychain_id,
-                                        confirmation_time: conf_time.clone(),
-                                        satoshis: utxo.value,
-                                        outpoint: local_utxo.outpoint,
-                                        address: utxo.address,
-                                        already_spent_tx_id: utxo.spend_detected_ledger_tx_id,
-                                    },
-                                },
-                            )
-                            .await?;
+                        settle_income_utxo(
+                            &deps,
+                            tx,
+                            &wallet,
+                            data.account_id,
+                            local_utxo.outpoint,
+                            conf_time.clone(),
+                            utxo,
+                        )
+                        .await?;
                     }
                 }
             }
@@ -433,27 +504,16 @@ pub async fn execute(
                     .await?;
                 trackers.n_confirmed_utxos += 1;
 
-                deps.ledger
-                    .utxo_settled(
-                        tx,
-                        utxo.utxo_settled_ledger_tx_id,
-                        UtxoSettledParams {
-                            journal_id: wallet.journal_id,
-                            ledger_account_ids: wallet.ledger_account_ids,
-                            pending_id: utxo.utxo_detected_ledger_tx_id,
-                            meta: UtxoSettledMeta {
-                                account_id: data.account_id,
-                                wallet_id: data.wallet_id,
-                                keychain_id,
-                                confirmation_time,
-                                satoshis: utxo.value,
-                                outpoint,
-                                address: utxo.address,
-                                already_spent_tx_id: utxo.spend_detected_ledger_tx_id,
-                            },
-                        },
-                    )
-                    .await?;
+                settle_income_utxo(
+                    &deps,
+                    tx,
+                    &wallet,
+                    data.account_id,
+                    outpoint,
+                    confirmation_time,
+                    utxo,
+                )
+                .await?;
             } else {
                 break;
             }
@@ -508,10 +568,20 @@ pub async fn execute(
                 bdk_txs
                     .delete_transaction_if_no_more_utxos_exist(&mut tx, outpoint)
                     .await?;
-                let detected_txn_id = deps
+                let detected_txn_id = match deps
                     .bria_utxos
                     .delete_utxo(&mut tx, outpoint, keychain_id)
-                    .await?;
+                    .await
+                {
+                    Ok(id) => id,
+                    // Change of a replaced batch transaction is removed when the batch is bumped
+                    Err(UtxoError::UtxoDoesNotExistError) => {
+                        tracing::info!(%outpoint, "skipping dropped utxo that is no longer tracked");
+                        tx.commit().await?;
+                        continue;
+                    }
+                    Err(e) => return Err(e.into()),
+                };
                 match deps
                     .ledger
                     .utxo_dropped(tx, LedgerTransactionId::new(), detected_txn_id)
@@ -538,15 +608,146 @@ pub async fn execute(
     Ok((has_more, data))
 }
 
-async fn init_electrum(electrum_url: &str) -> Result<(ElectrumBlockchain, u32), BdkError> {
-    let blockchain = ElectrumBlockchain::from(Client::from_config(
-        electrum_url,
-        ConfigBuilder::new()
-            .retry(10)
-            .timeout(Some(60))
-            .expect("couldn't set electrum timeout")
-            .build(),
-    )?);
+async fn settle_income_utxo(
+    deps: &Deps,
+    tx: sqlx::Transaction<'_, sqlx::Postgres>,
+    wallet: &Wallet,
+    account_id: AccountId,
+    outpoint: bitcoin::OutPoint,
+    confirmation_time: bitcoin::BlockTime,
+    utxo: SettledUtxo,
+) -> Result<(), JobError> {
+    match utxo.quarantine_sources {
+        // A quarantined utxo that has already been spent is settled as usual
+        // so that the spend can be accounted for
+        Some(blocked_sources) if utxo.spend_detected_ledger_tx_id.is_none() => {
+            deps.ledger
+                .utxo_quarantined(
+                    tx,
+                    utxo.utxo_settled_ledger_tx_id,
+                    UtxoQuarantinedParams {
+                        journal_id: wallet.journal_id,
+                        ledger_account_ids: wallet.ledger_account_ids,
+                        pending_id: utxo.utxo_detected_ledger_tx_id,
+                        meta: UtxoQuarantinedMeta {
+                            account_id,
+                            wallet_id: wallet.id,
+                            keychain_id: utxo.keychain_id,
+                            confirmation_time,
+                            satoshis: utxo.value,
+                            outpoint,
+                            address: utxo.address,
+                            blocked_sources,
+                        },
+                    },
+                )
+                .await?
+        }
+        // Dust that has already been spent is settled as usual for the same reason
+        _ if utxo.dust && utxo.spend_detected_ledger_tx_id.is_none() => {
+            deps.ledger
+                .utxo_dust_settled(
+                    tx,
+                    utxo.utxo_settled_ledger_tx_id,
+                    UtxoDustSettledParams {
+                        journal_id: wallet.journal_id,
+                        ledger_account_ids: wallet.ledger_account_ids,
+                        pending_id: utxo.utxo_detected_ledger_tx_id,
+                        meta: UtxoDustSettledMeta {
+                            account_id,
+                            wallet_id: wallet.id,
+                            keychain_id: utxo.keychain_id,
+                            confirmation_time,
+                            satoshis: utxo.value,
+                            outpoint,
+                            address: utxo.address,
+                        },
+                    },
+                )
+                .await?
+        }
+        _ => {
+            deps.ledger
+                .utxo_settled(
+                    tx,
+                    utxo.utxo_settled_ledger_tx_id,
+                    UtxoSettledParams {
+                        journal_id: wallet.journal_id,
+                        ledger_account_ids: wallet.ledger_account_ids,
+                        pending_id: utxo.utxo_detected_ledger_tx_id,
+                        meta: UtxoSettledMeta {
+                            account_id,
+                            wallet_id: wallet.id,
+                            keychain_id: utxo.keychain_id,
+                            confirmation_time,
+                            satoshis: utxo.value,
+                            outpoint,
+                            address: utxo.address,
+                            already_spent_tx_id: utxo.spend_detected_ledger_tx_id,
+                        },
+                    },
+                )
+                .await?
+        }
+    }
+    Ok(())
+}
+
+/// Looks up the addresses that funded the given inputs and returns the ones that are blocked.
+/// The electrum client is connected on first use and reused for the rest of the sync.
+async fn find_blocked_sources(
+    deps: &Deps,
+    client: &mut Option<Arc<electrum_client::Client>>,
+    network: bitcoin::Network,
+    previous_outputs: Vec<bitcoin::OutPoint>,
+) -> Result<Vec<bitcoin::Address>, BdkError> {
+    if previous_outputs.is_empty() {
+        return Ok(Vec::new());
+    }
+    let client = match client {
+        Some(client) => Arc::clone(client),
+        None => {
+            let electrum_url = deps.blockchain_cfg.electrum_url.clone();
+            let new_client = Arc::new(
+                tokio::task::spawn_blocking(move || electrum_client::Client::new(&electrum_url))
+                    .await??,
+            );
+            client.insert(new_client).clone()
+        }
+    };
+    let tx_ids: Vec<_> = previous_outputs
+        .iter()
+        .map(|outpoint| outpoint.txid)
+        .collect();
+    let previous_txs =
+        tokio::task::spawn_blocking(move || client.batch_transaction_get(&tx_ids)).await??;
+    let mut blocked_sources = Vec::new();
+    for (outpoint, previous_tx) in previous_outputs.iter().zip(previous_txs) {
+        let source = previous_tx
+            .output
+            .get(outpoint.vout as usize)
+            .and_then(|out| bitcoin::Address::from_script(&out.script_pubkey, network).ok());
+        if let Some(source) = source {
+            if deps.security_cfg.is_blocked_source(&source) && !blocked_sources.contains(&source) {
+                blocked_sources.push(source);
+            }
+        }
+    }
+    Ok(blocked_sources)
+}
+
+async fn init_electrum(
+    electrum_url: &str,
+    gap_limit: u32,
+) -> Result<(ElectrumBlockchain, u32), BdkError> {
+    let blockchain = ElectrumBlockchain::from_config(&ElectrumBlockchainConfig {
+        url: electrum_url.to_string(),
+        socks5: None,
+        retry: 10,
+        timeout: Some(60),
+        stop_gap: gap_limit as usize,
+        validate_domain: true,
+    })?;
     let current_height = blockchain.get_height()?;
     Ok((blockchain, current_height))
 }
@@ -10,6 +10,18 @@ pub(super) const UTXO_SETTLED_ID: Uuid = uuid!("00000000-0000-0000-0000-00000000
 pub(super) const UTXO_DROPPED_CODE: &str = "UTXO_DROPPED";
 pub(super) const UTXO_DROPPED_ID: Uuid = uuid!("00000000-0000-0000-0000-000000000009");
 
+pub(super) const UTXO_QUARANTINED_CODE: &str = "UTXO_QUARANTINED";
+pub(super) const UTXO_QUARANTINED_ID: Uuid = uuid!("00000000-0000-0000-0000-000000000012");
+
+pub(super) const UTXO_QUARANTINE_RELEASED_CODE: &str = "UTXO_QUARANTINE_RELEASED";
+pub(super) const UTXO_QUARANTINE_RELEASED_ID: Uuid = uuid!("00000000-0000-0000-0000-000000000015");
+
+pub(super) const UTXO_DUST_SETTLED_CODE: &str = "UTXO_DUST_SETTLED";
+pub(super) const UTXO_DUST_SETTLED_ID: Uuid = uuid!("00000000-0000-0000-0000-000000000013");
+
+pub(super) const DUST_SWEPT_CODE: &str = "DUST_SWEPT";
+pub(super) const DUST_SWEPT_ID: Uuid = uuid!("00000000-0000-0000-0000-000000000014");
+
 pub(super) const SPENT_UTXO_SETTLED_CODE: &str = "SPENT_UTXO_SETTLED";
 pub(super) const SPENT_UTXO_SETTLED_ID: Uuid = uuid!("00000000-0000-0000-0000-000000000003");
 
@@ -31,12 +43,18 @@ pub(super) const _BATCH_CREATED_LEGACY_ID: Uuid = uuid!("00000000-0000-0000-0000
 pub(super) const FIX_BATCH_CREATED_LEGACY_CODE: &str = "FIX_BATCH_CREATED";
 pub(super) const FIX_BATCH_CREATED_LEGACY_ID: Uuid = uuid!("00000000-0000-0000-0000-100000000007");
 
-pub(super) const BATCH_CREATED_CODE: &str = "BATCH_CREATED_V2";
-pub(super) const BATCH_CREATED_ID: Uuid = uuid!("10000000-0000-0000-0000-000000000007");
+pub(super) const BATCH_CREATED_V2_CODE: &str = "BATCH_CREATED_V2";
+pub(super) const BATCH_CREATED_V2_ID: Uuid = uuid!("10000000-0000-0000-0000-000000000007");
+
+pub(super) const BATCH_CREATED_CODE: &str = "BATCH_CREATED_V3";
+pub(super) const BATCH_CREATED_ID: Uuid = uuid!("20000000-0000-0000-0000-000000000007");
 
 pub(super) const BATCH_BROADCAST_CODE: &str = "BATCH_BROADCAST";
 pub(super) const BATCH_BROADCAST_ID: Uuid = uuid!("00000000-0000-0000-0000-000000000008");
 
+pub(super) const BATCH_DROPPED_CODE: &str = "BATCH_DROPPED";
+pub(super) const BATCH_DROPPED_ID: Uuid = uuid!("00000000-0000-0000-0000-000000000011");
+
 // Onchain/Omnibus Ledger Accounts
 pub(super) const ONCHAIN_UTXO_INCOMING_CODE: &str = "ONCHAIN_UTXO_INCOMING";
 pub(super) const ONCHAIN_UTXO_INCOMING_ID: Uuid = uuid!("00000000-1910-0000-1000-000000000000");
@@ -59,6 +77,12 @@ pub(super) const EFFECTIVE_AT_REST_ID: Uuid = uuid!("00000000-1900-0000-2000-000
 pub(super) const EFFECTIVE_OUTGOING_CODE: &str = "EFFECTIVE_OUTGOING";
 pub(super) const EFFECTIVE_OUTGOING_ID: Uuid = uuid!("00000000-1920-0000-2000-000000000000");
 
+pub(super) const EFFECTIVE_QUARANTINED_CODE: &str = "EFFECTIVE_QUARANTINED";
+pub(super) const EFFECTIVE_QUARANTINED_ID: Uuid = uuid!("00000000-1900-0000-4000-000000000000");
+
+pub(super) const EFFECTIVE_DUST_CODE: &str = "EFFECTIVE_DUST";
+pub(super) const EFFECTIVE_DUST_ID: Uuid = uuid!("00000000-1900-0000-0000-000000000000");
+
 pub const CURRENCY_CODE: &str = "00000000";
 pub enum Element {
     #[allow(dead_code)] // Used in omnibus accounts
@@ -105,6 +129,7 @@ pub enum Category {
     Effective,
     Fee,
     Dust,
+    Quarantined,
 }
 
 impl Category {
@@ -114,6 +139,7 @@ impl Category {
             Category::Effective => "2000",
             Category::Fee => "3000",
             Category::Dust => "0000",
+            Category::Quarantined => "4000",
         }
     }
 }
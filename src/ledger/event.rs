@@ -18,6 +18,8 @@ pub struct JournalEvent {
 pub enum JournalEventMetadata {
     UtxoDetected(UtxoDetectedMeta, SqlxLedgerEventId),
     UtxoSettled(UtxoSettledMeta),
+    UtxoQuarantined(UtxoQuarantinedMeta),
+    UtxoDustSettled(UtxoDustSettledMeta),
     UtxoDropped(UtxoDroppedMeta, SqlxLedgerEventId),
     SpendDetected(SpendDetectedMeta),
     SpendSettled(SpendSettledMeta),
@@ -25,6 +27,7 @@ pub enum JournalEventMetadata {
     PayoutCancelled(PayoutCancelledMeta),
     BatchCreated(BatchCreatedMeta),
     BatchBroadcast(BatchBroadcastMeta),
+    BatchDropped(BatchDroppedMeta),
     UnknownTransaction(Option<serde_json::Value>),
 }
 
@@ -51,6 +54,14 @@ impl TryFrom<SqlxLedgerEvent> for MaybeIgnored {
                         tx.metadata::<UtxoSettledMeta>()?
                             .ok_or(LedgerError::MissingTxMetadata)?,
                     ),
+                    UTXO_QUARANTINED_ID => JournalEventMetadata::UtxoQuarantined(
+                        tx.metadata::<UtxoQuarantinedMeta>()?
+                            .ok_or(LedgerError::MissingTxMetadata)?,
+                    ),
+                    UTXO_DUST_SETTLED_ID => JournalEventMetadata::UtxoDustSettled(
+                        tx.metadata::<UtxoDustSettledMeta>()?
+                            .ok_or(LedgerError::MissingTxMetadata)?,
+                    ),
                     UTXO_DROPPED_ID => JournalEventMetadata::UtxoDropped(
                         tx.metadata::<UtxoDroppedMeta>()?
                             .ok_or(LedgerError::MissingTxMetadata)?,
@@ -72,7 +83,7 @@ impl TryFrom<SqlxLedgerEvent> for MaybeIgnored {
                         tx.metadata::<PayoutCancelledMeta>()?
                             .ok_or(LedgerError::MissingTxMetadata)?,
                     ),
-                    BATCH_CREATED_ID => JournalEventMetadata::BatchCreated(
+                    BATCH_CREATED_ID | BATCH_CREATED_V2_ID => JournalEventMetadata::BatchCreated(
                         tx.metadata::<BatchCreatedMeta>()?
                             .ok_or(LedgerError::MissingTxMetadata)?,
                     ),
@@ -80,6 +91,10 @@ impl TryFrom<SqlxLedgerEvent> for MaybeIgnored {
                         tx.metadata::<BatchBroadcastMeta>()?
                             .ok_or(LedgerError::MissingTxMetadata)?,
                     ),
+                    BATCH_DROPPED_ID => JournalEventMetadata::BatchDropped(
+                        tx.metadata::<BatchDroppedMeta>()?
+                            .ok_or(LedgerError::MissingTxMetadata)?,
+                    ),
                     _ => JournalEventMetadata::UnknownTransaction(tx.metadata_json),
                 },
             ),
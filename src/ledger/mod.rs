@@ -4,7 +4,7 @@ mod event;
 mod templates;
 mod wallet_accounts;
 
-use sqlx::{PgPool, Postgres, Transaction};
+use sqlx::{Acquire, PgPool, Postgres, Transaction};
 use sqlx_ledger::{
     account::NewAccount as NewLedgerAccount, event::*, journal::*, Currency, DebitOrCredit,
     JournalId, SqlxLedger, SqlxLedgerError,
@@ -46,9 +46,15 @@ impl Ledger {
         Self::effective_income_account(&inner).await?;
         Self::effective_at_rest_account(&inner).await?;
         Self::effective_outgoing_account(&inner).await?;
+        Self::effective_quarantined_account(&inner).await?;
+        Self::effective_dust_account(&inner).await?;
 
         templates::UtxoDetected::init(&inner).await?;
         templates::UtxoSettled::init(&inner).await?;
+        templates::UtxoQuarantined::init(&inner).await?;
+        templates::UtxoQuarantineReleased::init(&inner).await?;
+        templates::UtxoDustSettled::init(&inner).await?;
+        templates::DustSwept::init(&inner).await?;
         templates::UtxoDropped::init(&inner).await?;
         templates::SpentUtxoSettled::init(&inner).await?;
         templates::SpendDetected::init(&inner).await?;
@@ -59,6 +65,7 @@ impl Ledger {
             templates::fix::legacy_batch_created(&inner).await?;
         }
         templates::BatchBroadcast::init(&inner).await?;
+        templates::BatchDropped::init(&inner).await?;
 
         Ok(Self {
             inner,
@@ -238,6 +245,79 @@ impl Ledger {
         Ok(())
     }
 
+    #[instrument(name = "ledger.utxo_quarantined", skip(self, tx))]
+    pub async fn utxo_quarantined(
+        &self,
+        mut tx: Transaction<'_, Postgres>,
+        tx_id: LedgerTransactionId,
+        params: UtxoQuarantinedParams,
+    ) -> Result<(), LedgerError> {
+        // Wallets created before quarantining was introduced don't have the account yet.
+        // A savepoint keeps a duplicate key from aborting the surrounding transaction.
+        let prefix = params.ledger_account_ids.get_wallet_id_prefix();
+        let mut savepoint = tx.begin().await.map_err(SqlxLedgerError::from)?;
+        match self
+            .create_account_for_wallet(
+                &mut savepoint,
+                &prefix,
+                params.ledger_account_ids.quarantined_id,
+                format!("WALLET_{prefix}_QUARANTINED"),
+                format!("{prefix}-quarantined"),
+                DebitOrCredit::Credit,
+            )
+            .await
+        {
+            Ok(_) => savepoint.commit().await.map_err(SqlxLedgerError::from)?,
+            Err(LedgerError::SqlxLedger(SqlxLedgerError::DuplicateKey(_))) => {
+                savepoint.rollback().await.map_err(SqlxLedgerError::from)?
+            }
+            Err(e) => return Err(e),
+        }
+        self.inner
+            .post_transaction_in_tx(tx, tx_id, UTXO_QUARANTINED_CODE, Some(params))
+            .await?;
+        Ok(())
+    }
+
+    #[instrument(name = "ledger.utxo_quarantine_released", skip(self, tx))]
+    pub async fn utxo_quarantine_released(
+        &self,
+        tx: Transaction<'_, Postgres>,
+        tx_id: LedgerTransactionId,
+        params: UtxoQuarantineReleasedParams,
+    ) -> Result<(), LedgerError> {
+        self.inner
+            .post_transaction_in_tx(tx, tx_id, UTXO_QUARANTINE_RELEASED_CODE, Some(params))
+            .await?;
+        Ok(())
+    }
+
+    #[instrument(name = "ledger.utxo_dust_settled", skip(self, tx))]
+    pub async fn utxo_dust_settled(
+        &self,
+        tx: Transaction<'_, Postgres>,
+        tx_id: LedgerTransactionId,
+        params: UtxoDustSettledParams,
+    ) -> Result<(), LedgerError> {
+        self.inner
+            .post_transaction_in_tx(tx, tx_id, UTXO_DUST_SETTLED_CODE, Some(params))
+            .await?;
+        Ok(())
+    }
+
+    #[instrument(name = "ledger.dust_swept", skip(self, tx))]
+    pub async fn dust_swept(
+        &self,
+        tx: Transaction<'_, Postgres>,
+        tx_id: LedgerTransactionId,
+        params: DustSweptParams,
+    ) -> Result<(), LedgerError> {
+        self.inner
+            .post_transaction_in_tx(tx, tx_id, DUST_SWEPT_CODE, Some(params))
+            .await?;
+        Ok(())
+    }
+
     #[instrument(name = "ledger.payout_submitted", skip(self, tx))]
     pub async fn payout_submitted(
         &self,
@@ -273,6 +353,7 @@ impl Ledger {
             payout_queue_id,
             satoshis,
             destination,
+            ..
         } = txn.metadata()?.ok_or(LedgerError::MissingTxMetadata)?;
         let entries = self
             .inner
@@ -362,6 +443,90 @@ impl Ledger {
         Ok(())
     }
 
+    #[instrument(name = "ledger.batch_dropped", skip(self, tx))]
+    pub async fn batch_dropped(
+        &self,
+        tx: Transaction<'_, Postgres>,
+        tx_id: LedgerTransactionId,
+        batch_created_tx_id: LedgerTransactionId,
+        batch_broadcast_tx_id: LedgerTransactionId,
+        ledger_account_ids: WalletLedgerAccountIds,
+    ) -> Result<(), LedgerError> {
+        let txs = self
+            .inner
+            .transactions()
+            .list_by_ids([batch_created_tx_id, batch_broadcast_tx_id])
+            .await?;
+        let created_txn = txs
+            .iter()
+            .find(|t| t.id == batch_created_tx_id)
+            .ok_or(LedgerError::TransactionNotFound)?;
+        let broadcast_txn = txs
+            .iter()
+            .find(|t| t.id == batch_broadcast_tx_id)
+            .ok_or(LedgerError::TransactionNotFound)?;
+        let BatchCreatedMeta {
+            batch_info,
+            tx_summary,
+        } = created_txn
+            .metadata()?
+            .ok_or(LedgerError::MissingTxMetadata)?;
+        let BatchBroadcastMeta {
+            encumbered_spending_fees,
+            ..
+        } = broadcast_txn
+            .metadata()?
+            .ok_or(LedgerError::MissingTxMetadata)?;
+        let entries = self
+            .inner
+            .entries()
+            .list_by_transaction_ids(std::iter::once(batch_created_tx_id))
+            .await?;
+        let encumbered_fees = entries
+            .into_values()
+            .flatten()
+            .find_map(|entry| match entry.entry_type.as_str() {
+                "BATCH_CREATED_FR_ENC_CR" => Some(Satoshis::from_btc(entry.units)),
+                _ => None,
+            })
+            .ok_or(LedgerError::ExpectedEntryNotFoundInTx(
+                "Encumbered fees entry not found",
+            ))?;
+
+        let params = BatchDroppedParams {
+            journal_id: created_txn.journal_id,
+            ledger_account_ids,
+            encumbered_fees,
+            encumbered_spending_fees: encumbered_spending_fees.values().copied().sum(),
+            meta: BatchDroppedMeta {
+                batch_info,
+                tx_summary,
+                batch_created_tx_id,
+                batch_broadcast_tx_id,
+            },
+        };
+        self.inner
+            .post_transaction_in_tx(tx, tx_id, BATCH_DROPPED_CODE, Some(params))
+            .await?;
+        Ok(())
+    }
+
+    #[instrument(name = "ledger.batch_inputs_settled_when_created", skip(self))]
+    pub async fn batch_inputs_settled_when_created(
+        &self,
+        batch_created_tx_id: LedgerTransactionId,
+    ) -> Result<bool, LedgerError> {
+        let txs = self
+            .inner
+            .transactions()
+            .list_by_ids(std::iter::once(batch_created_tx_id))
+            .await?;
+        let txn = txs.first().ok_or(LedgerError::TransactionNotFound)?;
+        let BatchCreatedMeta { tx_summary, .. } =
+            txn.metadata()?.ok_or(LedgerError::MissingTxMetadata)?;
+        Ok(tx_summary.total_utxo_in_sats == tx_summary.total_utxo_settled_in_sats)
+    }
+
     #[instrument(name = "ledger.spend_detected", skip(self, tx))]
     pub async fn spend_detected(
         &self,
@@ -465,6 +630,7 @@ impl Ledger {
             effective_outgoing_id,
             fee_id,
             dust_id,
+            quarantined_id,
         }: WalletLedgerAccountIds,
     ) -> Result<WalletLedgerAccountBalances, LedgerError> {
         let mut balances = self
@@ -481,6 +647,7 @@ impl Ledger {
                     effective_outgoing_id,
                     fee_id,
                     dust_id,
+                    quarantined_id,
                 ],
             )
             .await?;
@@ -505,6 +672,9 @@ impl Ledger {
                 .and_then(|b| b.remove(&self.btc)),
             fee: balances.get_mut(&fee_id).and_then(|b| b.remove(&self.btc)),
             dust: balances.get_mut(&dust_id).and_then(|b| b.remove(&self.btc)),
+            quarantined: balances
+                .get_mut(&quarantined_id)
+                .and_then(|b| b.remove(&self.btc)),
         })
     }
 
@@ -660,6 +830,16 @@ impl Ledger {
                     DebitOrCredit::Credit,
                 )
                 .await?,
+            quarantined_id: self
+                .create_account_for_wallet(
+                    tx,
+                    &prefix,
+                    wallet_ledger_account_ids.quarantined_id,
+                    format!("WALLET_{prefix}_QUARANTINED"),
+                    format!("{prefix}-quarantined"),
+                    DebitOrCredit::Credit,
+                )
+                .await?,
         };
         Ok(account_ids)
     }
@@ -819,4 +999,42 @@ impl Ledger {
             Ok(id) => Ok(id),
         }
     }
+
+    #[instrument(name = "ledger.effective_quarantined_account", skip_all)]
+    async fn effective_quarantined_account(
+        ledger: &SqlxLedger,
+    ) -> Result<LedgerAccountId, LedgerError> {
+        let new_account = NewLedgerAccount::builder()
+            .code(EFFECTIVE_QUARANTINED_CODE)
+            .id(EFFECTIVE_QUARANTINED_ID)
+            .name(EFFECTIVE_QUARANTINED_CODE)
+            .description("Account for quarantined effective funds".to_string())
+            .normal_balance_type(DebitOrCredit::Debit)
+            .build()
+            .expect("Couldn't create effective quarantined account");
+        match ledger.accounts().create(new_account).await {
+            Err(SqlxLedgerError::DuplicateKey(_)) => {
+                Ok(LedgerAccountId::from(EFFECTIVE_QUARANTINED_ID))
+            }
+            Err(e) => Err(e.into()),
+            Ok(id) => Ok(id),
+        }
+    }
+
+    #[instrument(name = "ledger.effective_dust_account", skip_all)]
+    async fn effective_dust_account(ledger: &SqlxLedger) -> Result<LedgerAccountId, LedgerError> {
+        let new_account = NewLedgerAccount::builder()
+            .code(EFFECTIVE_DUST_CODE)
+            .id(EFFECTIVE_DUST_ID)
+            .name(EFFECTIVE_DUST_CODE)
+            .description("Account for dust effective funds".to_string())
+            .normal_balance_type(DebitOrCredit::Debit)
+            .build()
+            .expect("Couldn't create effective dust account");
+        match ledger.accounts().create(new_account).await {
+            Err(SqlxLedgerError::DuplicateKey(_)) => Ok(LedgerAccountId::from(EFFECTIVE_DUST_ID)),
+            Err(e) => Err(e.into()),
+            Ok(id) => Ok(id),
+        }
+    }
 }
@@ -82,6 +82,11 @@ impl BatchCreatedParams {
                 .r#type(ParamDataType::DECIMAL)
                 .build()
                 .unwrap(),
+            ParamDefinition::builder()
+                .name("deducted_fees")
+                .r#type(ParamDataType::DECIMAL)
+                .build()
+                .unwrap(),
             ParamDefinition::builder()
                 .name("encumbered_fees")
                 .r#type(ParamDataType::DECIMAL)
@@ -128,6 +133,12 @@ impl From<BatchCreatedParams> for TxParams {
             .iter()
             .fold(Satoshis::ZERO, |s, u| s + u.satoshis)
             .to_btc();
+        let deducted_fees = meta
+            .batch_info
+            .included_payouts
+            .iter()
+            .fold(Satoshis::ZERO, |s, p| s + p.deducted_fee_sats)
+            .to_btc();
         let fee_sats = fee_sats.to_btc();
         let encumbered_fees = encumbered_fees.to_btc();
         let effective = Utc::now().date_naive();
@@ -159,6 +170,7 @@ impl From<BatchCreatedParams> for TxParams {
         params.insert("total_utxo_settled_in", total_utxo_settled_in_sats.to_btc());
         params.insert("change", change);
         params.insert("fees", fee_sats);
+        params.insert("deducted_fees", deducted_fees);
         params.insert("encumbered_fees", encumbered_fees);
         params.insert("correlation_id", Uuid::from(batch_id));
         params.insert("meta", meta);
@@ -170,8 +182,35 @@ impl From<BatchCreatedParams> for TxParams {
 pub struct BatchCreated {}
 
 impl BatchCreated {
+    /// Returns true if the V2 template was created, ie. the ledger was still
+    /// on the legacy template and needs to be fixed up.
     #[instrument(name = "ledger.batch_created.init", skip_all)]
     pub async fn init(ledger: &SqlxLedger) -> Result<bool, LedgerError> {
+        let v2_created = Self::create_template(
+            ledger,
+            BATCH_CREATED_V2_ID,
+            BATCH_CREATED_V2_CODE,
+            "params.total_utxo_in - params.change - params.fees",
+        )
+        .await?;
+        // Fees deducted from payout amounts were encumbered on submission
+        // so they must be released together with the payouts.
+        Self::create_template(
+            ledger,
+            BATCH_CREATED_ID,
+            BATCH_CREATED_CODE,
+            "params.total_utxo_in - params.change - params.fees + params.deducted_fees",
+        )
+        .await?;
+        Ok(v2_created)
+    }
+
+    async fn create_template(
+        ledger: &SqlxLedger,
+        id: Uuid,
+        code: &str,
+        encumbered_out_units: &str,
+    ) -> Result<bool, LedgerError> {
         let tx_input = TxInput::builder()
             .journal_id("params.journal_id")
             .effective("params.effective")
@@ -188,7 +227,7 @@ impl BatchCreated {
                 .account_id("params.effective_outgoing_account_id")
                 .direction("DEBIT")
                 .layer("ENCUMBERED")
-                .units("params.total_utxo_in - params.change - params.fees")
+                .units(encumbered_out_units)
                 .build()
                 .expect("Couldn't build entry"),
             EntryInput::builder()
@@ -197,7 +236,7 @@ impl BatchCreated {
                 .account_id(format!("uuid('{EFFECTIVE_OUTGOING_ID}')"))
                 .direction("CREDIT")
                 .layer("ENCUMBERED")
-                .units("params.total_utxo_in - params.change - params.fees")
+                .units(encumbered_out_units)
                 .build()
                 .expect("Couldn't build entry"),
             EntryInput::builder()
@@ -332,8 +371,8 @@ impl BatchCreated {
 
         let params = BatchCreatedParams::defs();
         let template = NewTxTemplate::builder()
-            .id(BATCH_CREATED_ID)
-            .code(BATCH_CREATED_CODE)
+            .id(id)
+            .code(code)
             .tx_input(tx_input)
             .entries(entries)
             .params(params)
@@ -0,0 +1,156 @@
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use sqlx_ledger::{tx_template::*, JournalId, SqlxLedger, SqlxLedgerError};
+use tracing::instrument;
+
+use crate::{
+    ledger::{constants::*, error::LedgerError, WalletLedgerAccountIds},
+    primitives::*,
+};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DustSweptMeta {
+    pub account_id: AccountId,
+    pub wallet_id: WalletId,
+    pub keychain_id: KeychainId,
+    pub outpoint: bitcoin::OutPoint,
+    pub satoshis: Satoshis,
+}
+
+#[derive(Debug)]
+pub struct DustSweptParams {
+    pub journal_id: JournalId,
+    pub ledger_account_ids: WalletLedgerAccountIds,
+    pub meta: DustSweptMeta,
+}
+
+impl DustSweptParams {
+    pub fn defs() -> Vec<ParamDefinition> {
+        vec![
+            ParamDefinition::builder()
+                .name("journal_id")
+                .r#type(ParamDataType::UUID)
+                .build()
+                .unwrap(),
+            ParamDefinition::builder()
+                .name("dust_account_id")
+                .r#type(ParamDataType::UUID)
+                .build()
+                .unwrap(),
+            ParamDefinition::builder()
+                .name("effective_at_rest_account_id")
+                .r#type(ParamDataType::UUID)
+                .build()
+                .unwrap(),
+            ParamDefinition::builder()
+                .name("amount")
+                .r#type(ParamDataType::DECIMAL)
+                .build()
+                .unwrap(),
+            ParamDefinition::builder()
+                .name("meta")
+                .r#type(ParamDataType::JSON)
+                .build()
+                .unwrap(),
+            ParamDefinition::builder()
+                .name("effective")
+                .r#type(ParamDataType::DATE)
+                .build()
+                .unwrap(),
+        ]
+    }
+}
+
+impl From<DustSweptParams> for TxParams {
+    fn from(
+        DustSweptParams {
+            journal_id,
+            ledger_account_ids: accounts,
+            meta,
+        }: DustSweptParams,
+    ) -> Self {
+        let effective = Utc::now().date_naive();
+        let amount = meta.satoshis.to_btc();
+        let meta = serde_json::to_value(meta).expect("Couldn't serialize meta");
+        let mut params = Self::default();
+        params.insert("journal_id", journal_id);
+        params.insert("dust_account_id", accounts.dust_id);
+        params.insert(
+            "effective_at_rest_account_id",
+            accounts.effective_at_rest_id,
+        );
+        params.insert("amount", amount);
+        params.insert("meta", meta);
+        params.insert("effective", effective);
+        params
+    }
+}
+
+pub struct DustSwept {}
+
+impl DustSwept {
+    #[instrument(name = "ledger.dust_swept.init", skip_all)]
+    pub async fn init(ledger: &SqlxLedger) -> Result<(), LedgerError> {
+        let tx_input = TxInput::builder()
+            .journal_id("params.journal_id")
+            .effective("params.effective")
+            .metadata("params.meta")
+            .description("'Dust utxo released for sweeping'")
+            .build()
+            .expect("Couldn't build TxInput");
+        let entries = vec![
+            // EFFECTIVE
+            EntryInput::builder()
+                .entry_type("'DUST_SWEPT_LOG_DUST_DR'")
+                .currency("'BTC'")
+                .account_id("params.dust_account_id")
+                .direction("DEBIT")
+                .layer("SETTLED")
+                .units("params.amount")
+                .build()
+                .expect("Couldn't build entry"),
+            EntryInput::builder()
+                .entry_type("'DUST_SWEPT_LOG_DUST_CR'")
+                .currency("'BTC'")
+                .account_id(format!("uuid('{EFFECTIVE_DUST_ID}')"))
+                .direction("CREDIT")
+                .layer("SETTLED")
+                .units("params.amount")
+                .build()
+                .expect("Couldn't build entry"),
+            EntryInput::builder()
+                .entry_type("'DUST_SWEPT_LOG_SET_DR'")
+                .currency("'BTC'")
+                .account_id(format!("uuid('{EFFECTIVE_AT_REST_ID}')"))
+                .direction("DEBIT")
+                .layer("SETTLED")
+                .units("params.amount")
+                .build()
+                .expect("Couldn't build entry"),
+            EntryInput::builder()
+                .entry_type("'DUST_SWEPT_LOG_SET_CR'")
+                .currency("'BTC'")
+                .account_id("params.effective_at_rest_account_id")
+                .direction("CREDIT")
+                .layer("SETTLED")
+                .units("params.amount")
+                .build()
+                .expect("Couldn't build entry"),
+        ];
+
+        let params = DustSweptParams::defs();
+        let template = NewTxTemplate::builder()
+            .id(DUST_SWEPT_ID)
+            .code(DUST_SWEPT_CODE)
+            .tx_input(tx_input)
+            .entries(entries)
+            .params(params)
+            .build()
+            .expect("Couldn't build DUST_SWEPT_CODE");
+        match ledger.tx_templates().create(template).await {
+            Err(SqlxLedgerError::DuplicateKey(_)) => Ok(()),
+            Err(e) => Err(e.into()),
+            Ok(_) => Ok(()),
+        }
+    }
+}
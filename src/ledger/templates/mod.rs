@@ -1,5 +1,7 @@
 mod batch_broadcast;
 mod batch_created;
+mod batch_dropped;
+mod dust_swept;
 mod payout_cancelled;
 mod payout_submitted;
 mod shared_meta;
@@ -8,10 +10,15 @@ mod spend_settled;
 mod spent_utxo_settled;
 mod utxo_detected;
 mod utxo_dropped;
+mod utxo_dust_settled;
+mod utxo_quarantine_released;
+mod utxo_quarantined;
 mod utxo_settled;
 
 pub use batch_broadcast::*;
 pub use batch_created::*;
+pub use batch_dropped::*;
+pub use dust_swept::*;
 pub use payout_cancelled::*;
 pub use payout_submitted::*;
 pub use shared_meta::*;
@@ -20,6 +27,9 @@ pub use spend_settled::*;
 pub use spent_utxo_settled::*;
 pub use utxo_detected::*;
 pub use utxo_dropped::*;
+pub use utxo_dust_settled::*;
+pub use utxo_quarantine_released::*;
+pub use utxo_quarantined::*;
 pub use utxo_settled::*;
 
 pub mod fix;
@@ -17,6 +17,8 @@ pub struct PayoutSubmittedMeta {
     pub profile_id: ProfileId,
     pub satoshis: Satoshis,
     pub destination: PayoutDestination,
+    #[serde(default)]
+    pub fee_from_amount: bool,
 }
 
 #[derive(Debug)]
@@ -45,6 +45,10 @@ pub struct PayoutInfo {
     pub satoshis: Satoshis,
     pub destination: PayoutDestination,
     pub vout_in_tx: u32,
+    #[serde(default)]
+    pub net_satoshis: Option<Satoshis>,
+    #[serde(default)]
+    pub deducted_fee_sats: Satoshis,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -0,0 +1,157 @@
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use sqlx_ledger::{tx_template::*, JournalId, SqlxLedger, SqlxLedgerError};
+use tracing::instrument;
+
+use crate::{
+    ledger::{constants::*, error::LedgerError, WalletLedgerAccountIds},
+    primitives::*,
+};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UtxoQuarantineReleasedMeta {
+    pub account_id: AccountId,
+    pub wallet_id: WalletId,
+    pub keychain_id: KeychainId,
+    pub outpoint: bitcoin::OutPoint,
+    pub satoshis: Satoshis,
+    pub released_by_profile_id: ProfileId,
+}
+
+#[derive(Debug)]
+pub struct UtxoQuarantineReleasedParams {
+    pub journal_id: JournalId,
+    pub ledger_account_ids: WalletLedgerAccountIds,
+    pub meta: UtxoQuarantineReleasedMeta,
+}
+
+impl UtxoQuarantineReleasedParams {
+    pub fn defs() -> Vec<ParamDefinition> {
+        vec![
+            ParamDefinition::builder()
+                .name("journal_id")
+                .r#type(ParamDataType::UUID)
+                .build()
+                .unwrap(),
+            ParamDefinition::builder()
+                .name("quarantined_account_id")
+                .r#type(ParamDataType::UUID)
+                .build()
+                .unwrap(),
+            ParamDefinition::builder()
+                .name("effective_at_rest_account_id")
+                .r#type(ParamDataType::UUID)
+                .build()
+                .unwrap(),
+            ParamDefinition::builder()
+                .name("amount")
+                .r#type(ParamDataType::DECIMAL)
+                .build()
+                .unwrap(),
+            ParamDefinition::builder()
+                .name("meta")
+                .r#type(ParamDataType::JSON)
+                .build()
+                .unwrap(),
+            ParamDefinition::builder()
+                .name("effective")
+                .r#type(ParamDataType::DATE)
+                .build()
+                .unwrap(),
+        ]
+    }
+}
+
+impl From<UtxoQuarantineReleasedParams> for TxParams {
+    fn from(
+        UtxoQuarantineReleasedParams {
+            journal_id,
+            ledger_account_ids: accounts,
+            meta,
+        }: UtxoQuarantineReleasedParams,
+    ) -> Self {
+        let effective = Utc::now().date_naive();
+        let amount = meta.satoshis.to_btc();
+        let meta = serde_json::to_value(meta).expect("Couldn't serialize meta");
+        let mut params = Self::default();
+        params.insert("journal_id", journal_id);
+        params.insert("quarantined_account_id", accounts.quarantined_id);
+        params.insert(
+            "effective_at_rest_account_id",
+            accounts.effective_at_rest_id,
+        );
+        params.insert("amount", amount);
+        params.insert("meta", meta);
+        params.insert("effective", effective);
+        params
+    }
+}
+
+pub struct UtxoQuarantineReleased {}
+
+impl UtxoQuarantineReleased {
+    #[instrument(name = "ledger.utxo_quarantine_released.init", skip_all)]
+    pub async fn init(ledger: &SqlxLedger) -> Result<(), LedgerError> {
+        let tx_input = TxInput::builder()
+            .journal_id("params.journal_id")
+            .effective("params.effective")
+            .metadata("params.meta")
+            .description("'Quarantined utxo released'")
+            .build()
+            .expect("Couldn't build TxInput");
+        let entries = vec![
+            // EFFECTIVE
+            EntryInput::builder()
+                .entry_type("'UTXO_QUARANTINE_RELEASED_LOG_QUA_DR'")
+                .currency("'BTC'")
+                .account_id("params.quarantined_account_id")
+                .direction("DEBIT")
+                .layer("SETTLED")
+                .units("params.amount")
+                .build()
+                .expect("Couldn't build entry"),
+            EntryInput::builder()
+                .entry_type("'UTXO_QUARANTINE_RELEASED_LOG_QUA_CR'")
+                .currency("'BTC'")
+                .account_id(format!("uuid('{EFFECTIVE_QUARANTINED_ID}')"))
+                .direction("CREDIT")
+                .layer("SETTLED")
+                .units("params.amount")
+                .build()
+                .expect("Couldn't build entry"),
+            EntryInput::builder()
+                .entry_type("'UTXO_QUARANTINE_RELEASED_LOG_SET_DR'")
+                .currency("'BTC'")
+                .account_id(format!("uuid('{EFFECTIVE_AT_REST_ID}')"))
+                .direction("DEBIT")
+                .layer("SETTLED")
+                .units("params.amount")
+                .build()
+                .expect("Couldn't build entry"),
+            EntryInput::builder()
+                .entry_type("'UTXO_QUARANTINE_RELEASED_LOG_SET_CR'")
+                .currency("'BTC'")
+                .account_id("params.effective_at_rest_account_id")
+                .direction("CREDIT")
+                .layer("SETTLED")
+                .units("params.amount")
+                .build()
+                .expect("Couldn't build entry"),
+        ];
+
+        let params = UtxoQuarantineReleasedParams::defs();
+        let template = NewTxTemplate::builder()
+            .id(UTXO_QUARANTINE_RELEASED_ID)
+            .code(UTXO_QUARANTINE_RELEASED_CODE)
+            .tx_input(tx_input)
+            .entries(entries)
+            .params(params)
+            .build()
+            .expect("Couldn't build UTXO_QUARANTINE_RELEASED_CODE");
+        match ledger.tx_templates().create(template).await {
+            Err(SqlxLedgerError::DuplicateKey(_)) => Ok(()),
+            Err(e) => Err(e.into()),
+            Ok(_) => Ok(()),
+        }
+    }
+}
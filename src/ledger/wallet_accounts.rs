@@ -14,6 +14,7 @@ pub struct WalletLedgerAccountBalances {
     pub effective_outgoing: Option<AccountBalance>,
     pub fee: Option<AccountBalance>,
     pub dust: Option<AccountBalance>,
+    pub quarantined: Option<AccountBalance>,
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -26,6 +27,7 @@ pub struct WalletLedgerAccountIds {
     pub effective_outgoing_id: LedgerAccountId,
     pub fee_id: LedgerAccountId,
     pub dust_id: LedgerAccountId,
+    pub quarantined_id: LedgerAccountId,
 }
 
 impl WalletLedgerAccountIds {
@@ -148,6 +150,17 @@ impl From<WalletId> for WalletLedgerAccountIds {
         )
         .expect("Invalid Wallet_Id");
 
+        let quarantined_id = Uuid::parse_str(
+            derive_wallet_ledger_account_code(
+                Element::Liability,
+                SubGroup::AtRest,
+                Category::Quarantined,
+                suffix,
+            )
+            .as_str(),
+        )
+        .expect("Invalid Wallet_Id");
+
         Self {
             onchain_incoming_id: LedgerAccountId::from(onchain_incoming_id),
             onchain_at_rest_id: LedgerAccountId::from(onchain_at_rest_id),
@@ -157,6 +170,7 @@ impl From<WalletId> for WalletLedgerAccountIds {
             effective_outgoing_id: LedgerAccountId::from(effective_outgoing_id),
             fee_id: LedgerAccountId::from(fee_id),
             dust_id: LedgerAccountId::from(dust_id),
+            quarantined_id: LedgerAccountId::from(quarantined_id),
         }
     }
 }
@@ -39,6 +39,12 @@ impl Augmenter {
             | OutboxEventPayload::UtxoSettled {
                 address, wallet_id, ..
             }
+            | OutboxEventPayload::UtxoQuarantined {
+                address, wallet_id, ..
+            }
+            | OutboxEventPayload::UtxoDustSettled {
+                address, wallet_id, ..
+            }
             | OutboxEventPayload::UtxoDropped {
                 address, wallet_id, ..
             } => {
@@ -67,6 +73,12 @@ impl Augmenter {
                     address: None,
                 })
             }
+            OutboxEventPayload::UtxoTimelockExpiring { .. }
+            | OutboxEventPayload::UtxoFrozen { .. }
+            | OutboxEventPayload::UtxoUnfrozen { .. } => Ok(Augmentation {
+                address: None,
+                payout: None,
+            }),
         }
     }
 }
@@ -70,6 +70,25 @@ pub enum OutboxEventPayload {
         keychain_id: KeychainId,
         confirmation_time: bitcoin::BlockTime,
     },
+    UtxoQuarantined {
+        tx_id: bitcoin::Txid,
+        vout: u32,
+        satoshis: Satoshis,
+        address: bitcoin::Address,
+        wallet_id: WalletId,
+        keychain_id: KeychainId,
+        confirmation_time: bitcoin::BlockTime,
+        blocked_sources: Vec<bitcoin::Address>,
+    },
+    UtxoDustSettled {
+        tx_id: bitcoin::Txid,
+        vout: u32,
+        satoshis: Satoshis,
+        address: bitcoin::Address,
+        wallet_id: WalletId,
+        keychain_id: KeychainId,
+        confirmation_time: bitcoin::BlockTime,
+    },
     UtxoDropped {
         tx_id: bitcoin::Txid,
         vout: u32,
@@ -81,6 +100,33 @@ pub enum OutboxEventPayload {
         // detected -> dropped -> detected -> dropped sequence
         ledger_event_id: Option<SqlxLedgerEventId>,
     },
+    UtxoTimelockExpiring {
+        tx_id: bitcoin::Txid,
+        vout: u32,
+        satoshis: Satoshis,
+        wallet_id: WalletId,
+        keychain_id: KeychainId,
+        spendable_at_height: u32,
+    },
+    UtxoFrozen {
+        tx_id: bitcoin::Txid,
+        vout: u32,
+        satoshis: Satoshis,
+        wallet_id: WalletId,
+        keychain_id: KeychainId,
+        profile_id: ProfileId,
+        reason: String,
+        frozen_at: chrono::DateTime<chrono::Utc>,
+    },
+    UtxoUnfrozen {
+        tx_id: bitcoin::Txid,
+        vout: u32,
+        satoshis: Satoshis,
+        wallet_id: WalletId,
+        keychain_id: KeychainId,
+        profile_id: ProfileId,
+        unfrozen_at: chrono::DateTime<chrono::Utc>,
+    },
     PayoutSubmitted {
         id: PayoutId,
         profile_id: ProfileId,
@@ -108,6 +154,10 @@ pub enum OutboxEventPayload {
         satoshis: Satoshis,
         destination: PayoutDestination,
         proportional_fee: Satoshis,
+        #[serde(default)]
+        net_satoshis: Option<Satoshis>,
+        #[serde(default)]
+        deducted_fee_sats: Satoshis,
     },
     PayoutBroadcast {
         id: PayoutId,
@@ -158,6 +208,25 @@ impl From<JournalEventMetadata> for Vec<OutboxEventPayload> {
                 keychain_id: meta.keychain_id,
                 confirmation_time: meta.confirmation_time,
             }),
+            UtxoQuarantined(meta) => res.push(OutboxEventPayload::UtxoQuarantined {
+                tx_id: meta.outpoint.txid,
+                vout: meta.outpoint.vout,
+                satoshis: meta.satoshis,
+                address: meta.address,
+                wallet_id: meta.wallet_id,
+                keychain_id: meta.keychain_id,
+                confirmation_time: meta.confirmation_time,
+                blocked_sources: meta.blocked_sources,
+            }),
+            UtxoDustSettled(meta) => res.push(OutboxEventPayload::UtxoDustSettled {
+                tx_id: meta.outpoint.txid,
+                vout: meta.outpoint.vout,
+                satoshis: meta.satoshis,
+                address: meta.address,
+                wallet_id: meta.wallet_id,
+                keychain_id: meta.keychain_id,
+                confirmation_time: meta.confirmation_time,
+            }),
             UtxoDropped(meta, ledger_event_id) => res.push(OutboxEventPayload::UtxoDropped {
                 tx_id: meta.outpoint.txid,
                 vout: meta.outpoint.vout,
@@ -208,6 +277,8 @@ impl From<JournalEventMetadata> for Vec<OutboxEventPayload> {
                         proportional_fee: proportional_fees
                             .remove(&payout.id)
                             .expect("couldn't find proportional fee"),
+                        net_satoshis: payout.net_satoshis,
+                        deducted_fee_sats: payout.deducted_fee_sats,
                     })
                 }
             }
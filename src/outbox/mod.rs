@@ -5,7 +5,7 @@ mod listener;
 mod repo;
 
 use opentelemetry::trace::TraceContextExt;
-use sqlx::{postgres::PgListener, Pool, Postgres};
+use sqlx::{postgres::PgListener, Pool, Postgres, Transaction};
 use tokio::sync::{broadcast, RwLock};
 use tracing::instrument;
 use tracing_opentelemetry::OpenTelemetrySpanExt;
@@ -24,6 +24,7 @@ type SequenceElems = (EventSequence, Option<SqlxLedgerEventId>);
 type SequenceMap = HashMap<AccountId, Arc<RwLock<SequenceElems>>>;
 
 const DEFAULT_BUFFER_SIZE: usize = 100;
+const DUPLICATE_PAYLOAD_CONSTRAINT: &str = "bria_outbox_events_account_id_payload_key";
 
 #[derive(Clone)]
 pub struct Outbox {
@@ -104,6 +105,87 @@ impl Outbox {
         Ok(())
     }
 
+    /// Publishes an event that doesn't originate from a ledger transaction.
+    /// Returns false if an identical payload has already been published for the account.
+    #[instrument("outbox.publish_payload", skip(self), err)]
+    pub async fn publish_payload(
+        &self,
+        account_id: AccountId,
+        payload: OutboxEventPayload,
+    ) -> Result<bool, OutboxError> {
+        let sequences = self.sequences_for(account_id).await?;
+        let mut write_sequences = sequences.write().await;
+        let sequence = write_sequences.0.next();
+        let event = OutboxEvent::builder()
+            .account_id(account_id)
+            .sequence(sequence)
+            .payload(payload)
+            .recorded_at(chrono::Utc::now())
+            .build()
+            .expect("Could not build OutboxEvent");
+
+        match self.repo.persist_events(std::slice::from_ref(&event)).await {
+            Err(OutboxError::Sqlx(sqlx::Error::Database(err)))
+                if err.constraint() == Some(DUPLICATE_PAYLOAD_CONSTRAINT) =>
+            {
+                return Ok(false);
+            }
+            Err(res) => {
+                let mut write_seqs = self.sequences.write().await;
+                write_seqs.remove(&account_id);
+                return Err(res);
+            }
+            Ok(_) => (),
+        }
+        self.event_sender
+            .send(event)
+            .map_err(|_| OutboxError::SendEventError)?;
+        write_sequences.0 = sequence;
+
+        Ok(true)
+    }
+
+    /// Publishes an event that doesn't originate from a ledger transaction
+    /// atomically with the state change recorded in `tx`.
+    #[instrument("outbox.publish_payload_in_tx", skip(self, tx), err)]
+    pub async fn publish_payload_in_tx(
+        &self,
+        mut tx: Transaction<'_, Postgres>,
+        account_id: AccountId,
+        payload: OutboxEventPayload,
+    ) -> Result<(), OutboxError> {
+        let sequences = self.sequences_for(account_id).await?;
+        let mut write_sequences = sequences.write().await;
+        let sequence = write_sequences.0.next();
+        let event = OutboxEvent::builder()
+            .account_id(account_id)
+            .sequence(sequence)
+            .payload(payload)
+            .recorded_at(chrono::Utc::now())
+            .build()
+            .expect("Could not build OutboxEvent");
+
+        let res = match self
+            .repo
+            .persist_events_in_tx(&mut tx, std::slice::from_ref(&event))
+            .await
+        {
+            Ok(_) => tx.commit().await.map_err(OutboxError::from),
+            Err(e) => Err(e),
+        };
+        if let Err(res) = res {
+            let mut write_seqs = self.sequences.write().await;
+            write_seqs.remove(&account_id);
+            return Err(res);
+        }
+        self.event_sender
+            .send(event)
+            .map_err(|_| OutboxError::SendEventError)?;
+        write_sequences.0 = sequence;
+
+        Ok(())
+    }
+
     pub async fn register_listener(
         &self,
         account_id: AccountId,
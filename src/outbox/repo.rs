@@ -1,4 +1,4 @@
-use sqlx::{Pool, Postgres, QueryBuilder};
+use sqlx::{Pool, Postgres, QueryBuilder, Transaction};
 use uuid::Uuid;
 
 use std::{collections::HashMap, sync::Arc};
@@ -17,6 +17,17 @@ impl OutboxRepo {
     }
 
     pub async fn persist_events<T>(&self, events: &[OutboxEvent<T>]) -> Result<(), OutboxError> {
+        let mut tx = self.pool.begin().await?;
+        self.persist_events_in_tx(&mut tx, events).await?;
+        tx.commit().await?;
+        Ok(())
+    }
+
+    pub async fn persist_events_in_tx<T>(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        events: &[OutboxEvent<T>],
+    ) -> Result<(), OutboxError> {
         if events.is_empty() {
             return Ok(());
         }
@@ -37,7 +48,7 @@ impl OutboxRepo {
             builder.push_bind(event.recorded_at);
         });
         let query = query_builder.build();
-        query.execute(&self.pool).await?;
+        query.execute(&mut **tx).await?;
         Ok(())
     }
 
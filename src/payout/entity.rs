@@ -15,6 +15,10 @@ pub enum PayoutEvent {
         profile_id: ProfileId,
         destination: PayoutDestination,
         satoshis: Satoshis,
+        #[serde(default)]
+        fee_from_amount: bool,
+        #[serde(default)]
+        sweep: bool,
     },
     ExternalIdUpdated {
         external_id: String,
@@ -25,10 +29,36 @@ pub enum PayoutEvent {
     CommittedToBatch {
         batch_id: BatchId,
         outpoint: bitcoin::OutPoint,
+        #[serde(default)]
+        deducted_fee_sats: Satoshis,
     },
     Cancelled {
         executed_by: ProfileId,
     },
+    BatchTransactionReplaced {
+        outpoint: bitcoin::OutPoint,
+    },
+    ScheduleUpdated {
+        not_before: Option<chrono::DateTime<chrono::Utc>>,
+        not_before_block_height: Option<u32>,
+    },
+    ApprovalRequested {
+        required_approvals: u32,
+    },
+    Approved {
+        approved_by: ProfileId,
+    },
+    Rejected {
+        rejected_by: ProfileId,
+        reason: Option<String>,
+    },
+}
+
+/// Earliest time and/or block height at which a payout may be batched
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PayoutSchedule {
+    pub not_before: Option<chrono::DateTime<chrono::Utc>>,
+    pub not_before_block_height: Option<u32>,
 }
 
 #[derive(Builder)]
@@ -47,24 +77,104 @@ pub struct Payout {
     pub external_id: String,
     #[builder(setter(into), default)]
     pub metadata: Option<serde_json::Value>,
+    #[builder(default)]
+    pub fee_from_amount: bool,
+    #[builder(default)]
+    pub sweep: bool,
+    #[builder(default)]
+    pub deducted_fee_sats: Satoshis,
+    #[builder(setter(into), default)]
+    pub not_before: Option<chrono::DateTime<chrono::Utc>>,
+    #[builder(setter(into), default)]
+    pub not_before_block_height: Option<u32>,
+    #[builder(default)]
+    pub required_approvals: u32,
+    #[builder(default)]
+    pub approved_by: Vec<ProfileId>,
 
     pub(super) events: EntityEvents<PayoutEvent>,
 }
 
 impl Payout {
     pub fn cancel_payout(&mut self, profile_id: ProfileId) -> Result<(), PayoutError> {
-        if self.is_cancelled() {
-            return Err(PayoutError::PayoutAlreadyCancelled);
-        }
-        if self.is_already_committed() {
-            return Err(PayoutError::PayoutAlreadyCommitted);
-        }
+        self.ensure_pending()?;
         self.events.push(PayoutEvent::Cancelled {
             executed_by: profile_id,
         });
         Ok(())
     }
 
+    pub fn approve(&mut self, profile_id: ProfileId) -> Result<(), PayoutError> {
+        self.ensure_pending()?;
+        if self.required_approvals == 0 {
+            return Err(PayoutError::ApprovalNotRequired);
+        }
+        if self.profile_id == profile_id {
+            return Err(PayoutError::SubmitterCannotApprove);
+        }
+        if self.approved_by.contains(&profile_id) {
+            return Err(PayoutError::AlreadyApprovedBy(profile_id.to_string()));
+        }
+        self.approved_by.push(profile_id);
+        self.events.push(PayoutEvent::Approved {
+            approved_by: profile_id,
+        });
+        Ok(())
+    }
+
+    pub fn reject(
+        &mut self,
+        profile_id: ProfileId,
+        reason: Option<String>,
+    ) -> Result<(), PayoutError> {
+        self.ensure_pending()?;
+        if self.required_approvals == 0 {
+            return Err(PayoutError::ApprovalNotRequired);
+        }
+        if self.profile_id == profile_id {
+            return Err(PayoutError::SubmitterCannotReject);
+        }
+        self.events.push(PayoutEvent::Rejected {
+            rejected_by: profile_id,
+            reason,
+        });
+        Ok(())
+    }
+
+    pub fn is_approved(&self) -> bool {
+        self.approved_by.len() >= self.required_approvals as usize
+    }
+
+    pub fn is_rejected(&self) -> bool {
+        self.events
+            .iter()
+            .any(|event| matches!(event, PayoutEvent::Rejected { .. }))
+    }
+
+    pub fn update_schedule(
+        &mut self,
+        not_before: Option<chrono::DateTime<chrono::Utc>>,
+        not_before_block_height: Option<u32>,
+    ) -> Result<(), PayoutError> {
+        self.ensure_pending()?;
+        self.not_before = not_before;
+        self.not_before_block_height = not_before_block_height;
+        self.events.push(PayoutEvent::ScheduleUpdated {
+            not_before,
+            not_before_block_height,
+        });
+        Ok(())
+    }
+
+    pub fn batch_transaction_replaced(&mut self, tx_id: bitcoin::Txid) {
+        if let Some(outpoint) = self.outpoint.as_mut() {
+            outpoint.txid = tx_id;
+            self.events.push(PayoutEvent::BatchTransactionReplaced {
+                outpoint: *outpoint,
+            });
+        }
+    }
+
     pub fn is_cancelled(&self) -> bool {
         for event in self.events.iter() {
             if let PayoutEvent::Cancelled { .. } = event {
@@ -77,6 +187,19 @@ impl Payout {
     fn is_already_committed(&self) -> bool {
         self.batch_id.is_some()
     }
+
+    fn ensure_pending(&self) -> Result<(), PayoutError> {
+        if self.is_cancelled() {
+            return Err(PayoutError::PayoutAlreadyCancelled);
+        }
+        if self.is_rejected() {
+            return Err(PayoutError::PayoutAlreadyRejected);
+        }
+        if self.is_already_committed() {
+            return Err(PayoutError::PayoutAlreadyCommitted);
+        }
+        Ok(())
+    }
 }
 
 #[derive(Debug, Builder, Clone)]
@@ -96,6 +219,16 @@ pub struct NewPayout {
     pub(super) external_id: String,
     #[builder(default, setter(into))]
     pub(super) metadata: Option<serde_json::Value>,
+    #[builder(default)]
+    pub(super) fee_from_amount: bool,
+    #[builder(default)]
+    pub(super) sweep: bool,
+    #[builder(default, setter(into))]
+    pub(super) not_before: Option<chrono::DateTime<chrono::Utc>>,
+    #[builder(default, setter(into))]
+    pub(super) not_before_block_height: Option<u32>,
+    #[builder(default)]
+    pub(super) required_approvals: u32,
 }
 
 impl NewPayout {
@@ -114,6 +247,8 @@ impl NewPayout {
                 profile_id: self.profile_id,
                 destination: self.destination,
                 satoshis: self.satoshis,
+                fee_from_amount: self.fee_from_amount,
+                sweep: self.sweep,
             },
             PayoutEvent::ExternalIdUpdated {
                 external_id: self.external_id,
@@ -122,6 +257,17 @@ impl NewPayout {
         if let Some(metadata) = self.metadata {
             events.push(PayoutEvent::MetadataUpdated { metadata });
         }
+        if self.not_before.is_some() || self.not_before_block_height.is_some() {
+            events.push(PayoutEvent::ScheduleUpdated {
+                not_before: self.not_before,
+                not_before_block_height: self.not_before_block_height,
+            });
+        }
+        if self.required_approvals > 0 {
+            events.push(PayoutEvent::ApprovalRequested {
+                required_approvals: self.required_approvals,
+            });
+        }
         events
     }
 }
@@ -131,6 +277,7 @@ impl TryFrom<EntityEvents<PayoutEvent>> for Payout {
 
     fn try_from(events: EntityEvents<PayoutEvent>) -> Result<Self, Self::Error> {
         let mut builder = PayoutBuilder::default();
+        let mut approved_by = Vec::new();
         for event in events.iter() {
             match event {
                 PayoutEvent::Initialized {
@@ -140,7 +287,8 @@ impl TryFrom<EntityEvents<PayoutEvent>> for Payout {
                     payout_queue_id,
                     destination,
                     satoshis,
-                    ..
+                    fee_from_amount,
+                    sweep,
                 } => {
                     builder = builder
                         .id(*id)
@@ -148,7 +296,9 @@ impl TryFrom<EntityEvents<PayoutEvent>> for Payout {
                         .profile_id(*profile_id)
                         .payout_queue_id(*payout_queue_id)
                         .destination(destination.clone())
-                        .satoshis(*satoshis);
+                        .satoshis(*satoshis)
+                        .fee_from_amount(*fee_from_amount)
+                        .sweep(*sweep);
                 }
 
                 PayoutEvent::ExternalIdUpdated { external_id } => {
@@ -157,13 +307,39 @@ impl TryFrom<EntityEvents<PayoutEvent>> for Payout {
                 PayoutEvent::MetadataUpdated { metadata } => {
                     builder = builder.metadata(metadata.clone());
                 }
-                PayoutEvent::CommittedToBatch { batch_id, outpoint } => {
-                    builder = builder.batch_id(*batch_id).outpoint(*outpoint);
+                PayoutEvent::CommittedToBatch {
+                    batch_id,
+                    outpoint,
+                    deducted_fee_sats,
+                } => {
+                    builder = builder
+                        .batch_id(*batch_id)
+                        .outpoint(*outpoint)
+                        .deducted_fee_sats(*deducted_fee_sats);
+                }
+                PayoutEvent::BatchTransactionReplaced { outpoint } => {
+                    builder = builder.outpoint(*outpoint);
+                }
+                PayoutEvent::ScheduleUpdated {
+                    not_before,
+                    not_before_block_height,
+                } => {
+                    builder = builder
+                        .not_before(*not_before)
+                        .not_before_block_height(*not_before_block_height);
+                }
+                PayoutEvent::ApprovalRequested { required_approvals } => {
+                    builder = builder.required_approvals(*required_approvals);
+                }
+                PayoutEvent::Approved {
+                    approved_by: profile_id,
+                } => {
+                    approved_by.push(*profile_id);
                 }
                 _ => (),
             }
         }
-        builder.events(events).build()
+        builder.approved_by(approved_by).events(events).build()
     }
 }
 
@@ -186,6 +362,8 @@ mod tests {
                         .unwrap(),
                 },
                 satoshis: Satoshis::from(Decimal::from(21)),
+                fee_from_amount: false,
+                sweep: false,
             },
             PayoutEvent::ExternalIdUpdated {
                 external_id: "external_id".to_string(),
@@ -225,6 +403,7 @@ mod tests {
                     .unwrap(),
                 vout: 0,
             },
+            deducted_fee_sats: Satoshis::ZERO,
         });
 
         let mut payout = Payout::try_from(events).unwrap();
@@ -232,4 +411,82 @@ mod tests {
         let result = payout.cancel_payout(payout.profile_id);
         assert!(matches!(result, Err(PayoutError::PayoutAlreadyCommitted)));
     }
+
+    #[test]
+    fn update_schedule() {
+        let mut payout = Payout::try_from(init_events()).unwrap();
+        let not_before = chrono::Utc::now() + chrono::Duration::days(2);
+        assert!(payout
+            .update_schedule(Some(not_before), Some(800_000))
+            .is_ok());
+        assert_eq!(payout.not_before, Some(not_before));
+        assert_eq!(payout.not_before_block_height, Some(800_000));
+
+        let payout = Payout::try_from(payout.events).unwrap();
+        assert_eq!(payout.not_before, Some(not_before));
+        assert_eq!(payout.not_before_block_height, Some(800_000));
+    }
+
+    #[test]
+    fn can_not_update_schedule_of_cancelled_payout() {
+        let mut events = init_events();
+        events.push(PayoutEvent::Cancelled {
+            executed_by: ProfileId::new(),
+        });
+        let mut payout = Payout::try_from(events).unwrap();
+        let result = payout.update_schedule(None, None);
+        assert!(matches!(result, Err(PayoutError::PayoutAlreadyCancelled)));
+    }
+
+    #[test]
+    fn approve_payout() {
+        let mut events = init_events();
+        events.push(PayoutEvent::ApprovalRequested {
+            required_approvals: 2,
+        });
+        let mut payout = Payout::try_from(events).unwrap();
+        assert!(!payout.is_approved());
+
+        let approver = ProfileId::new();
+        assert!(payout.approve(approver).is_ok());
+        assert!(matches!(
+            payout.approve(approver),
+            Err(PayoutError::AlreadyApprovedBy(_))
+        ));
+        assert!(matches!(
+            payout.approve(payout.profile_id),
+            Err(PayoutError::SubmitterCannotApprove)
+        ));
+        assert!(!payout.is_approved());
+        assert!(payout.approve(ProfileId::new()).is_ok());
+        assert!(payout.is_approved());
+
+        let payout = Payout::try_from(payout.events).unwrap();
+        assert_eq!(payout.approved_by.len(), 2);
+        assert!(payout.is_approved());
+    }
+
+    #[test]
+    fn can_not_approve_rejected_payout() {
+        let mut events = init_events();
+        events.push(PayoutEvent::ApprovalRequested {
+            required_approvals: 1,
+        });
+        let mut payout = Payout::try_from(events).unwrap();
+        assert!(matches!(
+            payout.reject(payout.profile_id, None),
+            Err(PayoutError::SubmitterCannotReject)
+        ));
+        assert!(!payout.is_rejected());
+        assert!(payout.reject(ProfileId::new(), None).is_ok());
+        assert!(payout.is_rejected());
+        assert!(matches!(
+            payout.approve(ProfileId::new()),
+            Err(PayoutError::PayoutAlreadyRejected)
+        ));
+        assert!(matches!(
+            payout.cancel_payout(payout.profile_id),
+            Err(PayoutError::PayoutAlreadyRejected)
+        ));
+    }
 }
@@ -6,6 +6,8 @@ pub enum PayoutError {
     Sqlx(#[from] sqlx::Error),
     #[error("PayoutError - EntityError: {0}")]
     EntityError(#[from] crate::entity::EntityError),
+    #[error("PayoutError - BdkError: {0}")]
+    BdkError(#[from] crate::bdk::error::BdkError),
     #[error("PayoutError - Could not find payout with id: {0}")]
     PayoutIdNotFound(String),
     #[error("PayoutError - External Id does not exists")]
@@ -14,4 +16,22 @@ pub enum PayoutError {
     PayoutAlreadyCommitted,
     #[error("PayoutError - Payout is already cancelled")]
     PayoutAlreadyCancelled,
+    #[error("PayoutError - Payout is already rejected")]
+    PayoutAlreadyRejected,
+    #[error("PayoutError - Payout does not require approval")]
+    ApprovalNotRequired,
+    #[error("PayoutError - Payout can not be approved by its submitter")]
+    SubmitterCannotApprove,
+    #[error("PayoutError - Payout can not be rejected by its submitter")]
+    SubmitterCannotReject,
+    #[error("PayoutError - Payout is already approved by profile {0}")]
+    AlreadyApprovedBy(String),
+    #[error("PayoutError - A sweep payout is already queued for wallet {0}")]
+    SweepPayoutQueued(String),
+    #[error("PayoutError - Cannot sweep wallet {0} while it has queued payouts")]
+    WalletHasQueuedPayouts(String),
+    #[error("PayoutError - No settled balance to sweep")]
+    NothingToSweep,
+    #[error("PayoutError - External id '{0}' is used more than once")]
+    DuplicateExternalId(String),
 }
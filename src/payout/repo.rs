@@ -130,6 +130,8 @@ impl Payouts {
             let events = entity_events.entry(id).or_insert_with(EntityEvents::new);
             events.load_event(row.sequence as usize, row.event)?;
         }
+        let now = chrono::Utc::now();
+        let current_height = crate::bdk::last_sync_time(&self.pool).await?;
         let mut payouts: HashMap<WalletId, Vec<UnbatchedPayout>> = HashMap::new();
         for (id, wallet_id) in wallet_payouts {
             if let Some(events) = entity_events.remove(&id) {
@@ -145,11 +147,15 @@ impl Payouts {
                 let filtered_unbatched_payouts = unbatched_payouts
                     .into_iter()
                     .filter(|payout| {
-                        !payout
-                            .events
-                            .iter()
-                            .any(|event| matches!(event, PayoutEvent::Cancelled { .. }))
+                        !payout.events.iter().any(|event| {
+                            matches!(
+                                event,
+                                PayoutEvent::Cancelled { .. } | PayoutEvent::Rejected { .. }
+                            )
+                        })
                     })
+                    .filter(|payout| payout.is_approved())
+                    .filter(|payout| payout.is_eligible(now, current_height))
                     .collect();
                 (wallet_id, filtered_unbatched_payouts)
             })
@@ -157,6 +163,65 @@ impl Payouts {
         Ok(UnbatchedPayouts::new(filtered_payouts))
     }
 
+    /// Returns the number of queued payouts of the wallet and whether one of them is a sweep.
+    #[instrument(name = "payouts.unbatched_for_wallet", skip(self))]
+    pub async fn unbatched_for_wallet(
+        &self,
+        account_id: AccountId,
+        payout_queue_id: PayoutQueueId,
+        wallet_id: WalletId,
+    ) -> Result<(usize, bool), PayoutError> {
+        let row = sqlx::query!(
+            r#"
+              SELECT COUNT(*) AS "n_payouts!",
+                     COALESCE(BOOL_OR((e.event->>'sweep')::BOOLEAN), false) AS "has_sweep!"
+              FROM bria_payouts b
+              JOIN bria_payout_events e ON b.id = e.id AND e.event_type = 'initialized'
+              WHERE b.batch_id IS NULL AND b.account_id = $1 AND b.payout_queue_id = $2
+                AND b.wallet_id = $3
+                AND NOT EXISTS (
+                  SELECT 1 FROM bria_payout_events c
+                  WHERE c.id = b.id AND c.event_type IN ('cancelled', 'rejected')
+                )"#,
+            account_id as AccountId,
+            payout_queue_id as PayoutQueueId,
+            wallet_id as WalletId,
+        )
+        .fetch_one(&self.pool)
+        .await?;
+        Ok((row.n_payouts as usize, row.has_sweep))
+    }
+
+    #[instrument(name = "payouts.submitted_sats_since", skip(self, tx))]
+    pub async fn submitted_sats_since(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        account_id: AccountId,
+        profile_id: ProfileId,
+        wallet_id: Option<WalletId>,
+        since: chrono::DateTime<chrono::Utc>,
+    ) -> Result<Satoshis, PayoutError> {
+        let row = sqlx::query!(
+            r#"
+              SELECT COALESCE(SUM((e.event->>'satoshis')::NUMERIC), 0) AS "sats!"
+              FROM bria_payouts b
+              JOIN bria_payout_events e ON b.id = e.id AND e.event_type = 'initialized'
+              WHERE b.account_id = $1 AND b.profile_id = $2 AND b.created_at >= $3
+                AND ($4::UUID IS NULL OR b.wallet_id = $4)
+                AND NOT EXISTS (
+                  SELECT 1 FROM bria_payout_events c
+                  WHERE c.id = b.id AND c.event_type IN ('cancelled', 'rejected')
+                )"#,
+            account_id as AccountId,
+            profile_id as ProfileId,
+            since,
+            wallet_id.map(Uuid::from),
+        )
+        .fetch_one(&mut **tx)
+        .await?;
+        Ok(Satoshis::from(row.sats))
+    }
+
     #[instrument(name = "payouts.list_for_wallet", skip(self))]
     pub async fn list_for_wallet(
         &self,
@@ -290,8 +355,8 @@ impl Payouts {
         ))
     }
 
-    #[instrument(name = "payouts.find_by_id_for_cancellation", skip(self))]
-    pub async fn find_by_id_for_cancellation(
+    #[instrument(name = "payouts.find_by_id_for_update", skip(self))]
+    pub async fn find_by_id_for_update(
         &self,
         tx: &mut Transaction<'_, Postgres>,
         account_id: AccountId,
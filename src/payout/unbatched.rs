@@ -9,6 +9,7 @@ pub struct UnbatchedPayouts {
     inner: HashMap<WalletId, Vec<UnbatchedPayout>>,
     shifted: HashMap<PayoutId, UnbatchedPayout>,
     simulated_payout: Option<(WalletId, TxPayout)>,
+    simulated_sweep: bool,
     pub(super) batch_id: Option<BatchId>,
     pub(super) batched: Vec<UnbatchedPayout>,
 }
@@ -19,6 +20,7 @@ impl UnbatchedPayouts {
             inner,
             batch_id: None,
             simulated_payout: None,
+            simulated_sweep: false,
             shifted: HashMap::new(),
             batched: Vec::new(),
         }
@@ -38,17 +40,31 @@ impl UnbatchedPayouts {
 
     pub fn include_simulated_payout(&mut self, wallet_id: WalletId, payout: TxPayout) {
         self.simulated_payout = Some((wallet_id, payout));
+        self.simulated_sweep = false;
+    }
+
+    pub fn include_simulated_sweep(&mut self, wallet_id: WalletId, payout: TxPayout) {
+        self.simulated_payout = Some((wallet_id, payout));
+        self.simulated_sweep = true;
     }
 
     pub fn n_not_batched(&self) -> usize {
         self.shifted.len() + self.inner.values().fold(0, |acc, v| acc + v.len())
     }
 
+    pub fn total_sats(&self) -> Satoshis {
+        self.shifted
+            .values()
+            .chain(self.inner.values().flatten())
+            .map(|p| p.satoshis)
+            .sum()
+    }
+
     pub fn commit_to_batch(
         &mut self,
         bitcoin_tx_id: bitcoin::Txid,
         batch_id: impl Into<BatchId>,
-        payout_ids: impl Iterator<Item = (impl Into<PayoutId>, u32)>,
+        payout_ids: impl Iterator<Item = (impl Into<PayoutId>, u32, Satoshis)>,
     ) {
         if self.shifted.is_empty() {
             self.shifted.extend(
@@ -59,7 +75,7 @@ impl UnbatchedPayouts {
         }
         let batch_id = batch_id.into();
         self.batch_id = Some(batch_id);
-        for (id, vout) in payout_ids {
+        for (id, vout, deducted_fee_sats) in payout_ids {
             let mut payout = self
                 .shifted
                 .remove(&id.into())
@@ -70,11 +86,36 @@ impl UnbatchedPayouts {
                     txid: bitcoin_tx_id,
                     vout,
                 },
+                deducted_fee_sats,
             );
             self.batched.push(payout);
         }
     }
 
+    pub fn fee_from_amount_payout_ids(&self) -> HashSet<uuid::Uuid> {
+        self.inner
+            .values()
+            .flatten()
+            .filter(|p| p.fee_from_amount)
+            .map(|p| uuid::Uuid::from(p.id))
+            .collect()
+    }
+
+    pub fn sweep_payout_ids(&self) -> HashSet<uuid::Uuid> {
+        let simulated = self
+            .simulated_payout
+            .as_ref()
+            .filter(|_| self.simulated_sweep)
+            .map(|(_, (id, _, _))| *id);
+        self.inner
+            .values()
+            .flatten()
+            .filter(|p| p.sweep)
+            .map(|p| uuid::Uuid::from(p.id))
+            .chain(simulated)
+            .collect()
+    }
+
     pub fn into_tx_payouts(&self) -> HashMap<WalletId, Vec<TxPayout>> {
         let mut ret: HashMap<WalletId, Vec<TxPayout>> = self
             .inner
@@ -96,14 +137,46 @@ pub struct UnbatchedPayout {
     pub wallet_id: WalletId,
     pub destination: PayoutDestination,
     pub satoshis: Satoshis,
+    #[builder(default)]
+    pub fee_from_amount: bool,
+    #[builder(default)]
+    pub sweep: bool,
+    #[builder(default)]
+    pub not_before: Option<chrono::DateTime<chrono::Utc>>,
+    #[builder(default)]
+    pub not_before_block_height: Option<u32>,
+    #[builder(default)]
+    pub required_approvals: u32,
+    #[builder(default)]
+    pub n_approvals: u32,
 
     pub(super) events: EntityEvents<PayoutEvent>,
 }
 
 impl UnbatchedPayout {
-    pub(super) fn commit_to_batch(&mut self, batch_id: BatchId, outpoint: bitcoin::OutPoint) {
-        self.events
-            .push(PayoutEvent::CommittedToBatch { batch_id, outpoint });
+    pub fn is_eligible(&self, now: chrono::DateTime<chrono::Utc>, block_height: u32) -> bool {
+        self.not_before.map(|t| t <= now).unwrap_or(true)
+            && self
+                .not_before_block_height
+                .map(|h| h <= block_height)
+                .unwrap_or(true)
+    }
+
+    pub fn is_approved(&self) -> bool {
+        self.n_approvals >= self.required_approvals
+    }
+
+    pub(super) fn commit_to_batch(
+        &mut self,
+        batch_id: BatchId,
+        outpoint: bitcoin::OutPoint,
+        deducted_fee_sats: Satoshis,
+    ) {
+        self.events.push(PayoutEvent::CommittedToBatch {
+            batch_id,
+            outpoint,
+            deducted_fee_sats,
+        });
     }
 }
 
@@ -112,23 +185,44 @@ impl TryFrom<EntityEvents<PayoutEvent>> for UnbatchedPayout {
 
     fn try_from(events: EntityEvents<PayoutEvent>) -> Result<Self, Self::Error> {
         let mut builder = UnbatchedPayoutBuilder::default();
+        let mut n_approvals = 0;
         for event in events.iter() {
-            if let PayoutEvent::Initialized {
-                id,
-                wallet_id,
-                destination,
-                satoshis,
-                ..
-            } = event
-            {
-                builder = builder
-                    .id(*id)
-                    .wallet_id(*wallet_id)
-                    .destination(destination.clone())
-                    .satoshis(*satoshis);
+            match event {
+                PayoutEvent::Initialized {
+                    id,
+                    wallet_id,
+                    destination,
+                    satoshis,
+                    fee_from_amount,
+                    sweep,
+                    ..
+                } => {
+                    builder = builder
+                        .id(*id)
+                        .wallet_id(*wallet_id)
+                        .destination(destination.clone())
+                        .satoshis(*satoshis)
+                        .fee_from_amount(*fee_from_amount)
+                        .sweep(*sweep);
+                }
+                PayoutEvent::ScheduleUpdated {
+                    not_before,
+                    not_before_block_height,
+                } => {
+                    builder = builder
+                        .not_before(*not_before)
+                        .not_before_block_height(*not_before_block_height);
+                }
+                PayoutEvent::ApprovalRequested { required_approvals } => {
+                    builder = builder.required_approvals(*required_approvals);
+                }
+                PayoutEvent::Approved { .. } => {
+                    n_approvals += 1;
+                }
+                _ => (),
             }
         }
-        builder.events(events).build()
+        builder.n_approvals(n_approvals).events(events).build()
     }
 }
 
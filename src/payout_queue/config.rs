@@ -1,7 +1,11 @@
 use serde::{Deserialize, Serialize};
-use std::time::Duration;
+use std::{str::FromStr, time::Duration};
 
-use crate::primitives::TxPriority;
+use super::error::PayoutQueueError;
+use crate::{
+    primitives::{bitcoin, Satoshis, TxPriority},
+    wallet::{CoinSelectionStrategy, SpendingPath},
+};
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct PayoutQueueConfig {
@@ -10,7 +14,17 @@ pub struct PayoutQueueConfig {
     pub cpfp_payouts_after_mins: Option<u32>,
     #[serde(default)]
     pub cpfp_payouts_after_blocks: Option<u32>,
+    #[serde(default)]
+    pub bump_fee_after_mins: Option<u32>,
+    #[serde(default)]
+    pub max_fee_rate_sat_per_vb: Option<u64>,
+    #[serde(default)]
+    pub max_total_fee_sats: Option<Satoshis>,
     pub consolidate_deprecated_keychains: bool,
+    #[serde(default)]
+    pub spending_path: SpendingPath,
+    #[serde(default)]
+    pub coin_selection: CoinSelectionStrategy,
     pub trigger: PayoutQueueTrigger,
 }
 
@@ -31,6 +45,23 @@ impl PayoutQueueConfig {
     pub fn should_cpfp(&self) -> bool {
         self.cpfp_payouts_after_mins.is_some() || self.cpfp_payouts_after_blocks.is_some()
     }
+
+    pub fn fee_rate_exceeds_cap(&self, fee_rate: bitcoin::FeeRate) -> bool {
+        self.max_fee_rate_sat_per_vb
+            .map(|max| fee_rate.as_sat_per_vb() > max as f32)
+            .unwrap_or(false)
+    }
+
+    pub fn total_fee_exceeds_cap(&self, total_fee: Satoshis) -> bool {
+        self.max_total_fee_sats
+            .map(|max| total_fee > max)
+            .unwrap_or(false)
+    }
+
+    pub fn bump_fee_for_batches_built_before(&self) -> Option<chrono::DateTime<chrono::Utc>> {
+        self.bump_fee_after_mins
+            .map(|mins| chrono::Utc::now() - Duration::from_secs(mins as u64 * 60))
+    }
 }
 
 #[serde_with::serde_as]
@@ -42,19 +73,152 @@ pub enum PayoutQueueTrigger {
         #[serde(default = "default_interval")]
         seconds: Duration,
     },
+    Threshold {
+        #[serde(default)]
+        min_payouts: Option<u32>,
+        #[serde(default)]
+        min_total_sats: Option<Satoshis>,
+        #[serde_as(as = "serde_with::DurationSeconds<u64>")]
+        #[serde(default = "default_interval")]
+        seconds: Duration,
+    },
+    Schedule {
+        cron: String,
+        #[serde(default = "default_timezone")]
+        timezone: String,
+    },
     Manual,
 }
 
+impl PayoutQueueTrigger {
+    pub fn is_threshold(&self) -> bool {
+        matches!(self, PayoutQueueTrigger::Threshold { .. })
+    }
+
+    pub fn threshold_reached(&self, n_payouts: usize, total_sats: Satoshis) -> bool {
+        if let PayoutQueueTrigger::Threshold {
+            min_payouts,
+            min_total_sats,
+            ..
+        } = self
+        {
+            if n_payouts == 0 {
+                return false;
+            }
+            min_payouts
+                .map(|min| n_payouts >= min as usize)
+                .unwrap_or(false)
+                || min_total_sats.map(|min| total_sats >= min).unwrap_or(false)
+        } else {
+            false
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), PayoutQueueError> {
+        if let PayoutQueueTrigger::Schedule { cron, timezone } = self {
+            parse_schedule(cron, timezone)?;
+        }
+        Ok(())
+    }
+
+    /// For a `Schedule` trigger returns the first scheduled time strictly after `after`.
+    pub fn next_scheduled_after(
+        &self,
+        after: chrono::DateTime<chrono::Utc>,
+    ) -> Option<chrono::DateTime<chrono::Utc>> {
+        if let PayoutQueueTrigger::Schedule { cron, timezone } = self {
+            let (schedule, tz) = parse_schedule(cron, timezone).ok()?;
+            schedule
+                .after(&after.with_timezone(&tz))
+                .next()
+                .map(|next| next.with_timezone(&chrono::Utc))
+        } else {
+            None
+        }
+    }
+}
+
+fn parse_schedule(
+    cron: &str,
+    timezone: &str,
+) -> Result<(cron::Schedule, chrono_tz::Tz), PayoutQueueError> {
+    // Accept standard 5 field expressions by defaulting the seconds field
+    let mut fields: Vec<_> = cron.split_whitespace().map(str::to_string).collect();
+    if fields.len() == 5 {
+        fields.insert(0, "0".to_string());
+    }
+    if let Some(day_of_week) = fields.get_mut(5) {
+        *day_of_week = translate_day_of_week(day_of_week)
+            .map_err(|e| PayoutQueueError::InvalidScheduleCron(format!("{cron}: {e}")))?;
+    }
+    let expression = fields.join(" ");
+    let schedule = cron::Schedule::from_str(&expression)
+        .map_err(|e| PayoutQueueError::InvalidScheduleCron(format!("{cron}: {e}")))?;
+    let tz = chrono_tz::Tz::from_str(timezone)
+        .map_err(|_| PayoutQueueError::InvalidScheduleTimezone(timezone.to_string()))?;
+    Ok((schedule, tz))
+}
+
+/// Standard cron numbers the days of the week 0-7 (0 and 7 both being Sunday) while the
+/// cron crate numbers them 1-7 starting on Sunday. Numeric days are expanded to the cron
+/// crate's numbering for every expression length so that 5 and 6 field schedules agree,
+/// day names are passed through untouched.
+fn translate_day_of_week(field: &str) -> Result<String, String> {
+    fn parse_day(day: &str) -> Result<usize, String> {
+        match day.parse::<usize>() {
+            Ok(day) if day <= 7 => Ok(day),
+            _ => Err(format!("invalid day of week '{day}'")),
+        }
+    }
+
+    let mut days: Vec<String> = Vec::new();
+    for item in field.split(',') {
+        let (range, step) = match item.split_once('/') {
+            Some((range, step)) => match step.parse::<usize>() {
+                Ok(step) if step > 0 => (range, Some(step)),
+                _ => return Err(format!("invalid step '{step}'")),
+            },
+            None => (item, None),
+        };
+        let is_named = range.chars().all(|c| c.is_ascii_alphabetic() || c == '-');
+        if is_named || (step.is_none() && (range == "*" || range == "?")) {
+            days.push(item.to_string());
+            continue;
+        }
+        let (start, end) = match range.split_once('-') {
+            _ if range == "*" => (0, 6),
+            Some((start, end)) => (parse_day(start)?, parse_day(end)?),
+            None if step.is_some() => (parse_day(range)?, 7),
+            None => (parse_day(range)?, parse_day(range)?),
+        };
+        if start > end {
+            return Err(format!("invalid day of week range '{range}'"));
+        }
+        for day in (start..=end).step_by(step.unwrap_or(1)) {
+            let day = (day % 7 + 1).to_string();
+            if !days.contains(&day) {
+                days.push(day);
+            }
+        }
+    }
+    Ok(days.join(","))
+}
+
 impl Default for PayoutQueueConfig {
     fn default() -> Self {
         Self {
             tx_priority: TxPriority::NextBlock,
             consolidate_deprecated_keychains: false,
+            spending_path: SpendingPath::default(),
+            coin_selection: CoinSelectionStrategy::default(),
             trigger: PayoutQueueTrigger::Interval {
                 seconds: default_interval(),
             },
             cpfp_payouts_after_mins: None,
             cpfp_payouts_after_blocks: None,
+            bump_fee_after_mins: None,
+            max_fee_rate_sat_per_vb: None,
+            max_total_fee_sats: None,
         }
     }
 }
@@ -62,3 +226,184 @@ impl Default for PayoutQueueConfig {
 fn default_interval() -> Duration {
     Duration::from_secs(60)
 }
+
+fn default_timezone() -> String {
+    "UTC".to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn fee_rate_cap() {
+        let config = PayoutQueueConfig {
+            max_fee_rate_sat_per_vb: Some(10),
+            ..PayoutQueueConfig::default()
+        };
+        assert!(!config.fee_rate_exceeds_cap(bitcoin::FeeRate::from_sat_per_vb(9.5)));
+        assert!(!config.fee_rate_exceeds_cap(bitcoin::FeeRate::from_sat_per_vb(10.0)));
+        assert!(config.fee_rate_exceeds_cap(bitcoin::FeeRate::from_sat_per_vb(10.5)));
+        assert!(!PayoutQueueConfig::default()
+            .fee_rate_exceeds_cap(bitcoin::FeeRate::from_sat_per_vb(1_000.0)));
+    }
+
+    #[test]
+    fn total_fee_cap() {
+        let config = PayoutQueueConfig {
+            max_total_fee_sats: Some(Satoshis::from(5_000)),
+            ..PayoutQueueConfig::default()
+        };
+        assert!(!config.total_fee_exceeds_cap(Satoshis::from(4_999)));
+        assert!(!config.total_fee_exceeds_cap(Satoshis::from(5_000)));
+        assert!(config.total_fee_exceeds_cap(Satoshis::from(5_001)));
+        assert!(!PayoutQueueConfig::default().total_fee_exceeds_cap(Satoshis::from(1_000_000)));
+    }
+
+    #[test]
+    fn threshold_min_payouts() {
+        let trigger = PayoutQueueTrigger::Threshold {
+            min_payouts: Some(3),
+            min_total_sats: None,
+            seconds: default_interval(),
+        };
+        let sats = Satoshis::from(1_000_000_000);
+        assert!(!trigger.threshold_reached(0, sats));
+        assert!(!trigger.threshold_reached(2, sats));
+        assert!(trigger.threshold_reached(3, sats));
+        assert!(trigger.threshold_reached(4, sats));
+    }
+
+    #[test]
+    fn threshold_min_total_sats() {
+        let trigger = PayoutQueueTrigger::Threshold {
+            min_payouts: None,
+            min_total_sats: Some(Satoshis::from(100_000)),
+            seconds: default_interval(),
+        };
+        assert!(!trigger.threshold_reached(0, Satoshis::from(100_000)));
+        assert!(!trigger.threshold_reached(10, Satoshis::from(99_999)));
+        assert!(trigger.threshold_reached(1, Satoshis::from(100_000)));
+        assert!(trigger.threshold_reached(1, Satoshis::from(100_001)));
+    }
+
+    #[test]
+    fn threshold_either_limit() {
+        let trigger = PayoutQueueTrigger::Threshold {
+            min_payouts: Some(3),
+            min_total_sats: Some(Satoshis::from(100_000)),
+            seconds: default_interval(),
+        };
+        assert!(!trigger.threshold_reached(2, Satoshis::from(99_999)));
+        assert!(trigger.threshold_reached(3, Satoshis::from(1)));
+        assert!(trigger.threshold_reached(1, Satoshis::from(100_000)));
+        assert!(!PayoutQueueTrigger::Manual.threshold_reached(10, Satoshis::from(100_000)));
+    }
+
+    #[test]
+    fn schedule_next_scheduled_after() {
+        let trigger = PayoutQueueTrigger::Schedule {
+            cron: "0 9,17 * * Mon-Fri".to_string(),
+            timezone: "UTC".to_string(),
+        };
+        assert!(trigger.validate().is_ok());
+        // Friday 2023-12-01 17:00:00 UTC
+        let friday_evening = chrono::Utc.with_ymd_and_hms(2023, 12, 1, 17, 0, 0).unwrap();
+        assert_eq!(
+            trigger.next_scheduled_after(friday_evening),
+            Some(chrono::Utc.with_ymd_and_hms(2023, 12, 4, 9, 0, 0).unwrap())
+        );
+    }
+
+    #[test]
+    fn schedule_respects_timezone() {
+        let trigger = PayoutQueueTrigger::Schedule {
+            cron: "0 0 9 * * *".to_string(),
+            timezone: "America/New_York".to_string(),
+        };
+        let now = chrono::Utc.with_ymd_and_hms(2023, 12, 1, 0, 0, 0).unwrap();
+        assert_eq!(
+            trigger.next_scheduled_after(now),
+            Some(chrono::Utc.with_ymd_and_hms(2023, 12, 1, 14, 0, 0).unwrap())
+        );
+    }
+
+    #[test]
+    fn schedule_numeric_day_of_week() {
+        // Friday 2023-12-01 17:00:00 UTC
+        let friday_evening = chrono::Utc.with_ymd_and_hms(2023, 12, 1, 17, 0, 0).unwrap();
+        let next = |cron: &str| {
+            PayoutQueueTrigger::Schedule {
+                cron: cron.to_string(),
+                timezone: "UTC".to_string(),
+            }
+            .next_scheduled_after(friday_evening)
+        };
+        let saturday = chrono::Utc.with_ymd_and_hms(2023, 12, 2, 9, 0, 0).unwrap();
+        let sunday = chrono::Utc.with_ymd_and_hms(2023, 12, 3, 9, 0, 0).unwrap();
+        let monday = chrono::Utc.with_ymd_and_hms(2023, 12, 4, 9, 0, 0).unwrap();
+        assert_eq!(next("0 9 * * 1"), Some(monday));
+        assert_eq!(next("0 9 * * 1-5"), Some(monday));
+        assert_eq!(next("0 9 * * 0"), Some(sunday));
+        assert_eq!(next("0 9 * * 7"), Some(sunday));
+        assert_eq!(next("0 9 * * 0,6"), Some(saturday));
+        assert_eq!(next("0 9 * * 1/6"), Some(sunday));
+        assert_eq!(next("0 9 * * Mon"), Some(monday));
+
+        let invalid_day = PayoutQueueTrigger::Schedule {
+            cron: "0 9 * * 8".to_string(),
+            timezone: "UTC".to_string(),
+        };
+        assert!(matches!(
+            invalid_day.validate(),
+            Err(PayoutQueueError::InvalidScheduleCron(_))
+        ));
+    }
+
+    #[test]
+    fn schedule_day_of_week_agrees_across_field_counts() {
+        // Friday 2023-12-01 17:00:00 UTC
+        let friday_evening = chrono::Utc.with_ymd_and_hms(2023, 12, 1, 17, 0, 0).unwrap();
+        let next = |cron: &str| {
+            PayoutQueueTrigger::Schedule {
+                cron: cron.to_string(),
+                timezone: "UTC".to_string(),
+            }
+            .next_scheduled_after(friday_evening)
+        };
+        let sunday = chrono::Utc.with_ymd_and_hms(2023, 12, 3, 9, 0, 0).unwrap();
+        let monday = chrono::Utc.with_ymd_and_hms(2023, 12, 4, 9, 0, 0).unwrap();
+        let tuesday = chrono::Utc.with_ymd_and_hms(2023, 12, 5, 9, 0, 0).unwrap();
+        for (five_fields, six_fields, expected) in [
+            ("0 9 * * 0", "0 0 9 * * 0", sunday),
+            ("0 9 * * 1", "0 0 9 * * 1", monday),
+            ("0 9 * * 2", "0 0 9 * * 2", tuesday),
+            ("0 9 * * 1-2", "0 0 9 * * 1-2", monday),
+            ("0 9 * * Tue", "0 0 9 * * Tue", tuesday),
+        ] {
+            assert_eq!(next(five_fields), Some(expected), "{five_fields}");
+            assert_eq!(next(six_fields), Some(expected), "{six_fields}");
+        }
+    }
+
+    #[test]
+    fn schedule_validation() {
+        let invalid_cron = PayoutQueueTrigger::Schedule {
+            cron: "not a cron".to_string(),
+            timezone: "UTC".to_string(),
+        };
+        assert!(matches!(
+            invalid_cron.validate(),
+            Err(PayoutQueueError::InvalidScheduleCron(_))
+        ));
+        let invalid_tz = PayoutQueueTrigger::Schedule {
+            cron: "0 9 * * *".to_string(),
+            timezone: "Mars/Olympus".to_string(),
+        };
+        assert!(matches!(
+            invalid_tz.validate(),
+            Err(PayoutQueueError::InvalidScheduleTimezone(_))
+        ));
+    }
+}
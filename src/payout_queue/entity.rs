@@ -3,7 +3,7 @@ use serde::{Deserialize, Serialize};
 use std::time::Duration;
 
 use super::config::*;
-use crate::{entity::*, primitives::*};
+use crate::{entity::*, payout::UnbatchedPayouts, primitives::*};
 
 #[derive(Serialize, Deserialize)]
 #[serde(tag = "type", rename_all = "snake_case")]
@@ -35,14 +35,32 @@ pub struct PayoutQueue {
 }
 
 impl PayoutQueue {
+    /// Delay after which the next scheduled processing of the queue should run.
     pub fn spawn_in(&self) -> Option<Duration> {
         use PayoutQueueTrigger::*;
         match self.config.trigger {
-            Interval { seconds } => Some(seconds),
+            // Spawned a second early so that an interval doesn't slip by a full period
+            Interval { seconds } | Threshold { seconds, .. } => Some(
+                seconds
+                    .checked_sub(Duration::from_secs(1))
+                    .unwrap_or_default(),
+            ),
+            Schedule { .. } => {
+                let now = chrono::Utc::now();
+                let next = self.config.trigger.next_scheduled_after(now)?;
+                (next - now).to_std().ok()
+            }
             Manual => None,
         }
     }
 
+    pub fn threshold_reached(&self, unbatched_payouts: &UnbatchedPayouts) -> bool {
+        self.config.trigger.threshold_reached(
+            unbatched_payouts.n_not_batched(),
+            unbatched_payouts.total_sats(),
+        )
+    }
+
     pub fn description(&self) -> Option<String> {
         let mut ret = None;
         for event in self.events.iter() {
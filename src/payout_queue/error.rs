@@ -6,6 +6,10 @@ pub enum PayoutQueueError {
     PayoutQueueNameNotFound(String),
     #[error("PayoutQueueError - Could not find payout queue with id: {0}")]
     PayoutQueueIdNotFound(String),
+    #[error("PayoutQueueError - Invalid schedule cron expression: {0}")]
+    InvalidScheduleCron(String),
+    #[error("PayoutQueueError - Invalid schedule timezone: {0}")]
+    InvalidScheduleTimezone(String),
     #[error("PayoutQueueError - Sqlx: {0}")]
     Sqlx(#[from] sqlx::Error),
     #[error("PayoutQueueError - EntityError: {0}")]
@@ -79,7 +79,7 @@ pub mod bitcoin {
         bitcoin::{
             blockdata::{
                 script::Script,
-                transaction::{OutPoint, Transaction, TxOut},
+                transaction::{OutPoint, ParseOutPointError, Sequence, Transaction, TxOut},
             },
             consensus,
             hash_types::Txid,
@@ -119,22 +119,83 @@ pub mod bitcoin {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, clap::ValueEnum, PartialEq, Eq, Copy)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash, Copy)]
 #[serde(rename_all = "snake_case")]
 pub enum TxPriority {
     NextBlock,
     HalfHour,
     OneHour,
+    Economy,
+    Minimum,
+    CustomSatPerVb(CustomFeeRate),
 }
 
 impl TxPriority {
+    /// Confirmation target used when querying fee estimators.
+    /// A custom fee rate doesn't need an estimate so it targets the next block.
     pub fn n_blocks(&self) -> usize {
         match self {
-            Self::NextBlock => 1,
+            Self::NextBlock | Self::CustomSatPerVb(_) => 1,
             Self::HalfHour => 3,
             Self::OneHour => 6,
+            Self::Economy => 144,
+            Self::Minimum => 1008,
         }
     }
+
+    pub fn custom_fee_rate(&self) -> Option<bitcoin::FeeRate> {
+        match self {
+            Self::CustomSatPerVb(fee_rate) => Some(fee_rate.fee_rate()),
+            _ => None,
+        }
+    }
+}
+
+/// A positive fee rate stored as whole milli-sats per vbyte.
+/// It is (de)serialized as sat/vB to stay compatible with persisted configs.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct CustomFeeRate(u64);
+
+impl CustomFeeRate {
+    pub fn from_sat_per_vb(sat_per_vb: f32) -> Option<Self> {
+        if !sat_per_vb.is_finite() {
+            return None;
+        }
+        let milli_sats = (f64::from(sat_per_vb) * 1000.0).round();
+        if milli_sats < 1.0 || milli_sats > u64::MAX as f64 {
+            return None;
+        }
+        Some(Self(milli_sats as u64))
+    }
+
+    pub fn as_sat_per_vb(&self) -> f32 {
+        (self.0 as f64 / 1000.0) as f32
+    }
+
+    pub fn fee_rate(&self) -> bitcoin::FeeRate {
+        bitcoin::FeeRate::from_sat_per_vb(self.as_sat_per_vb())
+    }
+}
+
+impl std::fmt::Debug for CustomFeeRate {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.as_sat_per_vb())
+    }
+}
+
+impl Serialize for CustomFeeRate {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_f32(self.as_sat_per_vb())
+    }
+}
+
+impl<'de> Deserialize<'de> for CustomFeeRate {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let sat_per_vb = f32::deserialize(deserializer)?;
+        Self::from_sat_per_vb(sat_per_vb).ok_or_else(|| {
+            serde::de::Error::custom(format!("invalid custom fee rate: {sat_per_vb}"))
+        })
+    }
 }
 
 pub type TxPayout = (uuid::Uuid, bitcoin::Address, Satoshis);
@@ -1,6 +1,7 @@
 use derive_builder::Builder;
 use serde::{Deserialize, Serialize};
 
+use super::error::ProfileError;
 use crate::{entity::*, primitives::*};
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -16,6 +17,15 @@ pub enum ProfileEvent {
     SpendingPolicyUpdated {
         spending_policy: SpendingPolicy,
     },
+    Disabled,
+    Enabled,
+    ApiKeyRevoked {
+        api_key_id: ProfileApiKeyId,
+    },
+    ApiKeyExpiryUpdated {
+        api_key_id: ProfileApiKeyId,
+        expires_at: Option<chrono::DateTime<chrono::Utc>>,
+    },
 }
 
 #[derive(Debug, Builder)]
@@ -26,6 +36,10 @@ pub struct Profile {
     pub name: String,
     #[builder(default, setter(strip_option))]
     pub spending_policy: Option<SpendingPolicy>,
+    #[builder(default)]
+    pub disabled: bool,
+
+    pub(super) events: EntityEvents<ProfileEvent>,
 }
 
 impl Profile {
@@ -35,11 +49,74 @@ impl Profile {
             .map(|sp| sp.is_destination_allowed(destination))
             .unwrap_or(true)
     }
+
+    pub fn required_approvals(&self, satoshis: Satoshis) -> u32 {
+        self.spending_policy
+            .as_ref()
+            .map(|sp| sp.required_approvals(satoshis))
+            .unwrap_or(0)
+    }
+
+    pub fn spending_limits<'a>(
+        &'a self,
+        wallet_name: &'a str,
+    ) -> impl Iterator<Item = &'a SpendingLimit> + 'a {
+        self.spending_policy
+            .iter()
+            .flat_map(|sp| sp.limits.iter())
+            .filter(move |limit| limit.applies_to(wallet_name))
+    }
+
+    pub fn update_spending_policy(&mut self, spending_policy: SpendingPolicy) {
+        self.spending_policy = Some(spending_policy.clone());
+        self.events
+            .push(ProfileEvent::SpendingPolicyUpdated { spending_policy });
+    }
+
+    pub fn update_name(&mut self, name: String) {
+        if self.name != name {
+            self.name = name.clone();
+            self.events.push(ProfileEvent::NameUpdated { name });
+        }
+    }
+
+    pub fn disable(&mut self) {
+        if !self.disabled {
+            self.disabled = true;
+            self.events.push(ProfileEvent::Disabled);
+        }
+    }
+
+    pub fn enable(&mut self) {
+        if self.disabled {
+            self.disabled = false;
+            self.events.push(ProfileEvent::Enabled);
+        }
+    }
+
+    pub fn revoke_api_key(&mut self, api_key_id: ProfileApiKeyId) {
+        self.events.push(ProfileEvent::ApiKeyRevoked { api_key_id });
+    }
+
+    pub fn update_api_key_expiry(
+        &mut self,
+        api_key_id: ProfileApiKeyId,
+        expires_at: Option<chrono::DateTime<chrono::Utc>>,
+    ) {
+        self.events.push(ProfileEvent::ApiKeyExpiryUpdated {
+            api_key_id,
+            expires_at,
+        });
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SpendingPolicy {
     pub allowed_payout_addresses: Vec<bitcoin::Address>,
+    #[serde(default)]
+    pub approval: Option<ApprovalPolicy>,
+    #[serde(default)]
+    pub limits: Vec<SpendingLimit>,
 }
 
 impl SpendingPolicy {
@@ -47,6 +124,132 @@ impl SpendingPolicy {
         self.allowed_payout_addresses
             .contains(destination.onchain_address())
     }
+
+    fn required_approvals(&self, satoshis: Satoshis) -> u32 {
+        match self.approval.as_ref() {
+            Some(approval) if satoshis > approval.threshold_sats => approval.required_approvals,
+            _ => 0,
+        }
+    }
+}
+
+/// Payouts above `threshold_sats` are only batched after `required_approvals`
+/// distinct profiles other than the submitter have approved them.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApprovalPolicy {
+    pub required_approvals: u32,
+    pub threshold_sats: Satoshis,
+}
+
+/// Caps the sats a profile may submit, either across all wallets or only for `wallet_name`.
+/// The hourly and daily caps are rolling windows over the payouts already submitted.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SpendingLimit {
+    pub wallet_name: Option<String>,
+    pub max_payout_sats: Option<Satoshis>,
+    pub max_sats_per_hour: Option<Satoshis>,
+    pub max_sats_per_day: Option<Satoshis>,
+}
+
+impl SpendingLimit {
+    fn applies_to(&self, wallet_name: &str) -> bool {
+        self.wallet_name
+            .as_ref()
+            .map(|name| name == wallet_name)
+            .unwrap_or(true)
+    }
+
+    pub fn rolling_windows(
+        &self,
+    ) -> impl Iterator<Item = (SpendingLimitWindow, chrono::Duration, Satoshis)> + '_ {
+        [
+            (
+                SpendingLimitWindow::Hour,
+                chrono::Duration::hours(1),
+                self.max_sats_per_hour,
+            ),
+            (
+                SpendingLimitWindow::Day,
+                chrono::Duration::days(1),
+                self.max_sats_per_day,
+            ),
+        ]
+        .into_iter()
+        .filter_map(|(window, duration, max)| max.map(|max| (window, duration, max)))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpendingLimitWindow {
+    Payout,
+    Hour,
+    Day,
+}
+
+impl std::fmt::Display for SpendingLimitWindow {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Payout => write!(f, "per payout"),
+            Self::Hour => write!(f, "hourly"),
+            Self::Day => write!(f, "daily"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ApiKeyScope {
+    Read,
+    AddressesWrite,
+    PayoutsWrite,
+    PayoutsApprove,
+    WalletsAdmin,
+    SignersAdmin,
+    ProfilesAdmin,
+}
+
+impl ApiKeyScope {
+    pub fn all() -> Vec<Self> {
+        vec![
+            Self::Read,
+            Self::AddressesWrite,
+            Self::PayoutsWrite,
+            Self::PayoutsApprove,
+            Self::WalletsAdmin,
+            Self::SignersAdmin,
+            Self::ProfilesAdmin,
+        ]
+    }
+}
+
+impl std::fmt::Display for ApiKeyScope {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Read => write!(f, "read"),
+            Self::AddressesWrite => write!(f, "addresses:write"),
+            Self::PayoutsWrite => write!(f, "payouts:write"),
+            Self::PayoutsApprove => write!(f, "payouts:approve"),
+            Self::WalletsAdmin => write!(f, "wallets:admin"),
+            Self::SignersAdmin => write!(f, "signers:admin"),
+            Self::ProfilesAdmin => write!(f, "profiles:admin"),
+        }
+    }
+}
+
+impl std::str::FromStr for ApiKeyScope {
+    type Err = ProfileError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "read" => Ok(Self::Read),
+            "addresses:write" => Ok(Self::AddressesWrite),
+            "payouts:write" => Ok(Self::PayoutsWrite),
+            "payouts:approve" => Ok(Self::PayoutsApprove),
+            "wallets:admin" => Ok(Self::WalletsAdmin),
+            "signers:admin" => Ok(Self::SignersAdmin),
+            "profiles:admin" => Ok(Self::ProfilesAdmin),
+            _ => Err(ProfileError::UnknownApiKeyScope(s.to_string())),
+        }
+    }
 }
 
 pub struct ProfileApiKey {
@@ -54,6 +257,45 @@ pub struct ProfileApiKey {
     pub id: ProfileApiKeyId,
     pub profile_id: ProfileId,
     pub account_id: AccountId,
+    pub scopes: Vec<ApiKeyScope>,
+}
+
+/// A profile together with the scopes of the api key it was authenticated with.
+#[derive(Debug)]
+pub struct AuthenticatedProfile {
+    pub profile: Profile,
+    pub scopes: Vec<ApiKeyScope>,
+}
+
+impl AuthenticatedProfile {
+    pub fn has_scope(&self, scope: ApiKeyScope) -> bool {
+        self.scopes.contains(&scope)
+    }
+
+    pub fn ensure_scope(&self, scope: ApiKeyScope) -> Result<(), ProfileError> {
+        if !self.has_scope(scope) {
+            return Err(ProfileError::ApiKeyMissingScope(scope));
+        }
+        Ok(())
+    }
+}
+
+impl std::ops::Deref for AuthenticatedProfile {
+    type Target = Profile;
+
+    fn deref(&self) -> &Self::Target {
+        &self.profile
+    }
+}
+
+/// An api key as it is listed - the key itself can not be recovered after creation.
+pub struct ProfileApiKeySummary {
+    pub id: ProfileApiKeyId,
+    pub profile_id: ProfileId,
+    pub active: bool,
+    pub scopes: Vec<ApiKeyScope>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub expires_at: Option<chrono::DateTime<chrono::Utc>>,
 }
 
 #[derive(Builder, Clone, Debug)]
@@ -97,19 +339,26 @@ impl TryFrom<EntityEvents<ProfileEvent>> for Profile {
 
     fn try_from(events: EntityEvents<ProfileEvent>) -> Result<Self, Self::Error> {
         let mut builder = ProfileBuilder::default();
-        for event in events.into_iter() {
+        for event in events.iter() {
             match event {
                 ProfileEvent::Initialized { id, account_id } => {
-                    builder = builder.id(id).account_id(account_id)
+                    builder = builder.id(*id).account_id(*account_id)
                 }
                 ProfileEvent::NameUpdated { name } => {
-                    builder = builder.name(name);
+                    builder = builder.name(name.clone());
                 }
                 ProfileEvent::SpendingPolicyUpdated { spending_policy } => {
-                    builder = builder.spending_policy(spending_policy);
+                    builder = builder.spending_policy(spending_policy.clone());
+                }
+                ProfileEvent::Disabled => {
+                    builder = builder.disabled(true);
+                }
+                ProfileEvent::Enabled => {
+                    builder = builder.disabled(false);
                 }
+                _ => (),
             }
         }
-        builder.build()
+        builder.events(events).build()
     }
 }
@@ -4,8 +4,24 @@ use thiserror::Error;
 pub enum ProfileError {
     #[error("ProfileError - Api key does not exist")]
     ProfileKeyNotFound,
+    #[error("ProfileError - Profile is disabled")]
+    ProfileDisabled,
+    #[error("ProfileError - Could not find api key with id: {0}")]
+    ApiKeyNotFound(String),
+    #[error("ProfileError - Api key {0} is already revoked")]
+    ApiKeyAlreadyRevoked(String),
+    #[error("ProfileError - Api key is missing the '{0}' scope")]
+    ApiKeyMissingScope(super::ApiKeyScope),
+    #[error("ProfileError - Unknown api key scope: {0}")]
+    UnknownApiKeyScope(String),
     #[error("ProfileError - Could not find profile with name: {0}")]
     ProfileNameNotFound(String),
+    #[error("ProfileError - Could not find profile with id: {0}")]
+    ProfileIdNotFound(String),
+    #[error("ProfileError - Profile with name '{0}' already exists")]
+    ProfileNameAlreadyExists(String),
+    #[error("ProfileError - A profile can not disable itself")]
+    CannotDisableSelf,
     #[error("ProfileError - Sqlx: {0}")]
     Sqlx(#[from] sqlx::Error),
     #[error("ProfileError - EntityError: {0}")]
@@ -6,6 +6,8 @@ use uuid::Uuid;
 use super::{entity::*, error::ProfileError};
 use crate::{dev_constants, entity::*, primitives::*};
 
+const PROFILE_NAME_CONSTRAINT: &str = "bria_profiles_account_id_name_key";
+
 pub struct Profiles {
     pool: Pool<Postgres>,
 }
@@ -29,19 +31,183 @@ impl Profiles {
         )
         .execute(&mut **tx)
         .await?;
-        let res = Profile {
-            id: profile.id,
-            account_id: profile.account_id,
-            name: profile.name.clone(),
-            spending_policy: profile.spending_policy.clone(),
-        };
+        let id = profile.id;
+        let events = profile.initial_events();
         EntityEvents::<ProfileEvent>::persist(
             "bria_profile_events",
             &mut *tx,
-            profile.initial_events().new_serialized_events(res.id),
+            events.new_serialized_events(id),
+        )
+        .await?;
+        Ok(Profile::try_from(events)?)
+    }
+
+    pub async fn find_by_id(
+        &self,
+        account_id: AccountId,
+        id: ProfileId,
+    ) -> Result<Profile, ProfileError> {
+        let rows = sqlx::query!(
+            r#"SELECT e.sequence, e.event_type, e.event
+               FROM bria_profiles p
+               JOIN bria_profile_events e ON p.id = e.id
+               WHERE p.account_id = $1 AND p.id = $2
+               ORDER BY sequence"#,
+            account_id as AccountId,
+            id as ProfileId
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        if !rows.is_empty() {
+            let mut events = EntityEvents::new();
+            for row in rows {
+                events.load_event(row.sequence as usize, row.event)?;
+            }
+            Ok(Profile::try_from(events)?)
+        } else {
+            Err(ProfileError::ProfileIdNotFound(id.to_string()))
+        }
+    }
+
+    pub async fn find_by_api_key_id(
+        &self,
+        account_id: AccountId,
+        api_key_id: ProfileApiKeyId,
+    ) -> Result<Profile, ProfileError> {
+        let record = sqlx::query!(
+            r#"SELECT k.profile_id
+               FROM bria_profile_api_keys k
+               JOIN bria_profiles p ON k.profile_id = p.id
+               WHERE p.account_id = $1 AND k.id = $2"#,
+            account_id as AccountId,
+            api_key_id as ProfileApiKeyId
         )
+        .fetch_optional(&self.pool)
         .await?;
-        Ok(res)
+        match record {
+            Some(record) => {
+                self.find_by_id(account_id, ProfileId::from(record.profile_id))
+                    .await
+            }
+            None => Err(ProfileError::ApiKeyNotFound(api_key_id.to_string())),
+        }
+    }
+
+    pub async fn update_in_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        profile: Profile,
+    ) -> Result<(), ProfileError> {
+        if !profile.events.is_dirty() {
+            return Ok(());
+        }
+
+        sqlx::query!(
+            r#"UPDATE bria_profiles SET name = $2, modified_at = NOW()
+               WHERE id = $1"#,
+            profile.id as ProfileId,
+            profile.name,
+        )
+        .execute(&mut **tx)
+        .await
+        .map_err(
+            |err| match err.as_database_error().and_then(|e| e.constraint()) {
+                Some(PROFILE_NAME_CONSTRAINT) => {
+                    ProfileError::ProfileNameAlreadyExists(profile.name.clone())
+                }
+                _ => ProfileError::from(err),
+            },
+        )?;
+        EntityEvents::<ProfileEvent>::persist(
+            "bria_profile_events",
+            tx,
+            profile.events.new_serialized_events(profile.id),
+        )
+        .await?;
+        Ok(())
+    }
+
+    pub async fn list_api_keys(
+        &self,
+        profile_id: ProfileId,
+    ) -> Result<Vec<ProfileApiKeySummary>, ProfileError> {
+        let rows = sqlx::query!(
+            r#"SELECT id, active, scopes, created_at, expires_at
+               FROM bria_profile_api_keys
+               WHERE profile_id = $1
+               ORDER BY created_at"#,
+            profile_id as ProfileId
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        rows.into_iter()
+            .map(|row| {
+                Ok(ProfileApiKeySummary {
+                    id: ProfileApiKeyId::from(row.id),
+                    profile_id,
+                    active: row.active,
+                    scopes: parse_scopes(&row.scopes)?,
+                    created_at: row.created_at,
+                    expires_at: row.expires_at,
+                })
+            })
+            .collect()
+    }
+
+    pub async fn revoke_api_key_in_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        profile_id: ProfileId,
+        api_key_id: ProfileApiKeyId,
+    ) -> Result<(), ProfileError> {
+        let res = sqlx::query!(
+            r#"UPDATE bria_profile_api_keys SET active = false, modified_at = NOW()
+               WHERE id = $1 AND profile_id = $2 AND active = true"#,
+            api_key_id as ProfileApiKeyId,
+            profile_id as ProfileId,
+        )
+        .execute(&mut **tx)
+        .await?;
+        if res.rows_affected() == 0 {
+            return Err(ProfileError::ApiKeyAlreadyRevoked(api_key_id.to_string()));
+        }
+        Ok(())
+    }
+
+    pub async fn update_api_key_expiry_in_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        profile_id: ProfileId,
+        api_key_id: ProfileApiKeyId,
+        expires_at: Option<chrono::DateTime<chrono::Utc>>,
+    ) -> Result<(), ProfileError> {
+        sqlx::query!(
+            r#"UPDATE bria_profile_api_keys SET expires_at = $3, modified_at = NOW()
+               WHERE id = $1 AND profile_id = $2"#,
+            api_key_id as ProfileApiKeyId,
+            profile_id as ProfileId,
+            expires_at,
+        )
+        .execute(&mut **tx)
+        .await?;
+        Ok(())
+    }
+
+    /// Serializes concurrent payout submissions of a profile so that spending
+    /// limits are checked against all payouts that were submitted before.
+    pub async fn lock_for_spending_in_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        id: ProfileId,
+    ) -> Result<(), ProfileError> {
+        sqlx::query!(
+            r#"SELECT id FROM bria_profiles WHERE id = $1 FOR UPDATE"#,
+            id as ProfileId
+        )
+        .fetch_one(&mut **tx)
+        .await?;
+        Ok(())
     }
 
     pub async fn list_for_account(
@@ -106,6 +272,7 @@ impl Profiles {
         tx: &mut sqlx::Transaction<'_, Postgres>,
         profile: Profile,
         dev: bool,
+        scopes: Vec<ApiKeyScope>,
     ) -> Result<ProfileApiKey, ProfileError> {
         let key = if dev {
             dev_constants::BRIA_DEV_KEY.to_string()
@@ -114,10 +281,11 @@ impl Profiles {
             format!("bria_{code}")
         };
         let record = sqlx::query!(
-            r#"INSERT INTO bria_profile_api_keys (encrypted_key, profile_id)
-            VALUES (crypt($1, gen_salt('bf')), (SELECT id FROM bria_profiles WHERE id = $2)) RETURNING (id)"#,
+            r#"INSERT INTO bria_profile_api_keys (encrypted_key, profile_id, scopes)
+            VALUES (crypt($1, gen_salt('bf')), (SELECT id FROM bria_profiles WHERE id = $2), $3) RETURNING (id)"#,
             key,
             Uuid::from(profile.id),
+            &scopes.iter().map(|scope| scope.to_string()).collect::<Vec<_>>(),
         )
             .fetch_one(&mut **tx)
             .await?;
@@ -126,17 +294,19 @@ impl Profiles {
             id: ProfileApiKeyId::from(record.id),
             profile_id: profile.id,
             account_id: profile.account_id,
+            scopes,
         })
     }
 
-    pub async fn find_by_key(&self, key: &str) -> Result<Profile, ProfileError> {
+    pub async fn find_by_key(&self, key: &str) -> Result<AuthenticatedProfile, ProfileError> {
         let mut tx = self.pool.begin().await?;
 
         let record = sqlx::query!(
-            r#"SELECT p.id, p.account_id, p.name
+            r#"SELECT p.id, p.account_id, p.name, k.scopes
                FROM bria_profiles p
                JOIN bria_profile_api_keys k ON k.profile_id = p.id
-               WHERE k.active = true AND k.encrypted_key = crypt($1, encrypted_key)"#,
+               WHERE k.active = true AND k.encrypted_key = crypt($1, encrypted_key)
+                 AND (k.expires_at IS NULL OR k.expires_at > NOW())"#,
             key
         )
         .fetch_optional(&mut *tx)
@@ -155,9 +325,20 @@ impl Profiles {
             for row in rows {
                 events.load_event(row.sequence as usize, row.event)?;
             }
-            Ok(Profile::try_from(events)?)
+            let profile = Profile::try_from(events)?;
+            if profile.disabled {
+                return Err(ProfileError::ProfileDisabled);
+            }
+            Ok(AuthenticatedProfile {
+                profile,
+                scopes: parse_scopes(&record.scopes)?,
+            })
         } else {
             Err(ProfileError::ProfileKeyNotFound)
         }
     }
 }
+
+fn parse_scopes(scopes: &[String]) -> Result<Vec<ApiKeyScope>, ProfileError> {
+    scopes.iter().map(|scope| scope.parse()).collect()
+}
@@ -7,6 +7,7 @@ use std::collections::HashMap;
 use crate::{
     entity::*,
     primitives::{bitcoin::psbt, *},
+    wallet::SpendingPath,
     xpub::SigningClientError,
 };
 
@@ -19,6 +20,8 @@ pub enum SigningSessionEvent {
         account_id: AccountId,
         batch_id: BatchId,
         unsigned_psbt: psbt::PartiallySignedTransaction,
+        #[serde(default)]
+        spending_path: Option<SpendingPath>,
     },
     SigningAttemptFailed {
         reason: SigningFailureReason,
@@ -29,6 +32,9 @@ pub enum SigningSessionEvent {
     RemoteSigningCompleted {
         signed_psbt: psbt::PartiallySignedTransaction,
     },
+    UnsignedPsbtReplaced {
+        unsigned_psbt: psbt::PartiallySignedTransaction,
+    },
 }
 
 #[derive(Debug)]
@@ -46,6 +52,9 @@ pub struct SigningSession {
     pub batch_id: BatchId,
     pub xpub_id: XPubId,
     pub unsigned_psbt: psbt::PartiallySignedTransaction,
+    /// Only set for keychains whose policy has timelocked branches
+    #[builder(default)]
+    pub spending_path: Option<SpendingPath>,
     pub(super) events: EntityEvents<SigningSessionEvent>,
 }
 
@@ -66,6 +75,12 @@ impl SigningSession {
             .push(SigningSessionEvent::ExternallySignedPsbtSubmitted { signed_psbt })
     }
 
+    pub fn replace_unsigned_psbt(&mut self, unsigned_psbt: psbt::PartiallySignedTransaction) {
+        self.unsigned_psbt = unsigned_psbt.clone();
+        self.events
+            .push(SigningSessionEvent::UnsignedPsbtReplaced { unsigned_psbt })
+    }
+
     pub fn is_completed(&self) -> bool {
         self.signed_psbt().is_some()
     }
@@ -78,6 +93,7 @@ impl SigningSession {
                 | SigningSessionEvent::ExternallySignedPsbtSubmitted { signed_psbt } => {
                     ret = Some(signed_psbt);
                 }
+                SigningSessionEvent::UnsignedPsbtReplaced { .. } => ret = None,
                 _ => (),
             }
         }
@@ -94,6 +110,7 @@ impl SigningSession {
                 SigningSessionEvent::SigningAttemptFailed { reason } => Some(reason),
                 SigningSessionEvent::RemoteSigningCompleted { .. } => None,
                 SigningSessionEvent::ExternallySignedPsbtSubmitted { .. } => None,
+                SigningSessionEvent::UnsignedPsbtReplaced { .. } => None,
                 _ => ret,
             };
         }
@@ -109,6 +126,9 @@ impl SigningSession {
                 SigningSessionEvent::ExternallySignedPsbtSubmitted { .. } => {
                     SigningSessionState::Complete
                 }
+                SigningSessionEvent::UnsignedPsbtReplaced { .. } => {
+                    SigningSessionState::Initialized
+                }
                 _ => ret,
             };
         }
@@ -145,6 +165,8 @@ pub struct NewSigningSession {
     pub(super) batch_id: BatchId,
     pub(super) xpub_id: XPubId,
     unsigned_psbt: psbt::PartiallySignedTransaction,
+    #[builder(default)]
+    spending_path: Option<SpendingPath>,
 }
 
 impl NewSigningSession {
@@ -161,6 +183,7 @@ impl NewSigningSession {
             batch_id: self.batch_id,
             xpub_id: self.xpub_id,
             unsigned_psbt: self.unsigned_psbt,
+            spending_path: self.spending_path,
         }])
     }
 }
@@ -171,20 +194,27 @@ impl TryFrom<EntityEvents<SigningSessionEvent>> for SigningSession {
     fn try_from(events: EntityEvents<SigningSessionEvent>) -> Result<Self, Self::Error> {
         let mut builder = SigningSessionBuilder::default();
         for event in events.iter() {
-            if let SigningSessionEvent::Initialized {
-                id,
-                account_id,
-                batch_id,
-                unsigned_psbt,
-                xpub_id,
-            } = event
-            {
-                builder = builder
-                    .id(*id)
-                    .account_id(*account_id)
-                    .batch_id(*batch_id)
-                    .xpub_id(*xpub_id)
-                    .unsigned_psbt(unsigned_psbt.clone());
+            match event {
+                SigningSessionEvent::Initialized {
+                    id,
+                    account_id,
+                    batch_id,
+                    unsigned_psbt,
+                    xpub_id,
+                    spending_path,
+                } => {
+                    builder = builder
+                        .id(*id)
+                        .account_id(*account_id)
+                        .batch_id(*batch_id)
+                        .xpub_id(*xpub_id)
+                        .unsigned_psbt(unsigned_psbt.clone())
+                        .spending_path(*spending_path);
+                }
+                SigningSessionEvent::UnsignedPsbtReplaced { unsigned_psbt } => {
+                    builder = builder.unsigned_psbt(unsigned_psbt.clone());
+                }
+                _ => (),
             }
         }
         builder.events(events).build()
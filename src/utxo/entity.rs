@@ -15,6 +15,34 @@ pub struct WalletUtxo {
     pub utxo_detected_ledger_tx_id: LedgerTransactionId,
     pub utxo_settled_ledger_tx_id: Option<LedgerTransactionId>,
     pub spending_batch_id: Option<BatchId>,
+    pub frozen: Option<UtxoFreeze>,
+    pub dust: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct UtxoFreeze {
+    pub reason: String,
+    pub profile_id: Option<ProfileId>,
+    pub frozen_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Debug)]
+pub struct FrozenUtxo {
+    pub wallet_id: WalletId,
+    pub keychain_id: KeychainId,
+    pub outpoint: OutPoint,
+    pub value: Satoshis,
+    pub freeze: UtxoFreeze,
+}
+
+#[derive(Debug)]
+pub struct ReleasedUtxo {
+    pub wallet_id: WalletId,
+    pub keychain_id: KeychainId,
+    pub outpoint: OutPoint,
+    pub value: Satoshis,
+    /// Whether the income was already settled into the quarantined ledger account
+    pub settled: bool,
 }
 
 #[derive(Debug)]
@@ -25,6 +53,8 @@ pub struct SettledUtxo {
     pub utxo_detected_ledger_tx_id: LedgerTransactionId,
     pub utxo_settled_ledger_tx_id: LedgerTransactionId,
     pub spend_detected_ledger_tx_id: Option<LedgerTransactionId>,
+    pub quarantine_sources: Option<Vec<bitcoin::Address>>,
+    pub dust: bool,
 }
 
 #[derive(Debug)]
@@ -10,4 +10,21 @@ pub enum UtxoError {
     UtxoAlreadySettledError,
     #[error("UtxoError - Utxo does not exist")]
     UtxoDoesNotExistError,
+    #[error("UtxoError - Utxo {0} is already frozen")]
+    UtxoAlreadyFrozen(crate::primitives::bitcoin::OutPoint),
+    #[error("UtxoError - Utxo {0} is not frozen")]
+    UtxoNotFrozen(crate::primitives::bitcoin::OutPoint),
+    #[error("UtxoError - Utxo {0} is quarantined and can only be released explicitly")]
+    UtxoQuarantined(crate::primitives::bitcoin::OutPoint),
+    #[error("UtxoError - Utxo {0} is not quarantined")]
+    UtxoNotQuarantined(crate::primitives::bitcoin::OutPoint),
+    #[error("UtxoError - Utxo {0} has already been spent")]
+    UtxoAlreadySpent(crate::primitives::bitcoin::OutPoint),
+    #[error("UtxoError - Utxo {0} is already reserved in batch {1}")]
+    UtxoReservedInBatch(
+        crate::primitives::bitcoin::OutPoint,
+        crate::primitives::BatchId,
+    ),
+    #[error("UtxoError - Change of batch {0} is already being spent")]
+    ChangeOfReplacedBatchAlreadySpent(crate::primitives::BatchId),
 }
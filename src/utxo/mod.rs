@@ -190,6 +190,86 @@ impl Utxos {
         self.utxos.find_keychain_utxos(keychain_ids).await
     }
 
+    #[instrument(name = "utxos.freeze_utxo", skip(self, keychain_ids), err)]
+    pub async fn freeze_utxo(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        keychain_ids: impl Iterator<Item = KeychainId>,
+        outpoint: OutPoint,
+        profile_id: ProfileId,
+        reason: String,
+    ) -> Result<FrozenUtxo, UtxoError> {
+        self.utxos
+            .freeze_utxo(tx, keychain_ids, outpoint, profile_id, reason)
+            .await
+    }
+
+    #[instrument(name = "utxos.unfreeze_utxo", skip(self, keychain_ids), err)]
+    pub async fn unfreeze_utxo(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        keychain_ids: impl Iterator<Item = KeychainId>,
+        outpoint: OutPoint,
+    ) -> Result<FrozenUtxo, UtxoError> {
+        self.utxos.unfreeze_utxo(tx, keychain_ids, outpoint).await
+    }
+
+    /// Lifts the quarantine of a utxo that was wrongly attributed to a blocked source.
+    #[instrument(name = "utxos.release_quarantine", skip(self, keychain_ids), err)]
+    pub async fn release_quarantine(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        keychain_ids: impl Iterator<Item = KeychainId>,
+        outpoint: OutPoint,
+    ) -> Result<ReleasedUtxo, UtxoError> {
+        self.utxos
+            .release_quarantine(tx, keychain_ids, outpoint)
+            .await
+    }
+
+    #[instrument(name = "utxos.quarantine_utxo", skip(self, tx), err)]
+    pub async fn quarantine_utxo(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        keychain_id: KeychainId,
+        outpoint: OutPoint,
+        sources: &[bitcoin::Address],
+    ) -> Result<(), UtxoError> {
+        let reason = format!(
+            "Quarantined: received from blocked address(es) {}",
+            sources
+                .iter()
+                .map(|addr| addr.to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+        self.utxos
+            .quarantine_utxo(tx, keychain_id, outpoint, reason, sources)
+            .await
+    }
+
+    #[instrument(name = "utxos.mark_as_dust", skip(self, tx), err)]
+    pub async fn mark_as_dust(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        keychain_id: KeychainId,
+        outpoint: OutPoint,
+    ) -> Result<(), UtxoError> {
+        self.utxos.mark_as_dust(tx, keychain_id, outpoint).await
+    }
+
+    /// Makes a settled dust utxo selectable again so that it can be swept.
+    /// Returns false if the utxo is not (or no longer) eligible.
+    #[instrument(name = "utxos.release_dust", skip(self, tx), err)]
+    pub async fn release_dust(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        keychain_id: KeychainId,
+        outpoint: OutPoint,
+    ) -> Result<bool, UtxoError> {
+        self.utxos.release_dust(tx, keychain_id, outpoint).await
+    }
+
     #[instrument(name = "utxos.find_cpfp_utxos", skip_all, err)]
     pub async fn find_cpfp_utxos(
         &self,
@@ -219,10 +299,16 @@ impl Utxos {
 
         // We need to tell bdk which utxos not to select.
         // If we have included it in a batch OR
-        // it isn't confirmed / settled yet
+        // it isn't confirmed / settled yet OR
+        // it has been frozen manually OR
+        // it is dust that hasn't been released for sweeping
         // we need to flag it to bdk
         let filtered_utxos = reservable_utxos.into_iter().filter_map(|utxo| {
-            if utxo.spending_batch_id.is_some() || utxo.utxo_settled_ledger_tx_id.is_none() {
+            if utxo.spending_batch_id.is_some()
+                || utxo.utxo_settled_ledger_tx_id.is_none()
+                || utxo.frozen
+                || utxo.dust
+            {
                 Some((utxo.keychain_id, utxo.outpoint))
             } else {
                 None
@@ -255,6 +341,30 @@ impl Utxos {
             .await
     }
 
+    /// Whether the change created by the batch transaction is already spent,
+    /// in which case the transaction can no longer be replaced.
+    #[instrument(name = "utxos.is_change_of_batch_spent", skip(self), err)]
+    pub async fn is_change_of_batch_spent(
+        &self,
+        batch_id: BatchId,
+        tx_id: bitcoin::Txid,
+    ) -> Result<bool, UtxoError> {
+        self.utxos.is_change_of_batch_spent(batch_id, tx_id).await
+    }
+
+    #[instrument(name = "utxos.release_spent_in_replaced_batch", skip(self, tx), err)]
+    pub async fn release_spent_in_replaced_batch(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        batch_id: BatchId,
+        replaced_tx_id: bitcoin::Txid,
+        fee_rate: bitcoin::FeeRate,
+    ) -> Result<(), UtxoError> {
+        self.utxos
+            .release_spent_in_replaced_batch(tx, batch_id, replaced_tx_id, fee_rate)
+            .await
+    }
+
     pub async fn average_utxo_value(
         &self,
         wallet_id: WalletId,
@@ -263,6 +373,16 @@ impl Utxos {
         self.utxos.average_utxo_value(wallet_id, queue_id).await
     }
 
+    /// Total value of the utxos that are not excluded from coin selection
+    /// by [`Self::outpoints_bdk_should_not_select`]
+    #[instrument(name = "utxos.spendable_value", skip_all, err)]
+    pub async fn spendable_value(
+        &self,
+        ids: impl Iterator<Item = KeychainId>,
+    ) -> Result<Satoshis, UtxoError> {
+        self.utxos.spendable_value(ids).await
+    }
+
     #[instrument(name = "utxos.accounting_info_for_batch", skip_all, err)]
     pub async fn accounting_info_for_batch(
         &self,
@@ -12,6 +12,8 @@ pub struct ReservableUtxo {
     pub outpoint: OutPoint,
     pub spending_batch_id: Option<BatchId>,
     pub utxo_settled_ledger_tx_id: Option<LedgerTransactionId>,
+    pub frozen: bool,
+    pub dust: bool,
 }
 
 #[derive(Clone)]
@@ -89,7 +91,7 @@ impl UtxoRepo {
             WHERE keychain_id = $4
               AND tx_id = $5
               AND vout = $6
-            RETURNING address_idx, value, address, income_detected_ledger_tx_id, spend_detected_ledger_tx_id"#,
+            RETURNING address_idx, value, address, income_detected_ledger_tx_id, spend_detected_ledger_tx_id, quarantine_sources, dust"#,
             bdk_spent,
             block_height as i32,
             new_confirmed_ledger_tx_id as LedgerTransactionId,
@@ -109,9 +111,80 @@ impl UtxoRepo {
             spend_detected_ledger_tx_id: row
                 .spend_detected_ledger_tx_id
                 .map(LedgerTransactionId::from),
+            quarantine_sources: row.quarantine_sources.map(|sources| {
+                sources
+                    .into_iter()
+                    .map(|addr| addr.parse().expect("couldn't parse address"))
+                    .collect()
+            }),
+            dust: row.dust,
         })
     }
 
+    pub async fn mark_as_dust(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        keychain_id: KeychainId,
+        outpoint: OutPoint,
+    ) -> Result<(), UtxoError> {
+        sqlx::query!(
+            r#"UPDATE bria_utxos
+               SET dust = true, modified_at = NOW()
+               WHERE keychain_id = $1 AND tx_id = $2 AND vout = $3"#,
+            keychain_id as KeychainId,
+            outpoint.txid.to_string(),
+            outpoint.vout as i32,
+        )
+        .execute(&mut **tx)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn release_dust(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        keychain_id: KeychainId,
+        outpoint: OutPoint,
+    ) -> Result<bool, UtxoError> {
+        let result = sqlx::query!(
+            r#"UPDATE bria_utxos
+               SET dust = false, modified_at = NOW()
+               WHERE keychain_id = $1 AND tx_id = $2 AND vout = $3 AND dust = true
+                 AND income_settled_ledger_tx_id IS NOT NULL AND spending_batch_id IS NULL
+                 AND frozen_at IS NULL AND bdk_spent = false"#,
+            keychain_id as KeychainId,
+            outpoint.txid.to_string(),
+            outpoint.vout as i32,
+        )
+        .execute(&mut **tx)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    pub async fn quarantine_utxo(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        keychain_id: KeychainId,
+        outpoint: OutPoint,
+        reason: String,
+        sources: &[bitcoin::Address],
+    ) -> Result<(), UtxoError> {
+        let sources: Vec<String> = sources.iter().map(|addr| addr.to_string()).collect();
+        sqlx::query!(
+            r#"UPDATE bria_utxos
+               SET frozen_at = NOW(), frozen_reason = $4, quarantine_sources = $5, modified_at = NOW()
+               WHERE keychain_id = $1 AND tx_id = $2 AND vout = $3"#,
+            keychain_id as KeychainId,
+            outpoint.txid.to_string(),
+            outpoint.vout as i32,
+            reason,
+            &sources,
+        )
+        .execute(&mut **tx)
+        .await?;
+        Ok(())
+    }
+
     pub async fn mark_spent(
         &self,
         tx: &mut Transaction<'_, Postgres>,
@@ -205,7 +278,8 @@ impl UtxoRepo {
                       WHEN kind = 'external' THEN address
                       ELSE NULL
                   END as optional_address,
-                  block_height, income_detected_ledger_tx_id, income_settled_ledger_tx_id, spending_batch_id
+                  block_height, income_detected_ledger_tx_id, income_settled_ledger_tx_id, spending_batch_id,
+                  frozen_at, frozen_reason, frozen_by_profile_id, dust
            FROM bria_utxos
            WHERE keychain_id = ANY($1) AND bdk_spent = false
            ORDER BY created_at DESC"#,
@@ -239,6 +313,8 @@ impl UtxoRepo {
                     .income_settled_ledger_tx_id
                     .map(LedgerTransactionId::from),
                 spending_batch_id: row.spending_batch_id.map(BatchId::from),
+                frozen: utxo_freeze(row.frozen_at, row.frozen_reason, row.frozen_by_profile_id),
+                dust: row.dust,
             };
 
             let keychain_id = KeychainId::from(row.keychain_id);
@@ -264,7 +340,8 @@ impl UtxoRepo {
         let rows = sqlx::query!(
             r#"SELECT keychain_id,
                CASE WHEN kind = 'external' THEN true ELSE false END as income_address,
-               tx_id, vout, spending_batch_id, income_settled_ledger_tx_id
+               tx_id, vout, spending_batch_id, income_settled_ledger_tx_id,
+               frozen_at IS NOT NULL as "frozen!", dust
                FROM bria_utxos
                WHERE keychain_id = ANY($1) AND bdk_spent = false
                FOR UPDATE"#,
@@ -286,6 +363,8 @@ impl UtxoRepo {
                 utxo_settled_ledger_tx_id: row
                     .income_settled_ledger_tx_id
                     .map(LedgerTransactionId::from),
+                frozen: row.frozen,
+                dust: row.dust,
             })
             .collect();
 
@@ -329,6 +408,60 @@ impl UtxoRepo {
         Ok(())
     }
 
+    pub async fn is_change_of_batch_spent(
+        &self,
+        batch_id: BatchId,
+        tx_id: bitcoin::Txid,
+    ) -> Result<bool, UtxoError> {
+        let row = sqlx::query!(
+            r#"SELECT EXISTS (
+                 SELECT 1 FROM bria_utxos
+                 WHERE origin_tx_batch_id = $1 AND tx_id = $2
+                   AND (spending_batch_id IS NOT NULL OR spend_detected_ledger_tx_id IS NOT NULL)
+               ) AS "spent!""#,
+            batch_id as BatchId,
+            tx_id.to_string(),
+        )
+        .fetch_one(&self.pool)
+        .await?;
+        Ok(row.spent)
+    }
+
+    pub async fn release_spent_in_replaced_batch(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        batch_id: BatchId,
+        replaced_tx_id: bitcoin::Txid,
+        fee_rate: bitcoin::FeeRate,
+    ) -> Result<(), UtxoError> {
+        let change = sqlx::query!(
+            r#"DELETE FROM bria_utxos
+               WHERE origin_tx_batch_id = $1 AND tx_id = $2
+               RETURNING spending_batch_id, spend_detected_ledger_tx_id"#,
+            batch_id as BatchId,
+            replaced_tx_id.to_string(),
+        )
+        .fetch_all(&mut **tx)
+        .await?;
+        if change
+            .iter()
+            .any(|row| row.spending_batch_id.is_some() || row.spend_detected_ledger_tx_id.is_some())
+        {
+            return Err(UtxoError::ChangeOfReplacedBatchAlreadySpent(batch_id));
+        }
+
+        sqlx::query!(
+            r#"UPDATE bria_utxos
+               SET spend_detected_ledger_tx_id = NULL, spending_sats_per_vbyte = $2, modified_at = NOW()
+               WHERE spending_batch_id = $1"#,
+            batch_id as BatchId,
+            fee_rate.as_sat_per_vb(),
+        )
+        .execute(&mut **tx)
+        .await?;
+        Ok(())
+    }
+
     pub async fn accounting_info_for_batch(
         &self,
         batch_id: BatchId,
@@ -377,7 +510,8 @@ impl UtxoRepo {
                       WHEN kind = 'external' THEN address
                       ELSE NULL
                   END as optional_address,
-                  block_height, income_detected_ledger_tx_id, income_settled_ledger_tx_id, spending_batch_id
+                  block_height, income_detected_ledger_tx_id, income_settled_ledger_tx_id, spending_batch_id,
+                  frozen_at, frozen_reason, frozen_by_profile_id, dust
             FROM bria_utxos
             WHERE (keychain_id, tx_id, vout) IN"#,
         );
@@ -429,6 +563,12 @@ impl UtxoRepo {
                     .get::<Option<Uuid>, _>("spending_batch_id")
                     .map(BatchId::from),
                 block_height: row.get::<Option<i32>, _>("block_height").map(|h| h as u32),
+                frozen: utxo_freeze(
+                    row.get("frozen_at"),
+                    row.get("frozen_reason"),
+                    row.get("frozen_by_profile_id"),
+                ),
+                dust: row.get("dust"),
             })
             .collect())
     }
@@ -452,6 +592,25 @@ impl UtxoRepo {
         Ok(row.and_then(|res| res.avg_value.map(Satoshis::from)))
     }
 
+    pub async fn spendable_value(
+        &self,
+        ids: impl Iterator<Item = KeychainId>,
+    ) -> Result<Satoshis, UtxoError> {
+        let uuids = ids.into_iter().map(Uuid::from).collect::<Vec<_>>();
+        let row = sqlx::query!(
+            r#"SELECT COALESCE(SUM(value), 0) as "value!"
+               FROM bria_utxos
+               WHERE keychain_id = ANY($1) AND bdk_spent = false
+               AND spending_batch_id IS NULL AND income_settled_ledger_tx_id IS NOT NULL
+               AND frozen_at IS NULL AND dust = false"#,
+            &uuids[..]
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(Satoshis::from(row.value))
+    }
+
     pub async fn delete_utxo(
         &self,
         tx: &mut Transaction<'_, Postgres>,
@@ -477,6 +636,162 @@ impl UtxoRepo {
         }
     }
 
+    pub async fn freeze_utxo(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        ids: impl Iterator<Item = KeychainId>,
+        outpoint: OutPoint,
+        profile_id: ProfileId,
+        reason: String,
+    ) -> Result<FrozenUtxo, UtxoError> {
+        let keychain_ids: Vec<Uuid> = ids.map(Uuid::from).collect();
+        let row = sqlx::query!(
+            r#"SELECT wallet_id, keychain_id, value, spending_batch_id, frozen_at IS NOT NULL as "frozen!"
+               FROM bria_utxos
+               WHERE keychain_id = ANY($1) AND tx_id = $2 AND vout = $3 AND bdk_spent = false
+               FOR UPDATE"#,
+            &keychain_ids,
+            outpoint.txid.to_string(),
+            outpoint.vout as i32,
+        )
+        .fetch_optional(&mut **tx)
+        .await?
+        .ok_or(UtxoError::UtxoDoesNotExistError)?;
+        if row.frozen {
+            return Err(UtxoError::UtxoAlreadyFrozen(outpoint));
+        }
+        if let Some(batch_id) = row.spending_batch_id {
+            return Err(UtxoError::UtxoReservedInBatch(
+                outpoint,
+                BatchId::from(batch_id),
+            ));
+        }
+
+        let frozen_at = sqlx::query!(
+            r#"UPDATE bria_utxos
+               SET frozen_at = NOW(), frozen_reason = $4, frozen_by_profile_id = $5, modified_at = NOW()
+               WHERE keychain_id = $1 AND tx_id = $2 AND vout = $3
+               RETURNING frozen_at as "frozen_at!""#,
+            row.keychain_id,
+            outpoint.txid.to_string(),
+            outpoint.vout as i32,
+            reason,
+            profile_id as ProfileId,
+        )
+        .fetch_one(&mut **tx)
+        .await?
+        .frozen_at;
+
+        Ok(FrozenUtxo {
+            wallet_id: WalletId::from(row.wallet_id),
+            keychain_id: KeychainId::from(row.keychain_id),
+            outpoint,
+            value: Satoshis::from(row.value),
+            freeze: UtxoFreeze {
+                reason,
+                profile_id: Some(profile_id),
+                frozen_at,
+            },
+        })
+    }
+
+    pub async fn unfreeze_utxo(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        ids: impl Iterator<Item = KeychainId>,
+        outpoint: OutPoint,
+    ) -> Result<FrozenUtxo, UtxoError> {
+        let keychain_ids: Vec<Uuid> = ids.map(Uuid::from).collect();
+        let row = sqlx::query!(
+            r#"SELECT wallet_id, keychain_id, value, frozen_at, frozen_reason, frozen_by_profile_id,
+                      quarantine_sources IS NOT NULL as "quarantined!"
+               FROM bria_utxos
+               WHERE keychain_id = ANY($1) AND tx_id = $2 AND vout = $3
+               FOR UPDATE"#,
+            &keychain_ids,
+            outpoint.txid.to_string(),
+            outpoint.vout as i32,
+        )
+        .fetch_optional(&mut **tx)
+        .await?
+        .ok_or(UtxoError::UtxoDoesNotExistError)?;
+        // Funds received from blocked addresses are only released via release_quarantine
+        if row.quarantined {
+            return Err(UtxoError::UtxoQuarantined(outpoint));
+        }
+        let freeze = utxo_freeze(row.frozen_at, row.frozen_reason, row.frozen_by_profile_id)
+            .ok_or(UtxoError::UtxoNotFrozen(outpoint))?;
+
+        sqlx::query!(
+            r#"UPDATE bria_utxos
+               SET frozen_at = NULL, frozen_reason = NULL, frozen_by_profile_id = NULL, modified_at = NOW()
+               WHERE keychain_id = $1 AND tx_id = $2 AND vout = $3"#,
+            row.keychain_id,
+            outpoint.txid.to_string(),
+            outpoint.vout as i32,
+        )
+        .execute(&mut **tx)
+        .await?;
+
+        Ok(FrozenUtxo {
+            wallet_id: WalletId::from(row.wallet_id),
+            keychain_id: KeychainId::from(row.keychain_id),
+            outpoint,
+            value: Satoshis::from(row.value),
+            freeze,
+        })
+    }
+
+    pub async fn release_quarantine(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        ids: impl Iterator<Item = KeychainId>,
+        outpoint: OutPoint,
+    ) -> Result<ReleasedUtxo, UtxoError> {
+        let keychain_ids: Vec<Uuid> = ids.map(Uuid::from).collect();
+        let row = sqlx::query!(
+            r#"SELECT wallet_id, keychain_id, value,
+                      quarantine_sources IS NOT NULL as "quarantined!",
+                      income_settled_ledger_tx_id IS NOT NULL as "settled!",
+                      (bdk_spent OR spend_detected_ledger_tx_id IS NOT NULL) as "spent!"
+               FROM bria_utxos
+               WHERE keychain_id = ANY($1) AND tx_id = $2 AND vout = $3
+               FOR UPDATE"#,
+            &keychain_ids,
+            outpoint.txid.to_string(),
+            outpoint.vout as i32,
+        )
+        .fetch_optional(&mut **tx)
+        .await?
+        .ok_or(UtxoError::UtxoDoesNotExistError)?;
+        if !row.quarantined {
+            return Err(UtxoError::UtxoNotQuarantined(outpoint));
+        }
+        if row.spent {
+            return Err(UtxoError::UtxoAlreadySpent(outpoint));
+        }
+
+        sqlx::query!(
+            r#"UPDATE bria_utxos
+               SET frozen_at = NULL, frozen_reason = NULL, frozen_by_profile_id = NULL,
+                   quarantine_sources = NULL, modified_at = NOW()
+               WHERE keychain_id = $1 AND tx_id = $2 AND vout = $3"#,
+            row.keychain_id,
+            outpoint.txid.to_string(),
+            outpoint.vout as i32,
+        )
+        .execute(&mut **tx)
+        .await?;
+
+        Ok(ReleasedUtxo {
+            wallet_id: WalletId::from(row.wallet_id),
+            keychain_id: KeychainId::from(row.keychain_id),
+            outpoint,
+            value: Satoshis::from(row.value),
+            settled: row.settled,
+        })
+    }
+
     pub async fn find_cpfp_candidates(
         &self,
         tx: &mut Transaction<'_, Postgres>,
@@ -511,6 +826,7 @@ impl UtxoRepo {
                       AND bdk_spent IS FALSE
                       AND spend_detected_ledger_tx_id IS NULL
                       AND income_settled_ledger_tx_id IS NULL
+                      AND u1.frozen_at IS NULL
                       AND u1.trusted_origin_tx_input_tx_ids IS NOT NULL
                       AND array_length(u1.trusted_origin_tx_input_tx_ids, 1) > 0
                   FOR UPDATE
@@ -571,3 +887,18 @@ impl UtxoRepo {
         Ok(candidates)
     }
 }
+
+fn utxo_freeze(
+    frozen_at: Option<chrono::DateTime<chrono::Utc>>,
+    reason: Option<String>,
+    profile_id: Option<Uuid>,
+) -> Option<UtxoFreeze> {
+    match (frozen_at, reason) {
+        (Some(frozen_at), Some(reason)) => Some(UtxoFreeze {
+            reason,
+            profile_id: profile_id.map(ProfileId::from),
+            frozen_at,
+        }),
+        _ => None,
+    }
+}
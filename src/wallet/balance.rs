@@ -14,6 +14,8 @@ pub struct WalletBalanceSummary {
     pub effective_pending_income: Satoshis,
     pub effective_pending_outgoing: Satoshis,
     pub effective_encumbered_outgoing: Satoshis,
+    pub effective_quarantined: Satoshis,
+    pub effective_dust: Satoshis,
 }
 
 impl From<WalletLedgerAccountBalances> for WalletBalanceSummary {
@@ -83,6 +85,15 @@ impl From<WalletLedgerAccountBalances> for WalletBalanceSummary {
                     .map(|b| b.encumbered())
                     .unwrap_or(Decimal::ZERO),
             ),
+            effective_quarantined: Satoshis::from_btc(
+                balances
+                    .quarantined
+                    .map(|b| b.settled())
+                    .unwrap_or(Decimal::ZERO),
+            ),
+            effective_dust: Satoshis::from_btc(
+                balances.dust.map(|b| b.settled()).unwrap_or(Decimal::ZERO),
+            ),
         }
     }
 }
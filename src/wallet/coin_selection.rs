@@ -0,0 +1,282 @@
+use bdk::{
+    database::Database,
+    wallet::coin_selection::{
+        BranchAndBoundCoinSelection, CoinSelectionAlgorithm, CoinSelectionResult,
+        LargestFirstCoinSelection, OldestFirstCoinSelection,
+    },
+    FeeRate, WeightedUtxo,
+};
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, str::FromStr};
+
+use crate::primitives::bitcoin::Script;
+
+/// How the inputs of a payout batch are chosen from the spendable utxos of a wallet.
+#[derive(Deserialize, Serialize, PartialEq, Eq, Clone, Copy, Debug, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum CoinSelectionStrategy {
+    /// Searches for an input set that doesn't require a change output,
+    /// falls back to a random draw if none exists
+    #[default]
+    BranchAndBound,
+    LargestFirst,
+    OldestFirst,
+    /// Spends all utxos of an address together and avoids
+    /// combining utxos of different addresses whenever possible
+    Privacy,
+}
+
+impl std::fmt::Display for CoinSelectionStrategy {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::BranchAndBound => write!(f, "branch-and-bound"),
+            Self::LargestFirst => write!(f, "largest-first"),
+            Self::OldestFirst => write!(f, "oldest-first"),
+            Self::Privacy => write!(f, "privacy"),
+        }
+    }
+}
+
+impl FromStr for CoinSelectionStrategy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "branch-and-bound" | "branch_and_bound" => Ok(Self::BranchAndBound),
+            "largest-first" | "largest_first" => Ok(Self::LargestFirst),
+            "oldest-first" | "oldest_first" => Ok(Self::OldestFirst),
+            "privacy" => Ok(Self::Privacy),
+            _ => Err(format!(
+                "invalid coin selection '{s}': expected one of branch-and-bound, largest-first, oldest-first or privacy"
+            )),
+        }
+    }
+}
+
+/// Dispatches to the coin selection algorithm of the configured strategy
+/// so that a single `TxBuilder` type can serve every strategy.
+#[derive(Debug, Clone, Copy)]
+pub struct CoinSelector(pub CoinSelectionStrategy);
+
+impl<D: Database> CoinSelectionAlgorithm<D> for CoinSelector {
+    fn coin_select(
+        &self,
+        database: &D,
+        required_utxos: Vec<WeightedUtxo>,
+        optional_utxos: Vec<WeightedUtxo>,
+        fee_rate: FeeRate,
+        target_amount: u64,
+        drain_script: &Script,
+    ) -> Result<CoinSelectionResult, bdk::Error> {
+        match self.0 {
+            CoinSelectionStrategy::BranchAndBound => BranchAndBoundCoinSelection::default()
+                .coin_select(
+                    database,
+                    required_utxos,
+                    optional_utxos,
+                    fee_rate,
+                    target_amount,
+                    drain_script,
+                ),
+            CoinSelectionStrategy::LargestFirst => LargestFirstCoinSelection.coin_select(
+                database,
+                required_utxos,
+                optional_utxos,
+                fee_rate,
+                target_amount,
+                drain_script,
+            ),
+            CoinSelectionStrategy::OldestFirst => OldestFirstCoinSelection.coin_select(
+                database,
+                required_utxos,
+                optional_utxos,
+                fee_rate,
+                target_amount,
+                drain_script,
+            ),
+            CoinSelectionStrategy::Privacy => select_by_address_cluster(
+                database,
+                required_utxos,
+                optional_utxos,
+                fee_rate,
+                target_amount,
+                drain_script,
+            ),
+        }
+    }
+}
+
+/// Groups the optional utxos by address and spends whole groups.
+/// Prefers the smallest single group that covers the target,
+/// otherwise adds the largest groups until the target is reached.
+fn select_by_address_cluster<D: Database>(
+    database: &D,
+    required_utxos: Vec<WeightedUtxo>,
+    optional_utxos: Vec<WeightedUtxo>,
+    fee_rate: FeeRate,
+    target_amount: u64,
+    drain_script: &Script,
+) -> Result<CoinSelectionResult, bdk::Error> {
+    let mut clusters: HashMap<Script, Vec<WeightedUtxo>> = HashMap::new();
+    for utxo in optional_utxos {
+        clusters
+            .entry(utxo.utxo.txout().script_pubkey.clone())
+            .or_default()
+            .push(utxo);
+    }
+    let mut clusters: Vec<_> = clusters.into_values().collect();
+    clusters.sort_by_cached_key(|cluster| {
+        cluster
+            .iter()
+            .map(|utxo| utxo.utxo.txout().value)
+            .sum::<u64>()
+    });
+
+    let select = |utxos: Vec<WeightedUtxo>| {
+        LargestFirstCoinSelection.coin_select(
+            database,
+            utxos,
+            Vec::new(),
+            fee_rate,
+            target_amount,
+            drain_script,
+        )
+    };
+
+    for cluster in clusters.iter() {
+        let utxos = required_utxos.iter().chain(cluster).cloned().collect();
+        if let Ok(result) = select(utxos) {
+            return Ok(result);
+        }
+    }
+
+    let mut utxos = required_utxos;
+    let mut result = select(utxos.clone());
+    while result.is_err() {
+        match clusters.pop() {
+            Some(cluster) => {
+                utxos.extend(cluster);
+                result = select(utxos.clone());
+            }
+            None => break,
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use bdk::{database::MemoryDatabase, KeychainKind, LocalUtxo, Utxo};
+
+    use super::*;
+    use crate::primitives::bitcoin::{OutPoint, TxOut};
+
+    fn utxo(script: &Script, vout: u32, value: u64) -> WeightedUtxo {
+        WeightedUtxo {
+            satisfaction_weight: 107,
+            utxo: Utxo::Local(LocalUtxo {
+                outpoint: OutPoint {
+                    txid: "4010e27ff7dc6d9c66a5657e6b3d94b4c4e394d968398d16fefe4637463d194d"
+                        .parse()
+                        .unwrap(),
+                    vout,
+                },
+                txout: TxOut {
+                    value,
+                    script_pubkey: script.clone(),
+                },
+                keychain: KeychainKind::External,
+                is_spent: false,
+            }),
+        }
+    }
+
+    fn script(byte: u8) -> Script {
+        Script::from(
+            vec![0x00, 0x14]
+                .into_iter()
+                .chain([byte; 20])
+                .collect::<Vec<_>>(),
+        )
+    }
+
+    fn selected_vouts(result: &CoinSelectionResult) -> Vec<u32> {
+        let mut vouts: Vec<_> = result.selected.iter().map(|u| u.outpoint().vout).collect();
+        vouts.sort();
+        vouts
+    }
+
+    #[test]
+    fn privacy_spends_single_address_cluster() {
+        let (a, b, c) = (script(1), script(2), script(3));
+        let optional = vec![
+            utxo(&a, 0, 60_000),
+            utxo(&b, 1, 30_000),
+            utxo(&b, 2, 40_000),
+            utxo(&c, 3, 200_000),
+        ];
+        let result = CoinSelector(CoinSelectionStrategy::Privacy)
+            .coin_select(
+                &MemoryDatabase::default(),
+                vec![],
+                optional,
+                FeeRate::from_sat_per_vb(1.0),
+                50_000,
+                &script(4),
+            )
+            .unwrap();
+        assert_eq!(selected_vouts(&result), vec![0]);
+
+        let optional = vec![
+            utxo(&a, 0, 60_000),
+            utxo(&b, 1, 30_000),
+            utxo(&b, 2, 40_000),
+            utxo(&c, 3, 200_000),
+        ];
+        let result = CoinSelector(CoinSelectionStrategy::Privacy)
+            .coin_select(
+                &MemoryDatabase::default(),
+                vec![],
+                optional,
+                FeeRate::from_sat_per_vb(1.0),
+                65_000,
+                &script(4),
+            )
+            .unwrap();
+        assert_eq!(selected_vouts(&result), vec![1, 2]);
+    }
+
+    #[test]
+    fn privacy_combines_largest_clusters_when_needed() {
+        let (a, b, c) = (script(1), script(2), script(3));
+        let optional = vec![
+            utxo(&a, 0, 10_000),
+            utxo(&b, 1, 50_000),
+            utxo(&c, 2, 60_000),
+        ];
+        let result = CoinSelector(CoinSelectionStrategy::Privacy)
+            .coin_select(
+                &MemoryDatabase::default(),
+                vec![],
+                optional,
+                FeeRate::from_sat_per_vb(1.0),
+                100_000,
+                &script(4),
+            )
+            .unwrap();
+        assert_eq!(selected_vouts(&result), vec![1, 2]);
+    }
+
+    #[test]
+    fn strategy_from_str() {
+        for strategy in [
+            CoinSelectionStrategy::BranchAndBound,
+            CoinSelectionStrategy::LargestFirst,
+            CoinSelectionStrategy::OldestFirst,
+            CoinSelectionStrategy::Privacy,
+        ] {
+            assert_eq!(strategy.to_string().parse(), Ok(strategy));
+        }
+        assert!("random".parse::<CoinSelectionStrategy>().is_err());
+    }
+}
@@ -1,12 +1,96 @@
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+use super::error::WalletError;
+use crate::primitives::{bitcoin, PayoutQueueId, Satoshis};
+
+/// Every sync derives and stores `lookahead` scripts per keychain kind
+const MAX_LOOKAHEAD: u32 = 10_000;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct WalletConfig {
     pub settle_income_after_n_confs: u32,
     pub settle_change_after_n_confs: u32,
+    /// Number of consecutive unused addresses after which sync stops looking for transactions
+    #[serde(default = "default_gap_limit")]
+    pub gap_limit: u32,
+    /// Number of addresses past the last handed out one to derive before each sync
+    #[serde(default = "default_lookahead")]
+    pub lookahead: u32,
+    #[serde(default)]
+    pub consolidation: Option<ConsolidationPolicy>,
+    /// Income utxos below this value are accounted as dust and excluded from coin selection (0 disables)
+    #[serde(default)]
+    pub dust_threshold_sats: u64,
+    /// Dust is swept in a dust only transaction while fees are at or below this rate (0 disables).
+    /// Sweeping is done by the consolidation job and requires a `consolidation` policy
+    /// whose payout queue the sweep batches are attributed to.
+    #[serde(default)]
+    pub dust_sweep_max_fee_rate_sat_per_vb: u64,
+}
+
+/// Periodically merges the smallest spendable utxos of the current keychain
+/// while fees are cheap. The resulting batches are attributed to `payout_queue_id`
+/// whose `tx_priority` and `spending_path` are used to build and sign them.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConsolidationPolicy {
+    pub payout_queue_id: PayoutQueueId,
+    /// Consolidate while the wallet has more spendable utxos than this
+    pub target_utxo_count: u32,
+    pub max_fee_rate_sat_per_vb: u64,
+    pub max_inputs_per_tx: u32,
+}
+
+impl ConsolidationPolicy {
+    pub fn validate(&self) -> Result<(), WalletError> {
+        if self.max_inputs_per_tx < 2 {
+            return Err(WalletError::InvalidConfig(
+                "max_inputs_per_tx must be at least 2".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    pub fn fee_rate_exceeds_max(&self, fee_rate: bitcoin::FeeRate) -> bool {
+        fee_rate.as_sat_per_vb() > self.max_fee_rate_sat_per_vb as f32
+    }
+
+    /// Number of utxos to merge into a single output to get (closer) to the target
+    pub fn n_inputs_to_consolidate(&self, n_spendable_utxos: usize) -> Option<usize> {
+        let target = self.target_utxo_count as usize;
+        if n_spendable_utxos <= target.max(1) {
+            return None;
+        }
+        Some((n_spendable_utxos - target.max(1) + 1).min(self.max_inputs_per_tx as usize))
+    }
 }
 
 impl WalletConfig {
+    pub fn validate(&self) -> Result<(), WalletError> {
+        if self.gap_limit == 0 {
+            return Err(WalletError::InvalidConfig(
+                "gap_limit must be greater than 0".to_string(),
+            ));
+        }
+        if self.lookahead > MAX_LOOKAHEAD {
+            return Err(WalletError::InvalidConfig(format!(
+                "lookahead must not exceed {MAX_LOOKAHEAD}"
+            )));
+        }
+        if let Some(policy) = self.consolidation.as_ref() {
+            policy.validate()?;
+        }
+        Ok(())
+    }
+
+    pub fn is_dust(&self, value: Satoshis) -> bool {
+        value < Satoshis::from(self.dust_threshold_sats)
+    }
+
+    pub fn should_sweep_dust(&self, fee_rate: bitcoin::FeeRate) -> bool {
+        self.dust_sweep_max_fee_rate_sat_per_vb > 0
+            && fee_rate.as_sat_per_vb() <= self.dust_sweep_max_fee_rate_sat_per_vb as f32
+    }
+
     pub fn latest_income_settle_height(&self, current_height: u32) -> u32 {
         current_height - self.settle_income_after_n_confs.max(1) + 1
     }
@@ -29,6 +113,72 @@ impl Default for WalletConfig {
         Self {
             settle_income_after_n_confs: 2,
             settle_change_after_n_confs: 1,
+            gap_limit: default_gap_limit(),
+            lookahead: default_lookahead(),
+            consolidation: None,
+            dust_threshold_sats: 0,
+            dust_sweep_max_fee_rate_sat_per_vb: 0,
         }
     }
 }
+
+fn default_gap_limit() -> u32 {
+    20
+}
+
+fn default_lookahead() -> u32 {
+    100
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn n_inputs_to_consolidate() {
+        let policy = ConsolidationPolicy {
+            payout_queue_id: PayoutQueueId::new(),
+            target_utxo_count: 10,
+            max_fee_rate_sat_per_vb: 5,
+            max_inputs_per_tx: 50,
+        };
+        assert_eq!(policy.n_inputs_to_consolidate(10), None);
+        assert_eq!(policy.n_inputs_to_consolidate(11), Some(2));
+        assert_eq!(policy.n_inputs_to_consolidate(30), Some(21));
+        assert_eq!(policy.n_inputs_to_consolidate(1000), Some(50));
+        assert!(policy.fee_rate_exceeds_max(bitcoin::FeeRate::from_sat_per_vb(6.0)));
+        assert!(!policy.fee_rate_exceeds_max(bitcoin::FeeRate::from_sat_per_vb(5.0)));
+    }
+
+    #[test]
+    fn validate() {
+        let mut config = WalletConfig::default();
+        assert!(config.validate().is_ok());
+        config.lookahead = MAX_LOOKAHEAD;
+        assert!(config.validate().is_ok());
+        config.lookahead = MAX_LOOKAHEAD + 1;
+        assert!(matches!(
+            config.validate(),
+            Err(WalletError::InvalidConfig(_))
+        ));
+        config.lookahead = default_lookahead();
+        config.gap_limit = 0;
+        assert!(matches!(
+            config.validate(),
+            Err(WalletError::InvalidConfig(_))
+        ));
+    }
+
+    #[test]
+    fn dust() {
+        let mut config = WalletConfig::default();
+        assert!(!config.is_dust(Satoshis::from(1)));
+        assert!(!config.should_sweep_dust(bitcoin::FeeRate::from_sat_per_vb(1.0)));
+        config.dust_threshold_sats = 1_000;
+        config.dust_sweep_max_fee_rate_sat_per_vb = 2;
+        assert!(config.is_dust(Satoshis::from(999)));
+        assert!(!config.is_dust(Satoshis::from(1_000)));
+        assert!(config.should_sweep_dust(bitcoin::FeeRate::from_sat_per_vb(2.0)));
+        assert!(!config.should_sweep_dust(bitcoin::FeeRate::from_sat_per_vb(3.0)));
+    }
+}
@@ -4,7 +4,7 @@ use sqlx_ledger::{AccountId as LedgerAccountId, JournalId};
 
 use std::collections::HashMap;
 
-use super::{config::*, keychain::*};
+use super::{config::*, error::WalletError, keychain::*};
 use crate::{entity::*, ledger::WalletLedgerAccountIds, primitives::*, xpub::XPub};
 
 #[derive(Serialize, Deserialize)]
@@ -23,6 +23,8 @@ pub enum WalletEvent {
         effective_at_rest_ledger_account_id: LedgerAccountId,
         effective_outgoing_ledger_account_id: LedgerAccountId,
         dust_ledger_account_id: LedgerAccountId,
+        #[serde(default)]
+        quarantined_ledger_account_id: Option<LedgerAccountId>,
     },
     NameUpdated {
         name: String,
@@ -50,7 +52,7 @@ pub struct Wallet {
     pub network: bitcoin::Network,
     pub name: String,
 
-    events: EntityEvents<WalletEvent>,
+    pub(super) events: EntityEvents<WalletEvent>,
 }
 
 impl Wallet {
@@ -69,6 +71,11 @@ impl Wallet {
         })
     }
 
+    pub fn current_keychain_id(&self) -> KeychainId {
+        let (id, _) = self.iter_keychains().next().expect("No current keychain");
+        *id
+    }
+
     pub fn keychain_ids(&self) -> impl Iterator<Item = KeychainId> + '_ {
         self.iter_keychains().map(|(id, _)| *id)
     }
@@ -95,6 +102,49 @@ impl Wallet {
             .map(move |(id, cfg)| KeychainWallet::new(pool.clone(), self.network, *id, cfg.clone()))
     }
 
+    /// Adds a new keychain and makes it the current one. Previous keychains are kept
+    /// around as deprecated so that their funds keep being tracked and can be consolidated.
+    pub fn rotate_keychain(
+        &mut self,
+        keychain_config: KeychainConfig,
+    ) -> Result<KeychainId, WalletError> {
+        if self
+            .iter_keychains()
+            .any(|(_, cfg)| cfg == &keychain_config)
+        {
+            return Err(WalletError::KeychainAlreadyInUse);
+        }
+        let keychain_id = KeychainId::new();
+        let idx = self.iter_keychains().count();
+        self.events.push(WalletEvent::KeychainAdded {
+            keychain_id,
+            idx,
+            keychain_config,
+        });
+        self.events
+            .push(WalletEvent::KeychainActivated { keychain_id });
+        Ok(keychain_id)
+    }
+
+    pub fn update_config(&mut self, config: WalletConfig) -> Result<(), WalletError> {
+        config.validate()?;
+        if self.config != config {
+            self.config = config.clone();
+            self.events.push(WalletEvent::ConfigUpdated {
+                wallet_config: config,
+            });
+        }
+        Ok(())
+    }
+
+    /// Keychains that can also be spent via a timelocked branch of their policy
+    pub fn timelocked_keychains(&self) -> Vec<(KeychainId, PolicyTimelocks)> {
+        self.iter_keychains()
+            .map(|(id, cfg)| (*id, cfg.timelocks()))
+            .filter(|(_, timelocks)| !timelocks.is_empty())
+            .collect()
+    }
+
     pub fn xpubs_for_keychains<'a>(
         &self,
         keychain_ids: impl IntoIterator<Item = &'a KeychainId>,
@@ -148,6 +198,7 @@ impl NewWallet {
                 effective_at_rest_ledger_account_id: self.ledger_account_ids.effective_at_rest_id,
                 effective_outgoing_ledger_account_id: self.ledger_account_ids.effective_outgoing_id,
                 dust_ledger_account_id: self.ledger_account_ids.dust_id,
+                quarantined_ledger_account_id: Some(self.ledger_account_ids.quarantined_id),
             },
             WalletEvent::NameUpdated { name: self.name },
             WalletEvent::ConfigUpdated {
@@ -183,6 +234,7 @@ impl TryFrom<EntityEvents<WalletEvent>> for Wallet {
                     effective_at_rest_ledger_account_id,
                     effective_outgoing_ledger_account_id,
                     dust_ledger_account_id,
+                    quarantined_ledger_account_id,
                     ..
                 } => {
                     builder = builder
@@ -198,6 +250,11 @@ impl TryFrom<EntityEvents<WalletEvent>> for Wallet {
                             effective_at_rest_id: *effective_at_rest_ledger_account_id,
                             effective_outgoing_id: *effective_outgoing_ledger_account_id,
                             dust_id: *dust_ledger_account_id,
+                            // Wallets created before quarantining was introduced
+                            // derive the account id the same way new wallets do
+                            quarantined_id: quarantined_ledger_account_id.unwrap_or_else(|| {
+                                WalletLedgerAccountIds::from(*id).quarantined_id
+                            }),
                         });
                 }
                 ConfigUpdated {
@@ -6,12 +6,18 @@ pub enum WalletError {
     WalletNameNotFound(String),
     #[error("WalletError - Could not find wallet with id: {0}")]
     WalletIdNotFound(String),
+    #[error("WalletError - Keychain is already in use by this wallet")]
+    KeychainAlreadyInUse,
     #[error("WalletError - Sqlx: {0}")]
     Sqlx(#[from] sqlx::Error),
     #[error("WalletError - EntityError: {0}")]
     EntityError(#[from] crate::entity::EntityError),
     #[error("WalletError - UnsupportedPubKeyType")]
     UnsupportedPubKeyType,
+    #[error("WalletError - InvalidConfig: {0}")]
+    InvalidConfig(String),
+    #[error("WalletError - InvalidPolicy: {0}")]
+    InvalidPolicy(String),
     #[error("WalletError - BdkMiniscriptError: {0}")]
     BdkMiniscriptError(#[from] bdk::miniscript::Error),
     #[error("WalletError - Submitted Psbt does not have valid signatures.")]
@@ -0,0 +1,21 @@
+use crate::primitives::{bitcoin::*, *};
+
+/// The descriptors of a wallet together with how far they have been derived,
+/// enough to recover the funds with other wallet software.
+#[derive(Debug)]
+pub struct WalletExport {
+    pub wallet_id: WalletId,
+    pub wallet_name: String,
+    pub network: Network,
+    pub gap_limit: u32,
+    pub descriptors: Vec<ExportedDescriptor>,
+}
+
+#[derive(Debug)]
+pub struct ExportedDescriptor {
+    pub keychain_id: KeychainId,
+    pub kind: KeychainKind,
+    pub descriptor: ExtendedDescriptor,
+    pub active: bool,
+    pub last_derived_index: Option<u32>,
+}
@@ -2,6 +2,7 @@ use bdk::{descriptor::DescriptorPublicKey, miniscript::ForEachKey};
 use serde::{Deserialize, Serialize};
 use std::{collections::HashMap, str::FromStr};
 
+use super::policy::*;
 use crate::{primitives::bitcoin::ExtendedDescriptor, xpub::*};
 
 #[derive(Deserialize, Serialize, PartialEq, Eq, Clone, Debug)]
@@ -18,6 +19,18 @@ pub enum KeychainConfig {
         xpub: Vec<XPub>,
         threshold: u32,
     },
+    /// Single key taproot keychain that is spent via the key path
+    Tr {
+        xpub: XPub,
+    },
+    /// Keychain compiled from a miniscript policy whose keys reference xpubs
+    Policy {
+        policy: String,
+        script_type: PolicyScriptType,
+        xpubs: Vec<XPub>,
+        internal: ExtendedDescriptor,
+        external: ExtendedDescriptor,
+    },
 }
 
 impl KeychainConfig {
@@ -29,6 +42,43 @@ impl KeychainConfig {
         Self::SortedMultisig { xpub, threshold }
     }
 
+    pub fn tr(xpub: XPub) -> Self {
+        Self::Tr { xpub }
+    }
+
+    /// Compiles a policy like `or(thresh(2,pk(A),pk(B),pk(C)),and(thresh(1,pk(A),pk(B),pk(C)),older(26280)))`
+    /// where the keys are looked up by name in `xpubs`.
+    pub fn policy(
+        policy: String,
+        script_type: PolicyScriptType,
+        xpubs: HashMap<String, XPub>,
+    ) -> Result<Self, crate::wallet::error::WalletError> {
+        let external = compile_policy(&policy, script_type, &xpubs, 0)?;
+        let internal = compile_policy(&policy, script_type, &xpubs, 1)?;
+        let mut keys = HashMap::new();
+        for xpub in xpubs.into_values() {
+            keys.insert(xpub.id(), xpub);
+        }
+        Ok(Self::Policy {
+            policy,
+            script_type,
+            xpubs: keys.into_values().collect(),
+            internal,
+            external,
+        })
+    }
+
+    /// Names of the keys a policy refers to
+    pub fn policy_key_names(
+        policy: &str,
+    ) -> Result<Vec<String>, crate::wallet::error::WalletError> {
+        policy_key_names(policy)
+    }
+
+    pub fn timelocks(&self) -> PolicyTimelocks {
+        descriptor_timelocks(&self.external_descriptor())
+    }
+
     pub fn xpubs(&self) -> Vec<XPub> {
         match self {
             Self::Wpkh { xpub } => vec![xpub.clone()],
@@ -47,6 +97,8 @@ impl KeychainConfig {
                 ret.into_values().collect()
             }
             Self::SortedMultisig { xpub, .. } => xpub.clone(),
+            Self::Tr { xpub } => vec![xpub.clone()],
+            Self::Policy { xpubs, .. } => xpubs.clone(),
         }
     }
 
@@ -67,6 +119,10 @@ impl KeychainConfig {
                     .parse()
                     .expect("Couldn't create external sorted multisig descriptor")
             }
+            Self::Tr { xpub } => format!("tr({}/0/*)", xpub)
+                .parse()
+                .expect("Couldn't create external tr descriptor"),
+            Self::Policy { external, .. } => external.clone(),
         }
     }
 
@@ -86,6 +142,10 @@ impl KeychainConfig {
                     .parse()
                     .expect("Couldn't create internal sorted multisig descriptor")
             }
+            Self::Tr { xpub } => format!("tr({}/1/*)", xpub)
+                .parse()
+                .expect("Couldn't create internal tr descriptor"),
+            Self::Policy { internal, .. } => internal.clone(),
         }
     }
 }
@@ -1,5 +1,7 @@
 mod config;
+mod policy;
 mod wallet;
 
 pub use config::*;
+pub use policy::*;
 pub use wallet::*;
@@ -0,0 +1,401 @@
+use bdk::{
+    descriptor::{policy::SatisfiableItem, DescriptorPublicKey, Policy as BdkPolicy},
+    miniscript::{
+        bitcoin::hashes::{hash160, ripemd160, sha256},
+        policy::{Concrete, Liftable},
+        Descriptor, Segwitv0, Translator,
+    },
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, HashMap},
+    str::FromStr,
+};
+
+use crate::{
+    primitives::bitcoin::{self, ExtendedDescriptor},
+    wallet::error::WalletError,
+    xpub::XPub,
+};
+
+/// x-only NUMS point from BIP341 used as internal key for taproot policies
+/// that don't have a key that can satisfy the whole policy on its own.
+const UNSPENDABLE_INTERNAL_KEY: &str =
+    "50929b74c1a04954b78b4b6035e97a5e078a5a0f28ec96d547bfee9ace803ac0";
+const RELATIVE_LOCK_TIME_MASK: u32 = 0x0000_ffff;
+
+#[derive(Deserialize, Serialize, PartialEq, Eq, Clone, Copy, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum PolicyScriptType {
+    Wsh,
+    Tr,
+}
+
+/// Which branch of a timelocked policy a transaction is built for.
+/// `Primary` never depends on a timelock, `Recovery` prefers the timelocked branches.
+#[derive(Deserialize, Serialize, PartialEq, Eq, Clone, Copy, Debug, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum SpendingPath {
+    #[default]
+    Primary,
+    Recovery,
+}
+
+impl std::fmt::Display for SpendingPath {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::Primary => write!(f, "primary"),
+            Self::Recovery => write!(f, "recovery"),
+        }
+    }
+}
+
+impl FromStr for SpendingPath {
+    type Err = WalletError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "primary" => Ok(Self::Primary),
+            "recovery" => Ok(Self::Recovery),
+            _ => Err(WalletError::InvalidPolicy(format!(
+                "unknown spending path '{s}'"
+            ))),
+        }
+    }
+}
+
+/// Timelocks present in a descriptor, heights are in blocks
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct PolicyTimelocks {
+    pub relative_blocks: Option<u32>,
+    pub absolute_height: Option<u32>,
+}
+
+impl PolicyTimelocks {
+    pub fn is_empty(&self) -> bool {
+        self.relative_blocks.is_none() && self.absolute_height.is_none()
+    }
+
+    /// Height at which a timelocked branch can spend an output confirmed at `confirmation_height`
+    pub fn spendable_at_height(&self, confirmation_height: u32) -> Option<u32> {
+        let relative = self
+            .relative_blocks
+            .map(|blocks| confirmation_height + blocks);
+        match (relative, self.absolute_height) {
+            (Some(relative), Some(absolute)) => Some(relative.min(absolute)),
+            (relative, absolute) => relative.or(absolute),
+        }
+    }
+}
+
+/// Returns the key names referenced via `pk(NAME)` in the policy
+pub(super) fn policy_key_names(policy: &str) -> Result<Vec<String>, WalletError> {
+    let policy = parse_policy(policy)?;
+    let mut names: Vec<String> = policy.keys().into_iter().cloned().collect();
+    names.sort();
+    names.dedup();
+    Ok(names)
+}
+
+pub(super) fn compile_policy(
+    policy: &str,
+    script_type: PolicyScriptType,
+    xpubs: &HashMap<String, XPub>,
+    derivation_branch: u32,
+) -> Result<ExtendedDescriptor, WalletError> {
+    let policy = parse_policy(policy)?;
+    let policy = policy.translate_pk(&mut XPubTranslator {
+        xpubs,
+        derivation_branch,
+    })?;
+    let descriptor = match script_type {
+        PolicyScriptType::Wsh => {
+            let ms = policy
+                .compile::<Segwitv0>()
+                .map_err(|e| WalletError::InvalidPolicy(e.to_string()))?;
+            Descriptor::new_wsh(ms)?
+        }
+        PolicyScriptType::Tr => {
+            let internal_key = DescriptorPublicKey::from_str(UNSPENDABLE_INTERNAL_KEY)
+                .expect("Couldn't parse unspendable key");
+            policy
+                .compile_tr(Some(internal_key))
+                .map_err(|e| WalletError::InvalidPolicy(e.to_string()))?
+        }
+    };
+    descriptor.sanity_check()?;
+    Ok(descriptor)
+}
+
+pub(crate) fn descriptor_timelocks(descriptor: &ExtendedDescriptor) -> PolicyTimelocks {
+    let semantic = match descriptor.lift() {
+        Ok(semantic) => semantic,
+        Err(_) => return PolicyTimelocks::default(),
+    };
+    let relative_blocks = semantic
+        .relative_timelocks()
+        .into_iter()
+        .map(bitcoin::Sequence::from_consensus)
+        .filter(|seq| seq.is_relative_lock_time() && seq.is_height_locked())
+        .map(|seq| seq.to_consensus_u32() & RELATIVE_LOCK_TIME_MASK)
+        .min();
+    let absolute_height = semantic
+        .absolute_timelocks()
+        .into_iter()
+        .filter(|n| *n < bdk::bitcoin::blockdata::locktime::LOCK_TIME_THRESHOLD)
+        .min();
+    PolicyTimelocks {
+        relative_blocks,
+        absolute_height,
+    }
+}
+
+/// Builds the BDK policy path selecting the branches of every threshold that
+/// depends on a timelock according to the requested `SpendingPath`.
+/// Returns an empty map if the policy has no timelocked branches.
+pub fn select_spending_path(
+    policy: &BdkPolicy,
+    spending_path: SpendingPath,
+) -> BTreeMap<String, Vec<usize>> {
+    let mut path = BTreeMap::new();
+    select_items(policy, spending_path, &mut path);
+    path
+}
+
+fn select_items(
+    policy: &BdkPolicy,
+    spending_path: SpendingPath,
+    path: &mut BTreeMap<String, Vec<usize>>,
+) {
+    if let SatisfiableItem::Thresh { items, threshold } = &policy.item {
+        if !items.iter().any(has_timelock) {
+            return;
+        }
+        let mut indices: Vec<usize> = (0..items.len()).collect();
+        indices.sort_by_key(|idx| match spending_path {
+            SpendingPath::Primary => has_timelock(&items[*idx]),
+            SpendingPath::Recovery => !has_timelock(&items[*idx]),
+        });
+        indices.truncate(*threshold);
+        indices.sort_unstable();
+        for idx in indices.iter() {
+            select_items(&items[*idx], spending_path, path);
+        }
+        path.insert(policy.id.clone(), indices);
+    }
+}
+
+fn has_timelock(policy: &BdkPolicy) -> bool {
+    match &policy.item {
+        SatisfiableItem::AbsoluteTimelock { .. } | SatisfiableItem::RelativeTimelock { .. } => true,
+        SatisfiableItem::Thresh { items, .. } => items.iter().any(has_timelock),
+        _ => false,
+    }
+}
+
+fn parse_policy(policy: &str) -> Result<Concrete<String>, WalletError> {
+    let policy = Concrete::<String>::from_str(policy)
+        .map_err(|e| WalletError::InvalidPolicy(e.to_string()))?;
+    policy
+        .is_valid()
+        .map_err(|e| WalletError::InvalidPolicy(e.to_string()))?;
+    reject_time_based_timelocks(&policy)?;
+    Ok(policy)
+}
+
+/// Only block height timelocks are tracked when spending via the recovery path,
+/// so timestamp based `after()` / `older()` are rejected upfront.
+fn reject_time_based_timelocks(policy: &Concrete<String>) -> Result<(), WalletError> {
+    let semantic = policy
+        .lift()
+        .map_err(|e| WalletError::InvalidPolicy(e.to_string()))?;
+    if semantic
+        .absolute_timelocks()
+        .into_iter()
+        .any(|n| n >= bdk::bitcoin::blockdata::locktime::LOCK_TIME_THRESHOLD)
+    {
+        return Err(WalletError::InvalidPolicy(
+            "timestamp based after() is not supported, use a block height".to_string(),
+        ));
+    }
+    if semantic
+        .relative_timelocks()
+        .into_iter()
+        .map(bitcoin::Sequence::from_consensus)
+        .any(|seq| seq.is_time_locked())
+    {
+        return Err(WalletError::InvalidPolicy(
+            "time based older() is not supported, use a number of blocks".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+struct XPubTranslator<'a> {
+    xpubs: &'a HashMap<String, XPub>,
+    derivation_branch: u32,
+}
+
+impl<'a> Translator<String, DescriptorPublicKey, WalletError> for XPubTranslator<'a> {
+    fn pk(&mut self, name: &String) -> Result<DescriptorPublicKey, WalletError> {
+        let xpub = self
+            .xpubs
+            .get(name)
+            .ok_or_else(|| WalletError::InvalidPolicy(format!("unknown key '{name}'")))?;
+        let key = format!("{}/{}/*", xpub, self.derivation_branch);
+        DescriptorPublicKey::from_str(&key).map_err(|e| WalletError::InvalidPolicy(e.to_string()))
+    }
+
+    fn sha256(&mut self, _: &String) -> Result<sha256::Hash, WalletError> {
+        Err(WalletError::InvalidPolicy(
+            "hash locks are not supported".to_string(),
+        ))
+    }
+
+    fn hash256(&mut self, _: &String) -> Result<bdk::miniscript::hash256::Hash, WalletError> {
+        Err(WalletError::InvalidPolicy(
+            "hash locks are not supported".to_string(),
+        ))
+    }
+
+    fn ripemd160(&mut self, _: &String) -> Result<ripemd160::Hash, WalletError> {
+        Err(WalletError::InvalidPolicy(
+            "hash locks are not supported".to_string(),
+        ))
+    }
+
+    fn hash160(&mut self, _: &String) -> Result<hash160::Hash, WalletError> {
+        Err(WalletError::InvalidPolicy(
+            "hash locks are not supported".to_string(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bdk::{
+        bitcoin::secp256k1::Secp256k1,
+        descriptor::{policy::BuildSatisfaction, ExtractPolicy},
+        signer::SignersContainer,
+    };
+
+    use super::*;
+
+    const POLICY: &str = "or(99@pk(main),1@and(pk(backup),older(4320)))";
+
+    fn xpubs() -> HashMap<String, XPub> {
+        let mut xpubs = HashMap::new();
+        xpubs.insert(
+            "main".to_string(),
+            XPub::try_from(
+                ("tpubDD4vFnWuTMEcZiaaZPgvzeGyMzWe6qHW8gALk5Md9kutDvtdDjYFwzauEFFRHgov8pAwup5jX88j5YFyiACsPf3pqn5hBjvuTLRAseaJ6b4",
+                 Some("m/84'/0'/0'"))).unwrap(),
+        );
+        xpubs.insert(
+            "backup".to_string(),
+            XPub::try_from(
+                ("tpubDDdzmt7vndmNywiVAeBPuhYLTFa7hmtfaqUxxTv5iLy7bxU93B62M9WKFSmn1BEN2vte8GDD3SUNKbupRajFW4RK8hd3i6W15pvTRQfo1fK",
+                 Some("m/84'/0'/0'"))).unwrap(),
+        );
+        xpubs
+    }
+
+    #[test]
+    fn key_names() {
+        assert_eq!(
+            policy_key_names(POLICY).unwrap(),
+            vec!["backup".to_string(), "main".to_string()]
+        );
+    }
+
+    #[test]
+    fn compile_wsh_and_tr() {
+        let wsh = compile_policy(POLICY, PolicyScriptType::Wsh, &xpubs(), 0).unwrap();
+        assert!(wsh.to_string().starts_with("wsh("));
+        let tr = compile_policy(POLICY, PolicyScriptType::Tr, &xpubs(), 1).unwrap();
+        assert!(tr.to_string().starts_with("tr([8df69d29/84'/0'/0']"));
+        assert!(tr.to_string().contains("/1/*"));
+        let tr = compile_policy(
+            "and(pk(main),pk(backup))",
+            PolicyScriptType::Tr,
+            &xpubs(),
+            0,
+        )
+        .unwrap();
+        assert!(tr
+            .to_string()
+            .starts_with(&format!("tr({UNSPENDABLE_INTERNAL_KEY}")));
+    }
+
+    #[test]
+    fn unknown_key() {
+        assert!(matches!(
+            compile_policy("pk(unknown)", PolicyScriptType::Wsh, &xpubs(), 0),
+            Err(WalletError::InvalidPolicy(_))
+        ));
+    }
+
+    #[test]
+    fn timelocks() {
+        let descriptor = compile_policy(POLICY, PolicyScriptType::Wsh, &xpubs(), 0).unwrap();
+        let timelocks = descriptor_timelocks(&descriptor);
+        assert_eq!(timelocks.relative_blocks, Some(4320));
+        assert_eq!(timelocks.absolute_height, None);
+        assert_eq!(timelocks.spendable_at_height(100), Some(4420));
+
+        let descriptor = compile_policy("pk(main)", PolicyScriptType::Wsh, &xpubs(), 0).unwrap();
+        assert!(descriptor_timelocks(&descriptor).is_empty());
+
+        let descriptor = compile_policy(
+            "or(99@pk(main),1@and(pk(backup),after(800000)))",
+            PolicyScriptType::Wsh,
+            &xpubs(),
+            0,
+        )
+        .unwrap();
+        let timelocks = descriptor_timelocks(&descriptor);
+        assert_eq!(timelocks.relative_blocks, None);
+        assert_eq!(timelocks.absolute_height, Some(800000));
+        assert_eq!(timelocks.spendable_at_height(100), Some(800000));
+    }
+
+    #[test]
+    fn time_based_timelocks() {
+        for policy in [
+            "or(99@pk(main),1@and(pk(backup),after(1700000000)))",
+            "or(99@pk(main),1@and(pk(backup),older(4194305)))",
+        ] {
+            assert!(matches!(
+                compile_policy(policy, PolicyScriptType::Wsh, &xpubs(), 0),
+                Err(WalletError::InvalidPolicy(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn spending_paths() {
+        let descriptor = compile_policy(POLICY, PolicyScriptType::Wsh, &xpubs(), 0).unwrap();
+        let policy = descriptor
+            .extract_policy(
+                &SignersContainer::default(),
+                BuildSatisfaction::None,
+                &Secp256k1::new(),
+            )
+            .unwrap()
+            .unwrap();
+        let items = match &policy.item {
+            SatisfiableItem::Thresh { items, .. } => items,
+            _ => panic!("expected a threshold"),
+        };
+
+        let primary = select_spending_path(&policy, SpendingPath::Primary);
+        let selected = &primary[&policy.id];
+        assert_eq!(selected.len(), 1);
+        assert!(!has_timelock(&items[selected[0]]));
+
+        let recovery = select_spending_path(&policy, SpendingPath::Recovery);
+        let selected = &recovery[&policy.id];
+        assert_eq!(selected.len(), 1);
+        assert!(has_timelock(&items[selected[0]]));
+    }
+}
@@ -1,6 +1,6 @@
 use bdk::{
     blockchain::{GetHeight, WalletSync},
-    database::BatchDatabase,
+    database::{BatchDatabase, Database},
     wallet::{signer::SignOptions, AddressIndex},
     Wallet,
 };
@@ -105,9 +105,17 @@ impl KeychainWallet {
     pub async fn sync<B: WalletSync + GetHeight + Send + Sync + 'static>(
         &self,
         blockchain: B,
+        lookahead: u32,
     ) -> Result<(), BdkError> {
-        self.with_wallet(move |wallet| wallet.sync(&blockchain, Default::default()))
-            .await??;
+        self.with_wallet(move |wallet| {
+            let last_index = wallet
+                .database()
+                .get_last_index(KeychainKind::External)?
+                .unwrap_or(0);
+            wallet.ensure_addresses_cached(last_index + 1 + lookahead)?;
+            wallet.sync(&blockchain, Default::default())
+        })
+        .await??;
         Ok(())
     }
 
@@ -117,6 +125,21 @@ impl KeychainWallet {
         Ok(balance)
     }
 
+    pub fn descriptor(&self, kind: KeychainKind) -> ExtendedDescriptor {
+        match kind {
+            KeychainKind::External => self.config.external_descriptor(),
+            KeychainKind::Internal => self.config.internal_descriptor(),
+        }
+    }
+
+    #[instrument(name = "keychain_wallet.last_derived_index", skip(self))]
+    pub async fn last_derived_index(&self, kind: KeychainKind) -> Result<Option<u32>, BdkError> {
+        let idx = self
+            .with_wallet(move |wallet| wallet.database().get_last_index(kind))
+            .await??;
+        Ok(idx)
+    }
+
     #[instrument(name = "keychain_wallet.max_satisfaction_weight", skip_all)]
     pub fn max_satisfaction_weight(&self) -> usize {
         self.config
@@ -125,6 +148,13 @@ impl KeychainWallet {
             .expect("max_satisfaction_weight")
     }
 
+    pub fn change_script_pubkey(&self) -> Script {
+        self.config
+            .internal_descriptor()
+            .at_derivation_index(0)
+            .script_pubkey()
+    }
+
     async fn with_wallet<F, R>(&self, f: F) -> Result<R, tokio::task::JoinError>
     where
         F: 'static + Send + FnOnce(Wallet<SqlxWalletDb>) -> R,
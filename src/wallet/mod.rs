@@ -1,15 +1,19 @@
 pub mod balance;
+mod coin_selection;
 mod config;
 mod entity;
 pub mod error;
+mod export;
 mod keychain;
 mod psbt_builder;
 pub mod psbt_validator;
 mod repo;
 
 pub use balance::*;
+pub use coin_selection::*;
 pub use config::*;
 pub use entity::*;
+pub use export::*;
 pub use keychain::*;
 pub use psbt_builder::*;
 pub use repo::*;
@@ -1,6 +1,10 @@
 use bdk::{
     database::BatchDatabase,
-    wallet::{tx_builder::TxOrdering, AddressIndex, AddressInfo},
+    wallet::{
+        coin_selection::CoinSelectionAlgorithm,
+        tx_builder::{TxBuilder, TxBuilderContext, TxOrdering},
+        AddressIndex, AddressInfo,
+    },
     FeeRate, Wallet,
 };
 use std::{
@@ -9,7 +13,7 @@ use std::{
 };
 use tracing::instrument;
 
-use super::{keychain::*, Wallet as WalletEntity};
+use super::{coin_selection::*, keychain::*, Wallet as WalletEntity};
 use crate::{
     bdk::error::BdkError,
     primitives::{bitcoin::*, *},
@@ -39,6 +43,7 @@ pub struct FinishedPsbtBuild {
     pub included_utxos: HashMap<WalletId, HashMap<KeychainId, Vec<bitcoin::OutPoint>>>,
     pub included_wallet_keychains: HashMap<KeychainId, WalletId>,
     pub wallet_totals: HashMap<WalletId, WalletTotals>,
+    pub deducted_payout_fees: HashMap<uuid::Uuid, Satoshis>,
     pub fee_satoshis: Satoshis,
     pub tx_id: Option<bitcoin::Txid>,
     pub psbt: Option<psbt::PartiallySignedTransaction>,
@@ -75,6 +80,11 @@ pub struct PsbtBuilder<T> {
     input_weights: HashMap<OutPoint, usize>,
     all_included_utxos: HashSet<OutPoint>,
     for_estimation: bool,
+    fee_from_amount_payouts: HashSet<uuid::Uuid>,
+    sweep_payouts: HashSet<uuid::Uuid>,
+    spending_path: SpendingPath,
+    coin_selection: CoinSelectionStrategy,
+    self_pay: bool,
     _phantom: PhantomData<T>,
 }
 
@@ -164,6 +174,10 @@ impl PsbtBuilder<InitialPsbtBuilderState> {
         reserved_utxos: HashMap<KeychainId, Vec<bitcoin::OutPoint>>,
         cpfp_utxos: HashMap<KeychainId, Vec<CpfpUtxo>>,
         unbatched_payouts: HashMap<WalletId, Vec<TxPayout>>,
+        fee_from_amount_payouts: HashSet<uuid::Uuid>,
+        sweep_payouts: HashSet<uuid::Uuid>,
+        spending_path: SpendingPath,
+        coin_selection: CoinSelectionStrategy,
         mut wallets: HashMap<WalletId, WalletEntity>,
         for_estimation: bool,
     ) -> Result<FinishedPsbtBuild, BdkError> {
@@ -172,6 +186,10 @@ impl PsbtBuilder<InitialPsbtBuilderState> {
             .fee_rate(fee_rate)
             .reserved_utxos(reserved_utxos)
             .cpfp_utxos(cpfp_utxos)
+            .fee_from_amount_payouts(fee_from_amount_payouts)
+            .sweep_payouts(sweep_payouts)
+            .spending_path(spending_path)
+            .coin_selection(coin_selection)
             .for_estimation(for_estimation)
             .accept_wallets();
 
@@ -191,6 +209,32 @@ impl PsbtBuilder<InitialPsbtBuilderState> {
         Ok(outer_builder.finish())
     }
 
+    #[instrument(name = "psbt_builder.construct_consolidation_psbt", skip_all)]
+    pub async fn construct_consolidation_psbt(
+        pool: &sqlx::PgPool,
+        fee_rate: FeeRate,
+        reserved_utxos: HashMap<KeychainId, Vec<bitcoin::OutPoint>>,
+        spending_path: SpendingPath,
+        wallet: &WalletEntity,
+    ) -> Result<FinishedPsbtBuild, BdkError> {
+        let builder = PsbtBuilder::new()
+            .consolidate_deprecated_keychains(false)
+            .fee_rate(fee_rate)
+            .reserved_utxos(reserved_utxos)
+            .cpfp_utxos(HashMap::new())
+            .spending_path(spending_path)
+            .self_pay(true)
+            .accept_wallets()
+            .wallet_payouts(wallet.id, Vec::new())
+            .accept_current_keychain();
+        Ok(wallet
+            .current_keychain_wallet(pool)
+            .dispatch_bdk_wallet(builder)
+            .await?
+            .next_wallet()
+            .finish())
+    }
+
     pub fn new() -> Self {
         Self {
             consolidate_deprecated_keychains: None,
@@ -209,11 +253,17 @@ impl PsbtBuilder<InitialPsbtBuilderState> {
                 included_utxos: HashMap::new(),
                 included_wallet_keychains: HashMap::new(),
                 wallet_totals: HashMap::new(),
+                deducted_payout_fees: HashMap::new(),
                 fee_satoshis: Satoshis::from(0),
                 tx_id: None,
                 psbt: None,
             },
             for_estimation: false,
+            fee_from_amount_payouts: HashSet::new(),
+            sweep_payouts: HashSet::new(),
+            spending_path: SpendingPath::default(),
+            coin_selection: CoinSelectionStrategy::default(),
+            self_pay: false,
             _phantom: PhantomData,
         }
     }
@@ -241,6 +291,37 @@ impl PsbtBuilder<InitialPsbtBuilderState> {
         self
     }
 
+    pub fn fee_from_amount_payouts(mut self, payout_ids: HashSet<uuid::Uuid>) -> Self {
+        self.fee_from_amount_payouts = payout_ids;
+        self
+    }
+
+    /// Sweep payouts spend all spendable utxos of their wallet into a single output.
+    /// Other payouts of the same wallet are not included in the batch.
+    pub fn sweep_payouts(mut self, payout_ids: HashSet<uuid::Uuid>) -> Self {
+        self.sweep_payouts = payout_ids;
+        self
+    }
+
+    /// Branch to use for keychains whose policy contains timelocks
+    pub fn spending_path(mut self, spending_path: SpendingPath) -> Self {
+        self.spending_path = spending_path;
+        self
+    }
+
+    /// Algorithm used to pick the inputs funding the payouts of each wallet
+    pub fn coin_selection(mut self, coin_selection: CoinSelectionStrategy) -> Self {
+        self.coin_selection = coin_selection;
+        self
+    }
+
+    /// Wallets without payouts spend all of their spendable utxos into their change address.
+    /// Used to consolidate utxos, the utxos to leave untouched must be passed as reserved.
+    pub fn self_pay(mut self, self_pay: bool) -> Self {
+        self.self_pay = self_pay;
+        self
+    }
+
     pub fn fee_rate(mut self, fee_rate: FeeRate) -> Self {
         self.fee_rate = Some(fee_rate);
         self
@@ -288,6 +369,11 @@ impl PsbtBuilder<InitialPsbtBuilderState> {
             input_weights: self.input_weights,
             result: self.result,
             for_estimation: self.for_estimation,
+            fee_from_amount_payouts: self.fee_from_amount_payouts,
+            sweep_payouts: self.sweep_payouts,
+            spending_path: self.spending_path,
+            coin_selection: self.coin_selection,
+            self_pay: self.self_pay,
             _phantom: PhantomData,
         }
     }
@@ -315,6 +401,11 @@ impl PsbtBuilder<AcceptingWalletState> {
             input_weights: self.input_weights,
             result: self.result,
             for_estimation: self.for_estimation,
+            fee_from_amount_payouts: self.fee_from_amount_payouts,
+            sweep_payouts: self.sweep_payouts,
+            spending_path: self.spending_path,
+            coin_selection: self.coin_selection,
+            self_pay: self.self_pay,
             _phantom: PhantomData,
         }
     }
@@ -346,6 +437,7 @@ impl BdkWalletVisitor for PsbtBuilder<AcceptingDeprecatedKeychainState> {
         };
 
         let mut builder = wallet.build_tx();
+        apply_spending_path(&mut builder, wallet, self.spending_path)?;
         if let Some(reserved_utxos) = self
             .reserved_utxos
             .as_ref()
@@ -393,6 +485,11 @@ impl PsbtBuilder<AcceptingDeprecatedKeychainState> {
             input_weights: self.input_weights,
             result: self.result,
             for_estimation: self.for_estimation,
+            fee_from_amount_payouts: self.fee_from_amount_payouts,
+            sweep_payouts: self.sweep_payouts,
+            spending_path: self.spending_path,
+            coin_selection: self.coin_selection,
+            self_pay: self.self_pay,
             _phantom: PhantomData,
         }
     }
@@ -413,10 +510,33 @@ impl BdkWalletVisitor for PsbtBuilder<AcceptingCurrentKeychainState> {
             .get_descriptor_for_keychain(KeychainKind::External)
             .max_satisfaction_weight()
             .expect("Unsupported descriptor");
+        let sweep_payout = self
+            .current_payouts
+            .iter()
+            .position(|(id, _, _)| self.sweep_payouts.contains(id))
+            .map(|idx| self.current_payouts.remove(idx));
+        let sweep_destination = match sweep_payout.as_ref() {
+            Some((_, destination, _)) => Some(destination.clone()),
+            None if self.self_pay && self.current_payouts.is_empty() => {
+                Some(change_address.address.clone())
+            }
+            None => None,
+        };
         let mut max_payout = 0;
         let mut absolute_fee = 0;
         let mut inputs = Vec::new();
-        while max_payout < self.current_payouts.len() {
+        let mut sweep_input_satoshis = 0;
+        if let Some(destination) = sweep_destination.as_ref() {
+            match self.try_build_sweep_psbt(current_keychain_id, destination, wallet)? {
+                Some((fee, ins, input_satoshis)) => {
+                    absolute_fee = fee;
+                    inputs = ins;
+                    sweep_input_satoshis = input_satoshis;
+                }
+                None => return Ok(self),
+            }
+        }
+        while sweep_destination.is_none() && max_payout < self.current_payouts.len() {
             let (fee, ins, success) = self.try_build_current_wallet_psbt(
                 current_keychain_id,
                 &self.current_payouts[..=max_payout],
@@ -430,18 +550,56 @@ impl BdkWalletVisitor for PsbtBuilder<AcceptingCurrentKeychainState> {
             max_payout += 1;
             inputs = ins;
         }
-        if max_payout == 0 {
+        if max_payout == 0 && sweep_destination.is_none() {
             return Ok(self);
         }
 
-        let mut builder = wallet.build_tx();
-        if self.result.fee_satoshis == Satoshis::ZERO {
-            builder.fee_absolute(absolute_fee + self.fee_rate_inner().fee_vb(HEADER_VBYTES));
+        let mut current_wallet_fee = if self.result.fee_satoshis == Satoshis::ZERO {
+            absolute_fee + self.fee_rate_inner().fee_vb(HEADER_VBYTES)
         } else {
-            builder.fee_absolute(absolute_fee + u64::from(self.result.fee_satoshis));
+            absolute_fee
+        };
+
+        // A sweep never moves more than the amount its payout encumbered.
+        // Funds that arrived since it was requested stay in the wallet as change
+        // or, when too small for a change output, go towards the fee.
+        let mut capped_sweep_satoshis = None;
+        if let Some((_, destination, satoshis)) = sweep_payout.as_ref() {
+            let requested = u64::from(*satoshis);
+            if sweep_input_satoshis < requested {
+                // Part of what the sweep was sized for is no longer spendable,
+                // leave it unbatched rather than booking the shortfall as fee
+                return Ok(self);
+            }
+            let excess = sweep_input_satoshis - requested;
+            let change_script = change_address.script_pubkey();
+            let change_fee = crate::fees::output_fee(self.fee_rate_inner(), change_script.clone());
+            if excess >= change_fee + change_script.dust_value().to_sat() {
+                // The sweep pays the fee it was estimated with, the change output is
+                // paid for out of the excess
+                let capped = requested.saturating_sub(current_wallet_fee);
+                if capped < destination.script_pubkey().dust_value().to_sat() {
+                    return Ok(self);
+                }
+                current_wallet_fee += change_fee;
+                capped_sweep_satoshis = Some(capped);
+            } else {
+                current_wallet_fee = current_wallet_fee.max(excess);
+            }
         }
 
-        builder.drain_to(change_address.script_pubkey());
+        let mut builder = wallet.build_tx();
+        apply_spending_path(&mut builder, wallet, self.spending_path)?;
+        builder.fee_absolute(current_wallet_fee + u64::from(self.result.fee_satoshis));
+
+        match (sweep_payout.as_ref(), capped_sweep_satoshis) {
+            (Some((_, destination, _)), Some(capped)) => {
+                builder.add_recipient(destination.script_pubkey(), capped);
+                builder.drain_to(change_address.script_pubkey())
+            }
+            (Some((_, destination, _)), None) => builder.drain_to(destination.script_pubkey()),
+            (None, _) => builder.drain_to(change_address.script_pubkey()),
+        };
         builder.sighash(DEFAULT_SIGHASH_TYPE.into());
         builder.manually_selected_only();
         builder.add_utxos(&inputs)?;
@@ -449,6 +607,7 @@ impl BdkWalletVisitor for PsbtBuilder<AcceptingCurrentKeychainState> {
         if let Some(cpfp) = self
             .cpfp_utxos
             .as_ref()
+            .filter(|_| sweep_payout.is_none())
             .and_then(|m| m.get(&current_keychain_id))
         {
             for utxo in cpfp {
@@ -463,8 +622,33 @@ impl BdkWalletVisitor for PsbtBuilder<AcceptingCurrentKeychainState> {
             }
         }
 
+        // Payouts flagged with fee_from_amount carry their proportional share
+        // of this wallet's fee, the difference ends up in the change output
+        let mut fee_shares = if self.current_payouts[..max_payout]
+            .iter()
+            .any(|(id, _, _)| self.fee_from_amount_payouts.contains(id))
+        {
+            crate::fees::allocate_proportional_fees(
+                Satoshis::from(current_wallet_fee),
+                self.current_payouts[..max_payout]
+                    .iter()
+                    .map(|(id, _, satoshis)| (PayoutId::from(*id), *satoshis)),
+            )
+        } else {
+            HashMap::new()
+        };
+
         let mut total_output_satoshis = Satoshis::from(0);
         for (payout_id, destination, satoshis) in self.current_payouts.drain(..max_payout) {
+            let satoshis = match fee_shares.remove(&PayoutId::from(payout_id)) {
+                Some(share) if self.fee_from_amount_payouts.contains(&payout_id) => {
+                    let dust = Satoshis::from(destination.script_pubkey().dust_value().to_sat());
+                    let deducted = share.min(satoshis - dust).max(Satoshis::ZERO);
+                    self.result.deducted_payout_fees.insert(payout_id, deducted);
+                    satoshis - deducted
+                }
+                _ => satoshis,
+            };
             total_output_satoshis += satoshis;
             builder.add_recipient(destination.script_pubkey(), u64::from(satoshis));
             self.result
@@ -521,6 +705,7 @@ impl BdkWalletVisitor for PsbtBuilder<AcceptingCurrentKeychainState> {
             }
         }
 
+        builder.enable_rbf();
         builder.ordering(TxOrdering::Bip69Lexicographic);
         match builder.finish() {
             Ok((psbt, details)) => {
@@ -535,6 +720,25 @@ impl BdkWalletVisitor for PsbtBuilder<AcceptingCurrentKeychainState> {
                         .map(|out| out.value)
                         .unwrap_or(0),
                 );
+                if let Some((payout_id, destination, satoshis)) = sweep_payout {
+                    let swept = Satoshis::from(
+                        psbt.unsigned_tx
+                            .output
+                            .iter()
+                            .find(|out| out.script_pubkey == destination.script_pubkey())
+                            .map(|out| out.value)
+                            .expect("sweep output missing"),
+                    );
+                    total_output_satoshis += swept;
+                    self.result
+                        .deducted_payout_fees
+                        .insert(payout_id, satoshis - swept);
+                    self.result
+                        .included_payouts
+                        .entry(wallet_id)
+                        .or_default()
+                        .push(((payout_id, destination, swept), 0));
+                }
                 let mut cpfp_allocations = HashMap::new();
                 std::mem::swap(
                     &mut cpfp_allocations,
@@ -610,6 +814,11 @@ impl PsbtBuilder<AcceptingCurrentKeychainState> {
             input_weights: self.input_weights,
             result: self.result,
             for_estimation: self.for_estimation,
+            fee_from_amount_payouts: self.fee_from_amount_payouts,
+            sweep_payouts: self.sweep_payouts,
+            spending_path: self.spending_path,
+            coin_selection: self.coin_selection,
+            self_pay: self.self_pay,
             _phantom: PhantomData,
         }
     }
@@ -618,6 +827,71 @@ impl PsbtBuilder<AcceptingCurrentKeychainState> {
         self.finish_inner()
     }
 
+    fn try_build_sweep_psbt<D: BatchDatabase>(
+        &self,
+        keychain_id: KeychainId,
+        destination: &Address,
+        wallet: &Wallet<D>,
+    ) -> Result<Option<(u64, Vec<OutPoint>, u64)>, BdkError> {
+        let mut builder = wallet.build_tx();
+        apply_spending_path(&mut builder, wallet, self.spending_path)?;
+        builder.fee_rate(*self.fee_rate_inner());
+        builder.drain_wallet();
+        builder.drain_to(destination.script_pubkey());
+
+        if let Some(reserved_utxos) = self
+            .reserved_utxos
+            .as_ref()
+            .and_then(|m| m.get(&keychain_id))
+        {
+            for out in reserved_utxos {
+                builder.add_unspendable(*out);
+            }
+        }
+
+        let mut foreign_utxos = HashSet::new();
+        for (_, psbt) in self.current_wallet_psbts.iter() {
+            for (input, psbt_input) in psbt.unsigned_tx.input.iter().zip(psbt.inputs.iter()) {
+                foreign_utxos.insert(input.previous_output);
+                builder.add_foreign_utxo(
+                    input.previous_output,
+                    psbt_input.clone(),
+                    *self
+                        .input_weights
+                        .get(&input.previous_output)
+                        .expect("weight should always be present"),
+                )?;
+            }
+        }
+
+        match builder.finish() {
+            Ok((psbt, details)) => {
+                let fee = details.fee.expect("fee must be present");
+                let input_satoshis = psbt
+                    .unsigned_tx
+                    .output
+                    .iter()
+                    .map(|out| out.value)
+                    .sum::<u64>()
+                    + fee;
+                let inputs = psbt
+                    .unsigned_tx
+                    .input
+                    .into_iter()
+                    .map(|i| i.previous_output)
+                    .filter(|out| !foreign_utxos.contains(out))
+                    .collect();
+                Ok(Some((
+                    fee - self.fee_rate_inner().fee_vb(HEADER_VBYTES),
+                    inputs,
+                    input_satoshis,
+                )))
+            }
+            Err(bdk::Error::InsufficientFunds { .. }) => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
     fn try_build_current_wallet_psbt<D: BatchDatabase>(
         &self,
         keychain_id: KeychainId,
@@ -625,7 +899,10 @@ impl PsbtBuilder<AcceptingCurrentKeychainState> {
         wallet: &Wallet<D>,
         change_address: &AddressInfo,
     ) -> Result<(u64, Vec<OutPoint>, bool), BdkError> {
-        let mut builder = wallet.build_tx();
+        let mut builder = wallet
+            .build_tx()
+            .coin_selection(CoinSelector(self.coin_selection));
+        apply_spending_path(&mut builder, wallet, self.spending_path)?;
         builder.fee_rate(*self.fee_rate_inner());
         builder.drain_to(change_address.script_pubkey());
 
@@ -713,3 +990,67 @@ impl PsbtBuilder<AcceptingCurrentKeychainState> {
         }
     }
 }
+
+fn apply_spending_path<D: BatchDatabase, Cs: CoinSelectionAlgorithm<D>, Ctx: TxBuilderContext>(
+    builder: &mut TxBuilder<'_, D, Cs, Ctx>,
+    wallet: &Wallet<D>,
+    spending_path: SpendingPath,
+) -> Result<(), BdkError> {
+    for kind in [KeychainKind::External, KeychainKind::Internal] {
+        if let Some(policy) = wallet.policies(kind)? {
+            let path = select_spending_path(&policy, spending_path);
+            if !path.is_empty() {
+                builder.policy_path(path, kind);
+            }
+        }
+    }
+    if spending_path == SpendingPath::Recovery {
+        exclude_immature_utxos(builder, wallet)?;
+    }
+    Ok(())
+}
+
+/// Marks utxos that haven't been confirmed for the `older()` number of blocks as unspendable
+/// as their inputs would not be final when spent via the timelocked branch.
+/// Before the `after()` height is reached no utxo can be spent via the timelocked branch.
+fn exclude_immature_utxos<
+    D: BatchDatabase,
+    Cs: CoinSelectionAlgorithm<D>,
+    Ctx: TxBuilderContext,
+>(
+    builder: &mut TxBuilder<'_, D, Cs, Ctx>,
+    wallet: &Wallet<D>,
+) -> Result<(), BdkError> {
+    let timelocks =
+        descriptor_timelocks(wallet.get_descriptor_for_keychain(KeychainKind::External));
+    if timelocks.is_empty() {
+        return Ok(());
+    }
+    // The transaction can be mined in the block after the last synced one at the earliest
+    let next_height = wallet
+        .database()
+        .get_sync_time()?
+        .map(|sync_time| sync_time.block_time.height + 1)
+        .unwrap_or(0);
+    // bdk sets the nLockTime to the after() height which is final from the following block
+    let absolute_reached = timelocks
+        .absolute_height
+        .map(|height| height < next_height)
+        .unwrap_or(true);
+    for utxo in wallet.list_unspent()? {
+        let confirmation_height = wallet
+            .get_tx(&utxo.outpoint.txid, false)?
+            .and_then(|tx| tx.confirmation_time)
+            .map(|time| time.height);
+        let relative_reached = match timelocks.relative_blocks {
+            Some(blocks) => confirmation_height
+                .map(|height| height + blocks <= next_height)
+                .unwrap_or(false),
+            None => true,
+        };
+        if !(absolute_reached && relative_reached) {
+            builder.add_unspendable(utxo.outpoint);
+        }
+    }
+    Ok(())
+}
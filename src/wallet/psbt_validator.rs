@@ -11,12 +11,19 @@ pub fn validate_psbt(
     xpub: XPub,
     unsigned_psbt: &psbt::PartiallySignedTransaction,
 ) -> Result<(), WalletError> {
+    let fingerprint = xpub.inner().parent_fingerprint;
     let set: HashSet<_> = signed_psbt
         .inputs
         .iter()
         .flat_map(|inp| &inp.bip32_derivation)
-        .filter_map(|(pk, (fingerprint, _))| {
-            (fingerprint == &xpub.inner().parent_fingerprint).then_some(pk)
+        .filter_map(|(pk, (key_fingerprint, _))| (key_fingerprint == &fingerprint).then_some(pk))
+        .collect();
+    let tap_set: HashSet<_> = signed_psbt
+        .inputs
+        .iter()
+        .flat_map(|inp| &inp.tap_key_origins)
+        .filter_map(|(pk, (_, (key_fingerprint, _)))| {
+            (key_fingerprint == &fingerprint).then_some(pk)
         })
         .collect();
 
@@ -24,16 +31,28 @@ pub fn validate_psbt(
         return Err(WalletError::UnsignedTxnMismatch);
     }
 
-    if !(signed_psbt
+    let all_finalized = signed_psbt
         .inputs
         .iter()
-        .all(|inp| inp.final_script_witness.is_some() || inp.final_script_sig.is_some())
-        || signed_psbt
-            .inputs
-            .iter()
-            .flat_map(|inp| &inp.partial_sigs)
-            .any(|(pk, _)| set.contains(&pk.inner)))
-    {
+        .all(|inp| inp.final_script_witness.is_some() || inp.final_script_sig.is_some());
+    let has_ecdsa_sig = signed_psbt
+        .inputs
+        .iter()
+        .flat_map(|inp| &inp.partial_sigs)
+        .any(|(pk, _)| set.contains(&pk.inner));
+    let has_schnorr_sig = signed_psbt.inputs.iter().any(|inp| {
+        let key_spend_signed = inp.tap_key_sig.is_some()
+            && inp
+                .tap_internal_key
+                .map(|key| tap_set.contains(&key))
+                .unwrap_or(false);
+        key_spend_signed
+            || inp
+                .tap_script_sigs
+                .keys()
+                .any(|(pk, _)| tap_set.contains(pk))
+    });
+    if !(all_finalized || has_ecdsa_sig || has_schnorr_sig) {
         return Err(WalletError::PsbtDoesNotHaveValidSignatures);
     }
     Ok(())
@@ -41,6 +60,8 @@ pub fn validate_psbt(
 
 #[cfg(test)]
 mod tests {
+    use bdk::bitcoin::util::bip32::Fingerprint;
+
     use super::*;
 
     #[test]
@@ -75,4 +96,49 @@ mod tests {
             Err(WalletError::PsbtDoesNotHaveValidSignatures)
         ))
     }
+
+    fn sign_taproot_key_spend(
+        psbt: &mut psbt::PartiallySignedTransaction,
+        fingerprint: Fingerprint,
+    ) {
+        use bdk::bitcoin::{
+            secp256k1::{schnorr, Secp256k1, SecretKey},
+            util::bip32::DerivationPath,
+            SchnorrSig, SchnorrSighashType,
+        };
+        let secp = Secp256k1::new();
+        let secret_key = SecretKey::from_slice(&[1; 32]).unwrap();
+        let (internal_key, _) = secret_key.public_key(&secp).x_only_public_key();
+        let input = &mut psbt.inputs[0];
+        input.tap_internal_key = Some(internal_key);
+        input.tap_key_origins.insert(
+            internal_key,
+            (vec![], (fingerprint, DerivationPath::default())),
+        );
+        input.tap_key_sig = Some(SchnorrSig {
+            sig: schnorr::Signature::from_slice(&[1; 64]).unwrap(),
+            hash_ty: SchnorrSighashType::All,
+        });
+    }
+
+    #[test]
+    fn passes_if_taproot_key_spend_signed_psbt_passed() {
+        let xpub = XPub::try_from(("tpubDE8HT914zGpxhJhgoMX35xgNyjHy5d1neGXHjTLAtuUssTA7tNWNs177JsFPbJwD5FBXCHJYbwUC9AzSEpYHC4hKgaCvZyZTuCbWfNUWXoM", Some("m/48h/1h/0h/2h"))).unwrap();
+        let unsigned_psbt = "cHNidP8BAH0BAAAAASNihqnLFfz7pHt1zDeB/iB7ku75Ah6EFaFhQZnbErt9AAAAAAD+////Ap13fQEAAAAAIgAgO37beKyitaViJwyjZ3oTIwdBU0JTbBRa32V1zvdifQzAaHgEAAAAABYAFFPOvhKDbGzCHM0LNEHgSPJjuf7RzQAAAAABAPYCAAAAAAEBTEYh+JWYBjbSBgwY+QxYOE25/vFk5zdS61jKtc1HJjYAAAAAAP3///8CAOH1BQAAAAAiACCOipWPCjso1EpZQctqUeF6N4QjTNQ3c+15axzGinwSVhwEECQBAAAAIlEgzmi+Ha7O7p08hrHEzLrq68MJlSDW40V39kbqS+ArmTMCRzBEAiB5fcQ8lx7fp+Calgy7o9jQEsHEPho0zfP13TQsCC2/GgIgSL/zyp0nz5PzdMXxhgBJ59O2t7tUhAfKxBYtVjMYXR0BIQN39pz1kuRtgfVu5SMba1rXL5HXDIKq4/rq7I/342+/GsgAAAABASsA4fUFAAAAACIAII6KlY8KOyjUSllBy2pR4Xo3hCNM1Ddz7XlrHMaKfBJWAQMEAQAAAAEFR1EhAlBn4VwHril4Da/2rGzF/FZnM0gnAi5M7A7iHMxMA4tIIQKXjwOvzjfb1Y0HMvH2Bc2Eqtukx+dxd4V8qOus23qGhlKuIgYCUGfhXAeuKXgNr/asbMX8VmczSCcCLkzsDuIczEwDi0gcmFPdqTAAAIABAACAAAAAgAIAAIAAAAAAAAAAACIGApePA6/ON9vVjQcy8fYFzYSq26TH53F3hXyo66zbeoaGHB3opBwwAACAAQAAgAAAAIACAACAAAAAAAAAAAAAAQFHUSECXDnAvMuAqtaBxRvWWRK4cOeJCmnxrHmzX7Ys+TOgLkMhAsl2+NBf0WNXB5Dyu/j0+luIVYCV+21GR7hPI2AUvisiUq4iAgJcOcC8y4Cq1oHFG9ZZErhw54kKafGsebNftiz5M6AuQxyYU92pMAAAgAEAAIAAAACAAgAAgAEAAAAAAAAAIgICyXb40F/RY1cHkPK7+PT6W4hVgJX7bUZHuE8jYBS+KyIcHeikHDAAAIABAACAAAAAgAIAAIABAAAAAAAAAAAA".parse::<psbt::PartiallySignedTransaction>().unwrap();
+        let mut signed_psbt = unsigned_psbt.clone();
+        sign_taproot_key_spend(&mut signed_psbt, xpub.inner().parent_fingerprint);
+        assert!(validate_psbt(&signed_psbt, xpub, &unsigned_psbt).is_ok());
+    }
+
+    #[test]
+    fn fails_if_taproot_signature_of_other_key_passed() {
+        let xpub = XPub::try_from(("tpubDE8HT914zGpxhJhgoMX35xgNyjHy5d1neGXHjTLAtuUssTA7tNWNs177JsFPbJwD5FBXCHJYbwUC9AzSEpYHC4hKgaCvZyZTuCbWfNUWXoM", Some("m/48h/1h/0h/2h"))).unwrap();
+        let unsigned_psbt = "cHNidP8BAH0BAAAAASNihqnLFfz7pHt1zDeB/iB7ku75Ah6EFaFhQZnbErt9AAAAAAD+////Ap13fQEAAAAAIgAgO37beKyitaViJwyjZ3oTIwdBU0JTbBRa32V1zvdifQzAaHgEAAAAABYAFFPOvhKDbGzCHM0LNEHgSPJjuf7RzQAAAAABAPYCAAAAAAEBTEYh+JWYBjbSBgwY+QxYOE25/vFk5zdS61jKtc1HJjYAAAAAAP3///8CAOH1BQAAAAAiACCOipWPCjso1EpZQctqUeF6N4QjTNQ3c+15axzGinwSVhwEECQBAAAAIlEgzmi+Ha7O7p08hrHEzLrq68MJlSDW40V39kbqS+ArmTMCRzBEAiB5fcQ8lx7fp+Calgy7o9jQEsHEPho0zfP13TQsCC2/GgIgSL/zyp0nz5PzdMXxhgBJ59O2t7tUhAfKxBYtVjMYXR0BIQN39pz1kuRtgfVu5SMba1rXL5HXDIKq4/rq7I/342+/GsgAAAABASsA4fUFAAAAACIAII6KlY8KOyjUSllBy2pR4Xo3hCNM1Ddz7XlrHMaKfBJWAQMEAQAAAAEFR1EhAlBn4VwHril4Da/2rGzF/FZnM0gnAi5M7A7iHMxMA4tIIQKXjwOvzjfb1Y0HMvH2Bc2Eqtukx+dxd4V8qOus23qGhlKuIgYCUGfhXAeuKXgNr/asbMX8VmczSCcCLkzsDuIczEwDi0gcmFPdqTAAAIABAACAAAAAgAIAAIAAAAAAAAAAACIGApePA6/ON9vVjQcy8fYFzYSq26TH53F3hXyo66zbeoaGHB3opBwwAACAAQAAgAAAAIACAACAAAAAAAAAAAAAAQFHUSECXDnAvMuAqtaBxRvWWRK4cOeJCmnxrHmzX7Ys+TOgLkMhAsl2+NBf0WNXB5Dyu/j0+luIVYCV+21GR7hPI2AUvisiUq4iAgJcOcC8y4Cq1oHFG9ZZErhw54kKafGsebNftiz5M6AuQxyYU92pMAAAgAEAAIAAAACAAgAAgAEAAAAAAAAAIgICyXb40F/RY1cHkPK7+PT6W4hVgJX7bUZHuE8jYBS+KyIcHeikHDAAAIABAACAAAAAgAIAAIABAAAAAAAAAAAA".parse::<psbt::PartiallySignedTransaction>().unwrap();
+        let mut signed_psbt = unsigned_psbt.clone();
+        sign_taproot_key_spend(&mut signed_psbt, Fingerprint::from(&[0; 4][..]));
+        assert!(matches!(
+            validate_psbt(&signed_psbt, xpub, &unsigned_psbt),
+            Err(WalletError::PsbtDoesNotHaveValidSignatures)
+        ));
+    }
 }
@@ -37,6 +37,23 @@ impl Wallets {
         Ok(WalletId::from(record.id))
     }
 
+    pub async fn update_in_tx(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        wallet: Wallet,
+    ) -> Result<(), WalletError> {
+        if !wallet.events.is_dirty() {
+            return Ok(());
+        }
+        EntityEvents::<WalletEvent>::persist(
+            "bria_wallet_events",
+            tx,
+            wallet.events.new_serialized_events(wallet.id),
+        )
+        .await?;
+        Ok(())
+    }
+
     pub async fn find_by_name(
         &self,
         account_id: AccountId,
@@ -79,6 +96,32 @@ impl Wallets {
         }))
     }
 
+    /// Ids of the wallets whose current config has a consolidation policy
+    pub async fn all_ids_with_consolidation_policy(
+        &self,
+    ) -> Result<impl Iterator<Item = (AccountId, WalletId)>, WalletError> {
+        let rows = sqlx::query!(
+            r#"
+              SELECT account_id AS "account_id!", id AS "wallet_id!"
+              FROM (
+                SELECT DISTINCT ON (b.id) b.account_id, b.id, e.event
+                FROM bria_wallets b
+                JOIN bria_wallet_events e ON b.id = e.id
+                WHERE e.event_type = 'config_updated'
+                ORDER BY b.id, e.sequence DESC
+              ) latest
+              WHERE jsonb_typeof(latest.event->'wallet_config'->'consolidation') = 'object'"#,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows.into_iter().map(|row| {
+            (
+                AccountId::from(row.account_id),
+                WalletId::from(row.wallet_id),
+            )
+        }))
+    }
+
     pub async fn find_by_id(&self, id: WalletId) -> Result<Wallet, WalletError> {
         let ids: HashSet<WalletId> = std::iter::once(id).collect();
         if let Some(wallet) = self.find_by_ids(ids).await?.remove(&id) {
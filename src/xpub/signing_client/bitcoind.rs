@@ -43,10 +43,17 @@ impl RemoteSigningClient for BitcoindRemoteSigner {
         let raw_psbt = consensus::encode::serialize(&psbt);
         let hex_psbt = general_purpose::STANDARD.encode(raw_psbt);
 
-        let sighash_type = Some(DEFAULT_SIGHASH_TYPE.into());
+        // bitcoind refuses to sign (taproot inputs included) when the requested sighash
+        // differs from the one stored in the psbt
+        let sighash_type = psbt
+            .inputs
+            .iter()
+            .find_map(|input| input.sighash_type)
+            .and_then(|sighash_type| sighash_type.ecdsa_hash_ty().ok())
+            .unwrap_or(DEFAULT_SIGHASH_TYPE);
         let response = self
             .inner
-            .wallet_process_psbt(&hex_psbt, None, sighash_type, None)
+            .wallet_process_psbt(&hex_psbt, None, Some(sighash_type.into()), None)
             .map_err(|e| {
                 SigningClientError::RemoteCallFailure(format!(
                     "Failed to sign psbt via bitcoind: {e}"
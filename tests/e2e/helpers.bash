@@ -110,6 +110,9 @@ bitcoind_init() {
     bitcoin_signer_cli -rpcwallet=multisig importdescriptors "$(cat ${REPO_ROOT}/tests/e2e/bitcoind_multisig_signer_descriptors.json)"
     bitcoin_signer_cli createwallet "multisig2" || true 
     bitcoin_signer_cli -rpcwallet=multisig2 importdescriptors "$(cat ${REPO_ROOT}/tests/e2e/bitcoind_multisig2_signer_descriptors.json)"
+  elif [[ "${wallet}" == "taproot" ]]; then
+    bitcoin_signer_cli createwallet "taproot" || true
+    bitcoin_signer_cli -rpcwallet=taproot importdescriptors "$(cat ${REPO_ROOT}/tests/e2e/bitcoind_taproot_signer_descriptors.json)"
   fi
 }
 
@@ -154,6 +157,9 @@ bria_init() {
     $retry_cmd bria_cmd import-xpub -x "${key1}" -n key1 -d m/48h/1h/0h/2h
     bria_cmd import-xpub -x "${key2}" -n key2 -d m/48h/1h/0h/2h
     bria_cmd create-wallet -n multisig sorted-multisig -x key1 key2 -t 2
+  elif [[ "${wallet_type}" == "taproot" ]]; then
+    $retry_cmd bria_cmd import-xpub -x tpubDDDDGYiFda8HfJRc2AHFJDxVzzEtBPrKsbh35EaW2UGd5qfzrF2G87ewAgeeRyHEz4iB3kvhAYW1sH6dpLepTkFUzAktumBN8AXeXWE9nd1 -n tr_key -d m/84h/0h/0h
+    bria_cmd create-wallet -n taproot tr -x tr_key
   fi
 
   echo "Bria Initialization Complete"
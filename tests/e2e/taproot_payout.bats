@@ -0,0 +1,89 @@
+#!/usr/bin/env bats
+
+load "helpers"
+
+setup_file() {
+  restart_bitcoin_stack
+  reset_pg
+  bitcoind_init taproot
+  start_daemon
+  bria_init taproot
+}
+
+teardown_file() {
+  stop_daemon
+}
+
+@test "taproot_payout: Generates the same address as the bitcoind signer" {
+  bitcoind_signer_address=$(bitcoin_signer_cli -rpcwallet=taproot getnewaddress "" bech32m)
+  bria_address=$(bria_cmd new-address -w taproot | jq -r '.address')
+
+  [ "$bitcoind_signer_address" = "$bria_address" ] || exit 1
+}
+
+@test "taproot_payout: Fund an address and see if the balance is reflected" {
+  bria_address=$(bria_cmd new-address -w taproot | jq -r '.address')
+  if [ -z "$bria_address" ]; then
+    echo "Failed to get a new address"
+    exit 1
+  fi
+
+  bitcoin_cli -regtest sendtoaddress ${bria_address} 1
+
+  for i in {1..30}; do
+    n_utxos=$(bria_cmd list-utxos -w taproot | jq '.keychains[0].utxos | length')
+    [[ "${n_utxos}" == "1" ]] && break
+    sleep 1
+  done
+
+  cache_wallet_balance taproot
+  [[ $(cached_encumbered_fees) != 0 ]] || exit 1
+  [[ $(cached_pending_income) == 100000000 ]] || exit 1;
+}
+
+@test "taproot_payout: Bitcoind signer signs the key path spend" {
+  bria_cmd create-payout-queue --name high --interval-trigger 5
+  bria_cmd submit-payout --wallet taproot --queue-name high --destination bcrt1q208tuy5rd3kvy8xdpv6yrczg7f3mnlk3lql7ej --amount 75000000
+  bitcoin_cli -generate 5
+
+  for i in {1..20}; do
+    batch_id=$(bria_cmd list-payouts -w taproot | jq -r '.payouts[0].batchId')
+    [[ "${batch_id}" != "null" ]] && break
+    sleep 1
+  done
+  [[ "${batch_id}" != "null" ]] || exit 1
+
+  bria_cmd set-signer-config \
+    --xpub tr_key bitcoind \
+    --endpoint "${BITCOIND_SIGNER_ENDPOINT}"/wallet/taproot \
+    --rpc-user "rpcuser" \
+    --rpc-password "rpcpassword"
+
+  for i in {1..20}; do
+    signing_status=$(bria_cmd get-batch -b "${batch_id}" | jq -r '.signingSessions[0].state')
+    [[ "${signing_status}" == "Complete" ]] && break
+    sleep 1
+  done
+  if [[ "${signing_status}" != "Complete" ]]; then
+    signing_failure_reason=$(bria_cmd get-batch -b "${batch_id}" | jq -r '.signingSessions[0].failureReason')
+    echo "signing_status: ${signing_status}"
+    echo "signing_failure_reason: ${signing_failure_reason}"
+  fi
+  [[ "${signing_status}" == "Complete" ]] || exit 1
+
+  for i in {1..20}; do
+    cache_wallet_balance taproot
+    [[ $(cached_pending_income) != 0 ]] && break;
+    sleep 1
+  done
+  [[ $(cached_pending_income) != 0 ]] || exit 1
+
+  bitcoin_cli -generate 2
+
+  for i in {1..20}; do
+    cache_wallet_balance taproot
+    [[ $(cached_current_settled) != 0 ]] && break;
+    sleep 1
+  done
+  [[ $(cached_current_settled) != 0 ]] || exit 1;
+}
@@ -27,20 +27,15 @@ pub async fn init_pool() -> anyhow::Result<sqlx::PgPool> {
     Ok(pool)
 }
 
-pub async fn create_test_account(pool: &sqlx::PgPool) -> anyhow::Result<Profile> {
+pub async fn create_test_account(pool: &sqlx::PgPool) -> anyhow::Result<AuthenticatedProfile> {
     let name = format!(
         "TEST_{}",
         Alphanumeric.sample_string(&mut rand::thread_rng(), 32)
     );
     let app = AdminApp::new(pool.clone(), bitcoin::Network::Regtest);
 
-    let profile_key = app.create_account(name.clone()).await?;
-    Ok(Profile {
-        id: profile_key.profile_id,
-        account_id: profile_key.account_id,
-        name,
-        spending_policy: None,
-    })
+    let profile_key = app.create_account(name).await?;
+    Ok(Profiles::new(pool).find_by_key(&profile_key.key).await?)
 }
 
 pub async fn bitcoind_client() -> anyhow::Result<bitcoincore_rpc::Client> {
@@ -172,6 +167,14 @@ pub async fn electrum_blockchain() -> anyhow::Result<ElectrumBlockchain> {
     }
 }
 
+pub fn random_tpub() -> String {
+    let seed: [u8; 32] = rand::random();
+    let xpriv =
+        bdk::bitcoin::util::bip32::ExtendedPrivKey::new_master(bitcoin::Network::Regtest, &seed)
+            .expect("Couldn't create xpriv");
+    bdk::bitcoin::util::bip32::ExtendedPubKey::from_priv(&Secp256k1::new(), &xpriv).to_string()
+}
+
 pub fn random_bdk_wallet() -> anyhow::Result<bdk::Wallet<MemoryDatabase>> {
     let secp = Secp256k1::new();
     let sk: GeneratedKey<PrivateKey, Segwitv0> =
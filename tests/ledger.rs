@@ -298,6 +298,7 @@ async fn queue_payout() -> anyhow::Result<()> {
                     destination: PayoutDestination::OnchainAddress {
                         value: "1A1zP1eP5QGefi2DMPTfTL5SLmv7DivfNa".parse().unwrap(),
                     },
+                    fee_from_amount: false,
                 },
             },
         )
@@ -680,6 +681,271 @@ async fn spend_detected_unconfirmed() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[tokio::test]
+async fn utxo_quarantined_and_released() -> anyhow::Result<()> {
+    let pool = helpers::init_pool().await?;
+
+    let ledger = Ledger::init(&pool).await?;
+
+    let account_id = AccountId::new();
+    let name = Alphanumeric.sample_string(&mut rand::thread_rng(), 32);
+    let mut tx = pool.begin().await?;
+    let journal_id = ledger
+        .create_journal_for_account(&mut tx, account_id, name.clone())
+        .await?;
+    let wallet_id = WalletId::new();
+    let wallet_ledger_accounts = ledger
+        .create_ledger_accounts_for_wallet(&mut tx, wallet_id)
+        .await?;
+
+    let one_btc = Satoshis::from(100_000_000);
+    let one_sat = Satoshis::from(1);
+    let zero = Satoshis::from(0);
+    let address: bitcoin::Address = "1A1zP1eP5QGefi2DMPTfTL5SLmv7DivfNa".parse().unwrap();
+    let blocked: bitcoin::Address = "bc1qar0srrr7xfkvy5l643lydnw9re59gtzzwf5mdq"
+        .parse()
+        .unwrap();
+    let outpoint = OutPoint {
+        txid: "4010e27ff7dc6d9c66a5657e6b3d94b4c4e394d968398d16fefe4637463d194d"
+            .parse()
+            .unwrap(),
+        vout: 0,
+    };
+
+    let keychain_id = KeychainId::new();
+    let pending_id = LedgerTransactionId::new();
+
+    ledger
+        .utxo_detected(
+            tx,
+            pending_id,
+            UtxoDetectedParams {
+                journal_id,
+                onchain_incoming_account_id: wallet_ledger_accounts.onchain_incoming_id,
+                onchain_fee_account_id: wallet_ledger_accounts.fee_id,
+                effective_incoming_account_id: wallet_ledger_accounts.effective_incoming_id,
+                meta: UtxoDetectedMeta {
+                    account_id,
+                    wallet_id,
+                    keychain_id,
+                    outpoint,
+                    satoshis: one_btc,
+                    address: address.clone(),
+                    encumbered_spending_fees: std::iter::once((outpoint, one_sat)).collect(),
+                    confirmation_time: None,
+                },
+            },
+        )
+        .await?;
+
+    let tx = pool.begin().await?;
+    ledger
+        .utxo_quarantined(
+            tx,
+            LedgerTransactionId::new(),
+            UtxoQuarantinedParams {
+                journal_id,
+                ledger_account_ids: wallet_ledger_accounts,
+                pending_id,
+                meta: UtxoQuarantinedMeta {
+                    account_id,
+                    wallet_id,
+                    keychain_id,
+                    outpoint,
+                    satoshis: one_btc,
+                    address,
+                    confirmation_time: BlockTime {
+                        height: 1,
+                        timestamp: 123409,
+                    },
+                    blocked_sources: vec![blocked],
+                },
+            },
+        )
+        .await?;
+
+    let summary = WalletBalanceSummary::from(
+        ledger
+            .get_wallet_ledger_account_balances(journal_id, wallet_ledger_accounts)
+            .await?,
+    );
+
+    assert_eq!(summary.utxo_pending_incoming, zero);
+    assert_eq!(summary.effective_pending_income, zero);
+    assert_eq!(summary.utxo_settled, one_btc);
+    assert_eq!(summary.effective_settled, zero);
+    assert_eq!(summary.effective_quarantined, one_btc);
+
+    let tx = pool.begin().await?;
+    ledger
+        .utxo_quarantine_released(
+            tx,
+            LedgerTransactionId::new(),
+            UtxoQuarantineReleasedParams {
+                journal_id,
+                ledger_account_ids: wallet_ledger_accounts,
+                meta: UtxoQuarantineReleasedMeta {
+                    account_id,
+                    wallet_id,
+                    keychain_id,
+                    outpoint,
+                    satoshis: one_btc,
+                    released_by_profile_id: ProfileId::new(),
+                },
+            },
+        )
+        .await?;
+
+    let summary = WalletBalanceSummary::from(
+        ledger
+            .get_wallet_ledger_account_balances(journal_id, wallet_ledger_accounts)
+            .await?,
+    );
+
+    assert_eq!(summary.utxo_settled, one_btc);
+    assert_eq!(summary.effective_settled, one_btc);
+    assert_eq!(summary.effective_quarantined, zero);
+
+    let account_summary = AccountBalanceSummary::from(
+        ledger
+            .get_account_ledger_account_balances(journal_id)
+            .await?,
+    );
+    assert_summaries_match(summary, account_summary);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn utxo_dust_settled_and_swept() -> anyhow::Result<()> {
+    let pool = helpers::init_pool().await?;
+
+    let ledger = Ledger::init(&pool).await?;
+
+    let account_id = AccountId::new();
+    let name = Alphanumeric.sample_string(&mut rand::thread_rng(), 32);
+    let mut tx = pool.begin().await?;
+    let journal_id = ledger
+        .create_journal_for_account(&mut tx, account_id, name.clone())
+        .await?;
+    let wallet_id = WalletId::new();
+    let wallet_ledger_accounts = ledger
+        .create_ledger_accounts_for_wallet(&mut tx, wallet_id)
+        .await?;
+
+    let dust = Satoshis::from(500);
+    let one_sat = Satoshis::from(1);
+    let zero = Satoshis::from(0);
+    let address: bitcoin::Address = "1A1zP1eP5QGefi2DMPTfTL5SLmv7DivfNa".parse().unwrap();
+    let outpoint = OutPoint {
+        txid: "4010e27ff7dc6d9c66a5657e6b3d94b4c4e394d968398d16fefe4637463d194d"
+            .parse()
+            .unwrap(),
+        vout: 0,
+    };
+
+    let keychain_id = KeychainId::new();
+    let pending_id = LedgerTransactionId::new();
+
+    ledger
+        .utxo_detected(
+            tx,
+            pending_id,
+            UtxoDetectedParams {
+                journal_id,
+                onchain_incoming_account_id: wallet_ledger_accounts.onchain_incoming_id,
+                onchain_fee_account_id: wallet_ledger_accounts.fee_id,
+                effective_incoming_account_id: wallet_ledger_accounts.effective_incoming_id,
+                meta: UtxoDetectedMeta {
+                    account_id,
+                    wallet_id,
+                    keychain_id,
+                    outpoint,
+                    satoshis: dust,
+                    address: address.clone(),
+                    encumbered_spending_fees: std::iter::once((outpoint, one_sat)).collect(),
+                    confirmation_time: None,
+                },
+            },
+        )
+        .await?;
+
+    let tx = pool.begin().await?;
+    ledger
+        .utxo_dust_settled(
+            tx,
+            LedgerTransactionId::new(),
+            UtxoDustSettledParams {
+                journal_id,
+                ledger_account_ids: wallet_ledger_accounts,
+                pending_id,
+                meta: UtxoDustSettledMeta {
+                    account_id,
+                    wallet_id,
+                    keychain_id,
+                    outpoint,
+                    satoshis: dust,
+                    address,
+                    confirmation_time: BlockTime {
+                        height: 1,
+                        timestamp: 123409,
+                    },
+                },
+            },
+        )
+        .await?;
+
+    let summary = WalletBalanceSummary::from(
+        ledger
+            .get_wallet_ledger_account_balances(journal_id, wallet_ledger_accounts)
+            .await?,
+    );
+
+    assert_eq!(summary.utxo_pending_incoming, zero);
+    assert_eq!(summary.effective_pending_income, zero);
+    assert_eq!(summary.utxo_settled, dust);
+    assert_eq!(summary.effective_settled, zero);
+    assert_eq!(summary.effective_dust, dust);
+
+    let tx = pool.begin().await?;
+    ledger
+        .dust_swept(
+            tx,
+            LedgerTransactionId::new(),
+            DustSweptParams {
+                journal_id,
+                ledger_account_ids: wallet_ledger_accounts,
+                meta: DustSweptMeta {
+                    account_id,
+                    wallet_id,
+                    keychain_id,
+                    outpoint,
+                    satoshis: dust,
+                },
+            },
+        )
+        .await?;
+
+    let summary = WalletBalanceSummary::from(
+        ledger
+            .get_wallet_ledger_account_balances(journal_id, wallet_ledger_accounts)
+            .await?,
+    );
+
+    assert_eq!(summary.utxo_settled, dust);
+    assert_eq!(summary.effective_settled, dust);
+    assert_eq!(summary.effective_dust, zero);
+
+    let account_summary = AccountBalanceSummary::from(
+        ledger
+            .get_account_ledger_account_balances(journal_id)
+            .await?,
+    );
+    assert_summaries_match(summary, account_summary);
+
+    Ok(())
+}
+
 fn assert_summaries_match(wallet: WalletBalanceSummary, account: AccountBalanceSummary) {
     assert_eq!(
         wallet.effective_pending_outgoing,
@@ -822,6 +1088,7 @@ async fn payout_cancelled() -> anyhow::Result<()> {
                     destination: PayoutDestination::OnchainAddress {
                         value: "1A1zP1eP5QGefi2DMPTfTL5SLmv7DivfNa".parse().unwrap(),
                     },
+                    fee_from_amount: false,
                 },
             },
         )
@@ -4,8 +4,9 @@ use rand::distributions::{Alphanumeric, DistString};
 
 use bria::{
     app::{error::ApplicationError, *},
+    payout::{error::PayoutError, PayoutSchedule},
     primitives::*,
-    profile::SpendingPolicy,
+    profile::{ApprovalPolicy, SpendingLimit, SpendingLimitWindow, SpendingPolicy},
     xpub::*,
 };
 
@@ -32,7 +33,7 @@ async fn payout() -> anyhow::Result<()> {
         .await?;
 
     let app = App::run(pool, AppConfig::default()).await?;
-    app.create_wpkh_wallet(&profile, wallet_name.clone(), id.to_string(), None)
+    app.create_wpkh_wallet(&profile, wallet_name.clone(), id.to_string(), None, None)
         .await?;
 
     let group_name = Alphanumeric.sample_string(&mut rand::thread_rng(), 32);
@@ -48,6 +49,9 @@ async fn payout() -> anyhow::Result<()> {
             group_name,
             address,
             Satoshis::from(10000),
+            false,
+            false,
+            PayoutSchedule::default(),
             None,
             None,
         )
@@ -79,7 +83,7 @@ async fn spending_policy() -> anyhow::Result<()> {
         .await?;
 
     let app = App::run(pool, AppConfig::default()).await?;
-    app.create_wpkh_wallet(&profile, wallet_name.clone(), id.to_string(), None)
+    app.create_wpkh_wallet(&profile, wallet_name.clone(), id.to_string(), None, None)
         .await?;
 
     let queue_name = Alphanumeric.sample_string(&mut rand::thread_rng(), 32);
@@ -97,6 +101,8 @@ async fn spending_policy() -> anyhow::Result<()> {
             wallet_name.clone(),
             Some(SpendingPolicy {
                 allowed_payout_addresses: vec![address.clone()],
+                approval: None,
+                limits: Vec::new(),
             }),
         )
         .await?;
@@ -108,6 +114,9 @@ async fn spending_policy() -> anyhow::Result<()> {
             queue_name.clone(),
             address,
             Satoshis::from(10000),
+            false,
+            false,
+            PayoutSchedule::default(),
             None,
             None,
         )
@@ -124,6 +133,9 @@ async fn spending_policy() -> anyhow::Result<()> {
             queue_name,
             address,
             Satoshis::from(10000),
+            false,
+            false,
+            PayoutSchedule::default(),
             None,
             None,
         )
@@ -135,3 +147,305 @@ async fn spending_policy() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+#[tokio::test]
+async fn payout_approval() -> anyhow::Result<()> {
+    let pool = helpers::init_pool().await?;
+    let profile = helpers::create_test_account(&pool).await?;
+
+    let original = "tpubDD4vFnWuTMEcZiaaZPgvzeGyMzWe6qHW8gALk5Md9kutDvtdDjYFwzauEFFRHgov8pAwup5jX88j5YFyiACsPf3pqn5hBjvuTLRAseaJ6b4";
+    let xpub = XPub::try_from((original, Some("m/84'/0'/0'"))).unwrap();
+    let wallet_name = Alphanumeric.sample_string(&mut rand::thread_rng(), 32);
+    let repo = XPubs::new(&pool);
+
+    let id = repo
+        .persist(
+            NewAccountXPub::builder()
+                .account_id(profile.account_id)
+                .original(original.to_owned())
+                .key_name(wallet_name.clone())
+                .value(xpub)
+                .build()
+                .unwrap(),
+        )
+        .await?;
+
+    let app = App::run(pool, AppConfig::default()).await?;
+    app.create_wpkh_wallet(&profile, wallet_name.clone(), id.to_string(), None, None)
+        .await?;
+
+    let queue_name = Alphanumeric.sample_string(&mut rand::thread_rng(), 32);
+    let _ = app
+        .create_payout_queue(&profile, queue_name.clone(), None, None)
+        .await?;
+
+    let address = "mgWUuj1J1N882jmqFxtDepEC73Rr22E9GU"
+        .parse::<bitcoin::Address>()
+        .unwrap();
+
+    let spending_profile = app
+        .create_profile(
+            &profile,
+            wallet_name.clone(),
+            Some(SpendingPolicy {
+                allowed_payout_addresses: vec![address.clone()],
+                approval: Some(ApprovalPolicy {
+                    required_approvals: 1,
+                    threshold_sats: Satoshis::from(50000),
+                }),
+                limits: Vec::new(),
+            }),
+        )
+        .await?;
+
+    let (small_id, _) = app
+        .submit_payout_to_address(
+            &spending_profile,
+            wallet_name.clone(),
+            queue_name.clone(),
+            address.clone(),
+            Satoshis::from(10000),
+            false,
+            false,
+            PayoutSchedule::default(),
+            None,
+            None,
+        )
+        .await?;
+    let payout = app.find_payout(&profile, small_id).await?;
+    assert_eq!(payout.required_approvals, 0);
+    assert!(payout.is_approved());
+
+    let (large_id, _) = app
+        .submit_payout_to_address(
+            &spending_profile,
+            wallet_name,
+            queue_name,
+            address,
+            Satoshis::from(100000),
+            false,
+            false,
+            PayoutSchedule::default(),
+            None,
+            None,
+        )
+        .await?;
+    let payout = app.find_payout(&profile, large_id).await?;
+    assert_eq!(payout.required_approvals, 1);
+    assert!(!payout.is_approved());
+
+    let res = app.approve_payout(&spending_profile, large_id).await;
+    assert!(matches!(
+        res,
+        Err(ApplicationError::PayoutError(
+            PayoutError::SubmitterCannotApprove
+        ))
+    ));
+
+    app.approve_payout(&profile, large_id).await?;
+    let payout = app.find_payout(&profile, large_id).await?;
+    assert!(payout.is_approved());
+    assert_eq!(payout.approved_by, vec![profile.id]);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn spending_limits() -> anyhow::Result<()> {
+    let pool = helpers::init_pool().await?;
+    let profile = helpers::create_test_account(&pool).await?;
+
+    let original = "tpubDD4vFnWuTMEcZiaaZPgvzeGyMzWe6qHW8gALk5Md9kutDvtdDjYFwzauEFFRHgov8pAwup5jX88j5YFyiACsPf3pqn5hBjvuTLRAseaJ6b4";
+    let xpub = XPub::try_from((original, Some("m/84'/0'/0'"))).unwrap();
+    let wallet_name = Alphanumeric.sample_string(&mut rand::thread_rng(), 32);
+    let repo = XPubs::new(&pool);
+
+    let id = repo
+        .persist(
+            NewAccountXPub::builder()
+                .account_id(profile.account_id)
+                .original(original.to_owned())
+                .key_name(wallet_name.clone())
+                .value(xpub)
+                .build()
+                .unwrap(),
+        )
+        .await?;
+
+    let app = App::run(pool, AppConfig::default()).await?;
+    app.create_wpkh_wallet(&profile, wallet_name.clone(), id.to_string(), None, None)
+        .await?;
+
+    let queue_name = Alphanumeric.sample_string(&mut rand::thread_rng(), 32);
+    let _ = app
+        .create_payout_queue(&profile, queue_name.clone(), None, None)
+        .await?;
+
+    let address = "mgWUuj1J1N882jmqFxtDepEC73Rr22E9GU"
+        .parse::<bitcoin::Address>()
+        .unwrap();
+
+    let spending_profile = app
+        .create_profile(
+            &profile,
+            wallet_name.clone(),
+            Some(SpendingPolicy {
+                allowed_payout_addresses: vec![address.clone()],
+                approval: None,
+                limits: vec![SpendingLimit {
+                    wallet_name: Some(wallet_name.clone()),
+                    max_payout_sats: Some(Satoshis::from(30000)),
+                    max_sats_per_hour: None,
+                    max_sats_per_day: Some(Satoshis::from(50000)),
+                }],
+            }),
+        )
+        .await?;
+
+    let submit = |sats: u64| {
+        app.submit_payout_to_address(
+            &spending_profile,
+            wallet_name.clone(),
+            queue_name.clone(),
+            address.clone(),
+            Satoshis::from(sats),
+            false,
+            false,
+            PayoutSchedule::default(),
+            None,
+            None,
+        )
+    };
+
+    let res = submit(40000).await;
+    match res {
+        Err(ApplicationError::SpendingLimitExceeded(SpendingLimitWindow::Payout, remaining)) => {
+            assert_eq!(remaining, Satoshis::from(30000))
+        }
+        _ => panic!("expected per payout spending limit to be exceeded"),
+    }
+
+    submit(30000).await?;
+    let res = submit(40000).await;
+    match res {
+        Err(ApplicationError::SpendingLimitExceeded(SpendingLimitWindow::Day, remaining)) => {
+            assert_eq!(remaining, Satoshis::from(20000))
+        }
+        _ => panic!("expected the remaining daily allowance to be reported"),
+    }
+    let res = submit(30000).await;
+    match res {
+        Err(ApplicationError::SpendingLimitExceeded(SpendingLimitWindow::Day, remaining)) => {
+            assert_eq!(remaining, Satoshis::from(20000))
+        }
+        _ => panic!("expected daily spending limit to be exceeded"),
+    }
+    submit(20000).await?;
+
+    app.update_profile(
+        &profile,
+        spending_profile.id,
+        None,
+        Some(SpendingPolicy {
+            allowed_payout_addresses: vec![address.clone()],
+            approval: None,
+            limits: Vec::new(),
+        }),
+        None,
+    )
+    .await?;
+    let spending_profile = app
+        .list_profiles(&profile)
+        .await?
+        .into_iter()
+        .find(|p| p.id == spending_profile.id)
+        .unwrap();
+    assert!(spending_profile.spending_policy.unwrap().limits.is_empty());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn submit_payouts() -> anyhow::Result<()> {
+    let pool = helpers::init_pool().await?;
+    let profile = helpers::create_test_account(&pool).await?;
+
+    let original = "tpubDD4vFnWuTMEcZiaaZPgvzeGyMzWe6qHW8gALk5Md9kutDvtdDjYFwzauEFFRHgov8pAwup5jX88j5YFyiACsPf3pqn5hBjvuTLRAseaJ6b4";
+    let xpub = XPub::try_from((original, Some("m/84'/0'/0'"))).unwrap();
+    let wallet_name = Alphanumeric.sample_string(&mut rand::thread_rng(), 32);
+    let repo = XPubs::new(&pool);
+
+    let id = repo
+        .persist(
+            NewAccountXPub::builder()
+                .account_id(profile.account_id)
+                .original(original.to_owned())
+                .key_name(wallet_name.clone())
+                .value(xpub)
+                .build()
+                .unwrap(),
+        )
+        .await?;
+
+    let app = App::run(pool, AppConfig::default()).await?;
+    app.create_wpkh_wallet(&profile, wallet_name.clone(), id.to_string(), None, None)
+        .await?;
+
+    let group_name = Alphanumeric.sample_string(&mut rand::thread_rng(), 32);
+    let _ = app
+        .create_payout_queue(&profile, group_name.clone(), None, None)
+        .await?;
+
+    let external_id = Alphanumeric.sample_string(&mut rand::thread_rng(), 32);
+    let submissions = || {
+        (0..3)
+            .map(|_| PayoutSubmission {
+                wallet_name: wallet_name.clone(),
+                payout_queue_name: group_name.clone(),
+                destination: PayoutSubmissionDestination::OnchainAddress(
+                    "mgWUuj1J1N882jmqFxtDepEC73Rr22E9GU".parse().unwrap(),
+                ),
+                satoshis: Satoshis::from(10000),
+                fee_from_amount: false,
+                schedule: PayoutSchedule::default(),
+                external_id: Some(external_id.clone()),
+                metadata: None,
+            })
+            .collect::<Vec<_>>()
+    };
+
+    let result = app.submit_payouts(&profile, submissions(), true).await?;
+    assert!(result.submitted.is_empty());
+    assert_eq!(
+        result
+            .failed
+            .iter()
+            .map(|(idx, _)| *idx)
+            .collect::<Vec<_>>(),
+        vec![1, 2]
+    );
+
+    let result = app.submit_payouts(&profile, submissions(), false).await?;
+    assert_eq!(result.submitted.len(), 1);
+    assert_eq!(result.submitted[0].0, 0);
+    assert_eq!(result.failed.len(), 2);
+
+    let payout = app
+        .find_payout_by_external_id(&profile, external_id.clone())
+        .await?;
+    assert_eq!(payout.id, result.submitted[0].1);
+
+    // The external id is taken now so persisting the first entry fails as well
+    let result = app.submit_payouts(&profile, submissions(), false).await?;
+    assert!(result.submitted.is_empty());
+    assert_eq!(
+        result
+            .failed
+            .iter()
+            .map(|(idx, _)| *idx)
+            .collect::<Vec<_>>(),
+        vec![1, 2, 0]
+    );
+
+    Ok(())
+}
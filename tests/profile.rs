@@ -0,0 +1,150 @@
+mod helpers;
+
+use rand::distributions::{Alphanumeric, DistString};
+
+use bria::{
+    app::{error::ApplicationError, *},
+    profile::{error::ProfileError, ApiKeyScope},
+};
+
+#[tokio::test]
+async fn revoke_and_expire_api_keys() -> anyhow::Result<()> {
+    let pool = helpers::init_pool().await?;
+    let profile = helpers::create_test_account(&pool).await?;
+    let app = App::run(pool, AppConfig::default()).await?;
+
+    let name = Alphanumeric.sample_string(&mut rand::thread_rng(), 32);
+    let new_profile = app.create_profile(&profile, name.clone(), None).await?;
+
+    let key = app
+        .create_profile_api_key(&profile, name.clone(), vec![])
+        .await?;
+    assert_eq!(app.authenticate(&key.key).await?.id, new_profile.id);
+    app.revoke_profile_api_key(&profile, key.id).await?;
+    assert!(matches!(
+        app.authenticate(&key.key).await,
+        Err(ApplicationError::ProfileError(
+            ProfileError::ProfileKeyNotFound
+        ))
+    ));
+    assert!(matches!(
+        app.revoke_profile_api_key(&profile, key.id).await,
+        Err(ApplicationError::ProfileError(
+            ProfileError::ApiKeyAlreadyRevoked(_)
+        ))
+    ));
+
+    let key = app
+        .create_profile_api_key(&profile, name.clone(), vec![])
+        .await?;
+    app.set_profile_api_key_expiry(
+        &profile,
+        key.id,
+        Some(chrono::Utc::now() - chrono::Duration::minutes(1)),
+    )
+    .await?;
+    assert!(matches!(
+        app.authenticate(&key.key).await,
+        Err(ApplicationError::ProfileError(
+            ProfileError::ProfileKeyNotFound
+        ))
+    ));
+    app.set_profile_api_key_expiry(&profile, key.id, None)
+        .await?;
+    assert_eq!(app.authenticate(&key.key).await?.id, new_profile.id);
+
+    let keys = app.list_profile_api_keys(&profile, new_profile.id).await?;
+    assert_eq!(keys.len(), 2);
+    assert_eq!(keys.iter().filter(|key| key.active).count(), 1);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn rename_and_disable_profile() -> anyhow::Result<()> {
+    let pool = helpers::init_pool().await?;
+    let profile = helpers::create_test_account(&pool).await?;
+    let app = App::run(pool, AppConfig::default()).await?;
+
+    let name = Alphanumeric.sample_string(&mut rand::thread_rng(), 32);
+    let new_profile = app.create_profile(&profile, name.clone(), None).await?;
+    let key = app.create_profile_api_key(&profile, name, vec![]).await?;
+
+    let new_name = Alphanumeric.sample_string(&mut rand::thread_rng(), 32);
+    app.update_profile(
+        &profile,
+        new_profile.id,
+        Some(new_name.clone()),
+        None,
+        Some(true),
+    )
+    .await?;
+    assert!(matches!(
+        app.authenticate(&key.key).await,
+        Err(ApplicationError::ProfileError(
+            ProfileError::ProfileDisabled
+        ))
+    ));
+
+    app.update_profile(&profile, new_profile.id, None, None, Some(false))
+        .await?;
+    let found = app.authenticate(&key.key).await?;
+    assert_eq!(found.name, new_name);
+    assert!(!found.disabled);
+    let key = app
+        .create_profile_api_key(&profile, new_name, vec![])
+        .await?;
+    assert_eq!(key.profile_id, new_profile.id);
+
+    let other_name = Alphanumeric.sample_string(&mut rand::thread_rng(), 32);
+    app.create_profile(&profile, other_name.clone(), None)
+        .await?;
+    assert!(matches!(
+        app.update_profile(&profile, new_profile.id, Some(other_name), None, None)
+            .await,
+        Err(ApplicationError::ProfileError(
+            ProfileError::ProfileNameAlreadyExists(_)
+        ))
+    ));
+    assert!(matches!(
+        app.update_profile(&profile, profile.id, None, None, Some(true))
+            .await,
+        Err(ApplicationError::ProfileError(
+            ProfileError::CannotDisableSelf
+        ))
+    ));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn scoped_api_keys() -> anyhow::Result<()> {
+    let pool = helpers::init_pool().await?;
+    let profile = helpers::create_test_account(&pool).await?;
+    let app = App::run(pool, AppConfig::default()).await?;
+    assert_eq!(profile.scopes, ApiKeyScope::all());
+
+    let name = Alphanumeric.sample_string(&mut rand::thread_rng(), 32);
+    app.create_profile(&profile, name.clone(), None).await?;
+    let key = app
+        .create_profile_api_key(&profile, name.clone(), vec![ApiKeyScope::Read])
+        .await?;
+    let read_only = app.authenticate(&key.key).await?;
+    assert!(read_only.ensure_scope(ApiKeyScope::Read).is_ok());
+    assert!(matches!(
+        read_only.ensure_scope(ApiKeyScope::PayoutsWrite),
+        Err(ProfileError::ApiKeyMissingScope(ApiKeyScope::PayoutsWrite))
+    ));
+
+    assert!(matches!(
+        app.create_profile_api_key(&read_only, name.clone(), vec![ApiKeyScope::SignersAdmin])
+            .await,
+        Err(ApplicationError::ProfileError(
+            ProfileError::ApiKeyMissingScope(ApiKeyScope::SignersAdmin)
+        ))
+    ));
+    let key = app.create_profile_api_key(&read_only, name, vec![]).await?;
+    assert_eq!(key.scopes, vec![ApiKeyScope::Read]);
+
+    Ok(())
+}
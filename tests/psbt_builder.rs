@@ -1,12 +1,23 @@
 mod helpers;
 
-use rand::Rng;
+use rand::{
+    distributions::{Alphanumeric, DistString},
+    Rng,
+};
 use serial_test::serial;
 
+use std::collections::HashMap;
+
 use bdk::{bitcoin::Network, blockchain::Blockchain, wallet::AddressIndex, FeeRate, SignOptions};
 use uuid::Uuid;
 
-use bria::{primitives::*, utxo::*, wallet::*, xpub::*};
+use bria::{
+    ledger::*,
+    primitives::{bitcoin::OutPoint, *},
+    utxo::*,
+    wallet::{balance::WalletBalanceSummary, *},
+    xpub::*,
+};
 
 #[tokio::test]
 #[serial]
@@ -80,7 +91,9 @@ async fn build_psbt() -> anyhow::Result<()> {
     }
     while !find_tx_id(&pool, domain_current_keychain_id, tx_id).await? {
         let blockchain = helpers::electrum_blockchain().await?;
-        domain_current_keychain.sync(blockchain).await?;
+        domain_current_keychain
+            .sync(blockchain, WalletConfig::default().lookahead)
+            .await?;
     }
 
     let fee = FeeRate::from_sat_per_vb(1.0);
@@ -288,7 +301,9 @@ async fn build_psbt_with_cpfp() -> anyhow::Result<()> {
         .accept_current_keychain();
     while !find_tx_id(&pool, domain_current_keychain_id, tx_id).await? {
         let blockchain = helpers::electrum_blockchain().await?;
-        domain_current_keychain.sync(blockchain).await?;
+        domain_current_keychain
+            .sync(blockchain, WalletConfig::default().lookahead)
+            .await?;
     }
     let builder = domain_current_keychain
         .dispatch_bdk_wallet(builder)
@@ -368,6 +383,536 @@ async fn build_psbt_with_cpfp() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[tokio::test]
+#[serial]
+async fn build_psbt_with_fee_from_amount() -> anyhow::Result<()> {
+    let pool = helpers::init_pool().await?;
+
+    let domain_current_keychain_id = Uuid::new_v4();
+    let xpub = XPub::try_from(("tpubDD4vFnWuTMEcZiaaZPgvzeGyMzWe6qHW8gALk5Md9kutDvtdDjYFwzauEFFRHgov8pAwup5jX88j5YFyiACsPf3pqn5hBjvuTLRAseaJ6b4", Some("m/84'/0'/0'"))).unwrap();
+    let keychain_cfg = KeychainConfig::wpkh(xpub);
+    let domain_current_keychain = KeychainWallet::new(
+        pool.clone(),
+        Network::Regtest,
+        domain_current_keychain_id.into(),
+        keychain_cfg,
+    );
+    let domain_addr = domain_current_keychain.new_external_address().await?;
+
+    let bitcoind = helpers::bitcoind_client().await?;
+    let wallet_funding = 500_000_000;
+    let tx_id = helpers::fund_addr(&bitcoind, &domain_addr, wallet_funding)?;
+    helpers::gen_blocks(&bitcoind, 10)?;
+    while !find_tx_id(&pool, domain_current_keychain_id, tx_id).await? {
+        let blockchain = helpers::electrum_blockchain().await?;
+        domain_current_keychain
+            .sync(blockchain, WalletConfig::default().lookahead)
+            .await?;
+    }
+
+    let fee_from_amount_id = Uuid::new_v4();
+    let builder = PsbtBuilder::new()
+        .fee_rate(FeeRate::from_sat_per_vb(10.0))
+        .fee_from_amount_payouts(std::iter::once(fee_from_amount_id).collect())
+        .accept_wallets();
+
+    let domain_wallet_id = WalletId::new();
+    let send_amount = Satoshis::from(100_000_000);
+    let destination: bitcoin::Address = "mgWUuj1J1N882jmqFxtDepEC73Rr22E9GU".parse().unwrap();
+    let payouts = vec![
+        (fee_from_amount_id, destination.clone(), send_amount),
+        (Uuid::new_v4(), destination, send_amount),
+    ];
+    let builder = builder
+        .wallet_payouts(domain_wallet_id, payouts)
+        .accept_current_keychain();
+    let builder = domain_current_keychain
+        .dispatch_bdk_wallet(builder)
+        .await?
+        .next_wallet();
+    let FinishedPsbtBuild {
+        psbt: unsigned_psbt,
+        included_payouts,
+        wallet_totals,
+        deducted_payout_fees,
+        ..
+    } = builder.finish();
+
+    let domain_wallet_total = wallet_totals.get(&domain_wallet_id).unwrap();
+    let deducted = *deducted_payout_fees
+        .get(&fee_from_amount_id)
+        .expect("fee was not deducted");
+    assert!(deducted > Satoshis::ZERO);
+    assert!(deducted < domain_wallet_total.total_fee_satoshis);
+    assert_eq!(deducted_payout_fees.len(), 1);
+
+    let included_payouts = included_payouts
+        .get(&domain_wallet_id)
+        .expect("wallet not included in payouts");
+    for ((id, _, satoshis), _) in included_payouts {
+        if id == &fee_from_amount_id {
+            assert_eq!(*satoshis, send_amount - deducted);
+        } else {
+            assert_eq!(*satoshis, send_amount);
+        }
+    }
+    assert_eq!(
+        domain_wallet_total.output_satoshis,
+        send_amount + send_amount - deducted
+    );
+    assert_eq!(
+        domain_wallet_total.output_satoshis
+            + domain_wallet_total.total_fee_satoshis
+            + domain_wallet_total.change_satoshis,
+        domain_wallet_total.input_satoshis
+    );
+
+    let unsigned_psbt = unsigned_psbt.expect("unsigned psbt");
+    assert!(unsigned_psbt
+        .unsigned_tx
+        .output
+        .iter()
+        .any(|out| out.value == u64::from(send_amount - deducted)));
+    Ok(())
+}
+
+#[tokio::test]
+#[serial]
+async fn build_psbt_with_sweep() -> anyhow::Result<()> {
+    let pool = helpers::init_pool().await?;
+
+    let domain_current_keychain_id = Uuid::new_v4();
+    let xpub = XPub::try_from(("tpubDD4vFnWuTMEcZiaaZPgvzeGyMzWe6qHW8gALk5Md9kutDvtdDjYFwzauEFFRHgov8pAwup5jX88j5YFyiACsPf3pqn5hBjvuTLRAseaJ6b4", Some("m/84'/0'/0'"))).unwrap();
+    let keychain_cfg = KeychainConfig::wpkh(xpub);
+    let domain_current_keychain = KeychainWallet::new(
+        pool.clone(),
+        Network::Regtest,
+        domain_current_keychain_id.into(),
+        keychain_cfg,
+    );
+    let domain_addr = domain_current_keychain.new_external_address().await?;
+
+    let bitcoind = helpers::bitcoind_client().await?;
+    let wallet_funding = 500_000_000;
+    let tx_id = helpers::fund_addr(&bitcoind, &domain_addr, wallet_funding)?;
+    helpers::gen_blocks(&bitcoind, 10)?;
+    while !find_tx_id(&pool, domain_current_keychain_id, tx_id).await? {
+        let blockchain = helpers::electrum_blockchain().await?;
+        domain_current_keychain
+            .sync(blockchain, WalletConfig::default().lookahead)
+            .await?;
+    }
+
+    let sweep_id = Uuid::new_v4();
+    let builder = PsbtBuilder::new()
+        .fee_rate(FeeRate::from_sat_per_vb(10.0))
+        .sweep_payouts(std::iter::once(sweep_id).collect())
+        .accept_wallets();
+
+    let domain_wallet_id = WalletId::new();
+    let sweep_amount = Satoshis::from(wallet_funding);
+    let destination: bitcoin::Address = "mgWUuj1J1N882jmqFxtDepEC73Rr22E9GU".parse().unwrap();
+    let payouts = vec![
+        (Uuid::new_v4(), destination.clone(), Satoshis::from(100_000)),
+        (sweep_id, destination, sweep_amount),
+    ];
+    let builder = builder
+        .wallet_payouts(domain_wallet_id, payouts)
+        .accept_current_keychain();
+    let builder = domain_current_keychain
+        .dispatch_bdk_wallet(builder)
+        .await?
+        .next_wallet();
+    let FinishedPsbtBuild {
+        psbt: unsigned_psbt,
+        included_payouts,
+        wallet_totals,
+        deducted_payout_fees,
+        ..
+    } = builder.finish();
+
+    let domain_wallet_total = wallet_totals.get(&domain_wallet_id).unwrap();
+    assert_eq!(domain_wallet_total.change_satoshis, Satoshis::ZERO);
+    assert_eq!(
+        domain_wallet_total.output_satoshis + domain_wallet_total.total_fee_satoshis,
+        domain_wallet_total.input_satoshis
+    );
+
+    let included_payouts = included_payouts
+        .get(&domain_wallet_id)
+        .expect("wallet not included in payouts");
+    assert_eq!(included_payouts.len(), 1);
+    let ((id, _, swept), _) = &included_payouts[0];
+    assert_eq!(id, &sweep_id);
+    assert_eq!(*swept, domain_wallet_total.output_satoshis);
+    assert_eq!(
+        *deducted_payout_fees.get(&sweep_id).unwrap(),
+        sweep_amount - *swept
+    );
+
+    let unsigned_psbt = unsigned_psbt.expect("unsigned psbt");
+    assert_eq!(unsigned_psbt.unsigned_tx.output.len(), 1);
+    Ok(())
+}
+
+#[tokio::test]
+#[serial]
+async fn build_psbt_with_sweep_exceeding_requested_amount() -> anyhow::Result<()> {
+    let pool = helpers::init_pool().await?;
+
+    let domain_current_keychain_id = Uuid::new_v4();
+    let xpub = XPub::try_from(("tpubDD4vFnWuTMEcZiaaZPgvzeGyMzWe6qHW8gALk5Md9kutDvtdDjYFwzauEFFRHgov8pAwup5jX88j5YFyiACsPf3pqn5hBjvuTLRAseaJ6b4", Some("m/84'/0'/0'"))).unwrap();
+    let keychain_cfg = KeychainConfig::wpkh(xpub);
+    let domain_current_keychain = KeychainWallet::new(
+        pool.clone(),
+        Network::Regtest,
+        domain_current_keychain_id.into(),
+        keychain_cfg,
+    );
+    let domain_addr = domain_current_keychain.new_external_address().await?;
+
+    let bitcoind = helpers::bitcoind_client().await?;
+    let wallet_funding = 500_000_000;
+    let tx_id = helpers::fund_addr(&bitcoind, &domain_addr, wallet_funding)?;
+    helpers::gen_blocks(&bitcoind, 10)?;
+    while !find_tx_id(&pool, domain_current_keychain_id, tx_id).await? {
+        let blockchain = helpers::electrum_blockchain().await?;
+        domain_current_keychain
+            .sync(blockchain, WalletConfig::default().lookahead)
+            .await?;
+    }
+
+    let sweep_id = Uuid::new_v4();
+    let builder = PsbtBuilder::new()
+        .fee_rate(FeeRate::from_sat_per_vb(10.0))
+        .sweep_payouts(std::iter::once(sweep_id).collect())
+        .accept_wallets();
+
+    // The wallet received more funds after the sweep was requested
+    let domain_wallet_id = WalletId::new();
+    let requested_amount = Satoshis::from(wallet_funding / 2);
+    let destination: bitcoin::Address = "mgWUuj1J1N882jmqFxtDepEC73Rr22E9GU".parse().unwrap();
+    let builder = builder
+        .wallet_payouts(
+            domain_wallet_id,
+            vec![(sweep_id, destination, requested_amount)],
+        )
+        .accept_current_keychain();
+    let builder = domain_current_keychain
+        .dispatch_bdk_wallet(builder)
+        .await?
+        .next_wallet();
+    let FinishedPsbtBuild {
+        psbt: unsigned_psbt,
+        included_payouts,
+        wallet_totals,
+        deducted_payout_fees,
+        ..
+    } = builder.finish();
+
+    let included_payouts = included_payouts
+        .get(&domain_wallet_id)
+        .expect("wallet not included in payouts");
+    let ((_, destination, swept), _) = &included_payouts[0];
+    let deducted_fee_sats = *deducted_payout_fees.get(&sweep_id).unwrap();
+    assert!(*swept < requested_amount);
+    assert_eq!(*swept + deducted_fee_sats, requested_amount);
+
+    // The excess stays in the wallet as change
+    let domain_wallet_total = wallet_totals.get(&domain_wallet_id).unwrap();
+    assert!(domain_wallet_total.change_satoshis > Satoshis::ZERO);
+    assert_eq!(
+        domain_wallet_total.output_satoshis
+            + domain_wallet_total.total_fee_satoshis
+            + domain_wallet_total.change_satoshis,
+        domain_wallet_total.input_satoshis
+    );
+
+    let unsigned_tx = unsigned_psbt.expect("unsigned psbt").unsigned_tx;
+    let vout_of = |script: bitcoin::Script| {
+        unsigned_tx
+            .output
+            .iter()
+            .position(|out| out.script_pubkey == script)
+            .expect("output missing") as u32
+    };
+
+    let ledger = Ledger::init(&pool).await?;
+    let account_id = AccountId::new();
+    let name = Alphanumeric.sample_string(&mut rand::thread_rng(), 32);
+    let mut tx = pool.begin().await?;
+    let journal_id = ledger
+        .create_journal_for_account(&mut tx, account_id, name)
+        .await?;
+    let wallet_ledger_accounts = ledger
+        .create_ledger_accounts_for_wallet(&mut tx, domain_wallet_id)
+        .await?;
+    tx.commit().await?;
+
+    let payout_id = PayoutId::from(sweep_id);
+    let profile_id = ProfileId::new();
+    let payout_queue_id = PayoutQueueId::new();
+    let payout_destination = PayoutDestination::OnchainAddress {
+        value: destination.clone(),
+    };
+    let tx = pool.begin().await?;
+    ledger
+        .payout_submitted(
+            tx,
+            LedgerTransactionId::new(),
+            PayoutSubmittedParams {
+                journal_id,
+                effective_outgoing_account_id: wallet_ledger_accounts.effective_outgoing_id,
+                external_id: payout_id.to_string(),
+                meta: PayoutSubmittedMeta {
+                    account_id,
+                    payout_id,
+                    wallet_id: domain_wallet_id,
+                    payout_queue_id,
+                    profile_id,
+                    satoshis: requested_amount,
+                    destination: payout_destination.clone(),
+                    fee_from_amount: false,
+                },
+            },
+        )
+        .await?;
+
+    let tx = pool.begin().await?;
+    ledger
+        .batch_created(
+            tx,
+            LedgerTransactionId::new(),
+            BatchCreatedParams {
+                journal_id,
+                ledger_account_ids: wallet_ledger_accounts,
+                encumbered_fees: Satoshis::ZERO,
+                meta: BatchCreatedMeta {
+                    batch_info: BatchWalletInfo {
+                        account_id,
+                        wallet_id: domain_wallet_id,
+                        batch_id: BatchId::new(),
+                        payout_queue_id,
+                        included_payouts: vec![PayoutInfo {
+                            id: payout_id,
+                            profile_id,
+                            satoshis: requested_amount,
+                            destination: payout_destination,
+                            vout_in_tx: vout_of(destination.script_pubkey()),
+                            net_satoshis: Some(*swept),
+                            deducted_fee_sats,
+                        }],
+                        cpfp_fee_sats: Satoshis::ZERO,
+                        cpfp_details: HashMap::new(),
+                    },
+                    tx_summary: WalletTransactionSummary {
+                        account_id,
+                        wallet_id: domain_wallet_id,
+                        bitcoin_tx_id: unsigned_tx.txid(),
+                        total_utxo_settled_in_sats: domain_wallet_total.input_satoshis,
+                        total_utxo_in_sats: domain_wallet_total.input_satoshis,
+                        fee_sats: domain_wallet_total.total_fee_satoshis,
+                        change_utxos: vec![ChangeOutput {
+                            outpoint: OutPoint {
+                                txid: unsigned_tx.txid(),
+                                vout: vout_of(domain_wallet_total.change_address.script_pubkey()),
+                            },
+                            satoshis: domain_wallet_total.change_satoshis,
+                            address: domain_wallet_total.change_address.address.clone(),
+                        }],
+                        current_keychain_id: domain_current_keychain_id.into(),
+                    },
+                },
+            },
+        )
+        .await?;
+
+    let summary = WalletBalanceSummary::from(
+        ledger
+            .get_wallet_ledger_account_balances(journal_id, wallet_ledger_accounts)
+            .await?,
+    );
+    assert_eq!(summary.effective_encumbered_outgoing, Satoshis::ZERO);
+    assert_eq!(summary.effective_pending_outgoing, *swept);
+    assert_eq!(summary.fees_pending, domain_wallet_total.total_fee_satoshis);
+    Ok(())
+}
+
+#[tokio::test]
+#[serial]
+async fn build_psbt_on_recovery_path_skips_immature_utxos() -> anyhow::Result<()> {
+    let pool = helpers::init_pool().await?;
+
+    let keychain_cfg = KeychainConfig::policy(
+        "or(99@pk(main),1@and(pk(backup),older(5)))".to_owned(),
+        PolicyScriptType::Wsh,
+        [
+            ("main".to_owned(), random_xpub()),
+            ("backup".to_owned(), random_xpub()),
+        ]
+        .into_iter()
+        .collect(),
+    )?;
+    let keychain_id = Uuid::new_v4();
+    let keychain = KeychainWallet::new(
+        pool.clone(),
+        Network::Regtest,
+        keychain_id.into(),
+        keychain_cfg,
+    );
+
+    let bitcoind = helpers::bitcoind_client().await?;
+    let mature_funding = 300_000_000;
+    let mature_addr = keychain.new_external_address().await?;
+    let mature_tx_id = helpers::fund_addr(&bitcoind, &mature_addr, mature_funding)?;
+    helpers::gen_blocks(&bitcoind, 10)?;
+    // Confirmed just once so it can't be spent via the older(5) branch yet
+    let immature_funding = 500_000_000;
+    let immature_addr = keychain.new_external_address().await?;
+    let immature_tx_id = helpers::fund_addr(&bitcoind, &immature_addr, immature_funding)?;
+    helpers::gen_blocks(&bitcoind, 1)?;
+    let (mature_outpoint, _, _) = helpers::lookup_tx_info(&bitcoind, mature_tx_id, mature_funding)?;
+    let (immature_outpoint, _, _) =
+        helpers::lookup_tx_info(&bitcoind, immature_tx_id, immature_funding)?;
+    while !find_tx_id(&pool, keychain_id, mature_tx_id).await?
+        || !find_tx_id(&pool, keychain_id, immature_tx_id).await?
+    {
+        let blockchain = helpers::electrum_blockchain().await?;
+        keychain
+            .sync(blockchain, WalletConfig::default().lookahead)
+            .await?;
+    }
+
+    let destination: bitcoin::Address = "mgWUuj1J1N882jmqFxtDepEC73Rr22E9GU".parse().unwrap();
+    let wallet_id = WalletId::new();
+    let build = |spending_path| {
+        let builder = PsbtBuilder::new()
+            .fee_rate(FeeRate::from_sat_per_vb(1.0))
+            .coin_selection(CoinSelectionStrategy::LargestFirst)
+            .spending_path(spending_path)
+            .accept_wallets()
+            .wallet_payouts(
+                wallet_id,
+                vec![(
+                    Uuid::new_v4(),
+                    destination.clone(),
+                    Satoshis::from(100_000_000),
+                )],
+            )
+            .accept_current_keychain();
+        let keychain = &keychain;
+        async move {
+            let builder = keychain.dispatch_bdk_wallet(builder).await?.next_wallet();
+            let inputs: Vec<_> = builder
+                .finish()
+                .psbt
+                .expect("unsigned psbt")
+                .unsigned_tx
+                .input
+                .into_iter()
+                .map(|input| input.previous_output)
+                .collect();
+            Ok::<_, anyhow::Error>(inputs)
+        }
+    };
+
+    assert_eq!(build(SpendingPath::Primary).await?, vec![immature_outpoint]);
+    assert_eq!(build(SpendingPath::Recovery).await?, vec![mature_outpoint]);
+    Ok(())
+}
+
+#[tokio::test]
+#[serial]
+async fn build_consolidation_psbt() -> anyhow::Result<()> {
+    let pool = helpers::init_pool().await?;
+
+    let keychain_id = Uuid::new_v4();
+    let keychain = KeychainWallet::new(
+        pool.clone(),
+        Network::Regtest,
+        keychain_id.into(),
+        KeychainConfig::wpkh(random_xpub()),
+    );
+
+    let bitcoind = helpers::bitcoind_client().await?;
+    let fundings = [100_000_000, 200_000_000, 300_000_000];
+    let mut outpoints = Vec::new();
+    for funding in fundings {
+        let addr = keychain.new_external_address().await?;
+        let tx_id = helpers::fund_addr(&bitcoind, &addr, funding)?;
+        let (outpoint, _, _) = helpers::lookup_tx_info(&bitcoind, tx_id, funding)?;
+        outpoints.push(outpoint);
+    }
+    helpers::gen_blocks(&bitcoind, 10)?;
+    for outpoint in outpoints.iter() {
+        while !find_tx_id(&pool, keychain_id, outpoint.txid).await? {
+            let blockchain = helpers::electrum_blockchain().await?;
+            keychain
+                .sync(blockchain, WalletConfig::default().lookahead)
+                .await?;
+        }
+    }
+
+    let reserved = outpoints[2];
+    let wallet_id = WalletId::new();
+    let builder = PsbtBuilder::new()
+        .consolidate_deprecated_keychains(false)
+        .fee_rate(FeeRate::from_sat_per_vb(1.0))
+        .reserved_utxos(std::iter::once((KeychainId::from(keychain_id), vec![reserved])).collect())
+        .cpfp_utxos(Default::default())
+        .self_pay(true)
+        .accept_wallets()
+        .wallet_payouts(wallet_id, Vec::new())
+        .accept_current_keychain();
+    let FinishedPsbtBuild {
+        psbt: unsigned_psbt,
+        included_payouts,
+        included_utxos,
+        wallet_totals,
+        fee_satoshis,
+        ..
+    } = keychain
+        .dispatch_bdk_wallet(builder)
+        .await?
+        .next_wallet()
+        .finish();
+
+    assert!(included_payouts.is_empty());
+    let mut consolidated = included_utxos
+        .get(&wallet_id)
+        .and_then(|keychains| keychains.get(&KeychainId::from(keychain_id)))
+        .expect("utxos not included")
+        .clone();
+    consolidated.sort();
+    let mut expected = outpoints[..2].to_vec();
+    expected.sort();
+    assert_eq!(consolidated, expected);
+
+    let totals = wallet_totals
+        .get(&wallet_id)
+        .expect("wallet totals missing");
+    let input_satoshis = Satoshis::from(fundings[0] + fundings[1]);
+    assert_eq!(totals.input_satoshis, input_satoshis);
+    assert_eq!(totals.total_fee_satoshis, fee_satoshis);
+    assert_eq!(totals.change_satoshis, input_satoshis - fee_satoshis);
+
+    let unsigned_psbt = unsigned_psbt.expect("unsigned psbt");
+    let mut inputs: Vec<_> = unsigned_psbt
+        .unsigned_tx
+        .input
+        .iter()
+        .map(|input| input.previous_output)
+        .collect();
+    inputs.sort();
+    assert_eq!(inputs, expected);
+    assert!(!inputs.contains(&reserved));
+    assert_eq!(unsigned_psbt.unsigned_tx.output.len(), 1);
+    let change = &unsigned_psbt.unsigned_tx.output[0];
+    assert_eq!(change.script_pubkey, totals.change_address.script_pubkey());
+    assert_eq!(Satoshis::from(change.value), totals.change_satoshis);
+    Ok(())
+}
+
 async fn find_tx_id(
     pool: &sqlx::PgPool,
     keychain_id: Uuid,
@@ -382,3 +927,14 @@ async fn find_tx_id(
     .await?;
     Ok(utxos.count != 0)
 }
+
+fn random_xpub() -> XPub {
+    let seed: [u8; 32] = rand::thread_rng().gen();
+    let xpriv =
+        bdk::bitcoin::util::bip32::ExtendedPrivKey::new_master(Network::Regtest, &seed).unwrap();
+    let xpub = bdk::bitcoin::util::bip32::ExtendedPubKey::from_priv(
+        &bdk::bitcoin::secp256k1::Secp256k1::new(),
+        &xpriv,
+    );
+    XPub::try_from((xpub.to_string(), None::<String>)).unwrap()
+}
@@ -3,7 +3,14 @@ mod helpers;
 use rand::distributions::{Alphanumeric, DistString};
 use serde_json::json;
 
-use bria::{app::*, xpub::*};
+use bria::{
+    app::{error::ApplicationError, *},
+    primitives::*,
+    profile::Profile,
+    utxo::{error::UtxoError, *},
+    wallet::{error::WalletError, *},
+    xpub::*,
+};
 
 #[tokio::test]
 async fn create_wpkh_wallet() -> anyhow::Result<()> {
@@ -29,7 +36,7 @@ async fn create_wpkh_wallet() -> anyhow::Result<()> {
         .await?;
 
     let app = App::run(pool, AppConfig::default()).await?;
-    app.create_wpkh_wallet(&profile, name.clone(), id.to_string(), None)
+    app.create_wpkh_wallet(&profile, name.clone(), id.to_string(), None, None)
         .await?;
 
     let (_, addr) = app.new_address(&profile, name.clone(), None, None).await?;
@@ -59,9 +66,551 @@ async fn create_descriptors_wallet() -> anyhow::Result<()> {
     let app = App::run(pool, AppConfig::default()).await?;
     let wallet_name = "test_import_descriptor".to_owned();
     let (_, xpub_ids) = app
-        .create_descriptors_wallet(&profile, wallet_name, external, internal)
+        .create_descriptors_wallet(&profile, wallet_name, external, internal, None)
         .await?;
     assert_eq!(xpub_ids.len(), 1);
     assert_eq!(xpub_ids[0].to_string(), "2f18f2f7");
     Ok(())
 }
+
+#[tokio::test]
+async fn rotate_wallet_keychain() -> anyhow::Result<()> {
+    let pool = helpers::init_pool().await?;
+    let profile = helpers::create_test_account(&pool).await?;
+    let app = App::run(pool.clone(), AppConfig::default()).await?;
+
+    let name = Alphanumeric.sample_string(&mut rand::thread_rng(), 32);
+    let original = "tpubDD4vFnWuTMEcZiaaZPgvzeGyMzWe6qHW8gALk5Md9kutDvtdDjYFwzauEFFRHgov8pAwup5jX88j5YFyiACsPf3pqn5hBjvuTLRAseaJ6b4";
+    app.create_wpkh_wallet(
+        &profile,
+        name.clone(),
+        original.to_owned(),
+        Some("m/84'/0'/0'".to_owned()),
+        None,
+    )
+    .await?;
+    let (_, addr) = app.new_address(&profile, name.clone(), None, None).await?;
+    assert_eq!(
+        addr.to_string(),
+        "bcrt1qzg4a08kc2xrp08d9k5jadm78ehf7catp735zn0"
+    );
+
+    let external = "wpkh([1ff51810/84'/0'/0']tpubDDdzmt7vndmNywiVAeBPuhYLTFa7hmtfaqUxxTv5iLy7bxU93B62M9WKFSmn1BEN2vte8GDD3SUNKbupRajFW4RK8hd3i6W15pvTRQfo1fK/0/*)#q8r69l4d".to_owned();
+    let internal = "wpkh([1ff51810/84'/0'/0']tpubDDdzmt7vndmNywiVAeBPuhYLTFa7hmtfaqUxxTv5iLy7bxU93B62M9WKFSmn1BEN2vte8GDD3SUNKbupRajFW4RK8hd3i6W15pvTRQfo1fK/1/*)#3nxmc294".to_owned();
+    let (keychain_id, xpub_ids) = app
+        .rotate_descriptors_wallet_keychain(&profile, name.clone(), external, internal)
+        .await?;
+    assert_eq!(xpub_ids[0].to_string(), "2f18f2f7");
+
+    let wallet = Wallets::new(&pool)
+        .find_by_name(profile.account_id, name.clone())
+        .await?;
+    assert_eq!(wallet.keychain_ids().collect::<Vec<_>>().len(), 2);
+    assert_eq!(
+        wallet.current_keychain_wallet(&pool).keychain_id,
+        keychain_id
+    );
+    let (_, addr) = app.new_address(&profile, name.clone(), None, None).await?;
+    assert_eq!(
+        addr.to_string(),
+        "bcrt1q65vn55cppn9ndg0gc3pj7cuzuazn8rur9yscry"
+    );
+
+    assert!(matches!(
+        app.rotate_wpkh_wallet_keychain(
+            &profile,
+            name,
+            original.to_owned(),
+            Some("m/84'/0'/0'".to_owned()),
+        )
+        .await,
+        Err(ApplicationError::WalletError(
+            WalletError::KeychainAlreadyInUse
+        ))
+    ));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn create_tr_wallet() -> anyhow::Result<()> {
+    let pool = helpers::init_pool().await?;
+    let profile = helpers::create_test_account(&pool).await?;
+    let app = App::run(pool.clone(), AppConfig::default()).await?;
+
+    let name = Alphanumeric.sample_string(&mut rand::thread_rng(), 32);
+    let original = "tpubDD4vFnWuTMEcZiaaZPgvzeGyMzWe6qHW8gALk5Md9kutDvtdDjYFwzauEFFRHgov8pAwup5jX88j5YFyiACsPf3pqn5hBjvuTLRAseaJ6b4";
+    app.create_tr_wallet(
+        &profile,
+        name.clone(),
+        original.to_owned(),
+        Some("m/86'/0'/0'".to_owned()),
+        None,
+    )
+    .await?;
+
+    let (_, addr) = app.new_address(&profile, name.clone(), None, None).await?;
+    assert_eq!(
+        addr.to_string(),
+        "bcrt1p4qcn73cjku4dtmg2u7z9jpel07g099a0gfamazlnm99uerlprmqs8guyn6"
+    );
+
+    let wallet = Wallets::new(&pool)
+        .find_by_name(profile.account_id, name)
+        .await?;
+    // key path spend: scriptSig len + witness items + sig len + 64 byte sig + sighash flag
+    assert_eq!(
+        wallet
+            .current_keychain_wallet(&pool)
+            .max_satisfaction_weight(),
+        4 + 1 + 1 + 65
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn create_policy_wallet() -> anyhow::Result<()> {
+    let pool = helpers::init_pool().await?;
+    let profile = helpers::create_test_account(&pool).await?;
+    let app = App::run(pool.clone(), AppConfig::default()).await?;
+
+    app.import_xpub(
+        &profile,
+        "main".to_owned(),
+        "tpubDD4vFnWuTMEcZiaaZPgvzeGyMzWe6qHW8gALk5Md9kutDvtdDjYFwzauEFFRHgov8pAwup5jX88j5YFyiACsPf3pqn5hBjvuTLRAseaJ6b4".to_owned(),
+        Some("m/84'/0'/0'".to_owned()),
+    )
+    .await?;
+    app.import_xpub(
+        &profile,
+        "backup".to_owned(),
+        "tpubDDdzmt7vndmNywiVAeBPuhYLTFa7hmtfaqUxxTv5iLy7bxU93B62M9WKFSmn1BEN2vte8GDD3SUNKbupRajFW4RK8hd3i6W15pvTRQfo1fK".to_owned(),
+        Some("m/84'/0'/0'".to_owned()),
+    )
+    .await?;
+
+    let name = Alphanumeric.sample_string(&mut rand::thread_rng(), 32);
+    let (_, xpub_ids) = app
+        .create_policy_wallet(
+            &profile,
+            name.clone(),
+            "or(99@pk(main),1@and(pk(backup),older(4320)))".to_owned(),
+            PolicyScriptType::Wsh,
+            None,
+        )
+        .await?;
+    assert_eq!(xpub_ids.len(), 2);
+
+    let (_, addr) = app.new_address(&profile, name.clone(), None, None).await?;
+    assert!(addr.to_string().starts_with("bcrt1q"));
+
+    let wallet = Wallets::new(&pool)
+        .find_by_name(profile.account_id, name.clone())
+        .await?;
+    let timelocked = wallet.timelocked_keychains();
+    assert_eq!(timelocked.len(), 1);
+    assert_eq!(timelocked[0].1.relative_blocks, Some(4320));
+
+    assert!(matches!(
+        app.create_policy_wallet(
+            &profile,
+            name,
+            "or(pk(main),pk(unknown))".to_owned(),
+            PolicyScriptType::Tr,
+            None,
+        )
+        .await,
+        Err(ApplicationError::XPubError(_))
+    ));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn export_wallet() -> anyhow::Result<()> {
+    let pool = helpers::init_pool().await?;
+    let profile = helpers::create_test_account(&pool).await?;
+    let app = App::run(pool, AppConfig::default()).await?;
+
+    let name = Alphanumeric.sample_string(&mut rand::thread_rng(), 32);
+    let original = "tpubDD4vFnWuTMEcZiaaZPgvzeGyMzWe6qHW8gALk5Md9kutDvtdDjYFwzauEFFRHgov8pAwup5jX88j5YFyiACsPf3pqn5hBjvuTLRAseaJ6b4";
+    app.create_wpkh_wallet(
+        &profile,
+        name.clone(),
+        original.to_owned(),
+        Some("m/84'/0'/0'".to_owned()),
+        None,
+    )
+    .await?;
+    app.new_address(&profile, name.clone(), None, None).await?;
+    app.new_address(&profile, name.clone(), None, None).await?;
+
+    let export = app.export_wallet(&profile, name.clone()).await?;
+    assert_eq!(export.wallet_name, name);
+    assert_eq!(export.network, bria::primitives::bitcoin::Network::Regtest);
+    assert_eq!(export.descriptors.len(), 2);
+    let external = &export.descriptors[0];
+    assert!(external.active);
+    assert_eq!(
+        external.descriptor.to_string(),
+        "wpkh([8df69d29/84'/0'/0']tpubDD4vFnWuTMEcZiaaZPgvzeGyMzWe6qHW8gALk5Md9kutDvtdDjYFwzauEFFRHgov8pAwup5jX88j5YFyiACsPf3pqn5hBjvuTLRAseaJ6b4/0/*)#78t5f68g"
+    );
+    assert_eq!(external.last_derived_index, Some(1));
+    let internal = &export.descriptors[1];
+    assert!(internal.active);
+    assert_eq!(internal.last_derived_index, None);
+
+    let external = "wpkh([1ff51810/84'/0'/0']tpubDDdzmt7vndmNywiVAeBPuhYLTFa7hmtfaqUxxTv5iLy7bxU93B62M9WKFSmn1BEN2vte8GDD3SUNKbupRajFW4RK8hd3i6W15pvTRQfo1fK/0/*)#q8r69l4d".to_owned();
+    let internal = "wpkh([1ff51810/84'/0'/0']tpubDDdzmt7vndmNywiVAeBPuhYLTFa7hmtfaqUxxTv5iLy7bxU93B62M9WKFSmn1BEN2vte8GDD3SUNKbupRajFW4RK8hd3i6W15pvTRQfo1fK/1/*)#3nxmc294".to_owned();
+    app.rotate_descriptors_wallet_keychain(&profile, name.clone(), external.clone(), internal)
+        .await?;
+
+    let export = app.export_wallet(&profile, name).await?;
+    assert_eq!(export.descriptors.len(), 4);
+    assert_eq!(export.descriptors[0].descriptor.to_string(), external);
+    assert!(export.descriptors[0].active);
+    assert_eq!(export.descriptors[0].last_derived_index, None);
+    assert!(!export.descriptors[2].active);
+    assert_eq!(export.descriptors[2].last_derived_index, Some(1));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn rescan_wallet() -> anyhow::Result<()> {
+    let pool = helpers::init_pool().await?;
+    let profile = helpers::create_test_account(&pool).await?;
+    let electrum_host = std::env::var("ELECTRUM_HOST").unwrap_or("localhost".to_string());
+    let mut config = AppConfig::default();
+    config.blockchain.electrum_url = format!("{electrum_host}:50001");
+    let app = App::run(pool.clone(), config).await?;
+
+    let name = Alphanumeric.sample_string(&mut rand::thread_rng(), 32);
+    app.create_wpkh_wallet(&profile, name.clone(), helpers::random_tpub(), None, None)
+        .await?;
+    let (_, addr) = app.new_address(&profile, name.clone(), None, None).await?;
+
+    let bitcoind = helpers::bitcoind_client().await?;
+    let amount = 100_000_000;
+    let tx_id = helpers::fund_addr(&bitcoind, &addr, amount)?;
+    helpers::gen_blocks(&bitcoind, 10)?;
+
+    let settled = wait_for_settled_utxo(&app, &profile, &name, tx_id).await?;
+    let balance = app
+        .get_wallet_balance_summary(&profile, name.clone())
+        .await?;
+    assert_eq!(balance.utxo_settled, Satoshis::from(amount));
+
+    app.rescan_wallet(&profile, name.clone(), Some(100), Some(50))
+        .await?;
+    wait_for_reconciliation(&pool, &profile, &name).await?;
+    app.rescan_wallet(&profile, name.clone(), None, None)
+        .await?;
+    wait_for_reconciliation(&pool, &profile, &name).await?;
+
+    // The utxo is rediscovered without being recorded in the ledger a second time
+    let rediscovered = wait_for_settled_utxo(&app, &profile, &name, tx_id).await?;
+    assert_eq!(rediscovered.outpoint, settled.outpoint);
+    assert_eq!(
+        rediscovered.utxo_detected_ledger_tx_id,
+        settled.utxo_detected_ledger_tx_id
+    );
+    assert_eq!(
+        rediscovered.utxo_settled_ledger_tx_id,
+        settled.utxo_settled_ledger_tx_id
+    );
+    let (_, keychain_utxos) = app.list_utxos(&profile, name.clone()).await?;
+    assert_eq!(
+        keychain_utxos
+            .iter()
+            .flat_map(|keychain| keychain.utxos.iter())
+            .filter(|utxo| utxo.outpoint.txid == tx_id)
+            .count(),
+        1
+    );
+    let after_rescan = app
+        .get_wallet_balance_summary(&profile, name.clone())
+        .await?;
+    assert_eq!(after_rescan.utxo_settled, balance.utxo_settled);
+    assert_eq!(
+        after_rescan.utxo_pending_incoming,
+        balance.utxo_pending_incoming
+    );
+
+    assert!(matches!(
+        app.rescan_wallet(&profile, "unknown".to_owned(), None, None)
+            .await,
+        Err(ApplicationError::WalletError(
+            WalletError::WalletNameNotFound(_)
+        ))
+    ));
+
+    Ok(())
+}
+
+async fn wait_for_settled_utxo(
+    app: &App,
+    profile: &Profile,
+    name: &str,
+    tx_id: bitcoin::Txid,
+) -> anyhow::Result<WalletUtxo> {
+    for _ in 0..60 {
+        let (_, keychain_utxos) = app.list_utxos(profile, name.to_owned()).await?;
+        if let Some(utxo) = keychain_utxos
+            .into_iter()
+            .flat_map(|keychain| keychain.utxos)
+            .find(|utxo| utxo.outpoint.txid == tx_id && utxo.utxo_settled_ledger_tx_id.is_some())
+        {
+            return Ok(utxo);
+        }
+        tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+    }
+    Err(anyhow::anyhow!("utxo {tx_id} was not settled"))
+}
+
+async fn wait_for_reconciliation(
+    pool: &sqlx::PgPool,
+    profile: &Profile,
+    name: &str,
+) -> anyhow::Result<()> {
+    let wallet = Wallets::new(pool)
+        .find_by_name(profile.account_id, name.to_owned())
+        .await?;
+    let keychain_ids: Vec<uuid::Uuid> = wallet.keychain_ids().map(uuid::Uuid::from).collect();
+    for _ in 0..60 {
+        let n_unsynced: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM bdk_transactions WHERE keychain_id = ANY($1) AND synced_to_bria = false AND deleted_at IS NULL",
+        )
+        .bind(&keychain_ids)
+        .fetch_one(pool)
+        .await?;
+        if n_unsynced == 0 {
+            return Ok(());
+        }
+        tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+    }
+    Err(anyhow::anyhow!(
+        "wallet {name} was not reconciled after rescan"
+    ))
+}
+
+#[tokio::test]
+async fn update_wallet_config() -> anyhow::Result<()> {
+    let pool = helpers::init_pool().await?;
+    let profile = helpers::create_test_account(&pool).await?;
+    let app = App::run(pool.clone(), AppConfig::default()).await?;
+
+    let name = Alphanumeric.sample_string(&mut rand::thread_rng(), 32);
+    let original = "tpubDD4vFnWuTMEcZiaaZPgvzeGyMzWe6qHW8gALk5Md9kutDvtdDjYFwzauEFFRHgov8pAwup5jX88j5YFyiACsPf3pqn5hBjvuTLRAseaJ6b4";
+    app.create_wpkh_wallet(
+        &profile,
+        name.clone(),
+        original.to_owned(),
+        Some("m/84'/0'/0'".to_owned()),
+        Some(WalletConfig {
+            gap_limit: 200,
+            ..WalletConfig::default()
+        }),
+    )
+    .await?;
+    let wallets = Wallets::new(&pool);
+    let wallet = wallets
+        .find_by_name(profile.account_id, name.clone())
+        .await?;
+    assert_eq!(wallet.config.gap_limit, 200);
+    assert_eq!(wallet.config.lookahead, WalletConfig::default().lookahead);
+
+    app.update_wallet_config(
+        &profile,
+        name.clone(),
+        None,
+        Some(3),
+        None,
+        Some(500),
+        Some(546),
+        None,
+    )
+    .await?;
+    let wallet = wallets
+        .find_by_name(profile.account_id, name.clone())
+        .await?;
+    assert_eq!(wallet.config.gap_limit, 200);
+    assert_eq!(wallet.config.lookahead, 500);
+    assert_eq!(wallet.config.settle_change_after_n_confs, 3);
+    assert_eq!(wallet.config.dust_threshold_sats, 546);
+    assert_eq!(wallet.config.dust_sweep_max_fee_rate_sat_per_vb, 0);
+    assert_eq!(
+        wallet.config.settle_income_after_n_confs,
+        WalletConfig::default().settle_income_after_n_confs
+    );
+
+    assert!(matches!(
+        app.update_wallet_config(&profile, name, None, None, Some(0), None, None, None)
+            .await,
+        Err(ApplicationError::WalletError(WalletError::InvalidConfig(_)))
+    ));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn wallet_consolidation_policy() -> anyhow::Result<()> {
+    let pool = helpers::init_pool().await?;
+    let profile = helpers::create_test_account(&pool).await?;
+    let app = App::run(pool.clone(), AppConfig::default()).await?;
+
+    let name = Alphanumeric.sample_string(&mut rand::thread_rng(), 32);
+    let original = "tpubDD4vFnWuTMEcZiaaZPgvzeGyMzWe6qHW8gALk5Md9kutDvtdDjYFwzauEFFRHgov8pAwup5jX88j5YFyiACsPf3pqn5hBjvuTLRAseaJ6b4";
+    app.create_wpkh_wallet(
+        &profile,
+        name.clone(),
+        original.to_owned(),
+        Some("m/84'/0'/0'".to_owned()),
+        None,
+    )
+    .await?;
+    let queue_name = Alphanumeric.sample_string(&mut rand::thread_rng(), 32);
+    let queue_id = app
+        .create_payout_queue(&profile, queue_name.clone(), None, None)
+        .await?;
+
+    app.set_wallet_consolidation_policy(&profile, name.clone(), queue_name.clone(), 20, 3, 50)
+        .await?;
+    let wallets = Wallets::new(&pool);
+    let wallet = wallets
+        .find_by_name(profile.account_id, name.clone())
+        .await?;
+    assert_eq!(
+        wallet.config.consolidation,
+        Some(ConsolidationPolicy {
+            payout_queue_id: queue_id,
+            target_utxo_count: 20,
+            max_fee_rate_sat_per_vb: 3,
+            max_inputs_per_tx: 50,
+        })
+    );
+
+    app.update_wallet_config(
+        &profile,
+        name.clone(),
+        None,
+        None,
+        None,
+        Some(500),
+        None,
+        None,
+    )
+    .await?;
+    let wallet = wallets
+        .find_by_name(profile.account_id, name.clone())
+        .await?;
+    assert!(wallet.config.consolidation.is_some());
+
+    assert!(matches!(
+        app.set_wallet_consolidation_policy(&profile, name.clone(), queue_name, 20, 3, 1)
+            .await,
+        Err(ApplicationError::WalletError(WalletError::InvalidConfig(_)))
+    ));
+
+    app.remove_wallet_consolidation_policy(&profile, name.clone())
+        .await?;
+    let wallet = wallets.find_by_name(profile.account_id, name).await?;
+    assert_eq!(wallet.config.consolidation, None);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn freeze_utxo() -> anyhow::Result<()> {
+    let pool = helpers::init_pool().await?;
+    let profile = helpers::create_test_account(&pool).await?;
+    let app = App::run(pool.clone(), AppConfig::default()).await?;
+
+    let name = Alphanumeric.sample_string(&mut rand::thread_rng(), 32);
+    let original = "tpubDD4vFnWuTMEcZiaaZPgvzeGyMzWe6qHW8gALk5Md9kutDvtdDjYFwzauEFFRHgov8pAwup5jX88j5YFyiACsPf3pqn5hBjvuTLRAseaJ6b4";
+    let (wallet_id, _) = app
+        .create_wpkh_wallet(
+            &profile,
+            name.clone(),
+            original.to_owned(),
+            Some("m/84'/0'/0'".to_owned()),
+            None,
+        )
+        .await?;
+    let (_, address) = app.new_address(&profile, name.clone(), None, None).await?;
+    let keychain_id = Wallets::new(&pool)
+        .find_by_name(profile.account_id, name.clone())
+        .await?
+        .keychain_ids()
+        .next()
+        .unwrap();
+
+    let outpoint: bitcoin::OutPoint =
+        "4a5e1e4baab89f3a32518a88c31bc87f618f76673e2cc77ab2127b7afdeda33b:0".parse()?;
+    let utxos = Utxos::new(&pool);
+    let (_, tx) = utxos
+        .new_utxo_detected(
+            profile.account_id,
+            wallet_id,
+            keychain_id,
+            &bdk::wallet::AddressInfo {
+                index: 0,
+                address: address.clone(),
+                keychain: bitcoin::KeychainKind::External,
+            },
+            &bdk::LocalUtxo {
+                outpoint,
+                txout: bitcoin::TxOut {
+                    value: 50_000,
+                    script_pubkey: address.script_pubkey(),
+                },
+                keychain: bitcoin::KeychainKind::External,
+                is_spent: false,
+            },
+            Satoshis::from(200),
+            110,
+            false,
+            1,
+        )
+        .await?
+        .expect("utxo should be new");
+    tx.commit().await?;
+
+    app.freeze_utxo(&profile, name.clone(), outpoint, "suspicious".to_owned())
+        .await?;
+    let (_, keychains) = app.list_utxos(&profile, name.clone()).await?;
+    let freeze = keychains[0].utxos[0]
+        .frozen
+        .as_ref()
+        .expect("utxo not frozen");
+    assert_eq!(freeze.reason, "suspicious");
+    assert_eq!(freeze.profile_id, Some(profile.id));
+    assert!(matches!(
+        app.freeze_utxo(&profile, name.clone(), outpoint, "again".to_owned())
+            .await,
+        Err(ApplicationError::UtxoError(UtxoError::UtxoAlreadyFrozen(_)))
+    ));
+
+    app.unfreeze_utxo(&profile, name.clone(), outpoint).await?;
+    let (_, keychains) = app.list_utxos(&profile, name.clone()).await?;
+    assert!(keychains[0].utxos[0].frozen.is_none());
+    assert!(matches!(
+        app.unfreeze_utxo(&profile, name.clone(), outpoint).await,
+        Err(ApplicationError::UtxoError(UtxoError::UtxoNotFrozen(_)))
+    ));
+
+    let unknown = bitcoin::OutPoint {
+        vout: 1,
+        ..outpoint
+    };
+    assert!(matches!(
+        app.freeze_utxo(&profile, name, unknown, "unknown".to_owned())
+            .await,
+        Err(ApplicationError::UtxoError(
+            UtxoError::UtxoDoesNotExistError
+        ))
+    ));
+
+    Ok(())
+}